@@ -1044,7 +1044,7 @@ impl AnsiParser {
                         }
                     }
                 }
-                "9" => {
+                "9" if !arg.is_empty() && !arg.starts_with("4;") => {
                     segments.push(ParsedSegment::Notification(arg.to_string(), None));
                 }
                 "52" => {
@@ -1063,7 +1063,10 @@ impl AnsiParser {
                     let parts: Vec<&str> = arg.splitn(3, ';').collect();
                     if parts.len() >= 2 && parts[0] == "notify" {
                         let title = parts[1].to_string();
-                        let body = parts.get(2).map(|s| s.to_string());
+                        let body = parts
+                            .get(2)
+                            .filter(|s| !s.is_empty())
+                            .map(|s| s.to_string());
                         segments.push(ParsedSegment::Notification(title, body));
                     }
                 }
@@ -1947,6 +1950,28 @@ mod tests {
             .any(|s| matches!(s, ParsedSegment::InlineImage(_))));
     }
 
+    #[test]
+    fn test_osc_notifications() {
+        let mut parser = AnsiParser::new();
+
+        let segments = parser.parse(b"\x1b]9;Build finished\x07");
+        assert!(segments.contains(&ParsedSegment::Notification(
+            "Build finished".to_string(),
+            None
+        )));
+
+        let segments = parser.parse(b"\x1b]777;notify;cargo;tests passed\x1b\\");
+        assert!(segments.contains(&ParsedSegment::Notification(
+            "cargo".to_string(),
+            Some("tests passed".to_string())
+        )));
+
+        let segments = parser.parse(b"\x1b]9;4;1;50\x07");
+        assert!(!segments
+            .iter()
+            .any(|s| matches!(s, ParsedSegment::Notification(..))));
+    }
+
     #[test]
     fn test_base64_roundtrip() {
         let data = b"Hello, World!";
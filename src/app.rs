@@ -1,44 +1,93 @@
+use crate::auto_pair::{backspace_range, typed_edit, PairEdit};
 use crate::autosave::AutosaveManager;
-use crate::completion::{extract_symbols, CompletionItem, CompletionMenu, CompletionState};
-use crate::git_service::FileStatusKind;
+use crate::bracket_guide::enclosing_block;
+use crate::command_palette::CommandPalette;
+use crate::completion::{
+    builtin_items, extract_outline, extract_symbols, outline_entry_at, CompletionItem,
+    CompletionMenu, CompletionState,
+};
+use crate::editorconfig::EditorConfigIndent;
+use crate::explorer_tree::ExplorerTree;
+use crate::formatter::{apply_text_edits, changed_range, run_formatter};
+use crate::git_service::{
+    DiffConfig, FileStatusKind, GitService, LineChange, LineChangeKind, CONVENTIONAL_COMMIT_TYPES,
+};
 use crate::git_state::GitState;
-use crate::git_view::GitView;
+use crate::git_view::{status_color, status_letter, GitView};
+use crate::gutter::{gutter_slots, is_native, move_item, needs_wide_gutter, slot_x, GUTTER_WIDTH};
 use crate::review_state::{CommentStatus, ReviewState};
+use crate::rewrap::{continue_comment, rewrap_comment};
 use crate::ide_theme::{
     all_ide_themes, install_ide_theme, sync_adabraka_theme_from_ide, use_ide_theme, IdeTheme,
 };
+use crate::indent::{
+    detect_whitespace_issues, indent_lines, indent_unit, newline_with_indent, normalize_whitespace,
+    outdent_for_closer, outdent_lines, reindent_paste, strip_control_chars, WhitespaceIssues,
+};
 use crate::lsp::client::LspClient;
 use crate::lsp::registry::LspRegistry;
 use crate::lsp::types::Diagnostic as LspDiagnostic;
-use crate::search_bar::SearchBar;
-use crate::settings::ShioriSettings;
-use crate::terminal_view::TerminalView;
+use crate::markdown::render_markdown;
+use crate::multi_edit::{find_matches, MultiEdit, MAX_CURSORS};
+use crate::search_bar::{HistoryNext, HistoryPrevious, SearchBar, SearchHistory};
+use crate::selection_expand::{expand_range, word_range_at, word_range_with};
+use crate::snippet::{parse_snippet, TabStops};
+use crate::snippet_library::{load_snippet_library, snippets_dir, UserSnippet};
+use crate::settings::{FormatterConfig, GutterItem, IndentConfig, LineNumbers, ShioriSettings};
+use crate::terminal_view::{TerminalMenuTarget, TerminalView};
+use crate::toast::{render_toasts, Toast, ToastSeverity, MAX_TOASTS};
+use crate::undo_group::UndoStack;
+use crate::undo_history::UndoHistory;
+use crate::workspace_file::{
+    is_workspace_file, LoadedWorkspace, TerminalLayout, ViewState, WorkspaceFile, WorkspaceLayout,
+};
 use adabraka_ui::components::editor::{
     DiagnosticSeverity as EditorDiagSeverity, Editor, EditorDiagnostic, EditorState,
-    Enter as EditorEnter, Language, MoveDown, MoveUp, Tab as EditorTab,
+    Enter as EditorEnter, Language, MoveDown, MoveUp, Paste as EditorPaste, Redo as EditorRedo,
+    SelectDown, SelectLeft, SelectRight, Tab as EditorTab, Undo as EditorUndo,
 };
 use adabraka_ui::components::combobox::{Combobox, ComboboxState};
 use adabraka_ui::components::confirm_dialog::Dialog;
 use adabraka_ui::components::icon::Icon;
-use adabraka_ui::components::input::{Input, InputState};
+use adabraka_ui::components::input::{Input, InputState, SelectAll as InputSelectAll};
 use adabraka_ui::components::resizable::{
     h_resizable, resizable_panel, ResizableState,
 };
-use adabraka_ui::navigation::file_tree::{FileNode, FileTree};
+use adabraka_ui::navigation::file_tree::FileNode;
 use adabraka_ui::overlays::command_palette::{
-    CloseCommand, Command, CommandPalette, NavigateDown as CmdNavDown, NavigateUp as CmdNavUp,
-    SelectCommand,
+    CloseCommand, Command, NavigateDown as CmdNavDown, NavigateUp as CmdNavUp, SelectCommand,
 };
+use adabraka_ui::overlays::context_menu::{ContextMenu, ContextMenuItem};
 use gpui::prelude::FluentBuilder as _;
 use gpui::EntityId;
+use gpui::EntityInputHandler;
 use gpui::*;
+use serde::{Deserialize, Serialize};
 use smol::Timer;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const GIT_FOCUS_REFRESH_DELAY: Duration = Duration::from_millis(300);
+const GIT_FOCUS_MIN_INTERVAL: Duration = Duration::from_secs(5);
+const GIT_COMMAND_REFRESH_DELAY: Duration = Duration::from_millis(1500);
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_EDITOR_FILE_SIZE: u64 = 50 * 1024 * 1024;
+const SURROUND_PAIRS: [(&str, &str); 6] = [
+    ("(", ")"),
+    ("[", "]"),
+    ("{", "}"),
+    ("\"", "\""),
+    ("'", "'"),
+    ("`", "`"),
+];
 
 actions!(
     shiori,
@@ -77,10 +126,29 @@ actions!(
         ZoomIn,
         ZoomOut,
         ZoomReset,
+        TerminalZoomIn,
+        TerminalZoomOut,
+        TerminalZoomReset,
+        SwitchWorkspace,
+        SwitcherUp,
+        SwitcherDown,
+        SwitcherConfirm,
+        SwitcherDismiss,
+        ExpandSelection,
+        ShrinkSelection,
+        ShowHover,
+        RewrapComment,
+        CopyWithHighlighting,
+        InsertSnippet,
+        GoToNextProblem,
+        GoToPreviousProblem,
+        FormatDocument,
+        Outdent,
     ]
 );
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ViewMode {
     Explorer,
     Git,
@@ -91,6 +159,7 @@ pub enum ViewMode {
 
 pub fn init(cx: &mut App) {
     crate::search_bar::init(cx);
+    crate::terminal_view::init(cx);
     cx.bind_keys([
         KeyBinding::new("cmd-s", SaveFile, Some("ShioriApp")),
         KeyBinding::new("cmd-w", CloseTab, Some("ShioriApp")),
@@ -102,6 +171,15 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("cmd-h", ToggleSearchReplace, Some("ShioriApp")),
         KeyBinding::new("cmd-g", GotoLine, Some("ShioriApp")),
         KeyBinding::new("cmd-shift-o", OpenFolder, Some("ShioriApp")),
+        KeyBinding::new("cmd-alt-o", SwitchWorkspace, Some("ShioriApp")),
+        KeyBinding::new("up", SwitcherUp, Some("WorkspaceSwitcher")),
+        KeyBinding::new("down", SwitcherDown, Some("WorkspaceSwitcher")),
+        KeyBinding::new("enter", SwitcherConfirm, Some("WorkspaceSwitcher")),
+        KeyBinding::new("escape", SwitcherDismiss, Some("WorkspaceSwitcher")),
+        KeyBinding::new("up", SwitcherUp, Some("TabList")),
+        KeyBinding::new("down", SwitcherDown, Some("TabList")),
+        KeyBinding::new("enter", SwitcherConfirm, Some("TabList")),
+        KeyBinding::new("escape", SwitcherDismiss, Some("TabList")),
         KeyBinding::new("cmd-b", ToggleSidebar, Some("ShioriApp")),
         KeyBinding::new("cmd-`", ToggleTerminal, Some("ShioriApp")),
         KeyBinding::new(
@@ -116,6 +194,20 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("cmd-k cmd-0", FoldAll, Some("ShioriApp")),
         KeyBinding::new("cmd-k cmd-j", UnfoldAll, Some("ShioriApp")),
         KeyBinding::new("f12", GotoDefinition, Some("ShioriApp")),
+        KeyBinding::new("cmd-k cmd-i", ShowHover, Some("ShioriApp")),
+        KeyBinding::new("alt-up", ExpandSelection, Some("ShioriApp")),
+        KeyBinding::new("alt-down", ShrinkSelection, Some("ShioriApp")),
+        KeyBinding::new("alt-q", RewrapComment, Some("ShioriApp")),
+        KeyBinding::new("f8", GoToNextProblem, Some("ShioriApp")),
+        KeyBinding::new("shift-f8", GoToPreviousProblem, Some("ShioriApp")),
+        KeyBinding::new("f3", crate::search_bar::FindNext, Some("ShioriApp")),
+        KeyBinding::new(
+            "shift-f3",
+            crate::search_bar::FindPrevious,
+            Some("ShioriApp"),
+        ),
+        KeyBinding::new("shift-alt-f", FormatDocument, Some("ShioriApp")),
+        KeyBinding::new("shift-tab", Outdent, Some("Editor")),
         KeyBinding::new("cmd-=", ZoomIn, Some("ShioriApp")),
         KeyBinding::new("cmd--", ZoomOut, Some("ShioriApp")),
         KeyBinding::new("cmd-0", ZoomReset, Some("ShioriApp")),
@@ -141,15 +233,26 @@ pub struct AppState {
     buffer_index: HashMap<EntityId, usize>,
     active_tab: usize,
     autosave: AutosaveManager,
+    window_handle: Option<AnyWindowHandle>,
     tab_meta: Vec<TabMeta>,
     search_bar: Entity<SearchBar>,
     search_visible: bool,
     goto_line_visible: bool,
     goto_line_input: Entity<InputState>,
+    goto_line_error: bool,
+    goto_line_origin: Option<(EntityId, ViewState)>,
+    goto_line_flash: Option<(usize, Task<()>)>,
+    gutter_drag: Option<(usize, usize)>,
     tab_scroll_offset: usize,
     active_mode: ViewMode,
     panel_visible: bool,
-    workspace_root: Option<PathBuf>,
+    workspace_roots: Vec<PathBuf>,
+    pending_open_folder: Option<PathBuf>,
+    pending_external_open: Vec<PathBuf>,
+    editorconfig_indent: HashMap<PathBuf, EditorConfigIndent>,
+    workspace_file: Option<PathBuf>,
+    project_settings: serde_json::Map<String, serde_json::Value>,
+    lsp_scope_prompt: Option<bool>,
     file_tree_nodes: Vec<FileNode>,
     expanded_paths: Vec<PathBuf>,
     selected_tree_path: Option<PathBuf>,
@@ -160,6 +263,7 @@ pub struct AppState {
     sidebar_resizable_state: Entity<ResizableState>,
     completion_state: Entity<CompletionState>,
     cached_symbols: Vec<CompletionItem>,
+    snippet_library: HashMap<String, Vec<UserSnippet>>,
     last_symbol_update_line: usize,
     suppress_completion: bool,
     last_content_version: u64,
@@ -167,13 +271,22 @@ pub struct AppState {
     review_state: Entity<ReviewState>,
     symbol_outline_visible: bool,
     symbol_outline_filter: String,
+    symbol_outline_input: Entity<InputState>,
     command_palette: Option<Entity<CommandPalette>>,
     command_palette_open: bool,
     file_search_input: Entity<InputState>,
     file_search_query: String,
+    tree_filter_input: Entity<InputState>,
+    tree_filter_query: String,
     file_search_results: Vec<ContentSearchResult>,
+    file_search_next: Option<(PathBuf, usize)>,
+    file_search_total: Option<usize>,
+    expanded_search_results: HashSet<(PathBuf, usize)>,
+    search_result_buffers: HashMap<EntityId, Vec<SearchResultLine>>,
+    content_search_history: SearchHistory,
     file_index: Arc<Vec<(PathBuf, String, String)>>,
-    search_version: u64,
+    search_version: Arc<AtomicU64>,
+    content_search_task: Option<Task<()>>,
     explorer_scroll_handle: ScrollHandle,
     lsp_registry: LspRegistry,
     settings: ShioriSettings,
@@ -185,14 +298,101 @@ pub struct AppState {
     fallback_font_combobox_state: Entity<ComboboxState<String>>,
     fallback_font_combobox: Entity<Combobox<String>>,
     buffer_diagnostics: HashMap<PathBuf, Vec<LspDiagnostic>>,
+    pulled_diagnostics: HashMap<PathBuf, Vec<LspDiagnostic>>,
+    diagnostic_pulls: HashMap<PathBuf, Task<()>>,
+    queued_diagnostic_pulls: HashMap<PathBuf, Language>,
     lsp_poll_task: Option<Task<()>>,
     lsp_doc_versions: HashMap<PathBuf, i32>,
-    hover_info: Option<(String, Point<Pixels>)>,
+    hover_info: Option<HoverPopup>,
+    hover_scroll_handle: ScrollHandle,
+    mouse_hover_word: Option<(EntityId, Range<usize>)>,
+    mouse_hover_text: Option<(EntityId, u64, String)>,
     hover_task: Option<Task<()>>,
+    hover_request: Option<PendingLspRequest>,
     lsp_completion_task: Option<Task<()>>,
+    completion_request: Option<PendingLspRequest>,
+    lsp_refine_task: Option<Task<()>>,
     lsp_change_task: Option<Task<()>>,
-    zoom_level: f32,
+    format_task: Option<Task<()>>,
+    editor_zoom: f32,
+    terminal_zoom: f32,
     confirm_close_terminal: Option<usize>,
+    disk_watch_task: Option<Task<()>>,
+    confirm_deleted_file: Option<PathBuf>,
+    confirm_delete_path: Option<(PathBuf, bool)>,
+    confirm_quit: bool,
+    quit_confirmed: bool,
+    quit_after_save: bool,
+    toasts: Vec<Toast>,
+    next_toast_id: usize,
+    undo_history: UndoHistory,
+    tab_history: HashMap<EntityId, TabHistory>,
+    undo_stacks: HashMap<EntityId, UndoStack>,
+    selection_expansion: Option<SelectionExpansion>,
+    git_error_seen: Option<String>,
+    git_scope_file: Option<PathBuf>,
+    git_refresh_task: Option<Task<()>>,
+    last_git_focus_refresh: Option<std::time::Instant>,
+    workspace_switcher_open: bool,
+    workspace_switcher_selected: usize,
+    workspace_switcher_focus: FocusHandle,
+    tab_list_anchor: Option<Point<Pixels>>,
+    tab_list_selected: usize,
+    tab_list_focus: FocusHandle,
+    tab_strip_scroll: ScrollHandle,
+    git_generation_seen: u64,
+    hunk_popover: Option<HunkPopover>,
+    commit_prefix_menu_open: bool,
+    context_menu: Option<ContextMenuState>,
+    multi_edit: Option<MultiEditSession>,
+    snippet_session: Option<SnippetSession>,
+    _read_only_keys: Subscription,
+    _surround_keys: Subscription,
+    _auto_pair_keys: Subscription,
+    read_only_flash: Option<Task<()>>,
+    view_states: HashMap<PathBuf, ViewState>,
+    pending_view_restore: HashMap<EntityId, ViewState>,
+    whitespace_notices: HashMap<EntityId, WhitespaceIssues>,
+    whitespace_dismissed: HashSet<PathBuf>,
+    editor_viewport: Rc<Cell<Bounds<Pixels>>>,
+}
+
+struct HoverPopup {
+    contents: String,
+    anchor: Point<Pixels>,
+    pinned: bool,
+    from_pointer: bool,
+}
+
+enum ContextMenuTarget {
+    Tab(usize),
+    Path(PathBuf),
+    Terminal(TerminalMenuTarget),
+    TreeSort,
+    TreeActions,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ZoomTarget {
+    Editor,
+    Terminal,
+}
+
+struct ContextMenuState {
+    position: Point<Pixels>,
+    target: ContextMenuTarget,
+}
+
+const REVEAL_LABEL: &str = if cfg!(target_os = "macos") {
+    "Reveal in Finder"
+} else {
+    "Reveal in File Manager"
+};
+
+struct HunkPopover {
+    path: PathBuf,
+    change: LineChange,
+    position: Point<Pixels>,
 }
 
 struct TabMeta {
@@ -201,6 +401,62 @@ struct TabMeta {
     modified: bool,
     title: SharedString,
     is_image: bool,
+    deleted_on_disk: bool,
+    read_only_content: Option<String>,
+    has_bom: bool,
+    final_newline: bool,
+    stats: ContentStats,
+}
+
+#[derive(Clone, Copy, Default)]
+struct ContentStats {
+    version: u64,
+    len: usize,
+    missing_eol: bool,
+}
+
+impl ContentStats {
+    fn of(state: &EditorState) -> Self {
+        let content = state.content();
+        Self {
+            version: state.content_version(),
+            len: content.len(),
+            missing_eol: final_newline_edit(&content, true).is_some(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct PendingLspRequest {
+    language: Language,
+    id: i64,
+}
+
+struct SnippetSession {
+    buffer: EntityId,
+    stops: TabStops,
+    content: String,
+    _keystrokes: Subscription,
+}
+
+struct MultiEditSession {
+    buffer: EntityId,
+    version: u64,
+    edit: MultiEdit,
+    _keystrokes: Subscription,
+}
+
+struct SelectionExpansion {
+    buffer: EntityId,
+    stack: Vec<Range<usize>>,
+    current: Range<usize>,
+}
+
+struct TabHistory {
+    restored: Vec<String>,
+    anchor: String,
+    saved_versions: Vec<String>,
+    saved_version: u64,
 }
 
 fn capitalize(s: &str) -> String {
@@ -259,6 +515,138 @@ fn is_image_file(path: &Path) -> bool {
     )
 }
 
+fn looks_binary(path: &Path) -> bool {
+    use std::io::Read;
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut head = Vec::with_capacity(8192);
+    if file.take(8192).read_to_end(&mut head).is_err() {
+        return false;
+    }
+    head.contains(&0)
+}
+
+fn is_too_large(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| m.len() > MAX_EDITOR_FILE_SIZE)
+}
+
+fn should_open_externally(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str());
+    let text = matches!(ext, Some("svg" | "lock" | "d"));
+    (is_binary_file(path) && !text) || is_too_large(path) || looks_binary(path)
+}
+
+fn shebang_language(text: &str) -> Option<Language> {
+    let line = text.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+    }
+    let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    Some(match name {
+        "python" | "pypy" => Language::Python,
+        "sh" | "bash" | "zsh" | "ksh" | "dash" => Language::Bash,
+        "node" | "nodejs" | "deno" | "bun" => Language::JavaScript,
+        "ts-node" | "tsx" => Language::TypeScript,
+        "ruby" => Language::Ruby,
+        "lua" | "luajit" => Language::Lua,
+        "php" => Language::Php,
+        _ => return None,
+    })
+}
+
+const BOM: char = '\u{feff}';
+
+fn file_has_bom(path: &Path) -> bool {
+    use std::io::Read;
+    let mut head = [0u8; 3];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut head))
+        .is_ok_and(|_| head == [0xEF, 0xBB, 0xBF])
+}
+
+fn file_ends_with_newline(path: &Path) -> bool {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut last = [0u8; 1];
+    std::fs::File::open(path)
+        .and_then(|mut f| {
+            f.seek(SeekFrom::End(-1))?;
+            f.read_exact(&mut last)
+        })
+        .map_or(true, |_| last[0] == b'\n')
+}
+
+fn final_newline_edit(content: &str, want: bool) -> Option<(Range<usize>, &'static str)> {
+    let len = content.len();
+    match (want, content.ends_with('\n')) {
+        (true, false) if !content.is_empty() => {
+            let eol = if content.contains("\r\n") {
+                "\r\n"
+            } else {
+                "\n"
+            };
+            Some((len..len, eol))
+        }
+        (false, true) if content.ends_with("\r\n") => Some((len - 2..len, "")),
+        (false, true) => Some((len - 1..len, "")),
+        _ => None,
+    }
+}
+
+fn format_file_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+fn document_stats(state: &EditorState, len: usize) -> String {
+    match state.selection_text() {
+        Some(text) => format!(
+            "Sel {}, {}, {}",
+            plural(text.chars().count(), "char"),
+            plural(text.lines().count().max(1), "line"),
+            plural(text.split_whitespace().count(), "word")
+        ),
+        None => format!(
+            "{}, {}",
+            plural(state.line_count(), "line"),
+            format_file_size(len as u64)
+        ),
+    }
+}
+
+fn word_before_cursor(state: &EditorState, word_chars: &str) -> Option<(String, usize)> {
+    if word_chars.is_empty() {
+        return state.word_at_cursor();
+    }
+    let cursor = state.cursor();
+    let content = state.content();
+    let line = content.split('\n').nth(cursor.line)?;
+    let col = cursor.col.min(line.len());
+    let start = word_range_with(line, col, word_chars)?.start;
+    (start < col).then(|| (line[start..col].to_string(), start))
+}
+
+fn is_svg_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("svg"))
+}
+
 #[derive(Clone, Debug)]
 struct ContentSearchResult {
     path: PathBuf,
@@ -268,6 +656,8 @@ struct ContentSearchResult {
     line_content: String,
     col_start: usize,
     col_end: usize,
+    context_before: Vec<(usize, String)>,
+    context_after: Vec<(usize, String)>,
 }
 
 fn is_binary_file(path: &Path) -> bool {
@@ -322,94 +712,204 @@ fn is_binary_file(path: &Path) -> bool {
     )
 }
 
+#[derive(Clone)]
+struct SearchResultLine {
+    path: PathBuf,
+    line_number: usize,
+    prefix: String,
+    original: String,
+}
+
+#[derive(Clone, Copy)]
+struct ContentSearchLimits {
+    max_results: usize,
+    max_file_size: u64,
+    context_lines: usize,
+}
+
+struct ContentSearchPage {
+    results: Vec<ContentSearchResult>,
+    next: Option<(PathBuf, usize)>,
+}
+
+fn read_searchable(path: &Path, query_lower: &str, max_file_size: u64) -> Option<String> {
+    if is_binary_file(path) {
+        return None;
+    }
+    if let Ok(meta) = std::fs::metadata(path) {
+        if meta.len() > max_file_size {
+            return None;
+        }
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    memchr_find(content.to_lowercase().as_bytes(), query_lower.as_bytes()).then_some(content)
+}
+
+fn matching_lines<'a>(
+    content: &'a str,
+    query_lower: &'a str,
+) -> impl Iterator<Item = (usize, &'a str, usize)> + 'a {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(move |(line_idx, line)| {
+            let col = line.to_lowercase().find(query_lower)?;
+            (!line.trim().is_empty()).then_some((line_idx, line, col))
+        })
+}
+
+fn read_search_result_lines(results: Vec<(PathBuf, usize, String)>) -> Vec<SearchResultLine> {
+    let mut sources: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    let mut entries = Vec::new();
+    for (path, line_number, prefix) in results {
+        let lines = sources.entry(path.clone()).or_insert_with(|| {
+            std::fs::read_to_string(&path)
+                .map(|c| c.lines().map(str::to_string).collect())
+                .unwrap_or_default()
+        });
+        let Some(original) = lines.get(line_number - 1) else {
+            continue;
+        };
+        entries.push(SearchResultLine {
+            original: original.clone(),
+            path,
+            line_number,
+            prefix,
+        });
+    }
+    entries
+}
+
+async fn format_timeout<T>() -> Result<T, String> {
+    Timer::after(FORMAT_TIMEOUT).await;
+    Err(format!("timed out after {}s", FORMAT_TIMEOUT.as_secs()))
+}
+
+fn search_display_line(line: &str) -> String {
+    let trimmed = line.trim();
+    if trimmed.len() > 120 {
+        let mut end = 120;
+        while end > 0 && !trimmed.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}...", &trimmed[..end])
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn search_file(
+    (path, file_name, dir_path): &(PathBuf, String, String),
+    query: &str,
+    query_lower: &str,
+    skip: usize,
+    limits: ContentSearchLimits,
+) -> (Vec<ContentSearchResult>, bool) {
+    let Some(content) = read_searchable(path, query_lower, limits.max_file_size) else {
+        return (Vec::new(), false);
+    };
+    let all_lines: Vec<&str> = if limits.context_lines > 0 {
+        content.lines().collect()
+    } else {
+        Vec::new()
+    };
+    let context = |range: std::ops::Range<usize>| -> Vec<(usize, String)> {
+        range
+            .filter_map(|i| all_lines.get(i).map(|l| (i + 1, search_display_line(l))))
+            .collect()
+    };
+    let mut results = Vec::new();
+    for (line_idx, line, col) in matching_lines(&content, query_lower).skip(skip) {
+        if results.len() >= limits.max_results {
+            return (results, true);
+        }
+        let trimmed = line.trim();
+        let trim_offset = line.find(trimmed).unwrap_or(0);
+        let adjusted_col = col.saturating_sub(trim_offset);
+
+        results.push(ContentSearchResult {
+            path: path.clone(),
+            file_name: file_name.clone(),
+            dir_path: dir_path.clone(),
+            line_number: line_idx + 1,
+            line_content: search_display_line(line),
+            col_start: adjusted_col,
+            col_end: adjusted_col + query.len(),
+            context_before: context(line_idx.saturating_sub(limits.context_lines)..line_idx),
+            context_after: context(line_idx + 1..line_idx + 1 + limits.context_lines),
+        });
+    }
+    (results, false)
+}
+
+fn search_chunk_size(file_count: usize) -> usize {
+    let num_threads = std::thread::available_parallelism()
+        .map(|n| n.get().min(8))
+        .unwrap_or(4);
+    (file_count / num_threads).max(1)
+}
+
 fn search_content(
     query: &str,
     file_index: &[(PathBuf, String, String)],
-) -> Vec<ContentSearchResult> {
-    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-
-    if query.is_empty() || query.len() < 2 {
-        return Vec::new();
+    start: Option<(&Path, usize)>,
+    limits: ContentSearchLimits,
+) -> ContentSearchPage {
+    use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+    let mut page = ContentSearchPage {
+        results: Vec::new(),
+        next: None,
+    };
+    let max_results = limits.max_results;
+    if query.is_empty() || query.len() < 2 || max_results == 0 {
+        return page;
     }
 
-    let max_results = 100;
-    let max_file_size: u64 = 2 * 1024 * 1024;
     let query_lower = query.to_lowercase();
+    let (first, first_skip) = match start {
+        Some((path, skip)) => match file_index.iter().position(|(p, _, _)| p == path) {
+            Some(idx) => (idx, skip),
+            None => return page,
+        },
+        None => (0, 0),
+    };
+    let files = &file_index[first..];
 
     let result_count = AtomicUsize::new(0);
     let done = AtomicBool::new(false);
+    let chunk_size = search_chunk_size(files.len());
 
-    let num_threads = std::thread::available_parallelism()
-        .map(|n| n.get().min(8))
-        .unwrap_or(4);
-    let chunk_size = (file_index.len() / num_threads).max(1);
-
-    let results: Vec<Vec<ContentSearchResult>> = std::thread::scope(|s| {
-        let handles: Vec<_> = file_index
+    type FileMatches = (usize, Vec<ContentSearchResult>, bool);
+    let chunks: Vec<(Vec<FileMatches>, Option<usize>)> = std::thread::scope(|s| {
+        let handles: Vec<_> = files
             .chunks(chunk_size)
-            .map(|chunk| {
+            .enumerate()
+            .map(|(chunk_idx, chunk)| {
                 let query_lower = &query_lower;
                 let result_count = &result_count;
                 let done = &done;
                 s.spawn(move || {
                     let mut local_results = Vec::new();
-                    for (path, file_name, dir_path) in chunk {
+                    let base = first + chunk_idx * chunk_size;
+                    for (offset, entry) in chunk.iter().enumerate() {
+                        let idx = base + offset;
                         if done.load(Ordering::Relaxed) {
-                            break;
-                        }
-                        if is_binary_file(path) {
-                            continue;
-                        }
-                        if let Ok(meta) = std::fs::metadata(path) {
-                            if meta.len() > max_file_size {
-                                continue;
-                            }
+                            return (local_results, Some(idx));
                         }
-                        let content = match std::fs::read_to_string(path) {
-                            Ok(c) => c,
-                            Err(_) => continue,
-                        };
-                        let content_lower = content.to_lowercase();
-                        if !memchr_find(content_lower.as_bytes(), query_lower.as_bytes()) {
+                        let skip = if idx == first { first_skip } else { 0 };
+                        let (matches, truncated) =
+                            search_file(entry, query, query_lower, skip, limits);
+                        if matches.is_empty() {
                             continue;
                         }
-                        for (line_idx, line) in content.lines().enumerate() {
-                            if result_count.load(Ordering::Relaxed) >= max_results {
-                                done.store(true, Ordering::Relaxed);
-                                break;
-                            }
-                            let line_lower = line.to_lowercase();
-                            if let Some(col) = line_lower.find(query_lower) {
-                                let trimmed = line.trim();
-                                if trimmed.is_empty() {
-                                    continue;
-                                }
-                                let display_line = if trimmed.len() > 120 {
-                                    let mut end = 120;
-                                    while end > 0 && !trimmed.is_char_boundary(end) {
-                                        end -= 1;
-                                    }
-                                    format!("{}...", &trimmed[..end])
-                                } else {
-                                    trimmed.to_string()
-                                };
-                                let trim_offset = line.find(trimmed).unwrap_or(0);
-                                let adjusted_col = col.saturating_sub(trim_offset);
-
-                                local_results.push(ContentSearchResult {
-                                    path: path.clone(),
-                                    file_name: file_name.clone(),
-                                    dir_path: dir_path.clone(),
-                                    line_number: line_idx + 1,
-                                    line_content: display_line,
-                                    col_start: adjusted_col,
-                                    col_end: adjusted_col + query.len(),
-                                });
-                                result_count.fetch_add(1, Ordering::Relaxed);
-                            }
+                        let found = result_count.fetch_add(matches.len(), Ordering::Relaxed);
+                        if found + matches.len() >= max_results {
+                            done.store(true, Ordering::Relaxed);
                         }
+                        local_results.push((idx, matches, truncated));
                     }
-                    local_results
+                    (local_results, None)
                 })
             })
             .collect();
@@ -420,9 +920,59 @@ fn search_content(
             .collect()
     });
 
-    let mut merged: Vec<ContentSearchResult> = results.into_iter().flatten().collect();
-    merged.truncate(max_results);
-    merged
+    for (matches, stopped_at) in chunks {
+        for (idx, file_matches, truncated) in matches {
+            let skip = if idx == first { first_skip } else { 0 };
+            let room = max_results - page.results.len();
+            let found = file_matches.len();
+            page.results.extend(file_matches.into_iter().take(room));
+            if truncated || found > room {
+                page.next = Some((file_index[idx].0.clone(), skip + found.min(room)));
+                return page;
+            }
+            if page.results.len() == max_results {
+                page.next = file_index
+                    .get(idx + 1)
+                    .map(|(path, _, _)| (path.clone(), 0));
+                return page;
+            }
+        }
+        if let Some(idx) = stopped_at {
+            page.next = Some((file_index[idx].0.clone(), 0));
+            return page;
+        }
+    }
+    page
+}
+
+fn count_content_matches(
+    query: &str,
+    file_index: &[(PathBuf, String, String)],
+    max_file_size: u64,
+    cancelled: impl Fn() -> bool + Sync,
+) -> usize {
+    let query_lower = query.to_lowercase();
+    let chunk_size = search_chunk_size(file_index.len());
+    std::thread::scope(|s| {
+        let handles: Vec<_> = file_index
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let query_lower = &query_lower;
+                let cancelled = &cancelled;
+                s.spawn(move || {
+                    chunk
+                        .iter()
+                        .take_while(|_| !cancelled())
+                        .filter_map(|(path, _, _)| {
+                            read_searchable(path, query_lower, max_file_size)
+                        })
+                        .map(|content| matching_lines(&content, query_lower).count())
+                        .sum::<usize>()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap_or(0)).sum()
+    })
 }
 
 fn memchr_find(haystack: &[u8], needle: &[u8]) -> bool {
@@ -456,21 +1006,32 @@ fn scan_directory(path: &Path, depth: usize) -> Vec<FileNode> {
             .and_then(|n| n.to_str())
             .map(|n| n.starts_with('.'))
             .unwrap_or(false);
-        if entry_path.is_dir() {
-            let mut dir_node = FileNode::directory(&entry_path).hidden(is_hidden);
+        let mut node = if entry_path.is_dir() {
+            let dir_node = FileNode::directory(&entry_path).hidden(is_hidden);
             if depth > 0 {
-                dir_node = dir_node.with_children(scan_directory(&entry_path, depth - 1));
+                dir_node.with_children(scan_directory(&entry_path, depth - 1))
             } else {
-                dir_node = dir_node.with_unloaded_children(true);
+                dir_node.with_unloaded_children(true)
             }
-            nodes.push(dir_node);
         } else if entry_path.is_file() {
-            nodes.push(FileNode::file(&entry_path).hidden(is_hidden));
+            FileNode::file(&entry_path).hidden(is_hidden)
+        } else {
+            continue;
+        };
+        if let Some(stamp) = modified_stamp(&entry) {
+            node = node.with_modified(stamp);
         }
+        nodes.push(node);
     }
     nodes
 }
 
+fn modified_stamp(entry: &std::fs::DirEntry) -> Option<String> {
+    let modified = entry.metadata().ok()?.modified().ok()?;
+    let since = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+    Some(format!("{:020}", since.as_secs()))
+}
+
 fn count_visible_nodes(nodes: &[FileNode], expanded: &[PathBuf]) -> usize {
     let mut count = 0;
     for node in nodes {
@@ -482,6 +1043,23 @@ fn count_visible_nodes(nodes: &[FileNode], expanded: &[PathBuf]) -> usize {
     count
 }
 
+fn expand_nodes_to_level(nodes: &mut [FileNode], level: usize, out: &mut Vec<PathBuf>) {
+    if level == 0 {
+        return;
+    }
+    for node in nodes.iter_mut() {
+        if !node.is_directory() || node.is_hidden {
+            continue;
+        }
+        if node.has_unloaded_children && node.children.is_empty() {
+            node.children = scan_directory(&node.path, 1);
+            node.has_unloaded_children = false;
+        }
+        out.push(node.path.clone());
+        expand_nodes_to_level(&mut node.children, level - 1, out);
+    }
+}
+
 fn load_children_if_needed(nodes: &mut [FileNode], target: &Path) {
     for node in nodes.iter_mut() {
         if node.path == target {
@@ -504,7 +1082,12 @@ impl AppState {
         let completion_state = cx.new(CompletionState::new);
 
         let loaded_settings = ShioriSettings::load();
+        completion_state.update(cx, |s, _| {
+            s.set_max_items(loaded_settings.completion_max_items)
+        });
         let saved_theme_name = loaded_settings.theme.clone();
+        let editor_zoom = loaded_settings.editor_zoom.clamp(0.5, 3.0);
+        let terminal_zoom = loaded_settings.terminal_zoom.clamp(0.5, 3.0);
         if let Some(theme) = all_ide_themes()
             .iter()
             .find(|t| t.name == saved_theme_name.as_str())
@@ -512,9 +1095,22 @@ impl AppState {
             install_ide_theme(theme.clone());
             sync_adabraka_theme_from_ide(cx);
         }
+        let (user_bindings, binding_errors) =
+            crate::keymap::user_key_bindings(&loaded_settings.keybindings, cx);
+        cx.bind_keys(user_bindings);
+        for err in binding_errors {
+            eprintln!("[shiori] {}", err);
+        }
+        let (snippet_library, snippet_errors) = snippets_dir()
+            .map(|dir| load_snippet_library(&dir))
+            .unwrap_or_default();
+        for err in snippet_errors {
+            eprintln!("[shiori] {}", err);
+        }
 
         let goto_line_input = cx.new(InputState::new);
         let file_search_input = cx.new(InputState::new);
+        let tree_filter_input = cx.new(InputState::new);
         let system_mono_fonts = Self::enumerate_mono_fonts(cx);
 
         let mut fallback_fonts = vec!["None".to_string()];
@@ -608,6 +1204,10 @@ impl AppState {
         });
 
         let app_entity = cx.entity().clone();
+        let app_entity_history = cx.entity().clone();
+        let app_entity_select = cx.entity().clone();
+        let app_entity_unfocus = cx.entity().clone();
+        let app_entity_sticky = cx.entity().clone();
         let search_bar = cx.new(|cx| {
             let mut bar = SearchBar::new(cx);
             bar.set_dismiss(move |cx| {
@@ -615,15 +1215,61 @@ impl AppState {
                     this.close_search_internal(cx);
                 });
             });
+            bar.sticky = loaded_settings.search_sticky;
+            bar.on_unfocus(move |window, cx| {
+                app_entity_unfocus.update(cx, |this, cx| this.focus_active_editor(window, cx));
+            });
+            bar.on_sticky_change(move |sticky, cx| {
+                app_entity_sticky.update(cx, |this, _| {
+                    this.settings.search_sticky = sticky;
+                    this.settings.save();
+                });
+            });
+            bar.on_select_all_matches(move |window, cx| {
+                app_entity_select.update(cx, |this, cx| this.select_all_matches(window, cx));
+            });
+            bar.set_history(
+                loaded_settings.search_history.clone(),
+                loaded_settings.replace_history.clone(),
+                loaded_settings.search_history_limit,
+            );
+            bar.on_history_change(move |find, replace, cx| {
+                app_entity_history.update(cx, |this, _| {
+                    this.settings.search_history = find.to_vec();
+                    this.settings.replace_history = replace.to_vec();
+                    this.settings.save();
+                });
+            });
             bar
         });
+        let content_search_history =
+            SearchHistory::new(loaded_settings.content_search_history.clone());
 
         let buffer_index = HashMap::new();
         let tab_meta = Vec::new();
 
         let sidebar_resizable_state = ResizableState::new(cx);
         let git_state = cx.new(GitState::new);
+        git_state.update(cx, |gs, cx| {
+            gs.set_diff_config(Self::diff_config_from(&loaded_settings), cx)
+        });
+        cx.observe(&git_state, Self::on_git_state_changed).detach();
+        cx.observe_self(Self::sync_git_scope).detach();
         let review_state = cx.new(ReviewState::new);
+        let weak = cx.entity().downgrade();
+        let read_only_keys = cx.intercept_keystrokes(move |event, window, cx| {
+            let _ = weak.update(cx, |this, cx| this.block_read_only_key(event, window, cx));
+        });
+        let weak = cx.entity().downgrade();
+        let surround_keys = cx.intercept_keystrokes(move |event, window, cx| {
+            let _ = weak.update(cx, |this, cx| {
+                this.surround_selection_key(event, window, cx)
+            });
+        });
+        let weak = cx.entity().downgrade();
+        let auto_pair_keys = cx.intercept_keystrokes(move |event, window, cx| {
+            let _ = weak.update(cx, |this, cx| this.auto_pair_key(event, window, cx));
+        });
 
         Self {
             focus_handle,
@@ -631,15 +1277,26 @@ impl AppState {
             buffer_index,
             active_tab: 0,
             autosave: AutosaveManager::new(1),
+            window_handle: None,
             tab_meta,
             search_bar,
             search_visible: false,
             goto_line_visible: false,
             goto_line_input,
+            goto_line_error: false,
+            goto_line_origin: None,
+            goto_line_flash: None,
+            gutter_drag: None,
             tab_scroll_offset: 0,
             active_mode: ViewMode::Explorer,
             panel_visible: false,
-            workspace_root: None,
+            workspace_roots: Vec::new(),
+            pending_open_folder: None,
+            pending_external_open: Vec::new(),
+            editorconfig_indent: HashMap::new(),
+            workspace_file: None,
+            project_settings: serde_json::Map::new(),
+            lsp_scope_prompt: None,
             file_tree_nodes: Vec::new(),
             expanded_paths: Vec::new(),
             selected_tree_path: None,
@@ -650,6 +1307,7 @@ impl AppState {
             sidebar_resizable_state,
             completion_state,
             cached_symbols: Vec::new(),
+            snippet_library,
             last_symbol_update_line: usize::MAX,
             suppress_completion: false,
             last_content_version: 0,
@@ -657,13 +1315,22 @@ impl AppState {
             review_state,
             symbol_outline_visible: false,
             symbol_outline_filter: String::new(),
+            symbol_outline_input: cx.new(InputState::new),
             command_palette: None,
             command_palette_open: false,
             file_search_input,
             file_search_query: String::new(),
+            tree_filter_input,
+            tree_filter_query: String::new(),
             file_search_results: Vec::new(),
+            file_search_next: None,
+            file_search_total: None,
+            expanded_search_results: HashSet::new(),
+            search_result_buffers: HashMap::new(),
+            content_search_history,
             file_index: Arc::new(Vec::new()),
-            search_version: 0,
+            search_version: Arc::new(AtomicU64::new(0)),
+            content_search_task: None,
             explorer_scroll_handle: ScrollHandle::new(),
             lsp_registry: LspRegistry::new(),
             settings: loaded_settings,
@@ -675,14 +1342,63 @@ impl AppState {
             fallback_font_combobox_state,
             fallback_font_combobox,
             buffer_diagnostics: HashMap::new(),
+            pulled_diagnostics: HashMap::new(),
+            diagnostic_pulls: HashMap::new(),
+            queued_diagnostic_pulls: HashMap::new(),
             lsp_poll_task: None,
             lsp_doc_versions: HashMap::new(),
             hover_info: None,
+            hover_scroll_handle: ScrollHandle::new(),
+            mouse_hover_word: None,
+            mouse_hover_text: None,
             hover_task: None,
+            hover_request: None,
             lsp_completion_task: None,
+            completion_request: None,
+            lsp_refine_task: None,
             lsp_change_task: None,
-            zoom_level: 1.0,
+            format_task: None,
+            editor_zoom,
+            terminal_zoom,
             confirm_close_terminal: None,
+            disk_watch_task: None,
+            confirm_deleted_file: None,
+            confirm_delete_path: None,
+            confirm_quit: false,
+            quit_confirmed: false,
+            quit_after_save: false,
+            toasts: Vec::new(),
+            next_toast_id: 0,
+            undo_history: UndoHistory::load(),
+            tab_history: HashMap::new(),
+            undo_stacks: HashMap::new(),
+            selection_expansion: None,
+            git_error_seen: None,
+            git_scope_file: None,
+            git_refresh_task: None,
+            last_git_focus_refresh: None,
+            workspace_switcher_open: false,
+            workspace_switcher_selected: 0,
+            workspace_switcher_focus: cx.focus_handle(),
+            tab_list_anchor: None,
+            tab_list_selected: 0,
+            tab_list_focus: cx.focus_handle(),
+            tab_strip_scroll: ScrollHandle::new(),
+            git_generation_seen: 0,
+            hunk_popover: None,
+            commit_prefix_menu_open: false,
+            context_menu: None,
+            multi_edit: None,
+            snippet_session: None,
+            _read_only_keys: read_only_keys,
+            _surround_keys: surround_keys,
+            _auto_pair_keys: auto_pair_keys,
+            read_only_flash: None,
+            view_states: HashMap::new(),
+            pending_view_restore: HashMap::new(),
+            whitespace_notices: HashMap::new(),
+            whitespace_dismissed: HashSet::new(),
+            editor_viewport: Rc::new(Cell::new(Bounds::default())),
         }
     }
 
@@ -699,12 +1415,19 @@ impl AppState {
             .as_ref()
             .map(|p| is_image_file(p))
             .unwrap_or(false);
+        let has_bom = file_path.as_deref().is_some_and(file_has_bom);
+        let final_newline = file_path.as_deref().is_none_or(file_ends_with_newline);
         TabMeta {
             file_path,
             file_name,
             modified,
             title,
             is_image,
+            deleted_on_disk: false,
+            read_only_content: None,
+            has_bom,
+            final_newline,
+            stats: ContentStats::of(state),
         }
     }
 
@@ -731,6 +1454,9 @@ impl AppState {
 
         let meta = &mut self.tab_meta[idx];
         let mut changed = false;
+        if meta.stats.version != state.content_version() {
+            meta.stats = ContentStats::of(state);
+        }
 
         let file_path_changed = match (&meta.file_path, file_path) {
             (Some(prev), Some(current)) => prev != current,
@@ -740,6 +1466,7 @@ impl AppState {
 
         if file_path_changed {
             meta.file_path = file_path.cloned();
+            meta.deleted_on_disk = false;
             meta.file_name = meta
                 .file_path
                 .as_ref()
@@ -751,6 +1478,10 @@ impl AppState {
         if meta.modified != modified {
             meta.modified = modified;
             changed = true;
+            if !modified {
+                meta.has_bom = meta.file_path.as_deref().is_some_and(file_has_bom);
+                meta.final_newline = meta.file_path.as_deref().is_none_or(file_ends_with_newline);
+            }
         }
 
         if changed {
@@ -760,7 +1491,7 @@ impl AppState {
 
     fn refresh_untitled_titles_from(&mut self, start: usize) {
         for idx in start..self.tab_meta.len() {
-            if self.tab_meta[idx].file_path.is_none() {
+            if self.tab_meta[idx].file_path.is_none() && self.tab_meta[idx].file_name.is_none() {
                 let modified = self.tab_meta[idx].modified;
                 self.tab_meta[idx].title = Self::compose_tab_title(None, idx, modified);
             }
@@ -774,1249 +1505,6633 @@ impl AppState {
         self.buffers.push(buffer.clone());
         self.autosave.push();
         self.active_tab = idx;
+        self.record_undo_step(&buffer, cx);
         self.setup_overlay_check(&buffer, cx);
         self.lsp_notify_did_open(&buffer, cx);
-        let editor_font = self.settings.editor_font.clone();
-        let zoom = self.zoom_level;
-        buffer.update(cx, |state, cx| {
-            if editor_font != "JetBrains Mono" {
-                state.set_font_family(editor_font, cx);
-            }
-            if (zoom - 1.0).abs() > f32::EPSILON {
-                state.set_font_size(14.0 * zoom, cx);
-            }
-        });
+        self.start_disk_watch(cx);
+        self.apply_editor_prefs(&buffer, cx);
+        self.refresh_line_changes(&buffer, cx);
     }
 
-    fn setup_overlay_check(&self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
-        let completion_state = self.completion_state.clone();
-        buffer.update(cx, |state, _| {
-            state.set_overlay_active_check(move |cx| completion_state.read(cx).is_visible());
-        });
+    fn diff_config_from(settings: &ShioriSettings) -> DiffConfig {
+        DiffConfig {
+            ignore_whitespace: settings.diff_ignore_whitespace,
+            ignore_blank_lines: settings.diff_ignore_blank_lines,
+            word_diff: settings.diff_word_level,
+        }
     }
 
-    fn remove_buffer_at(&mut self, idx: usize) {
-        if idx >= self.buffers.len() {
+    fn sync_diff_config(&self, cx: &mut Context<Self>) {
+        let config = Self::diff_config_from(&self.settings);
+        self.git_state
+            .update(cx, |gs, cx| gs.set_diff_config(config, cx));
+    }
+
+    fn sync_git_scope(&mut self, cx: &mut Context<Self>) {
+        let path = self
+            .tab_meta
+            .get(self.active_tab)
+            .and_then(|m| m.file_path.clone());
+        if path.is_none() || path == self.git_scope_file {
             return;
         }
-        let buffer = self.buffers.remove(idx);
-        self.tab_meta.remove(idx);
-        self.autosave.remove(idx);
-        self.buffer_index.remove(&buffer.entity_id());
-        for i in idx..self.buffers.len() {
-            let id = self.buffers[i].entity_id();
-            self.buffer_index.insert(id, i);
+        self.git_scope_file = path.clone();
+        if let Some(path) = path {
+            self.git_state.update(cx, |gs, cx| gs.focus_path(&path, cx));
         }
-        self.refresh_untitled_titles_from(idx);
     }
 
-    pub fn open_paths(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
-        for path in paths {
-            if is_image_file(&path) {
-                self.open_image_tab(path, cx);
-            } else {
-                let completion_check = self.completion_state.clone();
-                let buffer = cx.new(|cx| {
-                    let mut state = EditorState::new(cx);
-                    state
-                        .set_overlay_active_check(move |cx| completion_check.read(cx).is_visible());
-                    state.load_file(&path, cx);
-                    state
-                });
-                cx.observe(&buffer, Self::on_buffer_changed).detach();
-                self.add_buffer(buffer, cx);
+    pub fn observe_window_activation(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.window_handle = Some(window.window_handle());
+        cx.observe_window_activation(window, |this, window, cx| {
+            if !window.is_window_active() {
+                return;
             }
-        }
-        self.clamp_tab_scroll();
-        self.update_search_editor(cx);
-        cx.notify();
+            let recent = this
+                .last_git_focus_refresh
+                .is_some_and(|at| at.elapsed() < GIT_FOCUS_MIN_INTERVAL);
+            if !recent {
+                this.last_git_focus_refresh = Some(std::time::Instant::now());
+                this.schedule_git_refresh(GIT_FOCUS_REFRESH_DELAY, cx);
+            }
+        })
+        .detach();
     }
 
-    fn open_image_tab(&mut self, path: PathBuf, cx: &mut Context<Self>) {
-        let idx = self.buffers.len();
-        let buffer = cx.new(EditorState::new);
-        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string());
-        let title = Self::compose_tab_title(file_name.as_deref(), idx, false);
-        self.buffer_index.insert(buffer.entity_id(), idx);
-        self.tab_meta.push(TabMeta {
-            file_path: Some(path),
-            file_name,
-            modified: false,
-            title,
-            is_image: true,
+    fn schedule_git_refresh(&mut self, delay: Duration, cx: &mut Context<Self>) {
+        let task = cx.spawn(async move |this, cx| {
+            Timer::after(delay).await;
+            let _ = this.update(cx, |this, cx| {
+                this.git_refresh_task = None;
+                this.git_state.update(cx, |gs, cx| gs.refresh(cx));
+            });
         });
-        self.buffers.push(buffer);
-        self.autosave.push();
-        self.active_tab = idx;
+        self.git_refresh_task = Some(task);
     }
 
-    fn on_buffer_changed(&mut self, buffer: Entity<EditorState>, cx: &mut Context<Self>) {
-        if let Some(&idx) = self.buffer_index.get(&buffer.entity_id()) {
-            if self.tab_meta.get(idx).map(|m| m.is_image).unwrap_or(false) {
-                return;
-            }
-            self.update_tab_meta_at(idx, cx);
-            let buf = buffer.clone();
-            let task = cx.spawn(async move |_, cx| {
-                Timer::after(AUTOSAVE_DELAY).await;
-                let _ = cx.update(|cx| {
-                    buf.update(cx, |state, cx| {
-                        if let Some(path) = state.file_path().cloned() {
-                            if state.is_modified() {
-                                state.save_to_file(path, cx);
-                            }
-                        }
-                    });
-                });
-            });
-            self.autosave.set(idx, task);
+    fn refresh_line_changes(&self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
+        let state = buffer.read(cx);
+        let Some(path) = state.file_path().cloned() else {
+            return;
+        };
+        let content = state.content();
+        self.git_state
+            .update(cx, |gs, cx| gs.update_line_changes(path, content, cx));
+    }
 
-            if idx == self.active_tab {
-                self.update_completion_for_typing(&buffer, cx);
-                self.lsp_notify_did_change(&buffer, cx);
-                self.dismiss_hover(cx);
-                self.request_hover(cx);
+    pub fn notify(
+        &mut self,
+        message: impl Into<String>,
+        severity: ToastSeverity,
+        cx: &mut Context<Self>,
+    ) {
+        let id = self.next_toast_id;
+        self.next_toast_id += 1;
+        self.toasts.push(Toast {
+            id,
+            message: message.into(),
+            severity,
+        });
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+        cx.spawn(async move |this, cx| {
+            Timer::after(severity.duration()).await;
+            let _ = this.update(cx, |this, cx| this.dismiss_toast(id, cx));
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn dismiss_toast(&mut self, id: usize, cx: &mut Context<Self>) {
+        let before = self.toasts.len();
+        self.toasts.retain(|t| t.id != id);
+        if self.toasts.len() != before {
+            cx.notify();
+        }
+    }
+
+    fn on_git_state_changed(&mut self, git_state: Entity<GitState>, cx: &mut Context<Self>) {
+        let error = git_state.read(cx).error_message.clone();
+        if error != self.git_error_seen {
+            self.git_error_seen = error.clone();
+            if let Some(message) = error {
+                self.notify(message, ToastSeverity::Error, cx);
+            }
+        }
+        if let Some(notice) = git_state.update(cx, |gs, _| gs.notice.take()) {
+            self.notify(notice, ToastSeverity::Success, cx);
+        }
+        let generation = git_state.read(cx).generation;
+        if generation != self.git_generation_seen {
+            self.git_generation_seen = generation;
+            for (idx, buffer) in self.buffers.clone().iter().enumerate() {
+                if !self.tab_meta.get(idx).map(|m| m.is_image).unwrap_or(false) {
+                    self.refresh_line_changes(buffer, cx);
+                }
             }
         }
         cx.notify();
     }
 
-    fn update_completion_for_typing(
-        &mut self,
+    fn render_gutter(
+        &self,
         buffer: &Entity<EditorState>,
         cx: &mut Context<Self>,
-    ) {
-        if self.suppress_completion {
-            self.suppress_completion = false;
-            return;
+    ) -> Option<impl IntoElement> {
+        let mode = self.settings.line_numbers;
+        let items = &self.settings.gutter_items;
+        let native = is_native(items);
+        if native && mode == LineNumbers::Absolute || !buffer.read(cx).show_line_numbers {
+            return None;
         }
+        let (slots, cover) = if native {
+            (vec![(GutterItem::LineNumbers, 8.0)], (8.0, 48.0))
+        } else {
+            (gutter_slots(items), (0.0, GUTTER_WIDTH))
+        };
+        let fold_x = slot_x(items, GutterItem::Folds).filter(|_| !native);
+        let buffer = buffer.clone();
+        let left = px(self.settings.editor_padding_left.max(0.0));
 
-        let state = buffer.read(cx);
-        let content_version = state.content_version();
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, cx| {
+                    let state = buffer.read(cx);
+                    let line_height = state.line_height;
+                    let cursor_line = state.cursor().line;
+                    let Some(anchor) = state.cursor_screen_position(line_height) else {
+                        return;
+                    };
+                    let Some(cursor_row) = state.buffer_line_to_display_row(cursor_line) else {
+                        return;
+                    };
+                    let origin_y = anchor.y - line_height * (cursor_line as f32 + 1.0);
+                    let theme = adabraka_ui::theme::use_theme();
+                    let gutter_bg = state.gutter_bg_override.unwrap_or(theme.tokens.background);
+                    let color = state
+                        .line_number_color_override
+                        .unwrap_or(theme.tokens.muted_foreground);
+                    let active_color = state
+                        .line_number_active_color_override
+                        .unwrap_or(theme.tokens.foreground);
+                    let fold_color = state
+                        .fold_marker_color_override
+                        .unwrap_or(theme.tokens.muted_foreground);
+                    let family = state
+                        .font_family_override
+                        .clone()
+                        .unwrap_or_else(|| theme.tokens.font_mono.clone());
+                    let focused = state.focus_handle(cx).is_focused(window);
+                    let rows = state.display_lines();
+                    let font_size = state.font_size;
+                    let fold_starts: Vec<usize> =
+                        state.fold_ranges().iter().map(|f| f.start_line).collect();
+                    let folded: Vec<usize> =
+                        state.folded_ranges().iter().map(|f| f.start_line).collect();
+                    let mut diagnostics: Vec<(usize, Hsla, u8)> = Vec::new();
+                    for diag in state.diagnostics() {
+                        let (color, rank) = match diag.severity {
+                            EditorDiagSeverity::Error => (
+                                state
+                                    .diagnostic_error_color
+                                    .unwrap_or(hsla(0.0, 0.85, 0.6, 1.0)),
+                                0,
+                            ),
+                            EditorDiagSeverity::Warning => (
+                                state
+                                    .diagnostic_warning_color
+                                    .unwrap_or(hsla(0.12, 0.85, 0.55, 1.0)),
+                                1,
+                            ),
+                            EditorDiagSeverity::Information => (
+                                state
+                                    .diagnostic_info_color
+                                    .unwrap_or(hsla(0.6, 0.7, 0.6, 1.0)),
+                                2,
+                            ),
+                            EditorDiagSeverity::Hint => (
+                                state
+                                    .diagnostic_hint_color
+                                    .unwrap_or(hsla(0.0, 0.0, 0.5, 0.6)),
+                                3,
+                            ),
+                        };
+                        diagnostics.push((diag.start_line as usize, color, rank));
+                    }
+                    let first = ((bounds.top() - origin_y) / line_height).floor().max(0.0) as usize;
+                    let last =
+                        (((bounds.bottom() - origin_y) / line_height).ceil().max(0.0) as usize + 1)
+                            .min(rows.len());
+                    let chevron_right = SharedString::from(
+                        adabraka_ui::icon_config::resolve_icon_path("chevron-right"),
+                    );
+                    let chevron_down = SharedString::from(
+                        adabraka_ui::icon_config::resolve_icon_path("chevron-down"),
+                    );
 
-        if content_version == self.last_content_version {
-            return;
-        }
-        self.last_content_version = content_version;
+                    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                        for row in first..last {
+                            let line = rows[row];
+                            let y = origin_y + line_height * row as f32;
+                            window.paint_quad(fill(
+                                Bounds::new(
+                                    point(bounds.left() + left + px(cover.0), y),
+                                    size(px(cover.1), line_height),
+                                ),
+                                gutter_bg,
+                            ));
+                            for &(item, x) in &slots {
+                                let x = bounds.left() + left + px(x);
+                                match item {
+                                    GutterItem::LineNumbers => {
+                                        let active = row == cursor_row && focused;
+                                        let label =
+                                            format!("{:>4}", mode.number(row, cursor_row, line));
+                                        let mut run_font = font(family.clone());
+                                        if active {
+                                            run_font.weight = FontWeight::BOLD;
+                                        }
+                                        let run = TextRun {
+                                            len: label.len(),
+                                            font: run_font,
+                                            color: if active { active_color } else { color },
+                                            background_color: None,
+                                            underline: None,
+                                            strikethrough: None,
+                                        };
+                                        let shaped = window.text_system().shape_line(
+                                            SharedString::from(label),
+                                            font_size,
+                                            &[run],
+                                            None,
+                                        );
+                                        let origin = point(x - px(2.0), y);
+                                        let _ = shaped.paint(origin, line_height, window, cx);
+                                    }
+                                    GutterItem::Diagnostics => {
+                                        let Some(&(_, dot_color, _)) = diagnostics
+                                            .iter()
+                                            .filter(|(l, _, _)| *l == line)
+                                            .min_by_key(|(_, _, rank)| *rank)
+                                        else {
+                                            continue;
+                                        };
+                                        let dot = px(6.0);
+                                        window.paint_quad(
+                                            fill(
+                                                Bounds::new(
+                                                    point(
+                                                        x + px(2.0),
+                                                        y + (line_height - dot) / 2.0,
+                                                    ),
+                                                    size(dot, dot),
+                                                ),
+                                                dot_color,
+                                            )
+                                            .corner_radii(dot / 2.0),
+                                        );
+                                    }
+                                    GutterItem::Folds => {
+                                        if !fold_starts.contains(&line) {
+                                            continue;
+                                        }
+                                        let icon = if folded.contains(&line) {
+                                            chevron_right.clone()
+                                        } else {
+                                            chevron_down.clone()
+                                        };
+                                        let icon_size = px(16.0);
+                                        let _ = window.paint_svg(
+                                            Bounds::new(
+                                                point(
+                                                    x - px(2.0),
+                                                    y + (line_height - icon_size) / 2.0,
+                                                ),
+                                                size(icon_size, icon_size),
+                                            ),
+                                            icon,
+                                            TransformationMatrix::default(),
+                                            fold_color,
+                                            cx,
+                                        );
+                                    }
+                                    GutterItem::GitChanges => {}
+                                }
+                            }
+                        }
+                    });
 
-        let completion_visible = self.completion_state.read(cx).is_visible();
-        let cursor = state.cursor();
-        let word_info = state.word_at_cursor();
-        let anchor = state.cursor_screen_position(px(20.0));
+                    if native {
+                        return;
+                    }
+                    let buffer = buffer.clone();
+                    window.on_mouse_event(move |event: &MouseDownEvent, phase, _, cx| {
+                        if phase != DispatchPhase::Bubble
+                            || event.button != MouseButton::Left
+                            || !bounds.contains(&event.position)
+                        {
+                            return;
+                        }
+                        let x = f32::from(event.position.x - bounds.left() - left);
+                        let row = ((event.position.y - origin_y) / line_height).floor();
+                        if !(0.0..GUTTER_WIDTH).contains(&x) || row < 0.0 {
+                            return;
+                        }
+                        let Some(&line) = rows.get(row as usize) else {
+                            return;
+                        };
+                        if !fold_starts.contains(&line) {
+                            return;
+                        }
+                        if fold_x.is_some_and(|fx| (fx..fx + 16.0).contains(&x)) {
+                            buffer.update(cx, |state, cx| state.toggle_fold_at_line(line, cx));
+                        } else if x < GUTTER_WIDTH - 16.0 {
+                            return;
+                        }
+                        cx.stop_propagation();
+                    });
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
+    }
 
-        if completion_visible {
-            let trigger_line = self.completion_state.read(cx).trigger_line();
+    fn render_gutter_line_select(
+        &self,
+        buffer: &Entity<EditorState>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        if !self.settings.gutter_click_selects_lines || !buffer.read(cx).show_line_numbers {
+            return None;
+        }
+        let items = &self.settings.gutter_items;
+        let number_x = if is_native(items) {
+            8.0
+        } else {
+            slot_x(items, GutterItem::LineNumbers)?
+        };
+        let left = self.settings.editor_padding_left.max(0.0) + number_x;
+        let region = left..left + 48.0;
+        let buffer = buffer.clone();
+        let app = cx.entity().downgrade();
 
-            if let Some((word, _word_start)) = word_info {
-                if cursor.line != trigger_line {
-                    self.completion_state.update(cx, |s, cx| s.dismiss(cx));
-                    return;
-                }
-                self.completion_state.update(cx, |s, cx| {
-                    s.set_filter(&word, cx);
-                });
-                if let Some(anchor) = anchor {
-                    self.completion_state.update(cx, |s, _| {
-                        s.update_anchor(anchor);
-                    });
-                }
-            } else {
-                self.completion_state.update(cx, |s, cx| s.dismiss(cx));
-            }
-        } else if let Some((word, word_start)) = word_info {
-            if word.len() >= 2 {
-                let state = buffer.read(cx);
-                let language = state.language();
-                let use_lsp = self.lsp_enabled() && self.lsp_registry.has_client_for(language);
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, cx| {
+                    let state = buffer.read(cx);
+                    let line_height = state.line_height;
+                    let cursor_line = state.cursor().line;
+                    let Some(anchor) = state.cursor_screen_position(line_height) else {
+                        return;
+                    };
+                    let origin_y = anchor.y - line_height * (cursor_line as f32 + 1.0);
+                    let rows = state.display_lines();
+                    let line_at = move |y: Pixels| {
+                        let row = ((y - origin_y) / line_height).floor().max(0.0) as usize;
+                        rows.get(row.min(rows.len().saturating_sub(1))).copied()
+                    };
 
-                if use_lsp {
-                    self.request_lsp_completion(cx);
-                } else {
-                    let tree_exists = state.syntax_tree().is_some();
-                    if tree_exists {
-                        if self.last_symbol_update_line != cursor.line {
-                            if let Some(tree) = state.syntax_tree() {
-                                let content = state.content();
-                                let symbols = extract_symbols(tree, &content, language);
-                                self.cached_symbols =
-                                    symbols.into_iter().map(CompletionItem::from).collect();
-                                self.last_symbol_update_line = cursor.line;
-                            }
+                    let down_app = app.clone();
+                    let down_buffer = buffer.clone();
+                    let down_line = line_at.clone();
+                    window.on_mouse_event(move |event: &MouseDownEvent, phase, window, cx| {
+                        if phase != DispatchPhase::Bubble
+                            || event.button != MouseButton::Left
+                            || !bounds.contains(&event.position)
+                            || !region.contains(&f32::from(event.position.x - bounds.left()))
+                        {
+                            return;
                         }
+                        let Some(line) = down_line(event.position.y) else {
+                            return;
+                        };
+                        let _ = down_app.update(cx, |this, cx| {
+                            this.gutter_drag = Some((line, line));
+                            window.focus(&down_buffer.read(cx).focus_handle(cx));
+                            this.select_lines(&down_buffer, line, line, window, cx);
+                        });
+                        cx.stop_propagation();
+                    });
 
-                        if !self.cached_symbols.is_empty() {
-                            if let Some(anchor) = anchor {
-                                let items = self.cached_symbols.clone();
-                                self.completion_state.update(cx, |s, cx| {
-                                    s.show(items, cursor.line, word_start, anchor, cx);
-                                    s.set_filter(&word, cx);
-                                });
+                    let move_app = app.clone();
+                    let move_buffer = buffer.clone();
+                    window.on_mouse_event(move |event: &MouseMoveEvent, phase, window, cx| {
+                        if phase != DispatchPhase::Bubble
+                            || event.pressed_button != Some(MouseButton::Left)
+                        {
+                            return;
+                        }
+                        let Some(line) = line_at(event.position.y) else {
+                            return;
+                        };
+                        let _ = move_app.update(cx, |this, cx| {
+                            let Some((anchor, current)) = this.gutter_drag else {
+                                return;
+                            };
+                            if current != line {
+                                this.gutter_drag = Some((anchor, line));
+                                this.select_lines(&move_buffer, anchor, line, window, cx);
                             }
+                        });
+                    });
+
+                    let up_app = app.clone();
+                    window.on_mouse_event(move |_: &MouseUpEvent, phase, _, cx| {
+                        if phase == DispatchPhase::Bubble {
+                            let _ = up_app.update(cx, |this, _| this.gutter_drag = None);
                         }
-                    }
-                }
-            }
-        }
+                    });
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
     }
 
-    fn trigger_completion(&mut self, cx: &mut Context<Self>) {
-        let buffer = match self.buffers.get(self.active_tab) {
-            Some(b) => b.clone(),
-            None => return,
+    fn select_lines(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        from: usize,
+        to: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let content = buffer.read(cx).content();
+        let line_start = |line: usize| {
+            content
+                .split('\n')
+                .take(line)
+                .map(|l| l.len() + 1)
+                .sum::<usize>()
+                .min(content.len())
         };
+        let range = line_start(from.min(to))..line_start(from.max(to) + 1);
+        Self::select_byte_range(buffer, &content, range, window, cx);
+        cx.notify();
+    }
 
+    fn render_git_gutter(
+        &self,
+        buffer: &Entity<EditorState>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
         let state = buffer.read(cx);
-        let language = state.language();
-
-        if self.lsp_enabled() && self.lsp_registry.has_client_for(language) {
-            self.request_lsp_completion(cx);
-            return;
+        let git_x = slot_x(&self.settings.gutter_items, GutterItem::GitChanges)?;
+        let changes = self
+            .git_state
+            .read(cx)
+            .line_changes
+            .get(state.file_path()?)?
+            .clone();
+        if changes.is_empty() {
+            return None;
         }
+        let chrome = use_ide_theme().chrome;
+        let path = state.file_path()?.clone();
+        let buffer = buffer.clone();
+        let app = cx.entity().downgrade();
+        let marker_x = px(self.settings.editor_padding_left.max(0.0) + git_x + 2.0);
 
-        let cursor = state.cursor();
-        let content = state.content();
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, cx| {
+                    let mut hits: Vec<(Bounds<Pixels>, LineChange)> = Vec::new();
+                    let state = buffer.read(cx);
+                    let line_height = state.line_height;
+                    let cursor_line = state.cursor().line;
+                    let Some(anchor) = state.cursor_screen_position(line_height) else {
+                        return;
+                    };
+                    let origin_y = anchor.y - line_height * (cursor_line as f32 + 1.0);
+                    let x = bounds.left() + marker_x;
+                    let line_count = state.line_count();
+                    let row_y = |line: usize| -> Option<Pixels> {
+                        let row = if line >= line_count {
+                            state.display_line_count()
+                        } else {
+                            state.buffer_line_to_display_row(line)?
+                        };
+                        Some(origin_y + line_height * row as f32)
+                    };
 
-        if self.last_symbol_update_line != cursor.line {
-            if let Some(tree) = state.syntax_tree() {
-                let symbols = extract_symbols(tree, &content, language);
-                self.cached_symbols = symbols.into_iter().map(CompletionItem::from).collect();
-                self.last_symbol_update_line = cursor.line;
-            }
-        }
+                    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                        for change in &changes {
+                            match change.kind {
+                                LineChangeKind::Deleted => {
+                                    let Some(y) = row_y(change.start_line) else {
+                                        continue;
+                                    };
+                                    if y < bounds.top() - px(8.0) || y > bounds.bottom() + px(8.0) {
+                                        continue;
+                                    }
+                                    let mut builder = PathBuilder::fill();
+                                    builder.move_to(point(x, y - px(4.0)));
+                                    builder.line_to(point(x + px(5.0), y));
+                                    builder.line_to(point(x, y + px(4.0)));
+                                    builder.close();
+                                    if let Ok(path) = builder.build() {
+                                        window.paint_path(
+                                            path,
+                                            status_color(FileStatusKind::Deleted, &chrome),
+                                        );
+                                    }
+                                    hits.push((
+                                        Bounds::new(
+                                            point(x - px(3.0), y - px(6.0)),
+                                            size(px(12.0), px(12.0)),
+                                        ),
+                                        change.clone(),
+                                    ));
+                                }
+                                LineChangeKind::Added | LineChangeKind::Modified => {
+                                    let color = status_color(
+                                        if change.kind == LineChangeKind::Added {
+                                            FileStatusKind::Added
+                                        } else {
+                                            FileStatusKind::Modified
+                                        },
+                                        &chrome,
+                                    );
+                                    let end = change.start_line + change.line_count;
+                                    let mut span: Option<(Pixels, Pixels)> = None;
+                                    for line in change.start_line..end.min(line_count) {
+                                        let Some(y) = row_y(line) else {
+                                            continue;
+                                        };
+                                        if y > bounds.bottom() {
+                                            break;
+                                        }
+                                        if y + line_height < bounds.top() {
+                                            continue;
+                                        }
+                                        window.paint_quad(fill(
+                                            Bounds::new(point(x, y), size(px(3.0), line_height)),
+                                            color,
+                                        ));
+                                        let top = span.map(|(top, _)| top).unwrap_or(y);
+                                        span = Some((top, y + line_height));
+                                    }
+                                    if let Some((top, bottom)) = span {
+                                        hits.push((
+                                            Bounds::new(
+                                                point(x - px(3.0), top),
+                                                size(px(10.0), bottom - top),
+                                            ),
+                                            change.clone(),
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                    });
 
-        if self.cached_symbols.is_empty() {
-            return;
-        }
+                    if hits.is_empty() {
+                        return;
+                    }
+                    window.on_mouse_event(move |event: &MouseDownEvent, phase, _, cx| {
+                        if phase != DispatchPhase::Bubble
+                            || event.button != MouseButton::Left
+                            || !bounds.contains(&event.position)
+                        {
+                            return;
+                        }
+                        let Some((_, change)) =
+                            hits.iter().find(|(hit, _)| hit.contains(&event.position))
+                        else {
+                            return;
+                        };
+                        let change = change.clone();
+                        let path = path.clone();
+                        let position = event.position;
+                        cx.stop_propagation();
+                        let _ = app.update(cx, |this, cx| {
+                            this.hunk_popover = Some(HunkPopover {
+                                path,
+                                change,
+                                position,
+                            });
+                            cx.notify();
+                        });
+                    });
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
+    }
 
-        let anchor = match state.cursor_screen_position(px(20.0)) {
-            Some(p) => p,
-            None => return,
+    fn revert_hunk(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(popover) = self.hunk_popover.take() else {
+            return;
         };
-
-        let (filter_prefix, trigger_col) = if let Some((word, word_start)) = state.word_at_cursor()
-        {
-            (word, word_start)
-        } else {
-            (String::new(), cursor.col)
+        let Some(buffer) = self
+            .tab_index_for_path(&popover.path)
+            .and_then(|idx| self.buffers.get(idx).cloned())
+        else {
+            return;
         };
+        let content = buffer.read(cx).content();
+        let Some(head) = self.git_state.read(cx).head_content(&popover.path) else {
+            return;
+        };
+        let changes = GitService::line_changes(head, &content).unwrap_or_default();
+        let Some(change) = changes.iter().find(|c| c.overlaps(&popover.change)) else {
+            self.refresh_line_changes(&buffer, cx);
+            return;
+        };
+        let old_text = GitService::hunk_old_text(head, change);
+        let line_offset = |line: usize| -> usize {
+            content
+                .split_inclusive('\n')
+                .take(line)
+                .map(str::len)
+                .sum::<usize>()
+        };
+        let start = line_offset(change.start_line);
+        let end = line_offset(change.start_line + change.line_count);
+        let start_utf16 = content[..start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[start..end].encode_utf16().count();
 
-        let items: Vec<CompletionItem> = self.cached_symbols.clone();
-
-        self.completion_state.update(cx, |s, cx| {
-            s.show(items, cursor.line, trigger_col, anchor, cx);
-            if !filter_prefix.is_empty() {
-                s.set_filter(&filter_prefix, cx);
-            }
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &old_text, window, cx);
         });
+        self.refresh_line_changes(&buffer, cx);
+        cx.notify();
     }
 
-    fn apply_completion(&mut self, cx: &mut Context<Self>) {
-        let item = match self.completion_state.read(cx).selected_item() {
-            Some(i) => i.clone(),
-            None => return,
+    fn stage_hunk(&mut self, cx: &mut Context<Self>) {
+        let Some(popover) = self.hunk_popover.take() else {
+            return;
         };
+        let Some(buffer) = self
+            .tab_index_for_path(&popover.path)
+            .and_then(|idx| self.buffers.get(idx))
+        else {
+            return;
+        };
+        let content = buffer.read(cx).content();
+        self.git_state.update(cx, |gs, cx| {
+            gs.stage_hunk(popover.path, popover.change, content, cx);
+        });
+        cx.notify();
+    }
 
-        let trigger_col = self.completion_state.read(cx).trigger_col();
-
-        self.suppress_completion = true;
-
-        if let Some(buffer) = self.buffers.get(self.active_tab).cloned() {
-            buffer.update(cx, |state, ecx| {
-                state.apply_completion(trigger_col, &item.insert_text, ecx);
-            });
-        }
+    fn render_hunk_popover(
+        &self,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let label = self
+            .hunk_popover
+            .as_ref()
+            .map(|p| match p.change.kind {
+                LineChangeKind::Added => format!("{} added line(s)", p.change.line_count),
+                LineChangeKind::Modified => format!("{} modified line(s)", p.change.line_count),
+                LineChangeKind::Deleted => format!("{} deleted line(s)", p.change.old_count),
+            })
+            .unwrap_or_default();
 
-        self.completion_state.update(cx, |s, cx| s.dismiss(cx));
-    }
+        let button = |id: &'static str, text: &'static str| {
+            div()
+                .id(id)
+                .px(px(10.0))
+                .py(px(4.0))
+                .rounded(px(6.0))
+                .text_size(px(12.0))
+                .cursor_pointer()
+                .text_color(chrome.bright)
+                .border_1()
+                .border_color(chrome.header_border)
+                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                .child(text)
+        };
 
-    fn completion_move_up(&mut self, cx: &mut Context<Self>) {
-        self.completion_state.update(cx, |s, cx| s.move_up(cx));
+        deferred(
+            anchored()
+                .position(position)
+                .snap_to_window_with_margin(px(8.0))
+                .child(
+                    div()
+                        .ml(px(8.0))
+                        .flex()
+                        .flex_col()
+                        .gap(px(8.0))
+                        .p(px(10.0))
+                        .bg(chrome.panel_bg)
+                        .border_1()
+                        .border_color(chrome.header_border)
+                        .rounded(px(8.0))
+                        .shadow_lg()
+                        .on_mouse_down_out(cx.listener(|this, _, _, cx| {
+                            this.hunk_popover = None;
+                            cx.notify();
+                        }))
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(chrome.text_secondary)
+                                .child(label),
+                        )
+                        .child(
+                            div()
+                                .flex()
+                                .gap(px(6.0))
+                                .child(button("hunk-revert", "Revert Hunk").on_click(
+                                    cx.listener(|this, _, window, cx| this.revert_hunk(window, cx)),
+                                ))
+                                .child(
+                                    button("hunk-stage", "Stage Hunk").on_click(
+                                        cx.listener(|this, _, _, cx| this.stage_hunk(cx)),
+                                    ),
+                                ),
+                        ),
+                ),
+        )
+        .with_priority(1)
     }
 
-    fn completion_move_down(&mut self, cx: &mut Context<Self>) {
-        self.completion_state.update(cx, |s, cx| s.move_down(cx));
+    fn apply_editor_prefs(&self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
+        let editor_font = self.settings.editor_font.clone();
+        let font_size = 14.0 * self.editor_zoom;
+        let line_height = self.editor_line_height(font_size);
+        buffer.update(cx, |state, cx| {
+            if editor_font != "JetBrains Mono" {
+                state.set_font_family(editor_font, cx);
+            }
+            if (self.editor_zoom - 1.0).abs() > f32::EPSILON || state.line_height != line_height {
+                state.set_font_size(font_size, cx);
+                state.line_height = line_height;
+            }
+        });
     }
 
-    fn completion_dismiss(&mut self, cx: &mut Context<Self>) {
-        self.completion_state.update(cx, |s, cx| s.dismiss(cx));
+    fn editor_line_height(&self, font_size: f32) -> Pixels {
+        let multiplier = self.settings.editor_line_height.clamp(1.0, 3.0);
+        if (multiplier - 1.5).abs() < f32::EPSILON && (font_size - 14.0).abs() < f32::EPSILON {
+            return px(20.0);
+        }
+        px((font_size * multiplier).round())
     }
 
-    fn lsp_enabled(&self) -> bool {
-        self.settings.lsp_enabled
+    fn setup_overlay_check(&self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
+        let completion_state = self.completion_state.clone();
+        buffer.update(cx, |state, _| {
+            state.set_overlay_active_check(move |cx| completion_state.read(cx).is_visible());
+        });
     }
 
-    fn lsp_notify_did_open(&mut self, buffer: &Entity<EditorState>, cx: &App) {
-        if !self.lsp_enabled() {
+    fn remove_buffer_at(&mut self, idx: usize) {
+        if idx >= self.buffers.len() {
             return;
         }
-        let state = buffer.read(cx);
-        let path = match state.file_path() {
-            Some(p) => p.clone(),
-            None => return,
-        };
-        let language = state.language();
-        let content = state.content();
-        self.lsp_doc_versions.insert(path.clone(), 1);
-        self.lsp_registry
-            .notify_did_open(language, &path, &content, &self.settings);
+        let buffer = self.buffers.remove(idx);
+        self.tab_meta.remove(idx);
+        self.autosave.remove(idx);
+        self.buffer_index.remove(&buffer.entity_id());
+        self.undo_stacks.remove(&buffer.entity_id());
+        self.whitespace_notices.remove(&buffer.entity_id());
+        self.search_result_buffers.remove(&buffer.entity_id());
+        for i in idx..self.buffers.len() {
+            let id = self.buffers[i].entity_id();
+            self.buffer_index.insert(id, i);
+        }
+        self.refresh_untitled_titles_from(idx);
     }
 
-    fn lsp_notify_did_change(&mut self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
-        if !self.lsp_enabled() {
+    fn start_disk_watch(&mut self, cx: &mut Context<Self>) {
+        if self.disk_watch_task.is_some() {
             return;
         }
-        let state = buffer.read(cx);
-        let path = match state.file_path() {
-            Some(p) => p.clone(),
-            None => return,
-        };
-        let language = state.language();
-        let version = self.lsp_doc_versions.entry(path.clone()).or_insert(0);
-        *version += 1;
-        let ver = *version;
-
-        let buffer = buffer.clone();
         let entity = cx.entity().clone();
-        let task = cx.spawn(async move |_, cx| {
-            Timer::after(Duration::from_millis(200)).await;
-            let _ = cx.update(|cx| {
-                let content = buffer.read(cx).content();
-                entity.update(cx, |this, _cx| {
-                    this.lsp_registry
-                        .notify_did_change(language, &path, &content, ver);
+        let task = cx.spawn(async move |_, cx| loop {
+            Timer::after(DISK_CHECK_INTERVAL).await;
+            let Ok(paths) = cx.update(|cx| entity.read(cx).disk_watch_paths()) else {
+                break;
+            };
+            let results = smol::unblock(move || {
+                paths
+                    .into_iter()
+                    .map(|path| {
+                        let deleted = !path.exists();
+                        (path, deleted)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await;
+            let ok = cx.update(|cx| {
+                entity.update(cx, |this, cx| {
+                    this.apply_deleted_files(results, cx);
                 });
             });
+            if ok.is_err() {
+                break;
+            }
         });
-        self.lsp_change_task = Some(task);
+        self.disk_watch_task = Some(task);
     }
 
-    fn lsp_notify_did_save(&self, buffer: &Entity<EditorState>, cx: &App) {
-        if !self.lsp_enabled() {
-            return;
+    fn disk_watch_paths(&self) -> Vec<PathBuf> {
+        self.tab_meta
+            .iter()
+            .filter(|meta| !meta.is_image)
+            .filter_map(|meta| meta.file_path.clone())
+            .collect()
+    }
+
+    fn apply_deleted_files(&mut self, results: Vec<(PathBuf, bool)>, cx: &mut Context<Self>) {
+        let mut changed = false;
+        for (path, deleted) in results {
+            let Some(idx) = self.tab_index_for_path(&path) else {
+                continue;
+            };
+            let meta = &mut self.tab_meta[idx];
+            if meta.is_image || deleted == meta.deleted_on_disk {
+                continue;
+            }
+            meta.deleted_on_disk = deleted;
+            changed = true;
+            if deleted {
+                self.autosave.cancel(idx);
+                if self.confirm_deleted_file.is_none() {
+                    self.confirm_deleted_file = Some(path);
+                }
+            } else if self.confirm_deleted_file.as_ref() == Some(&path) {
+                self.confirm_deleted_file = None;
+            }
         }
-        let state = buffer.read(cx);
-        if let Some(path) = state.file_path() {
-            let language = state.language();
-            self.lsp_registry.notify_did_save(language, path);
+        if changed {
+            cx.notify();
         }
     }
 
-    fn lsp_notify_did_close(&self, buffer: &Entity<EditorState>, cx: &App) {
-        if !self.lsp_enabled() {
-            return;
+    fn tab_index_for_path(&self, path: &Path) -> Option<usize> {
+        self.tab_meta
+            .iter()
+            .position(|m| m.file_path.as_deref() == Some(path))
+    }
+
+    fn next_deleted_prompt(&self) -> Option<PathBuf> {
+        self.tab_meta
+            .iter()
+            .find(|m| m.deleted_on_disk)
+            .and_then(|m| m.file_path.clone())
+    }
+
+    fn keep_deleted_tab(&mut self, path: &Path, cx: &mut Context<Self>) {
+        if let Some(idx) = self.tab_index_for_path(path) {
+            let old = self.buffers[idx].clone();
+            self.lsp_notify_did_close(&old, cx);
+            self.autosave.cancel(idx);
+            let (content, language) = {
+                let state = old.read(cx);
+                (state.content(), state.language())
+            };
+            let completion_check = self.completion_state.clone();
+            let buffer = cx.new(|cx| {
+                let mut state = EditorState::new(cx);
+                state.set_overlay_active_check(move |cx| completion_check.read(cx).is_visible());
+                state.set_language(language);
+                state.set_content(&content, cx);
+                state
+            });
+            cx.observe(&buffer, Self::on_buffer_changed).detach();
+            self.buffer_index.remove(&old.entity_id());
+            self.buffer_index.insert(buffer.entity_id(), idx);
+            self.buffers[idx] = buffer.clone();
+            self.tab_meta[idx] = Self::build_tab_meta(&buffer, idx, cx);
+            self.apply_editor_prefs(&buffer, cx);
+            if idx == self.active_tab {
+                self.update_search_editor(cx);
+            }
         }
-        let state = buffer.read(cx);
-        if let Some(path) = state.file_path() {
-            let language = state.language();
-            self.lsp_registry.notify_did_close(language, path);
+        self.confirm_deleted_file = self.next_deleted_prompt();
+        cx.notify();
+    }
+
+    fn close_deleted_tab(&mut self, path: &Path, cx: &mut Context<Self>) {
+        if let Some(idx) = self.tab_index_for_path(path) {
+            self.close_tab_at(idx, cx);
         }
+        self.confirm_deleted_file = self.next_deleted_prompt();
+        cx.notify();
     }
 
-    fn request_lsp_completion(&mut self, cx: &mut Context<Self>) {
-        if !self.lsp_enabled() {
-            return;
+    pub fn open_paths(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        let recent_before = self.settings.recent_files.clone();
+        for path in paths {
+            if is_image_file(&path) && !is_svg_file(&path) {
+                self.open_image_tab(path, cx);
+            } else if should_open_externally(&path) {
+                if !self.pending_external_open.contains(&path) {
+                    self.pending_external_open.push(path);
+                }
+            } else {
+                let editorconfig = crate::editorconfig::indent_for_file(&path);
+                if !editorconfig.is_empty() {
+                    self.editorconfig_indent.insert(path.clone(), editorconfig);
+                }
+                let completion_check = self.completion_state.clone();
+                let buffer = cx.new(|cx| {
+                    let mut state = EditorState::new(cx);
+                    state
+                        .set_overlay_active_check(move |cx| completion_check.read(cx).is_visible());
+                    state.load_file(&path, cx);
+                    if is_svg_file(&path) {
+                        state.set_language(Language::Html);
+                    } else if Language::from_path(&path) == Language::Plain {
+                        if let Some(language) = shebang_language(&state.content()) {
+                            state.set_language(language);
+                        }
+                    }
+                    state
+                });
+                cx.observe(&buffer, Self::on_buffer_changed).detach();
+                self.add_buffer(buffer.clone(), cx);
+                self.attach_undo_history(&buffer, cx);
+                self.settings.push_recent_file(path.clone());
+                if !self.whitespace_dismissed.contains(&path) {
+                    let issues = detect_whitespace_issues(&buffer.read(cx).content());
+                    if issues.any() {
+                        self.whitespace_notices.insert(buffer.entity_id(), issues);
+                    }
+                }
+                if let Some(view) = self.view_states.get(&path).copied() {
+                    buffer.update(cx, |state, cx| {
+                        state.set_cursor_position(view.line, view.col, cx);
+                    });
+                    self.pending_view_restore.insert(buffer.entity_id(), view);
+                }
+            }
         }
-        let buffer = match self.buffers.get(self.active_tab) {
-            Some(b) => b.clone(),
-            None => return,
-        };
+        if self.settings.recent_files != recent_before {
+            self.settings.save();
+        }
+        self.clamp_tab_scroll();
+        self.update_search_editor(cx);
+        cx.notify();
+    }
+
+    fn attach_undo_history(&mut self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
         let state = buffer.read(cx);
-        let path = match state.file_path() {
-            Some(p) => p.clone(),
-            None => return,
+        let Some(path) = state.file_path().cloned() else {
+            return;
         };
-        let language = state.language();
-        let cursor = state.cursor();
-        let line = cursor.line as u32;
-        let col = cursor.col as u32;
+        let content = state.content();
+        let saved_version = state.content_version();
+        let restored = self.undo_history.take(&path, &content);
+        self.tab_history.insert(
+            buffer.entity_id(),
+            TabHistory {
+                restored,
+                anchor: content.clone(),
+                saved_versions: vec![content],
+                saved_version,
+            },
+        );
+    }
 
-        if !self.lsp_registry.has_client_for(language) {
+    fn track_saved_version(&mut self, buffer: &Entity<EditorState>, cx: &App) {
+        let Some(history) = self.tab_history.get_mut(&buffer.entity_id()) else {
+            return;
+        };
+        let state = buffer.read(cx);
+        if state.is_modified() || state.content_version() == history.saved_version {
             return;
         }
+        history.saved_version = state.content_version();
+        let content = state.content();
+        if history.saved_versions.last() != Some(&content) {
+            history.saved_versions.push(content);
+        }
+    }
 
-        let rx = match self.lsp_registry.client_for(language) {
-            Some(client) => match client.completion(&path, line, col) {
-                Ok(rx) => rx,
-                Err(_) => return,
-            },
-            None => return,
+    fn record_undo_history(&mut self, buffer: &Entity<EditorState>, cx: &App) {
+        let Some(history) = self.tab_history.remove(&buffer.entity_id()) else {
+            return;
+        };
+        let Some(path) = buffer.read(cx).file_path().cloned() else {
+            return;
+        };
+        let mut snapshots = history.restored;
+        snapshots.extend(history.saved_versions);
+        let Some(current) = snapshots.pop() else {
+            return;
         };
+        let limit = self.settings.undo_history_limit;
+        self.undo_history.record(path, snapshots, &current, limit);
+    }
 
-        let entity = cx.entity().clone();
-        let _completion_state = self.completion_state.clone();
-        let task = cx.spawn(async move |_, cx| {
-            Timer::after(Duration::from_millis(100)).await;
-            if let Ok(response) = rx.recv_timeout(std::time::Duration::from_secs(5)) {
-                let items = LspClient::parse_completion_response(&response);
-                if items.is_empty() {
-                    return;
-                }
-                let _ = cx.update(|cx| {
-                    entity.update(cx, |this, cx| {
-                        this.show_lsp_completions(items, cx);
-                    });
-                });
-            }
-        });
-        self.lsp_completion_task = Some(task);
+    fn persist_undo_history(&mut self, cx: &App) {
+        for buffer in self.buffers.clone() {
+            self.record_undo_history(&buffer, cx);
+        }
+        self.undo_history.save();
     }
 
-    fn show_lsp_completions(
-        &mut self,
-        lsp_items: Vec<crate::lsp::types::LspCompletionItem>,
-        cx: &mut Context<Self>,
-    ) {
-        let buffer = match self.buffers.get(self.active_tab) {
-            Some(b) => b.clone(),
-            None => return,
-        };
-        let state = buffer.read(cx);
-        let cursor = state.cursor();
-        let anchor = match state.cursor_screen_position(px(20.0)) {
-            Some(a) => a,
-            None => return,
+    fn undo_from_history(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
         };
-
-        let (filter_prefix, trigger_col) = if let Some((word, word_start)) = state.word_at_cursor()
-        {
-            (word, word_start)
-        } else {
-            (String::new(), cursor.col)
+        let Some(history) = self.tab_history.get_mut(&buffer.entity_id()) else {
+            return;
         };
+        if history.restored.is_empty() {
+            return;
+        }
+        let content = buffer.read(cx).content();
+        if content != history.anchor {
+            return;
+        }
+        let Some(snapshot) = history.restored.pop() else {
+            return;
+        };
+        history.anchor = snapshot.clone();
+        let len = content.encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(0..len), &snapshot, window, cx);
+        });
+        cx.stop_propagation();
+    }
 
-        let items: Vec<CompletionItem> = lsp_items
-            .into_iter()
-            .map(|item| {
-                use crate::completion::SymbolKind;
-                let kind = match item.kind {
-                    crate::lsp::types::LspCompletionKind::Function => SymbolKind::Function,
-                    crate::lsp::types::LspCompletionKind::Method => SymbolKind::Method,
-                    crate::lsp::types::LspCompletionKind::Variable => SymbolKind::Variable,
-                    crate::lsp::types::LspCompletionKind::Field => SymbolKind::Field,
-                    crate::lsp::types::LspCompletionKind::Module => SymbolKind::Module,
-                    crate::lsp::types::LspCompletionKind::Struct => SymbolKind::Struct,
-                    crate::lsp::types::LspCompletionKind::Enum => SymbolKind::Enum,
-                    crate::lsp::types::LspCompletionKind::Constant => SymbolKind::Const,
-                    crate::lsp::types::LspCompletionKind::Class => SymbolKind::Class,
-                    crate::lsp::types::LspCompletionKind::Property => SymbolKind::Field,
-                    crate::lsp::types::LspCompletionKind::Interface => SymbolKind::Type,
-                    _ => SymbolKind::Variable,
-                };
-                CompletionItem {
-                    label: item.label,
-                    kind,
-                    insert_text: item.insert_text,
-                    detail: item.detail,
-                }
-            })
-            .collect();
-
-        self.completion_state.update(cx, |s, cx| {
-            s.show(items, cursor.line, trigger_col, anchor, cx);
-            if !filter_prefix.is_empty() {
-                s.set_filter(&filter_prefix, cx);
+    fn record_undo_step(&mut self, buffer: &Entity<EditorState>, cx: &App) {
+        let state = buffer.read(cx);
+        let version = state.content_version();
+        let cursor = (state.cursor().line, state.cursor().col);
+        let grouping = self.settings.undo_grouping;
+        let limit = self.settings.undo_max_entries;
+        match self.undo_stacks.get_mut(&buffer.entity_id()) {
+            Some(stack) if stack.version() != version => {
+                stack.record(state.content(), version, cursor, grouping, limit)
             }
-        });
+            Some(stack) => stack.move_cursor(cursor),
+            None => {
+                let stack = UndoStack::new(state.content(), version, cursor);
+                self.undo_stacks.insert(buffer.entity_id(), stack);
+            }
+        }
     }
 
-    fn goto_definition(&mut self, cx: &mut Context<Self>) {
-        if !self.lsp_enabled() {
+    fn grouped_undo(&mut self, redo: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
             return;
-        }
-        let buffer = match self.buffers.get(self.active_tab) {
-            Some(b) => b.clone(),
-            None => return,
         };
-        let state = buffer.read(cx);
-        let path = match state.file_path() {
-            Some(p) => p.clone(),
-            None => return,
+        self.record_undo_step(&buffer, cx);
+        let Some(stack) = self.undo_stacks.get_mut(&buffer.entity_id()) else {
+            return;
         };
-        let language = state.language();
-        let cursor = state.cursor();
-        let line = cursor.line as u32;
-        let col = cursor.col as u32;
-
-        let rx = match self.lsp_registry.client_for(language) {
-            Some(client) => match client.goto_definition(&path, line, col) {
-                Ok(rx) => rx,
-                Err(_) => return,
-            },
-            None => return,
+        if !redo && !stack.can_undo() {
+            self.undo_from_history(window, cx);
+            return;
+        }
+        let Some((range, text, caret)) = (if redo { stack.redo() } else { stack.undo() }) else {
+            return;
         };
+        let content = buffer.read(cx).content();
+        let start_utf16 = content[..range.start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[range].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &text, window, cx);
+        });
+        self.record_undo_step(&buffer, cx);
+        let content = buffer.read(cx).content();
+        Self::select_byte_range(&buffer, &content, caret..caret, window, cx);
+        cx.stop_propagation();
+    }
 
-        let entity = cx.entity().clone();
-        cx.spawn(async move |_, cx| {
-            if let Ok(response) = rx.recv_timeout(std::time::Duration::from_secs(5)) {
-                let locations = LspClient::parse_definition_response(&response);
-                if let Some(loc) = locations.first() {
-                    let target_path = loc.path.clone();
-                    let target_line = loc.line as usize;
-                    let target_col = loc.col as usize;
-                    let _ = cx.update(|cx| {
-                        entity.update(cx, |this, cx| {
-                            this.navigate_to_location(target_path, target_line, target_col, cx);
-                        });
-                    });
-                }
-            }
-        })
-        .detach();
+    fn word_chars_for(&self, buffer: &Entity<EditorState>, cx: &App) -> String {
+        let language = buffer.read(cx).language();
+        self.settings
+            .word_chars_for(language_key_for_display(language))
+            .to_string()
     }
 
-    fn navigate_to_location(
+    fn select_word_at(
         &mut self,
-        path: PathBuf,
-        line: usize,
-        col: usize,
+        position: Point<Pixels>,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let existing_idx = self
-            .tab_meta
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let Some(offset) = buffer.update(cx, |s, cx| {
+            s.character_index_for_point(position, window, cx)
+        }) else {
+            return;
+        };
+        let content = buffer.read(cx).content();
+        let mut utf16 = 0;
+        let byte = content
+            .char_indices()
+            .find(|(_, c)| {
+                utf16 += c.len_utf16();
+                utf16 > offset
+            })
+            .map_or(content.len(), |(i, _)| i);
+        let word_chars = self.word_chars_for(&buffer, cx);
+        if let Some(word) = word_range_with(&content, byte, &word_chars) {
+            Self::select_byte_range(&buffer, &content, word, window, cx);
+        }
+    }
+
+    fn indent_config_for(&self, buffer: &Entity<EditorState>, cx: &App) -> IndentConfig {
+        let state = buffer.read(cx);
+        let config = self
+            .settings
+            .indent_for(language_key_for_display(state.language()));
+        if self.settings.has_local_override("indent") {
+            return config;
+        }
+        state
+            .file_path()
+            .and_then(|path| self.editorconfig_indent.get(path))
+            .map_or(config, |editorconfig| editorconfig.apply(config))
+    }
+
+    fn reload_editorconfig(&mut self, cx: &App) {
+        self.editorconfig_indent = self
+            .buffers
             .iter()
-            .position(|m| m.file_path.as_ref() == Some(&path));
+            .filter_map(|buffer| buffer.read(cx).file_path().cloned())
+            .map(|path| (crate::editorconfig::indent_for_file(&path), path))
+            .filter(|(editorconfig, _)| !editorconfig.is_empty())
+            .map(|(editorconfig, path)| (path, editorconfig))
+            .collect();
+    }
 
-        if let Some(idx) = existing_idx {
-            self.active_tab = idx;
-        } else if path.exists() {
-            self.open_paths(vec![path], cx);
-        } else {
+    fn insert_indent(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.completion_state.read(cx).is_visible() {
             return;
         }
-
-        if let Some(buffer) = self.buffers.get(self.active_tab) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let unit = indent_unit(self.indent_config_for(&buffer, cx));
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        if content[range.clone()].contains('\n') {
+            self.reindent_selected_lines(&buffer, |text| indent_lines(text, &unit), window, cx);
+        } else {
             buffer.update(cx, |state, cx| {
-                state.set_cursor_position(line, col, cx);
+                state.replace_text_in_range(None, &unit, window, cx);
             });
         }
-        cx.notify();
+        cx.stop_propagation();
     }
 
-    fn request_hover(&mut self, cx: &mut Context<Self>) {
-        if !self.lsp_enabled() {
+    fn outdent_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.active_is_read_only() {
             return;
         }
-        let buffer = match self.buffers.get(self.active_tab) {
-            Some(b) => b.clone(),
-            None => return,
-        };
-        let state = buffer.read(cx);
-        let path = match state.file_path() {
-            Some(p) => p.clone(),
-            None => return,
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
         };
-        let language = state.language();
-        let cursor = state.cursor();
-        let line = cursor.line as u32;
-        let col = cursor.col as u32;
+        let tab_size = self.indent_config_for(&buffer, cx).tab_size;
+        self.reindent_selected_lines(&buffer, |text| outdent_lines(text, tab_size), window, cx);
+    }
 
-        if !self.lsp_registry.has_client_for(language) {
+    fn reindent_selected_lines(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        transform: impl Fn(&str) -> String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (content, range) = Self::selection_byte_range(buffer, window, cx);
+        let start = content[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let end = if range.end > range.start && content[..range.end].ends_with('\n') {
+            range.end - 1
+        } else {
+            content[range.end..]
+                .find('\n')
+                .map_or(content.len(), |i| range.end + i)
+        };
+        let text = transform(&content[start..end]);
+        if text == content[start..end] {
             return;
         }
+        let start_utf16 = content[..start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[start..end].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &text, window, cx);
+        });
+        let content = buffer.read(cx).content();
+        Self::select_byte_range(buffer, &content, start..start + text.len(), window, cx);
+    }
 
-        let rx = match self.lsp_registry.client_for(language) {
-            Some(client) => match client.hover(&path, line, col) {
-                Ok(rx) => rx,
-                Err(_) => return,
-            },
-            None => return,
+    fn outdent_before_closer(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        content: &str,
+        offset: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let config = self.indent_config_for(buffer, cx);
+        let start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let Some(indent) = outdent_for_closer(
+            &content[start..offset],
+            &indent_unit(config),
+            config.tab_size,
+        ) else {
+            return false;
         };
+        let start_utf16 = content[..start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[start..offset].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &indent, window, cx);
+        });
+        true
+    }
 
-        let anchor = state.cursor_screen_position(px(20.0));
-        let entity = cx.entity().clone();
-        let task = cx.spawn(async move |_, cx| {
-            Timer::after(Duration::from_millis(500)).await;
-            if let Ok(response) = rx.recv_timeout(std::time::Duration::from_secs(5)) {
-                if let Some(info) = LspClient::parse_hover_response(&response) {
-                    let _ = cx.update(|cx| {
-                        entity.update(cx, |this, cx| {
-                            if let Some(anchor) = anchor {
-                                this.hover_info = Some((info.contents, anchor));
-                                cx.notify();
-                            }
-                        });
-                    });
-                }
-            }
+    fn paste_preserving_indent(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.active_is_read_only() {
+            return;
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        cx.stop_propagation();
+        let (mut text, removed) = strip_control_chars(&text);
+        if removed > 0 {
+            self.notify(
+                format!(
+                    "Removed {} control character{} from pasted text",
+                    removed,
+                    if removed == 1 { "" } else { "s" }
+                ),
+                ToastSeverity::Info,
+                cx,
+            );
+        }
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        if self.settings.paste_normalize_indent && text.contains('\n') {
+            let config = self.indent_config_for(&buffer, cx);
+            let line_start = content[..range.start].rfind('\n').map_or(0, |i| i + 1);
+            text = reindent_paste(
+                &text,
+                &content[line_start..range.start],
+                &indent_unit(config),
+                config.tab_size,
+            );
+        }
+        let start_utf16 = content[..range.start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[range.clone()].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &text, window, cx);
         });
-        self.hover_task = Some(task);
+        let content = buffer.read(cx).content();
+        let caret = range.start + text.len();
+        Self::select_byte_range(&buffer, &content, caret..caret, window, cx);
     }
 
-    fn dismiss_hover(&mut self, cx: &mut Context<Self>) {
-        if self.hover_info.is_some() {
-            self.hover_info = None;
-            cx.notify();
+    fn insert_newline_with_indent(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.completion_state.read(cx).is_visible() {
+            return;
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        let continuation = range
+            .is_empty()
+            .then(|| continue_comment(buffer.read(cx).syntax_tree(), &content, range.start))
+            .flatten();
+        if let Some((edit, text)) = continuation {
+            let start_utf16 = content[..edit.start].encode_utf16().count();
+            let end_utf16 = start_utf16 + content[edit.clone()].encode_utf16().count();
+            buffer.update(cx, |state, cx| {
+                state.replace_text_in_range(Some(start_utf16..end_utf16), &text, window, cx);
+            });
+            let content = buffer.read(cx).content();
+            let caret = edit.start + text.len();
+            Self::select_byte_range(&buffer, &content, caret..caret, window, cx);
+            cx.stop_propagation();
+            return;
         }
+        let config = self.indent_config_for(&buffer, cx);
+        let line_start = content[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = content[range.end..]
+            .find('\n')
+            .map_or(content.len(), |i| range.end + i);
+        let (text, caret) = newline_with_indent(
+            &content[line_start..range.start],
+            &content[range.end..line_end],
+            &indent_unit(config),
+        );
+        let start_utf16 = content[..range.start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[range.clone()].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &text, window, cx);
+        });
+        let content = buffer.read(cx).content();
+        let caret = range.start + caret;
+        Self::select_byte_range(&buffer, &content, caret..caret, window, cx);
+        cx.stop_propagation();
     }
 
-    fn start_lsp_poll(&mut self, cx: &mut Context<Self>) {
-        if self.lsp_poll_task.is_some() {
+    fn copy_with_highlighting(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        if range.is_empty() {
+            self.notify("Select some text to copy", ToastSeverity::Info, cx);
             return;
         }
-        let entity = cx.entity().clone();
-        let task = cx.spawn(async move |_, cx| loop {
-            Timer::after(Duration::from_millis(200)).await;
-            let ok = cx.update(|cx| {
-                entity.update(cx, |this, cx| {
-                    this.poll_lsp_diagnostics(cx);
-                });
+        let text = content[range.clone()].to_string();
+        cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+        let ide = use_ide_theme();
+        let fg = adabraka_ui::theme::use_theme().tokens.foreground;
+        let bg = ide.chrome.editor_bg;
+        let font = self.settings.editor_font.clone();
+        let language = buffer.read(cx).language();
+        cx.spawn(async move |this, cx| {
+            let result = smol::unblock(move || {
+                let lines = crate::diff_highlighter::compute_line_highlights_with(
+                    &content,
+                    language,
+                    |name| ide.syntax.color_for_capture(name),
+                );
+                let html =
+                    crate::rich_copy::highlighted_html(&content, range, &lines, fg, bg, &font);
+                crate::rich_copy::copy_html(&text, &html)
+            })
+            .await;
+            let _ = this.update(cx, |this, cx| match result {
+                Ok(()) => this.notify("Copied with highlighting", ToastSeverity::Success, cx),
+                Err(err) => this.notify(
+                    format!("{}; copied as plain text", err),
+                    ToastSeverity::Info,
+                    cx,
+                ),
             });
-            if ok.is_err() {
-                break;
+        })
+        .detach();
+    }
+
+    fn selection_byte_range(
+        buffer: &Entity<EditorState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> (String, Range<usize>) {
+        let content = buffer.read(cx).content();
+        let selection = buffer.update(cx, |state, cx| state.selected_text_range(false, window, cx));
+        let to_byte = |utf16: usize| {
+            let mut count = 0;
+            for (idx, ch) in content.char_indices() {
+                if count >= utf16 {
+                    return idx;
+                }
+                count += ch.len_utf16();
+            }
+            content.len()
+        };
+        let range = selection
+            .map(|s| to_byte(s.range.start)..to_byte(s.range.end))
+            .unwrap_or(0..0);
+        (content, range)
+    }
+
+    fn select_byte_range(
+        buffer: &Entity<EditorState>,
+        text: &str,
+        range: Range<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let position = |offset: usize| {
+            let before = &text[..offset];
+            let line = before.matches('\n').count();
+            let col = offset - before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            (line, col)
+        };
+        let (start_line, start_col) = position(range.start);
+        let (end_line, end_col) = position(range.end);
+        buffer.update(cx, |state, cx| {
+            state.set_cursor_position(start_line, start_col, cx);
+            for _ in start_line..end_line {
+                state.select_down(&SelectDown, window, cx);
+            }
+            let col = state.cursor().col;
+            for _ in end_col..col {
+                state.select_left(&SelectLeft, window, cx);
+            }
+            for _ in col..end_col {
+                state.select_right(&SelectRight, window, cx);
             }
         });
-        self.lsp_poll_task = Some(task);
     }
 
-    fn poll_lsp_diagnostics(&mut self, cx: &mut Context<Self>) {
-        if !self.lsp_enabled() {
+    fn expand_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
             return;
-        }
-        self.lsp_registry.poll_ready();
-        let file_diags = self.lsp_registry.drain_diagnostics();
-        if file_diags.is_empty() {
+        };
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        let word_chars = self.word_chars_for(&buffer, cx);
+        let Some(expanded) = expand_range(
+            buffer.read(cx).syntax_tree(),
+            &content,
+            range.clone(),
+            &word_chars,
+        ) else {
             return;
-        }
-        for fd in file_diags {
-            self.buffer_diagnostics
-                .insert(fd.path.clone(), fd.diagnostics);
-        }
-        self.push_diagnostics_to_buffers(cx);
-        cx.notify();
+        };
+        let mut stack = match self.selection_expansion.take() {
+            Some(exp) if exp.buffer == buffer.entity_id() && exp.current == range => exp.stack,
+            _ => Vec::new(),
+        };
+        stack.push(range);
+        Self::select_byte_range(&buffer, &content, expanded.clone(), window, cx);
+        self.selection_expansion = Some(SelectionExpansion {
+            buffer: buffer.entity_id(),
+            stack,
+            current: expanded,
+        });
     }
 
-    fn push_diagnostics_to_buffers(&self, cx: &mut Context<Self>) {
-        let ide = use_ide_theme();
-        for buffer in &self.buffers {
-            let path = buffer.read(cx).file_path().cloned();
-            if let Some(path) = path {
-                let lsp_diags = self.diagnostics_for_path(&path);
-                let editor_diags: Vec<EditorDiagnostic> = lsp_diags
-                    .iter()
-                    .map(|d| EditorDiagnostic {
-                        start_line: d.range_start_line,
-                        start_col: d.range_start_col,
-                        end_line: d.range_end_line,
-                        end_col: d.range_end_col,
-                        severity: match d.severity {
-                            crate::lsp::types::DiagnosticSeverity::Error => {
-                                EditorDiagSeverity::Error
-                            }
-                            crate::lsp::types::DiagnosticSeverity::Warning => {
-                                EditorDiagSeverity::Warning
-                            }
-                            crate::lsp::types::DiagnosticSeverity::Information => {
-                                EditorDiagSeverity::Information
-                            }
-                            crate::lsp::types::DiagnosticSeverity::Hint => EditorDiagSeverity::Hint,
-                        },
-                        message: d.message.clone(),
-                    })
-                    .collect();
-                buffer.update(cx, |state, ecx| {
-                    state.diagnostic_error_color = Some(ide.editor.diagnostic_error);
-                    state.diagnostic_warning_color = Some(ide.editor.diagnostic_warning);
-                    state.diagnostic_info_color = Some(ide.editor.diagnostic_info);
-                    state.diagnostic_hint_color = Some(ide.editor.diagnostic_hint);
-                    state.set_diagnostics(editor_diags, ecx);
-                });
-            }
+    fn rewrap_comment_at_cursor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        let width = self.settings.preferred_line_length;
+        let Some((target, wrapped)) =
+            rewrap_comment(buffer.read(cx).syntax_tree(), &content, range.start, width)
+        else {
+            return;
+        };
+        let start_utf16 = content[..target.start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[target.clone()].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &wrapped, window, cx);
+        });
+    }
+
+    fn shrink_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        let Some(mut exp) = self.selection_expansion.take() else {
+            return;
+        };
+        if exp.buffer != buffer.entity_id() || exp.current != range {
+            return;
+        }
+        let Some(previous) = exp.stack.pop() else {
+            return;
+        };
+        Self::select_byte_range(&buffer, &content, previous.clone(), window, cx);
+        if !exp.stack.is_empty() {
+            exp.current = previous;
+            self.selection_expansion = Some(exp);
         }
     }
 
-    fn diagnostics_for_path(&self, path: &Path) -> &[LspDiagnostic] {
-        self.buffer_diagnostics
-            .get(path)
-            .map(|v| v.as_slice())
-            .unwrap_or(&[])
+    fn open_image_tab(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        let idx = self.buffers.len();
+        let buffer = cx.new(EditorState::new);
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string());
+        let title = Self::compose_tab_title(file_name.as_deref(), idx, false);
+        self.buffer_index.insert(buffer.entity_id(), idx);
+        self.tab_meta.push(TabMeta {
+            file_path: Some(path),
+            file_name,
+            modified: false,
+            title,
+            is_image: true,
+            deleted_on_disk: false,
+            read_only_content: None,
+            has_bom: false,
+            final_newline: true,
+            stats: ContentStats::default(),
+        });
+        self.buffers.push(buffer);
+        self.autosave.push();
+        self.active_tab = idx;
     }
 
-    fn save_active(&mut self, cx: &mut Context<Self>) {
-        if let Some(buffer) = self.buffers.get(self.active_tab) {
-            let has_path = buffer.read(cx).file_path().is_some();
-            if has_path {
-                let buffer = buffer.clone();
-                buffer.update(cx, |state, cx| {
-                    if let Some(path) = state.file_path().cloned() {
-                        state.save_to_file(path, cx);
-                    }
+    fn on_buffer_changed(&mut self, buffer: Entity<EditorState>, cx: &mut Context<Self>) {
+        self.track_snippet_edit(&buffer, cx);
+        if let Some(&idx) = self.buffer_index.get(&buffer.entity_id()) {
+            if self.tab_meta.get(idx).map(|m| m.is_image).unwrap_or(false) {
+                return;
+            }
+            if let Some(snapshot) = self
+                .tab_meta
+                .get(idx)
+                .and_then(|m| m.read_only_content.clone())
+            {
+                if buffer.read(cx).content() != snapshot {
+                    let cursor = buffer.read(cx).cursor();
+                    buffer.update(cx, |state, cx| {
+                        state.set_content(&snapshot, cx);
+                        state.set_cursor_position(cursor.line, cursor.col, cx);
+                    });
+                    self.flash_read_only(cx);
+                    return;
+                }
+            }
+            self.update_tab_meta_at(idx, cx);
+            self.track_saved_version(&buffer, cx);
+            self.record_undo_step(&buffer, cx);
+            let deleted = self
+                .tab_meta
+                .get(idx)
+                .map(|m| m.deleted_on_disk)
+                .unwrap_or(false);
+            if !deleted {
+                let buf = buffer.clone();
+                let window = self.window_handle;
+                let task = cx.spawn(async move |this, cx| {
+                    Timer::after(AUTOSAVE_DELAY).await;
+                    let Some(window) = window else {
+                        return;
+                    };
+                    let _ = cx.update_window(window, |_, window, cx| {
+                        let _ = this.update(cx, |this, cx| this.autosave_buffer(&buf, window, cx));
+                    });
                 });
-                self.lsp_notify_did_save(&buffer, cx);
-            } else {
-                let buffer = buffer.clone();
-                let rx = cx.prompt_for_new_path(Path::new(""), Some("untitled.txt"));
-                cx.spawn(async move |this, cx| {
-                    if let Ok(Ok(Some(path))) = rx.await {
-                        let _ = cx.update(|cx| {
-                            buffer.update(cx, |state, cx| {
-                                state.save_to_file(path, cx);
-                            });
-                            let _ = this.update(cx, |_, cx| cx.notify());
-                        });
-                    }
-                })
-                .detach();
+                self.autosave.set(idx, task);
+            }
+
+            if idx == self.active_tab {
+                self.update_completion_for_typing(&buffer, cx);
+                self.lsp_notify_did_change(&buffer, cx);
+                self.dismiss_hover(cx);
+                self.request_hover(cx);
             }
         }
+        cx.notify();
     }
 
-    fn close_active_tab(&mut self, cx: &mut Context<Self>) {
-        if self.buffers.is_empty() {
+    fn update_completion_for_typing(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.suppress_completion {
+            self.suppress_completion = false;
             return;
         }
-        let idx = self.active_tab;
-        self.autosave.cancel(idx);
-        self.remove_buffer_at(idx);
-        if self.active_tab >= self.buffers.len() {
-            self.active_tab = self.buffers.len().saturating_sub(1);
+
+        let word_chars = self.word_chars_for(buffer, cx);
+        let state = buffer.read(cx);
+        let content_version = state.content_version();
+
+        if content_version == self.last_content_version {
+            return;
         }
-        self.clamp_tab_scroll();
-        self.update_search_editor(cx);
-        cx.notify();
-    }
+        self.last_content_version = content_version;
 
-    fn open_file_dialog(&mut self, cx: &mut Context<Self>) {
-        let rx = cx.prompt_for_paths(PathPromptOptions {
-            files: true,
-            directories: false,
-            multiple: true,
-            prompt: None,
-        });
-        cx.spawn(async move |this, cx| {
-            if let Ok(Ok(Some(paths))) = rx.await {
-                let _ = cx.update(|cx| {
-                    let _ = this.update(cx, |this, cx| {
-                        this.open_paths(paths, cx);
-                    });
+        let completion_visible = self.completion_state.read(cx).is_visible();
+        let cursor = state.cursor();
+        let word_info = word_before_cursor(state, &word_chars);
+        let anchor = state.cursor_screen_position(state.line_height);
+
+        if word_info.is_none() {
+            if let Some(trigger) = self.lsp_trigger_before_cursor(buffer, cx) {
+                if completion_visible {
+                    self.completion_state.update(cx, |s, cx| s.dismiss(cx));
+                }
+                self.request_lsp_completion(Some(trigger), false, cx);
+                return;
+            }
+        }
+
+        if completion_visible {
+            let trigger_line = self.completion_state.read(cx).trigger_line();
+
+            if let Some((word, _word_start)) = word_info {
+                if cursor.line != trigger_line {
+                    self.completion_state.update(cx, |s, cx| s.dismiss(cx));
+                    return;
+                }
+                self.completion_state.update(cx, |s, cx| {
+                    s.set_filter(&word, cx);
                 });
+                if self.completion_state.read(cx).is_incomplete() {
+                    self.refine_lsp_completion(cx);
+                }
+                if let Some(anchor) = anchor {
+                    self.completion_state.update(cx, |s, _| {
+                        s.update_anchor(anchor);
+                    });
+                }
+            } else {
+                self.completion_state.update(cx, |s, cx| s.dismiss(cx));
             }
-        })
-        .detach();
-    }
+        } else if let Some((word, word_start)) = word_info {
+            if word.len() >= 2 {
+                let state = buffer.read(cx);
+                let language = state.language();
+                let use_lsp = self.lsp_enabled() && self.lsp_registry.has_client_for(language);
 
-    fn new_file(&mut self, cx: &mut Context<Self>) {
-        let completion_check = self.completion_state.clone();
-        let buffer = cx.new(|cx| {
-            let mut state = EditorState::new(cx);
-            state.set_overlay_active_check(move |cx| completion_check.read(cx).is_visible());
-            state
-        });
-        cx.observe(&buffer, Self::on_buffer_changed).detach();
-        self.add_buffer(buffer, cx);
-        self.clamp_tab_scroll();
-        self.update_search_editor(cx);
-        cx.notify();
-    }
+                if use_lsp {
+                    self.request_lsp_completion(None, false, cx);
+                } else {
+                    let tree_exists = state.syntax_tree().is_some();
+                    if tree_exists {
+                        if self.last_symbol_update_line != cursor.line {
+                            if let Some(tree) = state.syntax_tree() {
+                                let content = state.content();
+                                let symbols = extract_symbols(tree, &content, language);
+                                self.cached_symbols = symbols
+                                    .into_iter()
+                                    .map(CompletionItem::from)
+                                    .chain(self.snippet_items(language, true))
+                                    .collect();
+                                self.last_symbol_update_line = cursor.line;
+                            }
+                        }
 
-    fn update_search_editor(&self, cx: &mut Context<Self>) {
-        if let Some(buffer) = self.buffers.get(self.active_tab) {
-            let buffer = buffer.clone();
-            self.search_bar.update(cx, |bar, cx| {
-                bar.set_editor(buffer, cx);
-            });
+                        if !self.cached_symbols.is_empty() {
+                            if let Some(anchor) = anchor {
+                                let items = self.cached_symbols.clone();
+                                self.completion_state.update(cx, |s, cx| {
+                                    s.show(items, cursor.line, word_start, anchor, cx);
+                                    s.set_filter(&word, cx);
+                                });
+                            }
+                        }
+                    }
+                }
+            }
         }
     }
 
-    fn apply_prefill_to_search(&self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
-        let find_input = self.search_bar.read(cx).find_input_entity();
-        let editor = self.search_bar.read(cx).editor_entity();
-        find_input.update(cx, |state, cx| {
-            state.set_value(SharedString::from(text.to_string()), window, cx);
-        });
-        if let Some(editor) = editor {
-            editor.update(cx, |state, ecx| {
-                state.find_all(text, ecx);
-            });
-        }
-    }
+    fn trigger_completion(&mut self, cx: &mut Context<Self>) {
+        let buffer = match self.buffers.get(self.active_tab) {
+            Some(b) => b.clone(),
+            None => return,
+        };
 
-    fn close_search_internal(&mut self, cx: &mut Context<Self>) {
-        self.search_visible = false;
-        self.goto_line_visible = false;
-        if let Some(buffer) = self.buffers.get(self.active_tab) {
-            let buffer = buffer.clone();
-            buffer.update(cx, |state, ecx| state.clear_search(ecx));
-        }
-        cx.notify();
-    }
+        let word_chars = self.word_chars_for(&buffer, cx);
+        let state = buffer.read(cx);
+        let language = state.language();
 
-    fn clamp_tab_scroll(&mut self) {
-        let max = self.buffers.len().saturating_sub(1);
-        if self.tab_scroll_offset > max {
-            self.tab_scroll_offset = max;
-        }
-        if self.active_tab >= self.buffers.len() {
+        if self.lsp_enabled() && self.lsp_registry.has_client_for(language) {
+            self.request_lsp_completion(None, false, cx);
             return;
         }
-        if self.active_tab < self.tab_scroll_offset {
-            self.tab_scroll_offset = self.active_tab;
-        }
-    }
 
-    fn close_tab_at(&mut self, idx: usize, cx: &mut Context<Self>) {
-        if self.buffers.is_empty() {
+        let cursor = state.cursor();
+        let content = state.content();
+
+        if self.last_symbol_update_line != cursor.line {
+            if let Some(tree) = state.syntax_tree() {
+                let symbols = extract_symbols(tree, &content, language);
+                self.cached_symbols = symbols
+                    .into_iter()
+                    .map(CompletionItem::from)
+                    .chain(self.snippet_items(language, true))
+                    .collect();
+                self.last_symbol_update_line = cursor.line;
+            }
+        }
+
+        if self.cached_symbols.is_empty() {
+            return;
+        }
+
+        let anchor = match state.cursor_screen_position(state.line_height) {
+            Some(p) => p,
+            None => return,
+        };
+
+        let (filter_prefix, trigger_col) = match word_before_cursor(state, &word_chars) {
+            Some((word, word_start)) => (word, word_start),
+            None => (String::new(), cursor.col),
+        };
+
+        let items: Vec<CompletionItem> = self.cached_symbols.clone();
+
+        self.completion_state.update(cx, |s, cx| {
+            s.show(items, cursor.line, trigger_col, anchor, cx);
+            if !filter_prefix.is_empty() {
+                s.set_filter(&filter_prefix, cx);
+            }
+        });
+    }
+
+    fn snippet_items(&self, language: Language, with_builtins: bool) -> Vec<CompletionItem> {
+        let user: Vec<&UserSnippet> = ["global", language_key_for_display(language)]
+            .iter()
+            .filter_map(|key| self.snippet_library.get(*key))
+            .flatten()
+            .collect();
+        let builtins = if with_builtins {
+            builtin_items(language)
+        } else {
+            Vec::new()
+        };
+        let builtins = builtins
+            .into_iter()
+            .filter(|item| !user.iter().any(|s| s.prefix == item.label));
+        user.iter()
+            .map(|s| CompletionItem {
+                label: s.prefix.clone(),
+                kind: crate::completion::SymbolKind::Snippet,
+                insert_text: s.body.clone(),
+                detail: s.description.clone().or(Some("user snippet".into())),
+                documentation: None,
+                snippet: true,
+            })
+            .chain(builtins)
+            .collect()
+    }
+
+    fn open_snippet_picker(&mut self, cx: &mut Context<Self>) {
+        if self.active_is_read_only() {
+            return;
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab) else {
+            return;
+        };
+        let state = buffer.read(cx);
+        let items: Vec<CompletionItem> = self
+            .snippet_items(state.language(), true)
+            .into_iter()
+            .filter(|item| item.snippet)
+            .collect();
+        if items.is_empty() {
+            return;
+        }
+        let cursor = state.cursor();
+        let Some(anchor) = state.cursor_screen_position(state.line_height) else {
+            return;
+        };
+        self.completion_state.update(cx, |s, cx| {
+            s.show(items, cursor.line, cursor.col, anchor, cx);
+        });
+    }
+
+    fn apply_completion(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let item = match self.completion_state.read(cx).selected_item() {
+            Some(i) => i.clone(),
+            None => return,
+        };
+
+        let trigger_col = self.completion_state.read(cx).trigger_col();
+
+        self.suppress_completion = true;
+
+        if let Some(buffer) = self.buffers.get(self.active_tab).cloned() {
+            if item.snippet {
+                self.expand_snippet(&buffer, trigger_col, &item.insert_text, window, cx);
+            } else {
+                buffer.update(cx, |state, ecx| {
+                    state.apply_completion(trigger_col, &item.insert_text, ecx);
+                });
+            }
+        }
+
+        self.completion_state.update(cx, |s, cx| s.dismiss(cx));
+    }
+
+    fn expand_snippet(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        trigger_col: usize,
+        body: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let state = buffer.read(cx);
+        let content = state.content();
+        let cursor = state.cursor();
+        let line_start: usize = content
+            .split('\n')
+            .take(cursor.line)
+            .map(|l| l.len() + 1)
+            .sum();
+        let line = content[line_start..].split('\n').next().unwrap_or_default();
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let unit = indent_unit(self.indent_config_for(buffer, cx));
+        let snippet = parse_snippet(body, indent, &unit);
+        buffer.update(cx, |state, cx| {
+            state.apply_completion(trigger_col, &snippet.text, cx);
+        });
+
+        self.snippet_session = None;
+        let Some(stops) = TabStops::new(line_start + trigger_col, &snippet) else {
+            return;
+        };
+        let content = buffer.read(cx).content();
+        Self::select_byte_range(buffer, &content, stops.current(), window, cx);
+        let weak = cx.entity().downgrade();
+        let keystrokes = cx.intercept_keystrokes(move |event, window, cx| {
+            let _ = weak.update(cx, |this, cx| this.handle_snippet_key(event, window, cx));
+        });
+        self.snippet_session = Some(SnippetSession {
+            buffer: buffer.entity_id(),
+            stops,
+            content,
+            _keystrokes: keystrokes,
+        });
+    }
+
+    fn handle_snippet_key(
+        &mut self,
+        event: &KeystrokeEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            self.snippet_session = None;
+            return;
+        };
+        let Some(session) = self.snippet_session.as_mut() else {
+            return;
+        };
+        if session.buffer != buffer.entity_id() {
+            self.snippet_session = None;
+            return;
+        }
+        let keystroke = &event.keystroke;
+        let modifiers = &keystroke.modifiers;
+        if keystroke.key == "escape" {
+            self.snippet_session = None;
+            return;
+        }
+        if keystroke.key != "tab"
+            || modifiers.platform
+            || modifiers.control
+            || modifiers.alt
+            || self.completion_state.read(cx).is_visible()
+        {
+            return;
+        }
+        cx.stop_propagation();
+        let Some(range) = session.stops.advance(!modifiers.shift) else {
+            self.snippet_session = None;
+            return;
+        };
+        let last = session.stops.is_last();
+        let content = session.content.clone();
+        Self::select_byte_range(&buffer, &content, range, window, cx);
+        if last {
+            self.snippet_session = None;
+        }
+    }
+
+    fn track_snippet_edit(&mut self, buffer: &Entity<EditorState>, cx: &App) {
+        let Some(session) = self.snippet_session.as_mut() else {
+            return;
+        };
+        if session.buffer != buffer.entity_id() {
+            return;
+        }
+        let content = buffer.read(cx).content();
+        if let Some((old, new)) = changed_range(&session.content, &content) {
+            session.stops.apply_edit(old, new.len());
+            session.content = content;
+        }
+    }
+
+    fn completion_move_up(&mut self, cx: &mut Context<Self>) {
+        self.completion_state.update(cx, |s, cx| s.move_up(cx));
+    }
+
+    fn completion_move_down(&mut self, cx: &mut Context<Self>) {
+        self.completion_state.update(cx, |s, cx| s.move_down(cx));
+    }
+
+    fn completion_dismiss(&mut self, cx: &mut Context<Self>) {
+        self.completion_state.update(cx, |s, cx| s.dismiss(cx));
+    }
+
+    fn lsp_enabled(&self) -> bool {
+        self.settings.lsp_enabled
+    }
+
+    fn lsp_notify_did_open(&mut self, buffer: &Entity<EditorState>, cx: &App) {
+        if !self.lsp_enabled() {
+            return;
+        }
+        let state = buffer.read(cx);
+        let path = match state.file_path() {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        let language = state.language();
+        let content = state.content();
+        self.lsp_doc_versions.insert(path.clone(), 1);
+        self.lsp_registry
+            .notify_did_open(language, &path, &content, &self.settings);
+        self.queued_diagnostic_pulls.insert(path, language);
+    }
+
+    fn lsp_notify_did_change(&mut self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
+        if !self.lsp_enabled() {
+            return;
+        }
+        let state = buffer.read(cx);
+        let path = match state.file_path() {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        let language = state.language();
+        let version = self.lsp_doc_versions.entry(path.clone()).or_insert(0);
+        *version += 1;
+        let ver = *version;
+
+        let buffer = buffer.clone();
+        let entity = cx.entity().clone();
+        let task = cx.spawn(async move |_, cx| {
+            Timer::after(Duration::from_millis(200)).await;
+            let _ = cx.update(|cx| {
+                let content = buffer.read(cx).content();
+                entity.update(cx, |this, cx| {
+                    this.lsp_registry
+                        .notify_did_change(language, &path, &content, ver);
+                    this.pull_lsp_diagnostics(language, path.clone(), cx);
+                });
+            });
+        });
+        self.lsp_change_task = Some(task);
+    }
+
+    fn pull_lsp_diagnostics(&mut self, language: Language, path: PathBuf, cx: &mut Context<Self>) {
+        let Some(client) = self.lsp_registry.client_for(language) else {
+            return;
+        };
+        if !client.supports_pull_diagnostics() {
+            return;
+        }
+        let Ok(rx) = client.document_diagnostics(&path) else {
+            return;
+        };
+        let key = path.clone();
+        let task = cx.spawn(async move |this, cx| {
+            let Ok(response) = rx.recv_async().await else {
+                return;
+            };
+            let Some(diagnostics) = LspClient::parse_document_diagnostics(&response) else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.pulled_diagnostics.insert(path, diagnostics);
+                this.push_diagnostics_to_buffers(cx);
+                cx.notify();
+            });
+        });
+        self.diagnostic_pulls.insert(key, task);
+    }
+
+    fn flush_queued_diagnostic_pulls(&mut self, cx: &mut Context<Self>) {
+        if self.queued_diagnostic_pulls.is_empty() {
+            return;
+        }
+        let pending = self.lsp_registry.pending_languages();
+        let ready: Vec<(PathBuf, Language)> = self
+            .queued_diagnostic_pulls
+            .iter()
+            .filter(|(_, language)| !pending.contains(language))
+            .map(|(path, language)| (path.clone(), *language))
+            .collect();
+        for (path, language) in ready {
+            self.queued_diagnostic_pulls.remove(&path);
+            self.pull_lsp_diagnostics(language, path, cx);
+        }
+    }
+
+    fn lsp_notify_did_save(&self, buffer: &Entity<EditorState>, cx: &App) {
+        if !self.lsp_enabled() {
+            return;
+        }
+        let state = buffer.read(cx);
+        if let Some(path) = state.file_path() {
+            let language = state.language();
+            self.lsp_registry.notify_did_save(language, path);
+        }
+    }
+
+    fn lsp_notify_did_close(&self, buffer: &Entity<EditorState>, cx: &App) {
+        if !self.lsp_enabled() {
+            return;
+        }
+        let state = buffer.read(cx);
+        if let Some(path) = state.file_path() {
+            let language = state.language();
+            self.lsp_registry.notify_did_close(language, path);
+        }
+    }
+
+    fn lsp_trigger_before_cursor(&self, buffer: &Entity<EditorState>, cx: &App) -> Option<String> {
+        if !self.lsp_enabled() {
+            return None;
+        }
+        let state = buffer.read(cx);
+        let client = self.lsp_registry.client_for(state.language())?;
+        let cursor = state.cursor();
+        let content = state.content();
+        let line = content.split('\n').nth(cursor.line)?;
+        let before = line.get(..cursor.col)?;
+        client
+            .trigger_characters()
+            .iter()
+            .find(|t| before.ends_with(t.as_str()))
+            .cloned()
+    }
+
+    fn refine_lsp_completion(&mut self, cx: &mut Context<Self>) {
+        self.lsp_refine_task = Some(cx.spawn(async move |this, cx| {
+            Timer::after(Duration::from_millis(150)).await;
+            let _ = this.update(cx, |this, cx| this.request_lsp_completion(None, true, cx));
+        }));
+    }
+
+    fn request_lsp_completion(
+        &mut self,
+        trigger: Option<String>,
+        merge: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.lsp_enabled() {
+            return;
+        }
+        let buffer = match self.buffers.get(self.active_tab) {
+            Some(b) => b.clone(),
+            None => return,
+        };
+        let state = buffer.read(cx);
+        let path = match state.file_path() {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        let language = state.language();
+        let cursor = state.cursor();
+        let line = cursor.line as u32;
+        let col = cursor.col as u32;
+
+        if !self.lsp_registry.has_client_for(language) {
+            return;
+        }
+
+        let previous = self.completion_request.take();
+        self.cancel_lsp_request(previous);
+        let (id, rx) = match self.lsp_registry.client_for(language) {
+            Some(client) => match client.completion(&path, line, col, trigger.as_deref()) {
+                Ok(r) => r,
+                Err(_) => return,
+            },
+            None => return,
+        };
+        let request = PendingLspRequest { language, id };
+        self.completion_request = Some(request);
+
+        let buffer_id = buffer.entity_id();
+        let task = cx.spawn(async move |this, cx| {
+            let Ok(response) = rx.recv_async().await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                if this.completion_request != Some(request) {
+                    return;
+                }
+                this.completion_request = None;
+                let still_relevant = this.buffers.get(this.active_tab).is_some_and(|b| {
+                    let cursor = b.read(cx).cursor();
+                    b.entity_id() == buffer_id
+                        && cursor.line as u32 == line
+                        && cursor.col as u32 >= col
+                });
+                if !still_relevant {
+                    return;
+                }
+                let list = LspClient::parse_completion_response(&response);
+                if !list.items.is_empty() {
+                    this.show_lsp_completions(list, merge, cx);
+                }
+            });
+        });
+        self.lsp_completion_task = Some(task);
+    }
+
+    fn cancel_lsp_request(&self, request: Option<PendingLspRequest>) {
+        if let Some(request) = request {
+            if let Some(client) = self.lsp_registry.client_for(request.language) {
+                client.cancel_request(request.id);
+            }
+        }
+    }
+
+    fn cancel_hover_request(&mut self) {
+        self.hover_task = None;
+        let previous = self.hover_request.take();
+        self.cancel_lsp_request(previous);
+    }
+
+    fn show_lsp_completions(
+        &mut self,
+        list: crate::lsp::types::LspCompletionList,
+        merge: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if merge && !self.completion_state.read(cx).has_items() {
+            return;
+        }
+        let buffer = match self.buffers.get(self.active_tab) {
+            Some(b) => b.clone(),
+            None => return,
+        };
+        let word_chars = self.word_chars_for(&buffer, cx);
+        let state = buffer.read(cx);
+        let cursor = state.cursor();
+        let anchor = match state.cursor_screen_position(state.line_height) {
+            Some(a) => a,
+            None => return,
+        };
+
+        let (filter_prefix, trigger_col) = match word_before_cursor(state, &word_chars) {
+            Some((word, word_start)) => (word, word_start),
+            None => (String::new(), cursor.col),
+        };
+
+        let user_snippets = self.snippet_items(state.language(), false);
+        let items: Vec<CompletionItem> = list
+            .items
+            .into_iter()
+            .map(|item| {
+                use crate::completion::SymbolKind;
+                let kind = match item.kind {
+                    crate::lsp::types::LspCompletionKind::Function => SymbolKind::Function,
+                    crate::lsp::types::LspCompletionKind::Method => SymbolKind::Method,
+                    crate::lsp::types::LspCompletionKind::Variable => SymbolKind::Variable,
+                    crate::lsp::types::LspCompletionKind::Field => SymbolKind::Field,
+                    crate::lsp::types::LspCompletionKind::Module => SymbolKind::Module,
+                    crate::lsp::types::LspCompletionKind::Struct => SymbolKind::Struct,
+                    crate::lsp::types::LspCompletionKind::Enum => SymbolKind::Enum,
+                    crate::lsp::types::LspCompletionKind::Constant => SymbolKind::Const,
+                    crate::lsp::types::LspCompletionKind::Class => SymbolKind::Class,
+                    crate::lsp::types::LspCompletionKind::Property => SymbolKind::Field,
+                    crate::lsp::types::LspCompletionKind::Interface => SymbolKind::Type,
+                    crate::lsp::types::LspCompletionKind::Keyword => SymbolKind::Keyword,
+                    crate::lsp::types::LspCompletionKind::Snippet => SymbolKind::Snippet,
+                    _ => SymbolKind::Variable,
+                };
+                CompletionItem {
+                    label: item.label,
+                    kind,
+                    insert_text: item.insert_text,
+                    detail: item.detail,
+                    documentation: item.documentation,
+                    snippet: false,
+                }
+            })
+            .chain(user_snippets)
+            .collect();
+
+        self.completion_state.update(cx, |s, cx| {
+            if merge {
+                s.merge(items, cx);
+            } else {
+                s.show(items, cursor.line, trigger_col, anchor, cx);
+                if !filter_prefix.is_empty() {
+                    s.set_filter(&filter_prefix, cx);
+                }
+            }
+            s.set_incomplete(list.is_incomplete);
+        });
+    }
+
+    fn goto_definition(&mut self, cx: &mut Context<Self>) {
+        if !self.lsp_enabled() {
+            return;
+        }
+        let buffer = match self.buffers.get(self.active_tab) {
+            Some(b) => b.clone(),
+            None => return,
+        };
+        let state = buffer.read(cx);
+        let path = match state.file_path() {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        let language = state.language();
+        let cursor = state.cursor();
+        let line = cursor.line as u32;
+        let col = cursor.col as u32;
+
+        let rx = match self.lsp_registry.client_for(language) {
+            Some(client) => match client.goto_definition(&path, line, col) {
+                Ok(rx) => rx,
+                Err(_) => return,
+            },
+            None => return,
+        };
+
+        let entity = cx.entity().clone();
+        cx.spawn(async move |_, cx| {
+            if let Ok(response) = rx.recv_timeout(std::time::Duration::from_secs(5)) {
+                let locations = LspClient::parse_definition_response(&response);
+                if let Some(loc) = locations.first() {
+                    let target_path = loc.path.clone();
+                    let target_line = loc.line as usize;
+                    let target_col = loc.col as usize;
+                    let _ = cx.update(|cx| {
+                        entity.update(cx, |this, cx| {
+                            this.navigate_to_location(target_path, target_line, target_col, cx);
+                        });
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn navigate_to_location(
+        &mut self,
+        path: PathBuf,
+        line: usize,
+        col: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let existing_idx = self
+            .tab_meta
+            .iter()
+            .position(|m| m.file_path.as_ref() == Some(&path));
+
+        if let Some(idx) = existing_idx {
+            self.active_tab = idx;
+        } else if path.exists() {
+            self.open_paths(vec![path], cx);
+        } else {
+            return;
+        }
+
+        if let Some(buffer) = self.buffers.get(self.active_tab) {
+            buffer.update(cx, |state, cx| {
+                state.set_cursor_position(line, col, cx);
+            });
+        }
+        cx.notify();
+    }
+
+    fn go_to_problem(&mut self, forward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let current = self.active_file_path();
+        let cursor = self.buffers.get(self.active_tab).map(|buffer| {
+            let state = buffer.read(cx);
+            let cursor = state.cursor();
+            let content = state.content();
+            let line = content.split('\n').nth(cursor.line).unwrap_or("");
+            let col = line[..cursor.col.min(line.len())].encode_utf16().count();
+            (cursor.line as u32, col as u32)
+        });
+        let position = |d: &LspDiagnostic| (d.range_start_line, d.range_start_col);
+
+        let mut in_file: Vec<&LspDiagnostic> = current
+            .as_deref()
+            .map(|p| self.diagnostics_for_path(p))
+            .unwrap_or_default();
+        in_file.sort_by_key(|d| position(d));
+        let target = match (&current, cursor) {
+            (Some(path), Some(cursor)) if !in_file.is_empty() => {
+                let found = if forward {
+                    in_file
+                        .iter()
+                        .find(|d| position(d) > cursor)
+                        .or(in_file.first())
+                } else {
+                    in_file
+                        .iter()
+                        .rev()
+                        .find(|d| position(d) < cursor)
+                        .or(in_file.last())
+                };
+                found.map(|d| (path.clone(), (*d).clone()))
+            }
+            _ => {
+                let mut files: Vec<&PathBuf> = self
+                    .buffer_diagnostics
+                    .iter()
+                    .chain(&self.pulled_diagnostics)
+                    .filter(|(_, diags)| !diags.is_empty())
+                    .map(|(path, _)| path)
+                    .collect();
+                files.sort();
+                files.dedup();
+                let next = match (&current, forward) {
+                    (Some(cur), true) => files.iter().find(|p| **p > cur).or(files.first()),
+                    (Some(cur), false) => files.iter().rev().find(|p| **p < cur).or(files.last()),
+                    (None, true) => files.first(),
+                    (None, false) => files.last(),
+                };
+                next.and_then(|path| {
+                    let diags = self.diagnostics_for_path(path);
+                    let found = if forward {
+                        diags.iter().min_by_key(|d| position(d))
+                    } else {
+                        diags.iter().max_by_key(|d| position(d))
+                    };
+                    found.map(|d| ((*path).clone(), (*d).clone()))
+                })
+            }
+        };
+        let Some((path, diag)) = target else {
+            self.notify("No problems", ToastSeverity::Info, cx);
+            return;
+        };
+
+        let line = diag.range_start_line as usize;
+        self.navigate_to_location(path, line, 0, cx);
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let content = buffer.read(cx).content();
+        let text = content.split('\n').nth(line).unwrap_or("");
+        let mut utf16 = 0;
+        let col = text
+            .char_indices()
+            .find(|(_, c)| {
+                utf16 += c.len_utf16();
+                utf16 > diag.range_start_col as usize
+            })
+            .map_or(text.len(), |(i, _)| i);
+        buffer.update(cx, |state, cx| state.set_cursor_position(line, col, cx));
+
+        let label = match diag.severity {
+            crate::lsp::types::DiagnosticSeverity::Error => "error",
+            crate::lsp::types::DiagnosticSeverity::Warning => "warning",
+            crate::lsp::types::DiagnosticSeverity::Information => "info",
+            crate::lsp::types::DiagnosticSeverity::Hint => "hint",
+        };
+        let contents = format!("**{}**: {}", label, diag.message);
+        let app = cx.entity().downgrade();
+        window.on_next_frame(move |_, cx| {
+            let _ = app.update(cx, |this, cx| {
+                let state = buffer.read(cx);
+                let Some(anchor) = state.cursor_screen_position(state.line_height) else {
+                    return;
+                };
+                this.hover_task = None;
+                this.hover_info = Some(HoverPopup {
+                    contents,
+                    anchor,
+                    pinned: false,
+                    from_pointer: false,
+                });
+                this.hover_scroll_handle = ScrollHandle::new();
+                cx.notify();
+            });
+        });
+    }
+
+    fn request_hover(&mut self, cx: &mut Context<Self>) {
+        if self.settings.hover_enabled {
+            self.request_cursor_hover(self.hover_delay(), cx);
+        }
+    }
+
+    fn hover_delay(&self) -> Duration {
+        Duration::from_millis(self.settings.hover_delay_ms)
+    }
+
+    fn request_cursor_hover(&mut self, delay: Duration, cx: &mut Context<Self>) {
+        if !self.lsp_enabled() || self.hover_info.as_ref().is_some_and(|h| h.pinned) {
+            return;
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let state = buffer.read(cx);
+        let cursor = state.cursor();
+        let Some(anchor) = state.cursor_screen_position(state.line_height) else {
+            return;
+        };
+        self.spawn_hover(&buffer, cursor.line, cursor.col, anchor, false, delay, cx);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn spawn_hover(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        line: usize,
+        col: usize,
+        anchor: Point<Pixels>,
+        from_pointer: bool,
+        delay: Duration,
+        cx: &mut Context<Self>,
+    ) {
+        let state = buffer.read(cx);
+        let Some(path) = state.file_path().cloned() else {
+            return;
+        };
+        let language = state.language();
+        if !self.lsp_registry.has_client_for(language) {
+            return;
+        }
+
+        let buffer_id = buffer.entity_id();
+        let version = state.content_version();
+        let task = cx.spawn(async move |this, cx| {
+            Timer::after(delay).await;
+            let pending = cx
+                .update(|cx| {
+                    this.update(cx, |this, _| {
+                        let previous = this.hover_request.take();
+                        this.cancel_lsp_request(previous);
+                        let (id, rx) = this
+                            .lsp_registry
+                            .client_for(language)?
+                            .hover(&path, line as u32, col as u32)
+                            .ok()?;
+                        let request = PendingLspRequest { language, id };
+                        this.hover_request = Some(request);
+                        Some((request, rx))
+                    })
+                    .ok()
+                    .flatten()
+                })
+                .ok()
+                .flatten();
+            let Some((request, rx)) = pending else {
+                return;
+            };
+            let Ok(response) = rx.recv_async().await else {
+                return;
+            };
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |this, cx| {
+                    if this.hover_request != Some(request) {
+                        return;
+                    }
+                    this.hover_request = None;
+                    if this.hover_info.as_ref().is_some_and(|h| h.pinned) {
+                        return;
+                    }
+                    let still_relevant = if from_pointer {
+                        this.mouse_hover_word
+                            .as_ref()
+                            .is_some_and(|(id, _)| *id == buffer_id)
+                    } else {
+                        this.buffers.get(this.active_tab).is_some_and(|b| {
+                            let state = b.read(cx);
+                            let cursor = state.cursor();
+                            b.entity_id() == buffer_id
+                                && state.content_version() == version
+                                && (cursor.line, cursor.col) == (line, col)
+                        })
+                    };
+                    if !still_relevant {
+                        return;
+                    }
+                    let Some(info) = LspClient::parse_hover_response(&response) else {
+                        return;
+                    };
+                    this.hover_info = Some(HoverPopup {
+                        contents: info.contents,
+                        anchor,
+                        pinned: false,
+                        from_pointer,
+                    });
+                    this.hover_scroll_handle = ScrollHandle::new();
+                    cx.notify();
+                });
+            });
+        });
+        self.hover_task = Some(task);
+    }
+
+    fn on_editor_mouse_move(
+        &mut self,
+        event: &MouseMoveEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.lsp_enabled()
+            || !self.settings.hover_enabled
+            || event.pressed_button.is_some()
+            || self.hover_info.as_ref().is_some_and(|h| h.pinned)
+        {
+            return;
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let utf16_offset = buffer.update(cx, |s, cx| {
+            s.character_index_for_point(event.position, window, cx)
+        });
+
+        let (id, version) = (buffer.entity_id(), buffer.read(cx).content_version());
+        if !self
+            .mouse_hover_text
+            .as_ref()
+            .is_some_and(|(i, v, _)| *i == id && *v == version)
+        {
+            self.mouse_hover_text = Some((id, version, buffer.read(cx).content()));
+        }
+        let Some((_, _, text)) = self.mouse_hover_text.as_ref() else {
+            return;
+        };
+        let word = utf16_offset.and_then(|offset| {
+            let mut utf16 = 0;
+            let byte = text
+                .char_indices()
+                .find(|(_, c)| {
+                    utf16 += c.len_utf16();
+                    utf16 > offset
+                })
+                .map(|(i, _)| i)
+                .unwrap_or(text.len());
+            text[byte..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+                .then(|| word_range_at(text, byte))
+                .flatten()
+        });
+        let target = word.clone().map(|w| (id, w));
+        if target == self.mouse_hover_word {
+            return;
+        }
+        self.mouse_hover_word = target;
+        self.hover_task = None;
+        let previous = self.hover_request.take();
+        self.cancel_lsp_request(previous);
+        if self.hover_info.as_ref().is_some_and(|h| h.from_pointer) {
+            self.hover_info = None;
+            cx.notify();
+        }
+        let Some(word) = word else {
+            return;
+        };
+        let line = text[..word.start].matches('\n').count();
+        let col = word.start - text[..word.start].rfind('\n').map_or(0, |i| i + 1);
+        let anchor = event.position + point(px(0.0), px(12.0));
+        let delay = self.hover_delay();
+        self.spawn_hover(&buffer, line, col, anchor, true, delay, cx);
+    }
+
+    fn on_editor_hover_changed(&mut self, hovered: bool, cx: &mut Context<Self>) {
+        if hovered || self.mouse_hover_word.is_none() {
+            return;
+        }
+        self.mouse_hover_word = None;
+        self.cancel_hover_request();
+        if self
+            .hover_info
+            .as_ref()
+            .is_some_and(|h| h.from_pointer && !h.pinned)
+        {
+            self.hover_info = None;
+            cx.notify();
+        }
+    }
+
+    fn dismiss_hover(&mut self, cx: &mut Context<Self>) {
+        if self.hover_info.as_ref().is_some_and(|h| !h.pinned) {
+            self.hover_info = None;
+            cx.notify();
+        }
+    }
+
+    fn close_hover(&mut self, cx: &mut Context<Self>) {
+        if self.hover_info.take().is_some() {
+            self.cancel_hover_request();
+            cx.notify();
+        }
+    }
+
+    fn toggle_hover_pin(&mut self, cx: &mut Context<Self>) {
+        if let Some(hover) = self.hover_info.as_mut() {
+            hover.pinned = !hover.pinned;
+            cx.notify();
+        }
+    }
+
+    fn start_lsp_poll(&mut self, cx: &mut Context<Self>) {
+        if self.lsp_poll_task.is_some() {
+            return;
+        }
+        let entity = cx.entity().clone();
+        let task = cx.spawn(async move |_, cx| loop {
+            Timer::after(Duration::from_millis(200)).await;
+            let ok = cx.update(|cx| {
+                entity.update(cx, |this, cx| {
+                    this.poll_lsp_diagnostics(cx);
+                });
+            });
+            if ok.is_err() {
+                break;
+            }
+        });
+        self.lsp_poll_task = Some(task);
+    }
+
+    fn poll_lsp_diagnostics(&mut self, cx: &mut Context<Self>) {
+        if !self.lsp_enabled() {
+            return;
+        }
+        for (language, err) in self.lsp_registry.poll_ready() {
+            self.notify(
+                format!(
+                    "{} language server failed to start: {}",
+                    crate::lsp::registry::language_key(language),
+                    err
+                ),
+                ToastSeverity::Error,
+                cx,
+            );
+        }
+        for language in self.lsp_registry.reap_exited() {
+            self.notify(
+                format!(
+                    "{} language server exited unexpectedly",
+                    language_key_for_display(language)
+                ),
+                ToastSeverity::Error,
+                cx,
+            );
+        }
+        if self.lsp_registry.poll_progress() {
+            cx.notify();
+        }
+        self.flush_queued_diagnostic_pulls(cx);
+        let file_diags = self.lsp_registry.drain_diagnostics();
+        if file_diags.is_empty() {
+            return;
+        }
+        for fd in file_diags {
+            self.buffer_diagnostics
+                .insert(fd.path.clone(), fd.diagnostics);
+        }
+        self.push_diagnostics_to_buffers(cx);
+        cx.notify();
+    }
+
+    fn push_diagnostics_to_buffers(&self, cx: &mut Context<Self>) {
+        let ide = use_ide_theme();
+        for buffer in &self.buffers {
+            let path = buffer.read(cx).file_path().cloned();
+            if let Some(path) = path {
+                let lsp_diags = self.diagnostics_for_path(&path);
+                let editor_diags: Vec<EditorDiagnostic> = lsp_diags
+                    .iter()
+                    .map(|d| EditorDiagnostic {
+                        start_line: d.range_start_line,
+                        start_col: d.range_start_col,
+                        end_line: d.range_end_line,
+                        end_col: d.range_end_col,
+                        severity: match d.severity {
+                            crate::lsp::types::DiagnosticSeverity::Error => {
+                                EditorDiagSeverity::Error
+                            }
+                            crate::lsp::types::DiagnosticSeverity::Warning => {
+                                EditorDiagSeverity::Warning
+                            }
+                            crate::lsp::types::DiagnosticSeverity::Information => {
+                                EditorDiagSeverity::Information
+                            }
+                            crate::lsp::types::DiagnosticSeverity::Hint => EditorDiagSeverity::Hint,
+                        },
+                        message: d.message.clone(),
+                    })
+                    .collect();
+                buffer.update(cx, |state, ecx| {
+                    state.diagnostic_error_color = Some(ide.editor.diagnostic_error);
+                    state.diagnostic_warning_color = Some(ide.editor.diagnostic_warning);
+                    state.diagnostic_info_color = Some(ide.editor.diagnostic_info);
+                    state.diagnostic_hint_color = Some(ide.editor.diagnostic_hint);
+                    state.set_diagnostics(editor_diags, ecx);
+                });
+            }
+        }
+    }
+
+    fn diagnostics_for_path(&self, path: &Path) -> Vec<&LspDiagnostic> {
+        let pushed = self.buffer_diagnostics.get(path).into_iter().flatten();
+        let mut merged: Vec<&LspDiagnostic> = pushed.collect();
+        for diag in self.pulled_diagnostics.get(path).into_iter().flatten() {
+            if !merged.contains(&diag) {
+                merged.push(diag);
+            }
+        }
+        merged
+    }
+
+    fn remove_bom(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.remove_bom_at(self.active_tab, window, cx);
+    }
+
+    fn remove_bom_at(&mut self, idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(idx).cloned() else {
+            return;
+        };
+        if !buffer.read(cx).content().starts_with(BOM) {
+            return;
+        }
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(0..BOM.len_utf16()), "", window, cx);
+        });
+        if let Some(meta) = self.tab_meta.get_mut(idx) {
+            meta.has_bom = false;
+        }
+        cx.notify();
+    }
+
+    fn enforce_final_newline(&mut self, idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(idx).cloned() else {
+            return;
+        };
+        let Some(meta) = self.tab_meta.get(idx) else {
+            return;
+        };
+        if self.search_result_buffers.contains_key(&buffer.entity_id()) {
+            return;
+        }
+        let want = self.settings.insert_final_newline || meta.final_newline;
+        let (content, selection) = Self::selection_byte_range(&buffer, window, cx);
+        let Some((range, eol)) = final_newline_edit(&content, want) else {
+            return;
+        };
+        let start_utf16 = content[..range.start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[range.clone()].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), eol, window, cx);
+        });
+        let mut updated = content;
+        updated.replace_range(range.clone(), eol);
+        let clamp = |offset: usize| offset.min(range.start);
+        Self::select_byte_range(
+            &buffer,
+            &updated,
+            clamp(selection.start)..clamp(selection.end),
+            window,
+            cx,
+        );
+    }
+
+    fn active_is_read_only(&self) -> bool {
+        self.tab_meta
+            .get(self.active_tab)
+            .is_some_and(|meta| meta.read_only_content.is_some())
+    }
+
+    fn toggle_read_only(&mut self, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let Some(meta) = self.tab_meta.get_mut(self.active_tab) else {
+            return;
+        };
+        if meta.is_image {
+            return;
+        }
+        if meta.read_only_content.is_none() && buffer.read(cx).is_modified() {
+            self.notify(
+                "Save or discard changes before making this tab read-only",
+                ToastSeverity::Info,
+                cx,
+            );
+            return;
+        }
+        meta.read_only_content = match meta.read_only_content {
+            Some(_) => None,
+            None => Some(buffer.read(cx).content()),
+        };
+        self.multi_edit = None;
+        self.read_only_flash = None;
+        cx.notify();
+    }
+
+    fn flash_read_only(&mut self, cx: &mut Context<Self>) {
+        self.read_only_flash = Some(cx.spawn(async move |this, cx| {
+            Timer::after(Duration::from_millis(1200)).await;
+            let _ = this.update(cx, |this, cx| {
+                this.read_only_flash = None;
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
+
+    fn block_read_only_key(
+        &mut self,
+        event: &KeystrokeEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.active_is_read_only() || !event.context_stack.iter().any(|c| c.contains("Editor"))
+        {
+            return;
+        }
+        let focused = self
+            .buffers
+            .get(self.active_tab)
+            .is_some_and(|b| b.read(cx).focus_handle(cx).is_focused(window));
+        if !focused {
+            return;
+        }
+        let keystroke = &event.keystroke;
+        let modifiers = &keystroke.modifiers;
+        let key = keystroke.key.as_str();
+        let mutates = if modifiers.platform || modifiers.control {
+            matches!(key, "x" | "v" | "z" | "backspace" | "delete")
+        } else {
+            matches!(key, "backspace" | "delete" | "enter" | "tab")
+                || keystroke
+                    .key_char
+                    .as_deref()
+                    .is_some_and(|text| !text.chars().any(char::is_control))
+        };
+        if mutates {
+            self.flash_read_only(cx);
+            cx.stop_propagation();
+        }
+    }
+
+    fn formatter_for(&self, buffer: &Entity<EditorState>, cx: &App) -> Option<FormatterConfig> {
+        let state = buffer.read(cx);
+        let key = language_key_for_display(state.language());
+        self.settings.formatters.get(key).cloned().or_else(|| {
+            let ext = state.file_path()?.extension()?.to_str()?.to_lowercase();
+            self.settings.formatters.get(&ext).cloned()
+        })
+    }
+
+    fn format_active(&mut self, save_after: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        if self.active_is_read_only() {
+            return;
+        }
+        let state = buffer.read(cx);
+        let path = state.file_path().cloned();
+        let language = state.language();
+        let content = state.content();
+        let version = state.content_version();
+        let indent = self.indent_config_for(&buffer, cx);
+
+        let lsp_rx = path
+            .as_ref()
+            .filter(|_| self.lsp_enabled())
+            .and_then(|path| {
+                self.lsp_registry
+                    .client_for(language)
+                    .filter(|client| client.supports_formatting())?
+                    .formatting(path, indent.tab_size, !indent.hard_tabs)
+                    .ok()
+            });
+        let task = if let Some(rx) = lsp_rx {
+            cx.spawn_in(window, async move |this, cx| {
+                let response = async {
+                    rx.recv_async()
+                        .await
+                        .map_err(|_| "language server did not respond".to_string())
+                };
+                let result = match smol::future::or(response, format_timeout()).await {
+                    Ok(response) => LspClient::parse_text_edits(&response)
+                        .map(|edits| apply_text_edits(&content, &edits))
+                        .ok_or_else(|| {
+                            response
+                                .pointer("/error/message")
+                                .and_then(|m| m.as_str())
+                                .unwrap_or("invalid formatting response")
+                                .to_string()
+                        }),
+                    Err(message) => Err(message),
+                };
+                let _ = this.update_in(cx, |this, window, cx| {
+                    this.finish_format(&buffer, &content, version, result, save_after, window, cx);
+                });
+            })
+        } else if let Some(config) = self.formatter_for(&buffer, cx) {
+            let input = content.clone();
+            let job = cx.background_executor().spawn(async move {
+                run_formatter(&config, &input, path.as_deref()).map_err(|e| e.to_string())
+            });
+            cx.spawn_in(window, async move |this, cx| {
+                let result = smol::future::or(job, format_timeout()).await;
+                let _ = this.update_in(cx, |this, window, cx| {
+                    this.finish_format(&buffer, &content, version, result, save_after, window, cx);
+                });
+            })
+        } else {
+            if save_after {
+                self.write_active(window, cx);
+            } else {
+                self.notify(
+                    format!(
+                        "No formatter configured for {}",
+                        language_key_for_display(language)
+                    ),
+                    ToastSeverity::Info,
+                    cx,
+                );
+            }
+            return;
+        };
+        self.format_task = Some(task);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn finish_format(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        original: &str,
+        version: u64,
+        result: Result<String, String>,
+        save_after: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.format_task = None;
+        match result {
+            Err(message) => {
+                self.notify(
+                    format!("Format failed: {}", message),
+                    ToastSeverity::Error,
+                    cx,
+                );
+            }
+            Ok(_) if buffer.read(cx).content_version() != version => {
+                self.notify(
+                    "Buffer changed while formatting; result discarded",
+                    ToastSeverity::Info,
+                    cx,
+                );
+            }
+            Ok(formatted) => {
+                if let Some((old, new)) = changed_range(original, &formatted) {
+                    let cursor = buffer.read(cx).cursor();
+                    let start = original[..old.start].encode_utf16().count();
+                    let end = start + original[old].encode_utf16().count();
+                    buffer.update(cx, |state, cx| {
+                        state.replace_text_in_range(Some(start..end), &formatted[new], window, cx);
+                        let content = state.content();
+                        let lines: Vec<&str> = content.split('\n').collect();
+                        let line = cursor.line.min(lines.len().saturating_sub(1));
+                        let text = lines.get(line).copied().unwrap_or_default();
+                        let mut col = cursor.col.min(text.len());
+                        while !text.is_char_boundary(col) {
+                            col -= 1;
+                        }
+                        state.set_cursor_position(line, col, cx);
+                    });
+                }
+            }
+        }
+        let still_active = self
+            .buffers
+            .get(self.active_tab)
+            .is_some_and(|b| b.entity_id() == buffer.entity_id());
+        if save_after && still_active {
+            self.write_active(window, cx);
+        }
+        if std::mem::take(&mut self.quit_after_save) {
+            self.quit_now(true, window, cx);
+        }
+    }
+
+    fn save_active(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.settings.format_on_save && self.format_task.is_none() && !self.active_is_read_only()
+        {
+            self.format_active(true, window, cx);
+            return;
+        }
+        self.write_active(window, cx);
+    }
+
+    fn write_active(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.write_tab(self.active_tab, window, cx);
+    }
+
+    fn autosave_buffer(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(&idx) = self.buffer_index.get(&buffer.entity_id()) else {
+            return;
+        };
+        let state = buffer.read(cx);
+        if state.is_modified() && state.file_path().is_some() {
+            self.write_tab(idx, window, cx);
+        }
+    }
+
+    fn write_tab(&mut self, idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(meta) = self.tab_meta.get(idx) else {
+            return;
+        };
+        if meta.read_only_content.is_some() {
+            return;
+        }
+        if meta.has_bom && self.settings.strip_bom_on_save {
+            self.remove_bom_at(idx, window, cx);
+        }
+        self.enforce_final_newline(idx, window, cx);
+        if let Some(buffer) = self.buffers.get(idx).cloned() {
+            if self.search_result_buffers.contains_key(&buffer.entity_id()) {
+                self.apply_search_result_edits(&buffer, cx);
+                return;
+            }
+        }
+        if let Some(buffer) = self.buffers.get(idx) {
+            let has_path = buffer.read(cx).file_path().is_some();
+            if has_path {
+                let buffer = buffer.clone();
+                let failed = buffer.update(cx, |state, cx| {
+                    let path = state.file_path().cloned()?;
+                    (!state.save_to_file(&path, cx)).then_some(path)
+                });
+                if let Some(path) = failed {
+                    self.notify(
+                        format!("Failed to save {}", path.display()),
+                        ToastSeverity::Error,
+                        cx,
+                    );
+                    return;
+                }
+                self.lsp_notify_did_save(&buffer, cx);
+                self.refresh_line_changes(&buffer, cx);
+                if let Some(stack) = self.undo_stacks.get_mut(&buffer.entity_id()) {
+                    stack.seal();
+                }
+                let path = buffer.read(cx).file_path().cloned();
+                if path.is_some_and(|p| p.file_name().is_some_and(|n| n == ".editorconfig")) {
+                    self.reload_editorconfig(cx);
+                }
+            } else {
+                let buffer = buffer.clone();
+                let rx = cx.prompt_for_new_path(Path::new(""), Some("untitled.txt"));
+                cx.spawn(async move |this, cx| {
+                    if let Ok(Ok(Some(path))) = rx.await {
+                        let _ = cx.update(|cx| {
+                            let saved =
+                                buffer.update(cx, |state, cx| state.save_to_file(&path, cx));
+                            let _ = this.update(cx, |this, cx| {
+                                if !saved {
+                                    this.notify(
+                                        format!("Failed to save {}", path.display()),
+                                        ToastSeverity::Error,
+                                        cx,
+                                    );
+                                }
+                                cx.notify()
+                            });
+                        });
+                    }
+                })
+                .detach();
+            }
+        }
+    }
+
+    fn close_active_tab(&mut self, cx: &mut Context<Self>) {
+        self.close_tab_at(self.active_tab, cx);
+    }
+
+    fn open_file_dialog(&mut self, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: true,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            if let Ok(Ok(Some(paths))) = rx.await {
+                let _ = cx.update(|cx| {
+                    let _ = this.update(cx, |this, cx| {
+                        this.open_paths(paths, cx);
+                    });
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn new_file(&mut self, cx: &mut Context<Self>) {
+        let completion_check = self.completion_state.clone();
+        let buffer = cx.new(|cx| {
+            let mut state = EditorState::new(cx);
+            state.set_overlay_active_check(move |cx| completion_check.read(cx).is_visible());
+            state
+        });
+        cx.observe(&buffer, Self::on_buffer_changed).detach();
+        self.add_buffer(buffer, cx);
+        self.clamp_tab_scroll();
+        self.update_search_editor(cx);
+        cx.notify();
+    }
+
+    fn update_search_editor(&self, cx: &mut Context<Self>) {
+        if let Some(buffer) = self.buffers.get(self.active_tab) {
+            let buffer = buffer.clone();
+            self.search_bar.update(cx, |bar, cx| {
+                bar.set_editor(buffer, cx);
+            });
+        }
+    }
+
+    fn apply_prefill_to_search(&self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let find_input = self.search_bar.read(cx).find_input_entity();
+        let editor = self.search_bar.read(cx).editor_entity();
+        find_input.update(cx, |state, cx| {
+            state.set_value(SharedString::from(text.to_string()), window, cx);
+        });
+        if let Some(editor) = editor {
+            editor.update(cx, |state, ecx| {
+                state.find_all(text, ecx);
+            });
+        }
+    }
+
+    fn close_search_internal(&mut self, cx: &mut Context<Self>) {
+        self.search_visible = false;
+        if self.goto_line_visible {
+            self.revert_goto_preview(cx);
+        }
+        self.goto_line_visible = false;
+        if let Some(buffer) = self.buffers.get(self.active_tab) {
+            let buffer = buffer.clone();
+            buffer.update(cx, |state, ecx| state.clear_search(ecx));
+        }
+        cx.notify();
+    }
+
+    fn focus_active_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(buffer) = self.buffers.get(self.active_tab) {
+            window.focus(&buffer.read(cx).focus_handle(cx));
+        }
+        cx.notify();
+    }
+
+    fn select_all_matches(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        if self.active_is_read_only() {
+            self.flash_read_only(cx);
+            return;
+        }
+        let state = buffer.read(cx);
+        let content = state.content();
+        let matches = match find_matches(
+            &content,
+            state.search_query(),
+            state.search_use_regex(),
+            state.search_case_sensitive(),
+        ) {
+            Ok(matches) => matches,
+            Err(e) => {
+                self.notify(format!("Invalid regex: {}", e), ToastSeverity::Error, cx);
+                return;
+            }
+        };
+        if matches.is_empty() {
+            self.notify("No matches to select", ToastSeverity::Info, cx);
+            return;
+        }
+        if matches.len() > MAX_CURSORS {
+            self.notify(
+                format!(
+                    "{} matches exceeds the limit of {} cursors",
+                    matches.len(),
+                    MAX_CURSORS
+                ),
+                ToastSeverity::Error,
+                cx,
+            );
+            return;
+        }
+
+        let (_, selection) = Self::selection_byte_range(&buffer, window, cx);
+        let primary = matches
+            .iter()
+            .position(|m| m.start >= selection.start)
+            .unwrap_or(0);
+        let count = matches.len();
+        self.close_search_internal(cx);
+        self.start_multi_edit(&buffer, matches, primary, window, cx);
+        self.notify(
+            format!("{} cursors \u{2014} type to edit all, Esc to finish", count),
+            ToastSeverity::Info,
+            cx,
+        );
+    }
+
+    fn start_multi_edit(
+        &mut self,
+        buffer: &Entity<EditorState>,
+        regions: Vec<Range<usize>>,
+        primary: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let content = buffer.read(cx).content();
+        let edit = MultiEdit::new(regions, primary);
+        Self::select_byte_range(buffer, &content, edit.primary_selection(), window, cx);
+        let weak = cx.entity().downgrade();
+        let keystrokes = cx.intercept_keystrokes(move |event, window, cx| {
+            let _ = weak.update(cx, |this, cx| this.handle_multi_edit_key(event, window, cx));
+        });
+        self.multi_edit = Some(MultiEditSession {
+            buffer: buffer.entity_id(),
+            version: buffer.read(cx).content_version(),
+            edit,
+            _keystrokes: keystrokes,
+        });
+        let focus = buffer.read(cx).focus_handle(cx);
+        window.focus(&focus);
+    }
+
+    fn surround_selection_key(
+        &mut self,
+        event: &KeystrokeEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.active_is_read_only()
+            || self.multi_edit.is_some()
+            || self.snippet_session.is_some()
+            || !event.context_stack.iter().any(|c| c.contains("Editor"))
+        {
+            return;
+        }
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.platform || modifiers.control {
+            return;
+        }
+        let Some(typed) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        if !buffer.read(cx).focus_handle(cx).is_focused(window) {
+            return;
+        }
+        let language = language_key_for_display(buffer.read(cx).language());
+        let Some((open, close)) = self
+            .settings
+            .auto_pairs_for(language)
+            .iter()
+            .find(|(open, _)| open == typed)
+            .cloned()
+        else {
+            return;
+        };
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        if range.is_empty() {
+            return;
+        }
+        let text = format!("{}{}{}", open, &content[range.clone()], close);
+        let start_utf16 = content[..range.start].encode_utf16().count();
+        let end_utf16 = start_utf16 + content[range.clone()].encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &text, window, cx);
+        });
+        let content = buffer.read(cx).content();
+        let inner = range.start + open.len()..range.end + open.len();
+        Self::select_byte_range(&buffer, &content, inner, window, cx);
+        cx.stop_propagation();
+    }
+
+    fn auto_pair_key(
+        &mut self,
+        event: &KeystrokeEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.active_is_read_only()
+            || self.multi_edit.is_some()
+            || self.snippet_session.is_some()
+            || !event.context_stack.iter().any(|c| c.contains("Editor"))
+        {
+            return;
+        }
+        let keystroke = &event.keystroke;
+        let modifiers = &keystroke.modifiers;
+        let backspace = keystroke.key == "backspace";
+        if modifiers.platform || modifiers.control || modifiers.alt && backspace {
+            return;
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        if !buffer.read(cx).focus_handle(cx).is_focused(window) {
+            return;
+        }
+        let (mut content, mut range) = Self::selection_byte_range(&buffer, window, cx);
+        if !range.is_empty() {
+            return;
+        }
+        let closer = matches!(keystroke.key_char.as_deref(), Some("}" | "]" | ")"));
+        if closer && self.outdent_before_closer(&buffer, &content, range.start, window, cx) {
+            (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        }
+        let state = buffer.read(cx);
+        let cursor = state.cursor();
+        let pairs = self
+            .settings
+            .auto_pairs_for(language_key_for_display(state.language()));
+        let line = content.split('\n').nth(cursor.line).unwrap_or("");
+        if backspace {
+            let Some(delete) = backspace_range(line, cursor.col, pairs, &SURROUND_PAIRS) else {
+                return;
+            };
+            let start = range.start - (cursor.col - delete.start);
+            let end = range.start + (delete.end - cursor.col);
+            let start_utf16 = content[..start].encode_utf16().count();
+            let end_utf16 = start_utf16 + content[start..end].encode_utf16().count();
+            buffer.update(cx, |state, cx| {
+                state.replace_text_in_range(Some(start_utf16..end_utf16), "", window, cx);
+            });
+            cx.stop_propagation();
+            return;
+        }
+        let Some(typed) = keystroke.key_char.as_deref() else {
+            return;
+        };
+        let Some(edit) = typed_edit(line, cursor.col, typed, pairs, &SURROUND_PAIRS) else {
+            return;
+        };
+        buffer.update(cx, |state, cx| match edit {
+            PairEdit::Insert { text, caret } => {
+                state.apply_completion(cursor.col, &text, cx);
+                state.set_cursor_position(cursor.line, cursor.col + caret, cx);
+            }
+            PairEdit::Skip(len) => {
+                state.set_cursor_position(cursor.line, cursor.col + len, cx);
+            }
+        });
+        cx.stop_propagation();
+    }
+
+    fn cycle_line_numbers(&mut self, cx: &mut Context<Self>) {
+        self.settings.line_numbers = self.settings.line_numbers.cycle(1);
+        self.settings.save();
+        let label = self.settings.line_numbers.label();
+        self.notify(format!("Line numbers: {}", label), ToastSeverity::Info, cx);
+    }
+
+    fn wrap_with_tag(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        if self.active_is_read_only() {
+            self.flash_read_only(cx);
+            return;
+        }
+        let (content, range) = Self::selection_byte_range(&buffer, window, cx);
+        let tag = "div";
+        let inner = &content[range.clone()];
+        let text = format!("<{}>{}</{}>", tag, inner, tag);
+        let start_utf16 = content[..range.start].encode_utf16().count();
+        let end_utf16 = start_utf16 + inner.encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(start_utf16..end_utf16), &text, window, cx);
+        });
+        let open = range.start + 1;
+        let close = range.end + tag.len() + 4;
+        self.start_multi_edit(
+            &buffer,
+            vec![open..open + tag.len(), close..close + tag.len()],
+            0,
+            window,
+            cx,
+        );
+    }
+
+    fn active_multi_edit(&self, cx: &App) -> Option<&MultiEditSession> {
+        let session = self.multi_edit.as_ref()?;
+        let buffer = self.buffers.get(self.active_tab)?;
+        (buffer.entity_id() == session.buffer
+            && buffer.read(cx).content_version() == session.version)
+            .then_some(session)
+    }
+
+    fn handle_multi_edit_key(
+        &mut self,
+        event: &KeystrokeEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let in_editor = event.context_stack.iter().any(|c| c.contains("Editor"));
+        if self.active_multi_edit(cx).is_none() || !in_editor {
+            self.multi_edit = None;
+            cx.notify();
+            return;
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let Some(session) = self.multi_edit.as_mut() else {
+            return;
+        };
+        let keystroke = &event.keystroke;
+        let modifiers = &keystroke.modifiers;
+        let content = buffer.read(cx).content();
+        let plain = !modifiers.platform && !modifiers.control && !modifiers.function;
+        let typed = keystroke
+            .key_char
+            .as_deref()
+            .filter(|text| plain && !text.chars().any(char::is_control));
+        let change = match keystroke.key.as_str() {
+            "backspace" if plain => session.edit.delete(&content, false),
+            "delete" if plain => session.edit.delete(&content, true),
+            "left" | "right" if plain && !modifiers.shift && !modifiers.alt => {
+                session.edit.move_caret(&content, keystroke.key == "right");
+                None
+            }
+            key => match typed {
+                Some(text) => session.edit.insert(&content, text),
+                None => {
+                    self.multi_edit = None;
+                    if key == "escape" {
+                        cx.stop_propagation();
+                    }
+                    cx.notify();
+                    return;
+                }
+            },
+        };
+        if let Some((range, text)) = change {
+            let utf16 = |offset: usize| content[..offset].encode_utf16().count();
+            let range = utf16(range.start)..utf16(range.end);
+            self.suppress_completion = true;
+            buffer.update(cx, |state, cx| {
+                state.replace_text_in_range(Some(range), &text, window, cx);
+            });
+        }
+        let content = buffer.read(cx).content();
+        let selection = session.edit.primary_selection();
+        Self::select_byte_range(&buffer, &content, selection, window, cx);
+        session.version = buffer.read(cx).content_version();
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn clamp_tab_scroll(&mut self) {
+        let max = self.buffers.len().saturating_sub(1);
+        if self.tab_scroll_offset > max {
+            self.tab_scroll_offset = max;
+        }
+        if self.active_tab >= self.buffers.len() {
+            return;
+        }
+        if self.active_tab < self.tab_scroll_offset {
+            self.tab_scroll_offset = self.active_tab;
+        }
+    }
+
+    fn close_tab_at(&mut self, idx: usize, cx: &mut Context<Self>) {
+        if self.buffers.is_empty() {
+            return;
+        }
+        if let Some(buffer) = self.buffers.get(idx).cloned() {
+            self.lsp_notify_did_close(&buffer, cx);
+            self.record_undo_history(&buffer, cx);
+            self.undo_history.save();
+            if let Some(path) = buffer.read(cx).file_path().cloned() {
+                if let Some(view) = self.capture_view_state(&buffer, cx) {
+                    self.view_states.insert(path, view);
+                }
+            }
+        }
+        self.autosave.cancel(idx);
+        self.remove_buffer_at(idx);
+        if self.active_tab >= self.buffers.len() {
+            self.active_tab = self.buffers.len().saturating_sub(1);
+        } else if self.active_tab > idx {
+            self.active_tab -= 1;
+        }
+        self.clamp_tab_scroll();
+        self.update_search_editor(cx);
+        cx.notify();
+    }
+
+    fn dismiss_whitespace_notice(&mut self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
+        self.whitespace_notices.remove(&buffer.entity_id());
+        if let Some(path) = buffer.read(cx).file_path().cloned() {
+            self.whitespace_dismissed.insert(path);
+        }
+        cx.notify();
+    }
+
+    fn normalize_active_whitespace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        self.dismiss_whitespace_notice(&buffer, cx);
+        if self.active_is_read_only() {
+            return;
+        }
+        let config = self.indent_config_for(&buffer, cx);
+        let content = buffer.read(cx).content();
+        let normalized = normalize_whitespace(&content, &indent_unit(config), config.tab_size);
+        if normalized == content {
+            return;
+        }
+        let cursor = buffer.read(cx).cursor();
+        let end_utf16 = content.encode_utf16().count();
+        buffer.update(cx, |state, cx| {
+            state.replace_text_in_range(Some(0..end_utf16), &normalized, window, cx);
+            state.set_cursor_position(cursor.line, 0, cx);
+        });
+    }
+
+    fn render_whitespace_notice(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let buffer = self.buffers.get(self.active_tab)?;
+        let issues = self.whitespace_notices.get(&buffer.entity_id())?;
+        let chrome = use_ide_theme().chrome;
+        let message = match (issues.mixed_indent, issues.mixed_eol) {
+            (true, true) => "This file mixes tabs and spaces, and CRLF and LF line endings",
+            (true, false) => "This file mixes tabs and spaces for indentation",
+            _ => "This file mixes CRLF and LF line endings",
+        };
+        let button = |id: &'static str, label: &'static str| {
+            div()
+                .id(id)
+                .px(px(8.0))
+                .py(px(2.0))
+                .rounded(px(3.0))
+                .cursor_pointer()
+                .text_color(chrome.bright)
+                .hover(|s| s.bg(chrome.accent.opacity(0.2)))
+                .child(label)
+        };
+        Some(
+            div()
+                .w_full()
+                .h(px(28.0))
+                .flex()
+                .items_center()
+                .gap(px(8.0))
+                .px(px(12.0))
+                .bg(chrome.accent.opacity(0.08))
+                .border_b_1()
+                .border_color(hsla(0.0, 0.0, 1.0, 0.05))
+                .text_size(px(12.0))
+                .text_color(chrome.text_secondary)
+                .child(div().flex_1().child(message))
+                .child(button("whitespace-normalize", "Normalize").on_click(
+                    cx.listener(|this, _, window, cx| this.normalize_active_whitespace(window, cx)),
+                ))
+                .child(
+                    button("whitespace-dismiss", "Dismiss").on_click(cx.listener(
+                        |this, _, _, cx| {
+                            if let Some(buffer) = this.buffers.get(this.active_tab).cloned() {
+                                this.dismiss_whitespace_notice(&buffer, cx);
+                            }
+                        },
+                    )),
+                ),
+        )
+    }
+
+    fn capture_view_state(&self, buffer: &Entity<EditorState>, cx: &App) -> Option<ViewState> {
+        let state = buffer.read(cx);
+        let cursor = state.cursor();
+        let line_height = state.line_height;
+        let anchor = state.cursor_screen_position(line_height)?;
+        let content_top = anchor.y - line_height * (cursor.line as f32 + 1.0);
+        let scrolled = (self.editor_viewport.get().top() - content_top) / line_height;
+        Some(ViewState {
+            line: cursor.line,
+            col: cursor.col,
+            top_line: scrolled.round().max(0.0) as usize,
+        })
+    }
+
+    fn scroll_line_to_top(
+        &self,
+        buffer: &Entity<EditorState>,
+        line: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let line_height = buffer.read(cx).line_height;
+        let rows = (self.editor_viewport.get().size.height / line_height).max(1.0) as usize;
+        let top = self
+            .capture_view_state(buffer, cx)
+            .map_or(0, |v| v.top_line);
+        let edge = match line.cmp(&top) {
+            std::cmp::Ordering::Less => line,
+            std::cmp::Ordering::Greater => line + rows - 1,
+            std::cmp::Ordering::Equal => return,
+        };
+        buffer.update(cx, |state, cx| {
+            let last = state.line_count().saturating_sub(1);
+            state.set_cursor_position(edge.min(last), 0, cx);
+        });
+    }
+
+    fn scroll_to_view(
+        &self,
+        buffer: &Entity<EditorState>,
+        view: &ViewState,
+        cx: &mut Context<Self>,
+    ) {
+        self.scroll_line_to_top(buffer, view.top_line, cx);
+        buffer.update(cx, |state, cx| {
+            state.set_cursor_position(view.line, view.col, cx)
+        });
+    }
+
+    fn apply_view_restore(&mut self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
+        let Some(view) = self.pending_view_restore.remove(&buffer.entity_id()) else {
+            return;
+        };
+        let cursor = buffer.read(cx).cursor();
+        if (cursor.line, cursor.col) != (view.line, view.col) {
+            return;
+        }
+        self.scroll_to_view(buffer, &view, cx);
+    }
+
+    fn render_tab_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let dropdown = self.settings.tab_overflow == crate::settings::TabOverflow::Dropdown;
+        let offset = if dropdown { 0 } else { self.tab_scroll_offset };
+        let total = self.buffers.len();
+        let show_left = offset > 0;
+        let show_right = total > 0 && offset < total.saturating_sub(1);
+        let muted_fg = chrome.text_secondary;
+        let active_fg = chrome.bright;
+        let editor_bg = chrome.editor_bg;
+        let border_color = hsla(0.0, 0.0, 1.0, 0.05);
+
+        div()
+            .flex_1()
+            .h_full()
+            .flex()
+            .items_center()
+            .overflow_x_hidden()
+            .when(!dropdown, |el| {
+                el.child(
+                    div()
+                        .id("tab-scroll-left")
+                        .h_full()
+                        .w(px(28.0))
+                        .flex()
+                        .flex_shrink_0()
+                        .items_center()
+                        .justify_center()
+                        .border_r_1()
+                        .border_color(border_color)
+                        .when(show_left, |el| {
+                            el.cursor_pointer()
+                                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.tab_scroll_offset =
+                                        this.tab_scroll_offset.saturating_sub(1);
+                                    cx.notify();
+                                }))
+                                .child(Icon::new("chevron-left").size(px(14.0)).color(muted_fg))
+                        })
+                        .when(!show_left, |el| {
+                            el.child(
+                                Icon::new("chevron-left")
+                                    .size(px(14.0))
+                                    .color(muted_fg.opacity(0.2)),
+                            )
+                        }),
+                )
+            })
+            .child(
+                div()
+                    .id("tab-strip")
+                    .flex_1()
+                    .flex()
+                    .items_center()
+                    .overflow_x_hidden()
+                    .when(dropdown, |el| {
+                        el.overflow_x_scroll().track_scroll(&self.tab_strip_scroll)
+                    })
+                    .children(
+                        self.buffers
+                            .iter()
+                            .enumerate()
+                            .skip(offset)
+                            .map(|(idx, _)| {
+                                let is_active = idx == self.active_tab;
+                                let title = self
+                                    .tab_meta
+                                    .get(idx)
+                                    .map(|meta| meta.title.clone())
+                                    .unwrap_or_else(|| SharedString::from("Untitled"));
+                                let deleted = self
+                                    .tab_meta
+                                    .get(idx)
+                                    .map(|meta| meta.deleted_on_disk)
+                                    .unwrap_or(false);
+                                let read_only = self
+                                    .tab_meta
+                                    .get(idx)
+                                    .is_some_and(|meta| meta.read_only_content.is_some());
+
+                                div()
+                                    .id(ElementId::Name(format!("tab-{}", idx).into()))
+                                    .h_full()
+                                    .flex()
+                                    .flex_shrink_0()
+                                    .items_center()
+                                    .gap(px(6.0))
+                                    .px(px(14.0))
+                                    .cursor_pointer()
+                                    .text_size(px(13.0))
+                                    .border_r_1()
+                                    .border_color(border_color)
+                                    .when(is_active, |el| el.bg(editor_bg).text_color(active_fg))
+                                    .when(!is_active, |el| {
+                                        el.text_color(muted_fg)
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                    })
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.active_tab = idx;
+                                        this.update_search_editor(cx);
+                                        cx.notify();
+                                    }))
+                                    .on_mouse_down(
+                                        MouseButton::Right,
+                                        cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                                            this.context_menu = Some(ContextMenuState {
+                                                position: event.position,
+                                                target: ContextMenuTarget::Tab(idx),
+                                            });
+                                            cx.notify();
+                                        }),
+                                    )
+                                    .child(
+                                        div()
+                                            .when(deleted, |el| el.line_through().opacity(0.6))
+                                            .child(title),
+                                    )
+                                    .when(read_only, |el| {
+                                        el.child(
+                                            div()
+                                                .px(px(4.0))
+                                                .rounded(px(3.0))
+                                                .bg(hsla(0.0, 0.0, 1.0, 0.08))
+                                                .text_size(px(10.0))
+                                                .text_color(muted_fg)
+                                                .child("read-only"),
+                                        )
+                                    })
+                                    .child(
+                                        div()
+                                            .id(ElementId::Name(
+                                                format!("tab-close-{}", idx).into(),
+                                            ))
+                                            .w(px(16.0))
+                                            .h(px(16.0))
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .rounded(px(3.0))
+                                            .text_color(muted_fg)
+                                            .hover(|s| {
+                                                s.bg(hsla(0.0, 0.0, 1.0, 0.1)).text_color(active_fg)
+                                            })
+                                            .on_click(cx.listener(move |this, _, _, cx| {
+                                                this.close_tab_at(idx, cx);
+                                            }))
+                                            .child(Icon::new("x").size(px(12.0)).color(muted_fg)),
+                                    )
+                            }),
+                    )
+                    .child(
+                        div()
+                            .id("new-tab-btn")
+                            .h_full()
+                            .flex()
+                            .flex_shrink_0()
+                            .items_center()
+                            .px(px(6.0))
+                            .cursor_pointer()
+                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.new_file(cx);
+                            }))
+                            .child(Icon::new("plus").size(px(14.0)).color(muted_fg)),
+                    ),
+            )
+            .when(dropdown, |el| {
+                el.child(
+                    div()
+                        .id("tab-list-btn")
+                        .h_full()
+                        .w(px(28.0))
+                        .flex()
+                        .flex_shrink_0()
+                        .items_center()
+                        .justify_center()
+                        .border_l_1()
+                        .border_color(border_color)
+                        .cursor_pointer()
+                        .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, event: &MouseDownEvent, window, cx| {
+                                cx.stop_propagation();
+                                this.open_tab_list(event.position, window, cx);
+                            }),
+                        )
+                        .child(Icon::new("chevron-down").size(px(14.0)).color(muted_fg)),
+                )
+            })
+            .when(!dropdown, |el| {
+                el.child(
+                    div()
+                        .id("tab-scroll-right")
+                        .h_full()
+                        .w(px(28.0))
+                        .flex()
+                        .flex_shrink_0()
+                        .items_center()
+                        .justify_center()
+                        .border_l_1()
+                        .border_color(border_color)
+                        .when(show_right, |el| {
+                            el.cursor_pointer()
+                                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    let max = this.buffers.len().saturating_sub(1);
+                                    if this.tab_scroll_offset < max {
+                                        this.tab_scroll_offset += 1;
+                                    }
+                                    cx.notify();
+                                }))
+                                .child(Icon::new("chevron-right").size(px(14.0)).color(muted_fg))
+                        })
+                        .when(!show_right, |el| {
+                            el.child(
+                                Icon::new("chevron-right")
+                                    .size(px(14.0))
+                                    .color(muted_fg.opacity(0.2)),
+                            )
+                        }),
+                )
+            })
+    }
+
+    fn open_tab_list(
+        &mut self,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.tab_list_anchor = Some(position);
+        self.tab_list_selected = self.active_tab;
+        window.focus(&self.tab_list_focus);
+        cx.notify();
+    }
+
+    fn close_tab_list(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.tab_list_anchor = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn confirm_tab_list(&mut self, idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.close_tab_list(window, cx);
+        if idx < self.buffers.len() {
+            self.active_tab = idx;
+            self.tab_strip_scroll.scroll_to_item(idx);
+            self.update_search_editor(cx);
+        }
+    }
+
+    fn render_tab_list(&self, position: Point<Pixels>, cx: &mut Context<Self>) -> impl IntoElement {
+        let chrome = use_ide_theme().chrome;
+        let selected = self.tab_list_selected;
+        let root = self.workspace_root().cloned();
+        let mut list = div().flex().flex_col().gap(px(2.0));
+        for (idx, meta) in self.tab_meta.iter().enumerate() {
+            let is_selected = idx == selected;
+            let path = meta
+                .file_path
+                .as_ref()
+                .map(|p| {
+                    root.as_ref()
+                        .and_then(|r| p.strip_prefix(r).ok())
+                        .unwrap_or(p)
+                        .display()
+                        .to_string()
+                })
+                .unwrap_or_else(|| "Not saved".to_string());
+            list = list.child(
+                div()
+                    .id(ElementId::Name(format!("tab-list-{}", idx).into()))
+                    .w_full()
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .px(px(10.0))
+                    .py(px(4.0))
+                    .rounded(px(6.0))
+                    .cursor_pointer()
+                    .when(is_selected, |el| el.bg(chrome.accent.opacity(0.15)))
+                    .when(!is_selected, |el| {
+                        el.hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                    })
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.confirm_tab_list(idx, window, cx);
+                    }))
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .text_size(px(13.0))
+                            .text_color(if idx == self.active_tab {
+                                chrome.bright
+                            } else {
+                                chrome.text_secondary
+                            })
+                            .when(meta.deleted_on_disk, |el| el.line_through().opacity(0.6))
+                            .child(meta.title.clone()),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_size(px(11.0))
+                            .text_color(chrome.text_secondary.opacity(0.7))
+                            .text_ellipsis()
+                            .child(path),
+                    ),
+            );
+        }
+
+        deferred(
+            anchored()
+                .position(position)
+                .snap_to_window_with_margin(px(8.0))
+                .child(
+                    div()
+                        .id("tab-list")
+                        .track_focus(&self.tab_list_focus)
+                        .key_context("TabList")
+                        .on_action(cx.listener(|this, _: &SwitcherUp, _, cx| {
+                            this.tab_list_selected = this.tab_list_selected.saturating_sub(1);
+                            cx.notify();
+                        }))
+                        .on_action(cx.listener(|this, _: &SwitcherDown, _, cx| {
+                            if this.tab_list_selected + 1 < this.tab_meta.len() {
+                                this.tab_list_selected += 1;
+                            }
+                            cx.notify();
+                        }))
+                        .on_action(cx.listener(|this, _: &SwitcherConfirm, window, cx| {
+                            this.confirm_tab_list(this.tab_list_selected, window, cx);
+                        }))
+                        .on_action(cx.listener(|this, _: &SwitcherDismiss, window, cx| {
+                            this.close_tab_list(window, cx);
+                        }))
+                        .on_mouse_down_out(cx.listener(|this, _, window, cx| {
+                            this.close_tab_list(window, cx);
+                        }))
+                        .occlude()
+                        .mt(px(4.0))
+                        .w(px(420.0))
+                        .max_h(px(420.0))
+                        .overflow_y_scroll()
+                        .p(px(6.0))
+                        .bg(chrome.panel_bg)
+                        .border_1()
+                        .border_color(chrome.header_border)
+                        .rounded(px(8.0))
+                        .shadow_lg()
+                        .child(list),
+                ),
+        )
+        .with_priority(1)
+    }
+
+    fn render_goto_line(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let line_count = self
+            .buffers
+            .get(self.active_tab)
+            .map(|b| b.read(cx).line_count())
+            .unwrap_or(0);
+
+        div()
+            .w_full()
+            .flex()
+            .items_center()
+            .bg(chrome.panel_bg)
+            .border_b_1()
+            .border_color(chrome.header_border)
+            .px(px(12.0))
+            .py(px(6.0))
+            .gap(px(8.0))
+            .capture_action(cx.listener(
+                |this, _: &adabraka_ui::components::input::Escape, window, cx| {
+                    this.close_search_internal(cx);
+                    this.focus_active_editor(window, cx);
+                },
+            ))
+            .child(
+                div()
+                    .text_size(px(13.0))
+                    .text_color(chrome.text_secondary)
+                    .child("Go to Line:"),
+            )
+            .child(
+                div().w(px(140.0)).child(
+                    Input::new(&self.goto_line_input)
+                        .placeholder("Line[:Col] or %")
+                        .h(px(28.0))
+                        .text_size(px(13.0))
+                        .on_change({
+                            let app_entity = cx.entity().clone();
+                            move |text, cx| {
+                                app_entity.update(cx, |this, cx| {
+                                    if this.goto_line_error {
+                                        this.goto_line_error = false;
+                                        cx.notify();
+                                    }
+                                    this.preview_goto_line(&text, cx);
+                                });
+                            }
+                        })
+                        .on_enter({
+                            let goto_input = self.goto_line_input.clone();
+                            let app_entity = cx.entity().clone();
+                            move |_, cx| {
+                                let text = goto_input.read(cx).content().to_string();
+                                app_entity.update(cx, |this, cx| this.goto_line_target(&text, cx));
+                            }
+                        }),
+                ),
+            )
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(chrome.text_secondary)
+                    .child(format!("/ {}", line_count)),
+            )
+            .when(self.goto_line_error, |el| {
+                el.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(hsla(0.0, 0.7, 0.6, 0.9))
+                        .child("Enter a line, line:col, :col, or percentage"),
+                )
+            })
+    }
+
+    fn resolve_goto_target(&self, text: &str, cx: &App) -> Option<(usize, usize)> {
+        let state = self.buffers.get(self.active_tab)?.read(cx);
+        let current = match self.goto_line_origin {
+            Some((_, ref view)) => view.line,
+            None => state.cursor().line,
+        };
+        let target = crate::goto_line::parse_goto_target(text, current, state.line_count())?;
+        let col = target.col.map_or(0, |col| {
+            let content = state.content();
+            let line = content.lines().nth(target.line).unwrap_or("");
+            line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+        });
+        Some((target.line, col))
+    }
+
+    fn goto_line_target(&mut self, text: &str, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let Some((line, col)) = self.resolve_goto_target(text, cx) else {
+            self.goto_line_error = true;
+            cx.notify();
+            return;
+        };
+        buffer.update(cx, |s, cx| s.set_cursor_position(line, col, cx));
+        self.goto_line_origin = None;
+        self.goto_line_error = false;
+        self.flash_goto_line(line, cx);
+    }
+
+    fn preview_goto_line(&mut self, text: &str, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        if self
+            .goto_line_origin
+            .as_ref()
+            .is_none_or(|(id, _)| *id != buffer.entity_id())
+        {
+            self.goto_line_origin = self
+                .capture_view_state(&buffer, cx)
+                .map(|view| (buffer.entity_id(), view));
+        }
+        let Some((line, col)) = self.resolve_goto_target(text, cx) else {
+            return;
+        };
+        let line_height = buffer.read(cx).line_height;
+        let rows = (self.editor_viewport.get().size.height / line_height).max(1.0) as usize;
+        let top = self
+            .capture_view_state(&buffer, cx)
+            .map_or(0, |v| v.top_line);
+        let view = if (top..top + rows).contains(&line) {
+            None
+        } else {
+            Some(ViewState {
+                line,
+                col,
+                top_line: line.saturating_sub(rows / 2),
+            })
+        };
+        match view {
+            Some(view) => self.scroll_to_view(&buffer, &view, cx),
+            None => buffer.update(cx, |state, cx| state.set_cursor_position(line, col, cx)),
+        }
+        self.flash_goto_line(line, cx);
+    }
+
+    fn revert_goto_preview(&mut self, cx: &mut Context<Self>) {
+        self.goto_line_flash = None;
+        let Some((id, view)) = self.goto_line_origin.take() else {
+            return;
+        };
+        if let Some(buffer) = self.buffers.iter().find(|b| b.entity_id() == id).cloned() {
+            self.scroll_to_view(&buffer, &view, cx);
+        }
+        cx.notify();
+    }
+
+    fn flash_goto_line(&mut self, line: usize, cx: &mut Context<Self>) {
+        let task = cx.spawn(async move |this, cx| {
+            Timer::after(Duration::from_millis(900)).await;
+            let _ = this.update(cx, |this, cx| {
+                this.goto_line_flash = None;
+                cx.notify();
+            });
+        });
+        self.goto_line_flash = Some((line, task));
+        cx.notify();
+    }
+
+    fn render_goto_line_highlight(&self, buffer: &Entity<EditorState>) -> Option<impl IntoElement> {
+        let line = self.goto_line_flash.as_ref()?.0;
+        let color = use_ide_theme().chrome.accent.opacity(0.18);
+        let buffer = buffer.clone();
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, cx| {
+                    let state = buffer.read(cx);
+                    let line_height = state.line_height;
+                    let cursor_line = state.cursor().line;
+                    let Some(anchor) = state.cursor_screen_position(line_height) else {
+                        return;
+                    };
+                    let Some(row) = state.buffer_line_to_display_row(line) else {
+                        return;
+                    };
+                    let origin_y = anchor.y - line_height * (cursor_line as f32 + 1.0);
+                    let y = origin_y + line_height * row as f32;
+                    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                        window.paint_quad(fill(
+                            Bounds::new(
+                                point(bounds.left(), y),
+                                size(bounds.size.width, line_height),
+                            ),
+                            color,
+                        ));
+                    });
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
+    }
+
+    fn render_bracket_guide(
+        &self,
+        buffer: &Entity<EditorState>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        if !self.settings.bracket_pair_guides {
+            return None;
+        }
+        let state = buffer.read(cx);
+        let cursor = state.cursor();
+        let (open, close) = enclosing_block(state.syntax_tree()?, cursor.line, cursor.col)?;
+        let guide_col = open.column.min(close.column);
+        let color = use_ide_theme().editor.bracket_match;
+        let left = px(self.settings.editor_padding_left.max(0.0));
+        let buffer = buffer.clone();
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, cx| {
+                    let state = buffer.read(cx);
+                    let line_height = state.line_height;
+                    let cursor_line = state.cursor().line;
+                    let Some(anchor) = state.cursor_screen_position(line_height) else {
+                        return;
+                    };
+                    let (Some(open_row), Some(close_row)) = (
+                        state.buffer_line_to_display_row(open.row),
+                        state.buffer_line_to_display_row(close.row),
+                    ) else {
+                        return;
+                    };
+                    let family = state.font_family_override.clone().unwrap_or_else(|| {
+                        adabraka_ui::theme::use_theme().tokens.font_mono.clone()
+                    });
+                    let run = TextRun {
+                        len: 1,
+                        font: font(family),
+                        color,
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    };
+                    let char_width = window
+                        .text_system()
+                        .shape_line(" ".into(), state.font_size, &[run], None)
+                        .x_for_index(1);
+                    let gutter = if state.show_line_numbers {
+                        px(80.0)
+                    } else {
+                        px(12.0)
+                    };
+                    let text_left = bounds.left() + left + gutter;
+                    let scroll_x = state.scroll_offset_x();
+                    let x = |col: usize| text_left + char_width * col as f32 - scroll_x;
+                    let origin_y = anchor.y - line_height * (cursor_line as f32 + 1.0);
+                    let top = origin_y + line_height * (open_row as f32 + 1.0);
+                    let bottom = origin_y + line_height * close_row as f32;
+                    let guide_x = x(guide_col);
+                    let mask =
+                        Bounds::from_corners(point(text_left, bounds.top()), bounds.bottom_right());
+                    window.with_content_mask(Some(ContentMask { bounds: mask }), |window| {
+                        window.paint_quad(fill(
+                            Bounds::new(point(guide_x, top), size(px(1.0), bottom - top)),
+                            color,
+                        ));
+                        if open.column > guide_col {
+                            let width = x(open.column + 1) - guide_x;
+                            window.paint_quad(fill(
+                                Bounds::new(point(guide_x, top - px(1.0)), size(width, px(1.0))),
+                                color,
+                            ));
+                        }
+                        if close.column > guide_col {
+                            let width = x(close.column) - guide_x;
+                            window.paint_quad(fill(
+                                Bounds::new(point(guide_x, bottom), size(width, px(1.0))),
+                                color,
+                            ));
+                        }
+                    });
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
+    }
+
+    fn render_multi_edit_cursors(
+        &self,
+        buffer: &Entity<EditorState>,
+        cx: &mut Context<Self>,
+    ) -> Option<impl IntoElement> {
+        let session = self.active_multi_edit(cx)?;
+        if session.buffer != buffer.entity_id() {
+            return None;
+        }
+        let content = buffer.read(cx).content();
+        let mut segments = Vec::new();
+        let (mut line, mut line_start, mut scanned) = (0, 0, 0);
+        for range in session.edit.secondary_selections() {
+            for (i, b) in content[scanned..range.start].bytes().enumerate() {
+                if b == b'\n' {
+                    line += 1;
+                    line_start = scanned + i + 1;
+                }
+            }
+            scanned = range.start;
+            let col = content[line_start..range.start].chars().count();
+            for (i, piece) in content[range.clone()].split('\n').enumerate() {
+                let start = if i == 0 { col } else { 0 };
+                segments.push((line + i, start, start + piece.chars().count()));
+            }
+        }
+        let theme = use_ide_theme();
+        let (caret, selection) = (theme.editor.cursor, theme.editor.selection);
+        let left = px(self.settings.editor_padding_left.max(0.0));
+        let buffer = buffer.clone();
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, cx| {
+                    let state = buffer.read(cx);
+                    let line_height = state.line_height;
+                    let cursor_line = state.cursor().line;
+                    let Some(anchor) = state.cursor_screen_position(line_height) else {
+                        return;
+                    };
+                    let family = state.font_family_override.clone().unwrap_or_else(|| {
+                        adabraka_ui::theme::use_theme().tokens.font_mono.clone()
+                    });
+                    let run = TextRun {
+                        len: 1,
+                        font: font(family),
+                        color: caret,
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    };
+                    let char_width = window
+                        .text_system()
+                        .shape_line(" ".into(), state.font_size, &[run], None)
+                        .x_for_index(1);
+                    let gutter = if state.show_line_numbers {
+                        px(80.0)
+                    } else {
+                        px(12.0)
+                    };
+                    let text_left = bounds.left() + left + gutter;
+                    let scroll_x = state.scroll_offset_x();
+                    let x = |col: usize| text_left + char_width * col as f32 - scroll_x;
+                    let origin_y = anchor.y - line_height * (cursor_line as f32 + 1.0);
+                    let mask =
+                        Bounds::from_corners(point(text_left, bounds.top()), bounds.bottom_right());
+                    window.with_content_mask(Some(ContentMask { bounds: mask }), |window| {
+                        for &(line, start, end) in &segments {
+                            let Some(row) = state.buffer_line_to_display_row(line) else {
+                                continue;
+                            };
+                            let y = origin_y + line_height * row as f32;
+                            let (width, color) = if start == end {
+                                (px(2.0), caret)
+                            } else {
+                                (x(end) - x(start), selection)
+                            };
+                            window.paint_quad(fill(
+                                Bounds::new(point(x(start), y), size(width, line_height)),
+                                color,
+                            ));
+                        }
+                    });
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full(),
+        )
+    }
+
+    pub fn open_folder(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.workspace_file = None;
+        self.lsp_scope_prompt = None;
+        let lsp_before = self.lsp_config();
+        if !self.settings.local_overrides().is_empty() {
+            self.settings.clear_local_overrides();
+            self.apply_settings_to_views(cx);
+        }
+        self.settings.push_recent_folder(path.clone());
+        self.settings.save();
+        self.apply_project_settings(&path, cx);
+        self.expanded_paths = vec![path.clone()];
+        let git_path = path.clone();
+        self.workspace_roots = vec![path.clone()];
+        self.file_tree_nodes = self.scan_workspace_roots();
+        self.restore_expanded_paths(self.saved_expanded_paths(&path));
+        self.active_mode = ViewMode::Explorer;
+        self.panel_visible = true;
+        self.selected_tree_path = None;
+        self.rebuild_file_index();
+        let review_path = path.clone();
+        self.git_state
+            .update(cx, |s, cx| s.set_workspace(git_path, cx));
+        self.git_scope_file = None;
+        self.review_state
+            .update(cx, |s, cx| s.set_workspace(review_path, cx));
+        self.lsp_registry.set_roots(self.workspace_roots.clone());
+        if self.lsp_config() != lsp_before {
+            self.restart_lsp(cx);
+        }
+        self.start_lsp_poll(cx);
+        cx.notify();
+    }
+
+    fn saved_expanded_paths(&self, root: &Path) -> Vec<PathBuf> {
+        self.settings
+            .expanded_folders
+            .get(root)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn restore_expanded_paths(&mut self, mut saved: Vec<PathBuf>) {
+        saved.retain(|p| p.is_dir() && self.root_for_path(p).is_some());
+        saved.sort_by_key(|p| p.components().count());
+        for path in saved {
+            for dir in path.ancestors().collect::<Vec<_>>().into_iter().rev() {
+                load_children_if_needed(&mut self.file_tree_nodes, dir);
+            }
+            if !self.expanded_paths.contains(&path) {
+                self.expanded_paths.push(path);
+            }
+        }
+    }
+
+    fn collapse_all_folders(&mut self, cx: &mut Context<Self>) {
+        let roots = self.workspace_roots.clone();
+        self.expanded_paths.retain(|p| roots.contains(p));
+        let top = point(px(0.0), px(0.0));
+        self.explorer_scroll_handle.set_offset(top);
+        self.persist_expanded_paths();
+        cx.notify();
+    }
+
+    fn collapse_folder_recursive(&mut self, path: &Path, cx: &mut Context<Self>) {
+        self.expanded_paths.retain(|p| !p.starts_with(path));
+        self.persist_expanded_paths();
+        cx.notify();
+    }
+
+    fn expand_to_level(&mut self, level: usize, cx: &mut Context<Self>) {
+        let mut expanded = self.workspace_roots.clone();
+        if self.workspace_roots.len() == 1 {
+            expand_nodes_to_level(&mut self.file_tree_nodes, level, &mut expanded);
+        } else {
+            for root in self.file_tree_nodes.iter_mut() {
+                expand_nodes_to_level(&mut root.children, level, &mut expanded);
+            }
+        }
+        self.expanded_paths = expanded;
+        self.persist_expanded_paths();
+        cx.notify();
+    }
+
+    fn persist_expanded_paths(&mut self) {
+        for root in self.workspace_roots.clone() {
+            let expanded = self
+                .expanded_paths
+                .iter()
+                .filter(|p| self.root_for_path(p) == Some(&root))
+                .cloned()
+                .collect();
+            self.settings.remember_expanded(&root, expanded);
+        }
+        self.settings.save();
+    }
+
+    fn apply_project_settings(&mut self, root: &Path, cx: &mut Context<Self>) {
+        self.project_settings = match crate::settings::load_project_settings(root) {
+            Ok(settings) => settings,
+            Err(err) => {
+                let path = crate::settings::project_settings_path(root);
+                self.notify(
+                    format!("{}: {}", path.display(), err),
+                    ToastSeverity::Error,
+                    cx,
+                );
+                serde_json::Map::new()
+            }
+        };
+        let ignored = crate::settings::strip_command_settings(&mut self.project_settings);
+        if !ignored.is_empty() {
+            self.notify(
+                format!(
+                    "Ignoring {} from project settings; set them in your user settings",
+                    ignored.join(", ")
+                ),
+                ToastSeverity::Info,
+                cx,
+            );
+        }
+        if self.project_settings.is_empty() {
+            return;
+        }
+        match self.settings.apply_local_overrides(&self.project_settings) {
+            Ok(unknown) if !unknown.is_empty() => self.notify(
+                format!("Unknown project settings: {}", unknown.join(", ")),
+                ToastSeverity::Error,
+                cx,
+            ),
+            Ok(_) => {}
+            Err(err) => self.notify(
+                format!("Invalid project settings: {}", err),
+                ToastSeverity::Error,
+                cx,
+            ),
+        }
+        self.apply_settings_to_views(cx);
+    }
+
+    fn lsp_config(&self) -> (bool, HashMap<String, crate::settings::LanguageServerConfig>) {
+        (
+            self.settings.lsp_enabled,
+            self.settings.language_servers.clone(),
+        )
+    }
+
+    fn restart_lsp(&mut self, cx: &mut Context<Self>) {
+        self.lsp_registry.stop_all();
+        self.lsp_poll_task = None;
+        if self.settings.lsp_enabled {
+            self.lsp_registry.set_roots(self.workspace_roots.clone());
+            for buffer in self.buffers.clone() {
+                self.lsp_notify_did_open(&buffer, cx);
+            }
+            self.start_lsp_poll(cx);
+        }
+    }
+
+    fn toggle_lsp_enabled(&mut self, cx: &mut Context<Self>) {
+        let previous = self.settings.lsp_enabled;
+        self.settings.lsp_enabled = !previous;
+        let root = self.workspace_root().cloned();
+        match root.filter(|_| self.project_settings.contains_key("lsp_enabled")) {
+            Some(root) => self.save_project_lsp_enabled(&root, cx),
+            None => {
+                self.settings.save();
+                self.lsp_scope_prompt = self.workspace_root().is_some().then_some(previous);
+            }
+        }
+        self.restart_lsp(cx);
+        cx.notify();
+    }
+
+    fn save_project_lsp_enabled(&mut self, root: &Path, cx: &mut Context<Self>) {
+        let value = serde_json::Value::Bool(self.settings.lsp_enabled);
+        self.project_settings
+            .insert("lsp_enabled".into(), value.clone());
+        if let Err(err) = crate::settings::save_project_setting(root, "lsp_enabled", Some(value)) {
+            self.notify(
+                format!("Failed to save project settings: {}", err),
+                ToastSeverity::Error,
+                cx,
+            );
+        }
+    }
+
+    fn keep_lsp_choice_for_project(&mut self, cx: &mut Context<Self>) {
+        let (Some(previous), Some(root)) =
+            (self.lsp_scope_prompt.take(), self.workspace_root().cloned())
+        else {
+            return;
+        };
+        self.settings
+            .set_local_override("lsp_enabled", serde_json::Value::Bool(previous));
+        self.settings.save();
+        self.save_project_lsp_enabled(&root, cx);
+        cx.notify();
+    }
+
+    fn use_global_lsp_choice(&mut self, cx: &mut Context<Self>) {
+        let Some(root) = self.workspace_root().cloned() else {
+            return;
+        };
+        self.project_settings.remove("lsp_enabled");
+        if let Err(err) = crate::settings::save_project_setting(&root, "lsp_enabled", None) {
+            self.notify(
+                format!("Failed to save project settings: {}", err),
+                ToastSeverity::Error,
+                cx,
+            );
+        }
+        let before = self.settings.lsp_enabled;
+        self.settings.clear_local_override("lsp_enabled");
+        if self.settings.lsp_enabled != before {
+            self.restart_lsp(cx);
+        }
+        cx.notify();
+    }
+
+    pub fn add_workspace_root(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.workspace_roots.is_empty() {
+            self.open_folder(path, cx);
+            return;
+        }
+        if self.workspace_roots.contains(&path) {
+            return;
+        }
+        self.settings.push_recent_folder(path.clone());
+        self.settings.save();
+        self.workspace_roots.push(path.clone());
+        for root in self.workspace_roots.clone() {
+            if !self.expanded_paths.contains(&root) {
+                self.expanded_paths.push(root);
+            }
+        }
+        self.file_tree_nodes = self.scan_workspace_roots();
+        self.restore_expanded_paths(self.saved_expanded_paths(&path));
+        self.active_mode = ViewMode::Explorer;
+        self.panel_visible = true;
+        self.rebuild_file_index();
+        self.lsp_registry.set_roots(self.workspace_roots.clone());
+        let roots = self.workspace_roots.clone();
+        self.git_state.update(cx, |s, cx| s.set_roots(roots, cx));
+        self.git_scope_file = None;
+        self.start_lsp_poll(cx);
+        cx.notify();
+    }
+
+    fn remove_workspace_root(&mut self, path: &Path, cx: &mut Context<Self>) {
+        if self.workspace_roots.len() < 2 {
+            return;
+        }
+        let was_primary = self.workspace_roots.first().map(|p| p.as_path()) == Some(path);
+        self.workspace_roots.retain(|r| r != path);
+        self.expanded_paths.retain(|p| !p.starts_with(path));
+        self.file_tree_nodes = self.scan_workspace_roots();
+        self.rebuild_file_index();
+        self.lsp_registry.set_roots(self.workspace_roots.clone());
+        let roots = self.workspace_roots.clone();
+        self.git_state.update(cx, |s, cx| s.set_roots(roots, cx));
+        self.git_scope_file = None;
+        if was_primary {
+            let review_path = self.workspace_roots[0].clone();
+            self.review_state
+                .update(cx, |s, cx| s.set_workspace(review_path, cx));
+        }
+        cx.notify();
+    }
+
+    fn scan_workspace_roots(&self) -> Vec<FileNode> {
+        match self.workspace_roots.as_slice() {
+            [root] => scan_directory(root, 2),
+            roots => roots
+                .iter()
+                .map(|root| FileNode::directory(root).with_children(scan_directory(root, 2)))
+                .collect(),
+        }
+    }
+
+    pub fn open_workspace_file(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        let LoadedWorkspace {
+            mut file,
+            mut warnings,
+        } = match WorkspaceFile::load(&path) {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.notify(
+                    format!("{}: {}", path.display(), err),
+                    ToastSeverity::Error,
+                    cx,
+                );
+                return;
+            }
+        };
+        let ignored = crate::settings::strip_command_settings(&mut file.settings);
+        if !ignored.is_empty() {
+            warnings.push(format!(
+                "Ignored workspace settings: {}",
+                ignored.join(", ")
+            ));
+        }
+        self.restore_workspace(file, &mut warnings, cx);
+        self.workspace_file = Some(path.clone());
+
+        let name = path
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if warnings.is_empty() {
+            self.notify(
+                format!("Opened workspace {}", name),
+                ToastSeverity::Success,
+                cx,
+            );
+        } else {
+            self.notify(
+                format!(
+                    "Opened workspace {} with problems: {}",
+                    name,
+                    warnings.join("; ")
+                ),
+                ToastSeverity::Error,
+                cx,
+            );
+        }
+        cx.notify();
+    }
+
+    pub fn apply_startup_behavior(&mut self, cx: &mut Context<Self>) {
+        match self.settings.startup {
+            crate::settings::StartupBehavior::Welcome => {}
+            crate::settings::StartupBehavior::LastSession => self.restore_last_session(cx),
+            crate::settings::StartupBehavior::NewFile => self.new_file(cx),
+            crate::settings::StartupBehavior::Folder => {
+                if let Some(folder) = self.settings.startup_folder.clone().filter(|f| f.is_dir()) {
+                    self.open_folder(folder, cx);
+                }
+            }
+        }
+    }
+
+    fn restore_last_session(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = crate::workspace_file::last_session_path().filter(|p| p.exists()) else {
+            return;
+        };
+        match WorkspaceFile::load_session(&path) {
+            Ok(LoadedWorkspace { file, mut warnings }) => {
+                self.restore_workspace(file, &mut warnings, cx);
+                cx.notify();
+            }
+            Err(err) => eprintln!("[shiori] Failed to restore last session: {}", err),
+        }
+    }
+
+    fn save_last_session(&self, cx: &App) {
+        let Some(path) = crate::workspace_file::last_session_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Err(err) = self.current_workspace_file(cx).save(&path) {
+            eprintln!("[shiori] Failed to save session: {}", err);
+        }
+    }
+
+    fn restore_workspace(
+        &mut self,
+        file: WorkspaceFile,
+        warnings: &mut Vec<String>,
+        cx: &mut Context<Self>,
+    ) {
+        let mut folders = file.folders.into_iter();
+        if let Some(primary) = folders.next() {
+            self.open_folder(primary, cx);
+        }
+        for folder in folders {
+            self.add_workspace_root(folder, cx);
+        }
+
+        let lsp_before = self.lsp_config();
+        let mut overrides = self.project_settings.clone();
+        overrides.extend(file.settings.clone());
+        match self.settings.apply_local_overrides(&overrides) {
+            Ok(unknown) => warnings.extend(
+                unknown
+                    .into_iter()
+                    .map(|k| format!("Unknown setting: {}", k)),
+            ),
+            Err(err) => warnings.push(format!("Invalid settings: {}", err)),
+        }
+        self.apply_settings_to_views(cx);
+        if self.lsp_config() != lsp_before {
+            self.restart_lsp(cx);
+        }
+
+        for (tab, view) in file.tabs.iter().zip(&file.views) {
+            if let Some(view) = view {
+                self.view_states.insert(tab.clone(), *view);
+            }
+        }
+        let to_open: Vec<PathBuf> = file
+            .tabs
+            .iter()
+            .filter(|tab| {
+                !self
+                    .tab_meta
+                    .iter()
+                    .any(|m| m.file_path.as_ref() == Some(*tab))
+            })
+            .cloned()
+            .collect();
+        if !to_open.is_empty() {
+            self.open_paths(to_open, cx);
+        }
+        if let Some(active) = file.active_tab.and_then(|i| file.tabs.get(i)) {
+            if let Some(idx) = self
+                .tab_meta
+                .iter()
+                .position(|m| m.file_path.as_ref() == Some(active))
+            {
+                self.active_tab = idx;
+                self.clamp_tab_scroll();
+                self.update_search_editor(cx);
+            }
+        }
+
+        self.restore_expanded_paths(file.layout.expanded.clone());
+        self.active_mode = file.layout.view;
+        self.panel_visible = file.layout.panel_visible;
+        self.symbol_outline_visible = file.layout.symbol_outline_visible;
+        let restore_terminals = !file.layout.terminals.working_dirs.is_empty();
+        self.terminal_fullscreen =
+            file.layout.terminal_fullscreen && (!self.terminals.is_empty() || restore_terminals);
+        if restore_terminals {
+            let layout = file.layout.terminals;
+            cx.spawn(async move |this, cx| {
+                let Ok(Some(window)) = this.update(cx, |this, _| this.window_handle) else {
+                    return;
+                };
+                let _ = cx.update_window(window, |_, window, cx| {
+                    let _ = this.update(cx, |this, cx| {
+                        this.restore_terminal_layout(layout, window, cx)
+                    });
+                });
+            })
+            .detach();
+        }
+    }
+
+    fn current_workspace_file(&self, cx: &App) -> WorkspaceFile {
+        let tabs: Vec<(usize, PathBuf)> = self
+            .tab_meta
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| m.file_path.clone().map(|p| (i, p)))
+            .collect();
+        let views = tabs
+            .iter()
+            .map(|(i, _)| {
+                self.buffers
+                    .get(*i)
+                    .and_then(|buffer| self.capture_view_state(buffer, cx))
+            })
+            .collect();
+        WorkspaceFile {
+            version: 1,
+            folders: self.workspace_roots.clone(),
+            active_tab: tabs.iter().position(|(i, _)| *i == self.active_tab),
+            views,
+            tabs: tabs.into_iter().map(|(_, p)| p).collect(),
+            layout: WorkspaceLayout {
+                view: self.active_mode,
+                panel_visible: self.panel_visible,
+                symbol_outline_visible: self.symbol_outline_visible,
+                terminal_fullscreen: self.terminal_fullscreen,
+                terminals: TerminalLayout {
+                    working_dirs: self
+                        .terminals
+                        .iter()
+                        .map(|t| t.read(cx).working_directory().clone())
+                        .collect(),
+                    active: self.active_terminal,
+                },
+                expanded: self.expanded_paths.clone(),
+            },
+            settings: self.settings.local_overrides(),
+        }
+    }
+
+    fn save_workspace_as_dialog(&mut self, cx: &mut Context<Self>) {
+        let Some(root) = self.workspace_root().cloned() else {
+            self.notify(
+                "Open a folder before saving a workspace",
+                ToastSeverity::Info,
+                cx,
+            );
+            return;
+        };
+        let suggested = format!(
+            "{}.{}",
+            root.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "workspace".into()),
+            crate::workspace_file::WORKSPACE_EXTENSION
+        );
+        let workspace = self.current_workspace_file(cx);
+        let rx = cx.prompt_for_new_path(&root, Some(&suggested));
+        cx.spawn(async move |this, cx| {
+            if let Ok(Ok(Some(path))) = rx.await {
+                let result = workspace.save(&path);
+                let _ = cx.update(|cx| {
+                    let _ = this.update(cx, |this, cx| match result {
+                        Ok(()) => {
+                            this.workspace_file = Some(path.clone());
+                            this.notify(
+                                format!("Saved workspace to {}", path.display()),
+                                ToastSeverity::Success,
+                                cx,
+                            );
+                        }
+                        Err(err) => this.notify(err.to_string(), ToastSeverity::Error, cx),
+                    });
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn open_workspace_dialog(&mut self, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            if let Ok(Ok(Some(paths))) = rx.await {
+                if let Some(path) = paths.into_iter().next() {
+                    let _ = cx.update(|cx| {
+                        let _ = this.update(cx, |this, cx| {
+                            if is_workspace_file(&path) {
+                                this.open_workspace_file(path, cx);
+                            } else {
+                                this.notify(
+                                    format!(
+                                        "{} is not a .{} file",
+                                        path.display(),
+                                        crate::workspace_file::WORKSPACE_EXTENSION
+                                    ),
+                                    ToastSeverity::Error,
+                                    cx,
+                                );
+                            }
+                        });
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn apply_settings_to_views(&mut self, cx: &mut Context<Self>) {
+        let max_items = self.settings.completion_max_items;
+        self.completion_state
+            .update(cx, |s, _| s.set_max_items(max_items));
+        self.sync_diff_config(cx);
+        for buffer in self.buffers.clone() {
+            self.apply_editor_prefs(&buffer, cx);
+        }
+    }
+
+    fn workspace_root(&self) -> Option<&PathBuf> {
+        self.workspace_roots.first()
+    }
+
+    fn root_for_path(&self, path: &Path) -> Option<&PathBuf> {
+        self.workspace_roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+    }
+
+    fn workspace_switcher_entries(&self) -> Vec<(PathBuf, bool)> {
+        let mut entries: Vec<(PathBuf, bool)> = self
+            .settings
+            .favorite_folders
+            .iter()
+            .filter(|p| p.is_dir())
+            .map(|p| (p.clone(), true))
+            .collect();
+        for path in &self.settings.recent_folders {
+            if path.is_dir() && !self.settings.is_favorite_folder(path) {
+                entries.push((path.clone(), false));
+            }
+        }
+        entries
+    }
+
+    fn open_workspace_switcher(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.workspace_switcher_open = true;
+        self.workspace_switcher_selected = 0;
+        window.focus(&self.workspace_switcher_focus);
+        cx.notify();
+    }
+
+    fn close_workspace_switcher(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.workspace_switcher_open = false;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    fn confirm_workspace_switch(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entries = self.workspace_switcher_entries();
+        if let Some((path, _)) = entries.get(self.workspace_switcher_selected) {
+            let path = path.clone();
+            self.close_workspace_switcher(window, cx);
+            self.open_folder(path, cx);
+        }
+    }
+
+    fn toggle_favorite_workspace(&mut self, path: &Path, cx: &mut Context<Self>) {
+        self.settings.toggle_favorite_folder(path);
+        self.settings.save();
+        let entries = self.workspace_switcher_entries();
+        if let Some(idx) = entries.iter().position(|(p, _)| p == path) {
+            self.workspace_switcher_selected = idx;
+        }
+        cx.notify();
+    }
+
+    fn render_workspace_switcher(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let entries = self.workspace_switcher_entries();
+        let selected = self.workspace_switcher_selected;
+        let current = self.workspace_root().cloned();
+
+        let mut list = div().flex().flex_col().gap(px(2.0));
+        if entries.is_empty() {
+            list = list.child(
+                div()
+                    .px(px(12.0))
+                    .py(px(16.0))
+                    .text_size(px(12.0))
+                    .text_color(chrome.text_secondary)
+                    .child("No recent folders yet. Open a folder to see it here."),
+            );
+        }
+        for (i, (path, favorite)) in entries.into_iter().enumerate() {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string());
+            let is_selected = i == selected;
+            let is_current = current.as_ref() == Some(&path);
+            let star_path = path.clone();
+            let open_path = path.clone();
+            list = list.child(
+                div()
+                    .id(ElementId::Name(format!("workspace-entry-{}", i).into()))
+                    .w_full()
+                    .flex()
+                    .items_center()
+                    .gap(px(10.0))
+                    .px(px(10.0))
+                    .py(px(6.0))
+                    .rounded(px(6.0))
+                    .cursor_pointer()
+                    .when(is_selected, |el| el.bg(chrome.accent.opacity(0.15)))
+                    .when(!is_selected, |el| {
+                        el.hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                    })
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.close_workspace_switcher(window, cx);
+                        this.open_folder(open_path.clone(), cx);
+                    }))
+                    .child(
+                        div()
+                            .id(ElementId::Name(format!("workspace-star-{}", i).into()))
+                            .flex_shrink_0()
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _, _, cx| {
+                                    cx.stop_propagation();
+                                    this.toggle_favorite_workspace(&star_path, cx);
+                                }),
+                            )
+                            .child(Icon::new("star").size(px(14.0)).color(if favorite {
+                                hsla(0.12, 0.9, 0.6, 1.0)
+                            } else {
+                                chrome.text_secondary.opacity(0.4)
+                            })),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .overflow_hidden()
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap(px(6.0))
+                                    .text_size(px(13.0))
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .text_color(chrome.bright)
+                                    .child(name)
+                                    .when(is_current, |el| {
+                                        el.child(
+                                            div()
+                                                .text_size(px(10.0))
+                                                .text_color(chrome.accent)
+                                                .child("current"),
+                                        )
+                                    }),
+                            )
+                            .child(
+                                div()
+                                    .text_size(px(11.0))
+                                    .text_color(chrome.text_secondary)
+                                    .text_ellipsis()
+                                    .child(path.display().to_string()),
+                            ),
+                    ),
+            );
+        }
+
+        deferred(
+            div()
+                .absolute()
+                .top_0()
+                .left_0()
+                .size_full()
+                .flex()
+                .justify_center()
+                .pt(px(80.0))
+                .bg(hsla(0.0, 0.0, 0.0, 0.3))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _, window, cx| {
+                        this.close_workspace_switcher(window, cx);
+                    }),
+                )
+                .child(
+                    div()
+                        .id("workspace-switcher")
+                        .track_focus(&self.workspace_switcher_focus)
+                        .key_context("WorkspaceSwitcher")
+                        .on_action(cx.listener(|this, _: &SwitcherUp, _, cx| {
+                            this.workspace_switcher_selected =
+                                this.workspace_switcher_selected.saturating_sub(1);
+                            cx.notify();
+                        }))
+                        .on_action(cx.listener(|this, _: &SwitcherDown, _, cx| {
+                            let count = this.workspace_switcher_entries().len();
+                            if this.workspace_switcher_selected + 1 < count {
+                                this.workspace_switcher_selected += 1;
+                            }
+                            cx.notify();
+                        }))
+                        .on_action(cx.listener(|this, _: &SwitcherConfirm, window, cx| {
+                            this.confirm_workspace_switch(window, cx);
+                        }))
+                        .on_action(cx.listener(|this, _: &SwitcherDismiss, window, cx| {
+                            this.close_workspace_switcher(window, cx);
+                        }))
+                        .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                        .w(px(480.0))
+                        .max_h(px(420.0))
+                        .overflow_y_scroll()
+                        .flex()
+                        .flex_col()
+                        .gap(px(6.0))
+                        .p(px(8.0))
+                        .bg(chrome.panel_bg)
+                        .border_1()
+                        .border_color(chrome.header_border)
+                        .rounded(px(10.0))
+                        .shadow_lg()
+                        .child(
+                            div()
+                                .px(px(10.0))
+                                .pt(px(4.0))
+                                .text_xs()
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(chrome.text_secondary)
+                                .child("SWITCH WORKSPACE"),
+                        )
+                        .child(list),
+                ),
+        )
+        .with_priority(1)
+    }
+
+    fn rebuild_file_index(&mut self) {
+        let mut index = Vec::new();
+        fn walk_dir(
+            dir: &Path,
+            root: &Path,
+            out: &mut Vec<(PathBuf, String, String)>,
+            depth: usize,
+        ) {
+            if depth > 12 {
+                return;
+            }
+            let entries = match std::fs::read_dir(dir) {
+                Ok(e) => e,
+                Err(_) => return,
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                if name.starts_with('.') {
+                    continue;
+                }
+                if path.is_file() {
+                    let rel_dir = path
+                        .parent()
+                        .and_then(|p| p.strip_prefix(root).ok())
+                        .map(|p| p.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    out.push((path, name, rel_dir));
+                } else if path.is_dir() {
+                    if matches!(
+                        name.as_str(),
+                        "node_modules"
+                            | "target"
+                            | ".git"
+                            | "dist"
+                            | "build"
+                            | "__pycache__"
+                            | ".next"
+                    ) {
+                        continue;
+                    }
+                    walk_dir(&path, root, out, depth + 1);
+                }
+            }
+        }
+        let multi_root = self.workspace_roots.len() > 1;
+        for root in &self.workspace_roots {
+            let start = index.len();
+            walk_dir(root, root, &mut index, 0);
+            if multi_root {
+                let root_name = root
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                for (_, _, rel_dir) in &mut index[start..] {
+                    *rel_dir = if rel_dir.is_empty() {
+                        root_name.clone()
+                    } else {
+                        format!("{}/{}", root_name, rel_dir)
+                    };
+                }
+            }
+        }
+        self.file_index = Arc::new(index);
+    }
+
+    fn record_content_search(&mut self) {
+        let limit = self.settings.search_history_limit;
+        if self
+            .content_search_history
+            .record(&self.file_search_query, limit)
+        {
+            self.settings.content_search_history = self.content_search_history.entries().to_vec();
+            self.settings.save();
+        }
+    }
+
+    fn step_content_search_history(
+        &mut self,
+        older: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let entry = if older {
+            self.content_search_history.older(&self.file_search_query)
+        } else {
+            self.content_search_history.newer()
+        };
+        if let Some(entry) = entry {
+            self.file_search_input.update(cx, |input, cx| {
+                input.select_all(&InputSelectAll, window, cx);
+                input.set_value(SharedString::from(entry.clone()), window, cx);
+            });
+            self.file_search_query = entry;
+            if self.file_search_query.is_empty() {
+                self.clear_content_search(cx);
+            } else {
+                self.trigger_content_search(cx);
+            }
+        }
+    }
+
+    fn content_search_limits(&self) -> ContentSearchLimits {
+        ContentSearchLimits {
+            max_results: self.settings.search_max_results.max(1),
+            max_file_size: self.settings.search_max_file_size_mb.max(1) * 1024 * 1024,
+            context_lines: self.settings.search_context_lines,
+        }
+    }
+
+    fn bump_search_version(&self) -> u64 {
+        self.search_version.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    fn clear_content_search(&mut self, cx: &mut Context<Self>) {
+        self.bump_search_version();
+        self.content_search_task = None;
+        self.file_search_results.clear();
+        self.file_search_next = None;
+        cx.notify();
+    }
+
+    fn trigger_content_search(&mut self, cx: &mut Context<Self>) {
+        let version = self.bump_search_version();
+        let current = self.search_version.clone();
+        let query = self.file_search_query.clone();
+        let index = self.file_index.clone();
+        let limits = self.content_search_limits();
+        self.file_search_next = None;
+        self.file_search_total = None;
+        self.expanded_search_results.clear();
+        cx.notify();
+
+        self.content_search_task = Some(cx.spawn(async move |this, cx| {
+            Timer::after(Duration::from_millis(200)).await;
+            if current.load(Ordering::Relaxed) != version {
+                return;
+            }
+
+            let page = smol::unblock({
+                let query = query.clone();
+                let index = index.clone();
+                move || search_content(&query, &index, None, limits)
+            })
+            .await;
+            let has_more = page.next.is_some();
+            let total = (!has_more).then_some(page.results.len());
+
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |this, cx| {
+                    if this.search_version.load(Ordering::Relaxed) == version {
+                        this.file_search_results = page.results;
+                        this.file_search_next = page.next;
+                        this.file_search_total = total;
+                        cx.notify();
+                    }
+                });
+            });
+            if !has_more {
+                return;
+            }
+
+            let total = smol::unblock(move || {
+                count_content_matches(&query, &index, limits.max_file_size, || {
+                    current.load(Ordering::Relaxed) != version
+                })
+            })
+            .await;
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |this, cx| {
+                    if this.search_version.load(Ordering::Relaxed) == version {
+                        this.file_search_total = Some(total);
+                        cx.notify();
+                    }
+                });
+            });
+        }));
+    }
+
+    fn load_more_content_results(&mut self, cx: &mut Context<Self>) {
+        let Some(start) = self.file_search_next.take() else {
+            return;
+        };
+        let version = self.search_version.load(Ordering::Relaxed);
+        let query = self.file_search_query.clone();
+        let index = self.file_index.clone();
+        let limits = self.content_search_limits();
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let page = smol::unblock(move || {
+                search_content(&query, &index, Some((&start.0, start.1)), limits)
+            })
+            .await;
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |this, cx| {
+                    if this.search_version.load(Ordering::Relaxed) != version {
+                        return;
+                    }
+                    this.file_search_results.extend(page.results);
+                    if page.next.is_none() {
+                        this.file_search_total = Some(this.file_search_results.len());
+                    }
+                    this.file_search_next = page.next;
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn search_result_paths(&self) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        self.file_search_results
+            .iter()
+            .filter(|r| seen.insert(r.path.clone()))
+            .map(|r| r.path.clone())
+            .collect()
+    }
+
+    fn open_all_search_matches(&mut self, cx: &mut Context<Self>) {
+        let paths: Vec<PathBuf> = self
+            .search_result_paths()
+            .into_iter()
+            .filter(|path| {
+                !self
+                    .tab_meta
+                    .iter()
+                    .any(|meta| meta.file_path.as_ref() == Some(path))
+            })
+            .collect();
+        if !paths.is_empty() {
+            self.record_content_search();
+            self.open_paths(paths, cx);
+        }
+    }
+
+    fn open_search_results_editor(&mut self, cx: &mut Context<Self>) {
+        if self.file_search_results.is_empty() {
+            self.notify("No search results to edit", ToastSeverity::Info, cx);
+            return;
+        }
+        let results: Vec<(PathBuf, usize, String)> = self
+            .file_search_results
+            .iter()
+            .map(|result| {
+                let display = if result.dir_path.is_empty() {
+                    result.file_name.clone()
+                } else {
+                    format!("{}/{}", result.dir_path, result.file_name)
+                };
+                let prefix = format!("{}:{}: ", display, result.line_number);
+                (result.path.clone(), result.line_number, prefix)
+            })
+            .collect();
+        self.record_content_search();
+        cx.spawn(async move |this, cx| {
+            let entries = smol::unblock(move || read_search_result_lines(results)).await;
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |this, cx| this.show_search_results_editor(entries, cx));
+            });
+        })
+        .detach();
+    }
+
+    fn show_search_results_editor(
+        &mut self,
+        entries: Vec<SearchResultLine>,
+        cx: &mut Context<Self>,
+    ) {
+        let content: String = entries
+            .iter()
+            .map(|e| format!("{}{}\n", e.prefix, e.original))
+            .collect();
+        self.new_file(cx);
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        buffer.update(cx, |state, cx| state.set_content(&content, cx));
+        let idx = self.active_tab;
+        let name = format!("Search: {}", self.file_search_query);
+        if let Some(meta) = self.tab_meta.get_mut(idx) {
+            meta.title = Self::compose_tab_title(Some(&name), idx, false);
+            meta.file_name = Some(name);
+        }
+        self.search_result_buffers
+            .insert(buffer.entity_id(), entries);
+        self.active_mode = ViewMode::Explorer;
+        cx.notify();
+    }
+
+    fn apply_search_result_edits(&mut self, buffer: &Entity<EditorState>, cx: &mut Context<Self>) {
+        let Some(mut entries) = self.search_result_buffers.get(&buffer.entity_id()).cloned() else {
+            return;
+        };
+        let content = buffer.read(cx).content();
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.len() != entries.len() {
+            self.notify(
+                "Search results must keep one line per match to be applied",
+                ToastSeverity::Error,
+                cx,
+            );
+            return;
+        }
+        let mut edits: Vec<(usize, String)> = Vec::new();
+        for (i, (entry, line)) in entries.iter().zip(&lines).enumerate() {
+            let Some(text) = line.strip_prefix(entry.prefix.as_str()) else {
+                self.notify(
+                    format!("Line {} lost its file:line prefix", i + 1),
+                    ToastSeverity::Error,
+                    cx,
+                );
+                return;
+            };
+            if text != entry.original {
+                edits.push((i, text.to_string()));
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        let mut by_file: Vec<(PathBuf, Vec<(usize, String)>)> = Vec::new();
+        for (i, text) in edits {
+            let path = &entries[i].path;
+            match by_file.iter_mut().find(|(p, _)| p == path) {
+                Some((_, file_edits)) => file_edits.push((i, text)),
+                None => by_file.push((path.clone(), vec![(i, text)])),
+            }
+        }
+
+        let (mut applied, mut files, mut skipped) = (0, 0, 0);
+        for (path, file_edits) in by_file {
+            let dirty = self.buffers.iter().any(|b| {
+                let state = b.read(cx);
+                state.is_modified() && state.file_path() == Some(&path)
+            });
+            let source = if dirty {
+                None
+            } else {
+                std::fs::read_to_string(&path).ok()
+            };
+            let Some(source) = source else {
+                skipped += file_edits.len();
+                continue;
+            };
+            let mut source_lines: Vec<String> =
+                source.split_inclusive('\n').map(str::to_string).collect();
+            let mut changed = Vec::new();
+            for (i, text) in file_edits {
+                let entry = &entries[i];
+                let Some(line) = source_lines.get_mut(entry.line_number - 1) else {
+                    skipped += 1;
+                    continue;
+                };
+                let body_len = line.trim_end_matches(['\n', '\r']).len();
+                if line[..body_len] != entry.original {
+                    skipped += 1;
+                    continue;
+                }
+                line.replace_range(..body_len, &text);
+                changed.push((i, text));
+            }
+            if changed.is_empty() {
+                continue;
+            }
+            let updated = source_lines.concat();
+            if std::fs::write(&path, &updated).is_err() {
+                skipped += changed.len();
+                continue;
+            }
+            self.reload_clean_buffers(&path, &updated, cx);
+            files += 1;
+            for (i, text) in changed {
+                entries[i].original = text;
+                applied += 1;
+            }
+        }
+
+        let cursor = buffer.read(cx).cursor();
+        buffer.update(cx, |state, cx| {
+            state.set_content(&content, cx);
+            state.set_cursor_position(cursor.line, cursor.col, cx);
+        });
+        self.search_result_buffers
+            .insert(buffer.entity_id(), entries);
+        if applied > 0 {
+            self.notify(
+                format!(
+                    "Updated {} line{} in {} file{}",
+                    applied,
+                    if applied == 1 { "" } else { "s" },
+                    files,
+                    if files == 1 { "" } else { "s" }
+                ),
+                ToastSeverity::Success,
+                cx,
+            );
+        }
+        if skipped > 0 {
+            self.notify(
+                format!(
+                    "Skipped {} line{} that changed since the search or have unsaved edits",
+                    skipped,
+                    if skipped == 1 { "" } else { "s" }
+                ),
+                ToastSeverity::Error,
+                cx,
+            );
+        }
+    }
+
+    fn reload_clean_buffers(&mut self, path: &Path, content: &str, cx: &mut Context<Self>) {
+        for buffer in self.buffers.clone() {
+            let state = buffer.read(cx);
+            if state.is_modified() || state.file_path().map(|p| p.as_path()) != Some(path) {
+                continue;
+            }
+            let cursor = state.cursor();
+            buffer.update(cx, |state, cx| {
+                state.set_content(content, cx);
+                state.set_cursor_position(cursor.line, cursor.col, cx);
+            });
+        }
+    }
+
+    fn add_folder_dialog(&mut self, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: true,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            if let Ok(Ok(Some(paths))) = rx.await {
+                let _ = cx.update(|cx| {
+                    let _ = this.update(cx, |this, cx| {
+                        for path in paths {
+                            this.add_workspace_root(path, cx);
+                        }
+                    });
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn remove_active_workspace_root(&mut self, cx: &mut Context<Self>) {
+        let target = self.selected_tree_path.clone().or_else(|| {
+            self.tab_meta
+                .get(self.active_tab)
+                .and_then(|m| m.file_path.clone())
+        });
+        let root = target
+            .as_deref()
+            .and_then(|p| self.root_for_path(p))
+            .or_else(|| self.workspace_roots.last())
+            .cloned();
+        if let Some(root) = root {
+            self.remove_workspace_root(&root, cx);
+        }
+    }
+
+    fn open_folder_dialog(&mut self, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            if let Ok(Ok(Some(paths))) = rx.await {
+                if let Some(path) = paths.into_iter().next() {
+                    let _ = cx.update(|cx| {
+                        let _ = this.update(cx, |this, cx| {
+                            if this.workspace_roots.is_empty()
+                                || this.workspace_roots.contains(&path)
+                            {
+                                this.open_folder(path, cx);
+                            } else {
+                                this.pending_open_folder = Some(path);
+                                cx.notify();
+                            }
+                        });
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn toggle_terminal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.active_mode == ViewMode::Terminal {
+            self.active_mode = ViewMode::Explorer;
+            self.panel_visible = true;
+        } else {
+            self.active_mode = ViewMode::Terminal;
+            self.panel_visible = true;
+            if self.terminals.is_empty() {
+                self.new_terminal(window, cx);
+                return;
+            }
+            if let Some(terminal) = self.terminals.get(self.active_terminal) {
+                window.focus(&terminal.read(cx).focus_handle(cx));
+            }
+        }
+        cx.notify();
+    }
+
+    fn new_terminal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let working_dir = self.current_working_directory();
+        self.new_terminal_in(working_dir, window, cx);
+    }
+
+    fn new_terminal_in(
+        &mut self,
+        working_dir: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let zoom = self.terminal_zoom;
+        let font = self.settings.terminal_font.clone();
+        let font_fallback = self.settings.terminal_font_fallback.clone();
+        let line_wrap = self.settings.terminal_line_wrap;
+        let scrollback = self.settings.terminal_scrollback;
+        let confirm_paste = self.settings.terminal_confirm_multiline_paste;
+        let terminal = cx.new(|cx| TerminalView::new(cx).with_working_directory(working_dir));
+        cx.observe_in(&terminal, window, Self::on_terminal_changed)
+            .detach();
+        terminal.update(cx, |t, cx| {
+            t.set_font_family(font);
+            t.set_font_fallback(font_fallback);
+            t.set_line_wrap(line_wrap);
+            t.set_scrollback_limit(scrollback);
+            t.set_confirm_multiline_paste(confirm_paste);
+            if (zoom - 1.0).abs() > f32::EPSILON {
+                t.set_font_size(13.0 * zoom);
+            }
+            let _ = t.start_with_polling(window, cx);
+        });
+        self.terminals.push(terminal);
+        self.active_terminal = self.terminals.len() - 1;
+        self.active_mode = ViewMode::Terminal;
+        self.panel_visible = true;
+        cx.notify();
+    }
+
+    fn run_in_new_terminal(&mut self, command: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.new_terminal(window, cx);
+        if let Some(terminal) = self.terminals.last() {
+            terminal.update(cx, |t, _| t.send_str(&format!("{}\r", command)));
+            window.focus(&terminal.read(cx).focus_handle(cx));
+        }
+    }
+
+    fn restore_terminal_layout(
+        &mut self,
+        layout: TerminalLayout,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (mode, panel_visible) = (self.active_mode, self.panel_visible);
+        let mut reused = HashSet::new();
+        let mut restored = Vec::with_capacity(layout.working_dirs.len());
+        for dir in layout.working_dirs {
+            let existing = (0..self.terminals.len()).find(|i| {
+                !reused.contains(i) && *self.terminals[*i].read(cx).working_directory() == dir
+            });
+            let idx = match existing {
+                Some(idx) => idx,
+                None => {
+                    self.new_terminal_in(dir, window, cx);
+                    self.terminals.len() - 1
+                }
+            };
+            reused.insert(idx);
+            restored.push(idx);
+        }
+        if let Some(&active) = restored.get(layout.active).or(restored.last()) {
+            self.active_terminal = active;
+        }
+        self.active_mode = mode;
+        self.panel_visible = panel_visible;
+    }
+
+    fn on_terminal_changed(
+        &mut self,
+        terminal: Entity<TerminalView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if terminal.update(cx, |t, _| t.take_git_command()) {
+            self.schedule_git_refresh(GIT_COMMAND_REFRESH_DELAY, cx);
+        }
+        if let Some(url) = terminal.update(cx, |t, _| t.take_link()) {
+            self.open_terminal_link(&url, cx);
+        }
+        if let Some((path, line, col)) = terminal.update(cx, |t, _| t.take_location()) {
+            self.navigate_to_location(path, line, col, cx);
+        }
+        if let Some((position, target)) = terminal.update(cx, |t, _| t.take_context_menu()) {
+            self.context_menu = Some(ContextMenuState {
+                position,
+                target: ContextMenuTarget::Terminal(target),
+            });
+            cx.notify();
+        }
+        if terminal.update(cx, |t, _| t.take_paste_confirm_disabled()) {
+            self.settings.terminal_confirm_multiline_paste = false;
+            self.settings.save();
+            for terminal in &self.terminals {
+                terminal.update(cx, |tv, _| tv.set_confirm_multiline_paste(false));
+            }
+        }
+        let Some((title, body)) = terminal.update(cx, |t, _| t.take_notification()) else {
             return;
+        };
+        let (title, body) = match body {
+            Some(body) => (title, body),
+            None => (terminal.read(cx).title(), title),
+        };
+        if !window.is_window_active() {
+            if let Err(err) = cx.show_notification(&title, &body) {
+                eprintln!("[shiori] System notification failed: {}", err);
+            }
         }
-        if let Some(buffer) = self.buffers.get(idx) {
-            self.lsp_notify_did_close(buffer, cx);
+        self.notify(format!("{}: {}", title, body), ToastSeverity::Info, cx);
+    }
+
+    fn restart_terminal_at(&mut self, idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(terminal) = self.terminals.get(idx).cloned() else {
+            return;
+        };
+        self.active_terminal = idx;
+        let result = terminal.update(cx, |t, cx| t.restart(window, cx));
+        if let Err(e) = result {
+            self.notify(
+                format!("Failed to restart terminal: {}", e),
+                ToastSeverity::Error,
+                cx,
+            );
         }
-        self.autosave.cancel(idx);
-        self.remove_buffer_at(idx);
-        if self.active_tab >= self.buffers.len() {
-            self.active_tab = self.buffers.len().saturating_sub(1);
-        } else if self.active_tab > idx {
-            self.active_tab -= 1;
+        cx.notify();
+    }
+
+    fn close_terminal_at(&mut self, idx: usize, cx: &mut Context<Self>) {
+        if idx >= self.terminals.len() {
+            return;
+        }
+        let is_running = self.terminals[idx].read(cx).is_running();
+        if is_running {
+            self.confirm_close_terminal = Some(idx);
+            cx.notify();
+            return;
+        }
+        self.force_close_terminal_at(idx, cx);
+    }
+
+    fn force_close_terminal_at(&mut self, idx: usize, cx: &mut Context<Self>) {
+        if idx >= self.terminals.len() {
+            return;
+        }
+        self.terminals[idx].update(cx, |t, _| t.stop());
+        self.terminals.remove(idx);
+        if self.terminals.is_empty() {
+            self.terminal_fullscreen = false;
+            self.active_terminal = 0;
+        } else if self.active_terminal >= self.terminals.len() {
+            self.active_terminal = self.terminals.len() - 1;
+        }
+        cx.notify();
+    }
+
+    pub fn should_close_window(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.quit_confirmed {
+            return true;
+        }
+        let (unsaved, running) = self.quit_blockers(cx);
+        if unsaved.is_empty() && running == 0 {
+            self.persist_undo_history(cx);
+            self.save_last_session(cx);
+            return true;
+        }
+        self.confirm_quit = true;
+        cx.notify();
+        false
+    }
+
+    fn quit_blockers(&self, cx: &App) -> (Vec<String>, usize) {
+        let unsaved = self
+            .buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.read(cx).is_modified())
+            .map(|(idx, _)| {
+                self.tab_meta
+                    .get(idx)
+                    .and_then(|m| m.file_name.clone())
+                    .unwrap_or_else(|| "Untitled".to_string())
+            })
+            .collect();
+        let running = self
+            .terminals
+            .iter()
+            .filter(|t| t.read(cx).is_running())
+            .count();
+        (unsaved, running)
+    }
+
+    fn save_all_for_quit(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        while let Some(idx) = self.buffers.iter().position(|b| b.read(cx).is_modified()) {
+            let buffer = self.buffers[idx].clone();
+            self.active_tab = idx;
+            self.update_search_editor(cx);
+            let untitled = buffer.read(cx).file_path().is_none()
+                && !self.search_result_buffers.contains_key(&buffer.entity_id());
+            if untitled {
+                self.notify(
+                    "Choose where to save Untitled, then quit again",
+                    ToastSeverity::Info,
+                    cx,
+                );
+                self.write_active(window, cx);
+                return false;
+            }
+            self.quit_after_save = true;
+            self.save_active(window, cx);
+            if self.format_task.is_some() {
+                return false;
+            }
+            self.quit_after_save = false;
+            if buffer.read(cx).is_modified() {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn quit_now(&mut self, save: bool, window: &mut Window, cx: &mut Context<Self>) {
+        self.confirm_quit = false;
+        self.quit_after_save = false;
+        if save && !self.save_all_for_quit(window, cx) {
+            cx.notify();
+            return;
+        }
+        for terminal in &self.terminals {
+            terminal.update(cx, |t, _| t.stop());
+        }
+        self.confirm_quit = false;
+        self.quit_confirmed = true;
+        self.persist_undo_history(cx);
+        self.save_last_session(cx);
+        cx.quit();
+    }
+
+    fn zoom_target(&self, window: &Window, cx: &App) -> ZoomTarget {
+        let focused = self
+            .terminals
+            .iter()
+            .any(|t| t.focus_handle(cx).contains_focused(window, cx));
+        if focused {
+            ZoomTarget::Terminal
+        } else {
+            ZoomTarget::Editor
+        }
+    }
+
+    fn zoom_level(&self, target: ZoomTarget) -> f32 {
+        match target {
+            ZoomTarget::Editor => self.editor_zoom,
+            ZoomTarget::Terminal => self.terminal_zoom,
+        }
+    }
+
+    fn zoom_in(&mut self, target: ZoomTarget, cx: &mut Context<Self>) {
+        self.set_zoom(target, (self.zoom_level(target) + 0.1).min(3.0), cx);
+    }
+
+    fn zoom_out(&mut self, target: ZoomTarget, cx: &mut Context<Self>) {
+        self.set_zoom(target, (self.zoom_level(target) - 0.1).max(0.5), cx);
+    }
+
+    fn zoom_reset(&mut self, target: ZoomTarget, cx: &mut Context<Self>) {
+        self.set_zoom(target, 1.0, cx);
+    }
+
+    fn set_zoom(&mut self, target: ZoomTarget, level: f32, cx: &mut Context<Self>) {
+        match target {
+            ZoomTarget::Editor => {
+                self.editor_zoom = level;
+                let editor_font_size = 14.0 * level;
+                let line_height = self.editor_line_height(editor_font_size);
+                for buffer in &self.buffers {
+                    buffer.update(cx, |state, cx| {
+                        state.set_font_size(editor_font_size, cx);
+                        state.line_height = line_height;
+                    });
+                }
+            }
+            ZoomTarget::Terminal => {
+                self.terminal_zoom = level;
+                let terminal_font_size = 13.0 * level;
+                for terminal in &self.terminals {
+                    terminal.update(cx, |t, _| {
+                        t.set_font_size(terminal_font_size);
+                    });
+                }
+            }
+        }
+        if (self.settings.editor_zoom, self.settings.terminal_zoom)
+            != (self.editor_zoom, self.terminal_zoom)
+        {
+            self.settings.editor_zoom = self.editor_zoom;
+            self.settings.terminal_zoom = self.terminal_zoom;
+            self.settings.save();
+        }
+        cx.notify();
+    }
+
+    fn find_in_active(&mut self, forward: bool, cx: &mut Context<Self>) {
+        if let Some(buffer) = self.buffers.get(self.active_tab) {
+            buffer.update(cx, |state, cx| {
+                if forward {
+                    state.find_next(cx)
+                } else {
+                    state.find_previous(cx)
+                }
+            });
+        }
+    }
+
+    fn toggle_terminal_fullscreen(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.terminals.is_empty() {
+            self.new_terminal(window, cx);
         }
-        self.clamp_tab_scroll();
-        self.update_search_editor(cx);
+        self.terminal_fullscreen = !self.terminal_fullscreen;
         cx.notify();
     }
 
-    fn render_tab_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
-        let ide = use_ide_theme();
-        let chrome = &ide.chrome;
-        let offset = self.tab_scroll_offset;
-        let total = self.buffers.len();
-        let show_left = offset > 0;
-        let show_right = total > 0 && offset < total.saturating_sub(1);
-        let muted_fg = chrome.text_secondary;
-        let active_fg = chrome.bright;
-        let editor_bg = chrome.editor_bg;
-        let border_color = hsla(0.0, 0.0, 1.0, 0.05);
-
-        div()
-            .flex_1()
-            .h_full()
-            .flex()
-            .items_center()
-            .overflow_x_hidden()
-            .child(
-                div()
-                    .id("tab-scroll-left")
-                    .h_full()
-                    .w(px(28.0))
-                    .flex()
-                    .flex_shrink_0()
-                    .items_center()
-                    .justify_center()
-                    .border_r_1()
-                    .border_color(border_color)
-                    .when(show_left, |el| {
-                        el.cursor_pointer()
-                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
-                            .on_click(cx.listener(|this, _, _, cx| {
-                                this.tab_scroll_offset = this.tab_scroll_offset.saturating_sub(1);
-                                cx.notify();
-                            }))
-                            .child(Icon::new("chevron-left").size(px(14.0)).color(muted_fg))
-                    })
-                    .when(!show_left, |el| {
-                        el.child(
-                            Icon::new("chevron-left")
-                                .size(px(14.0))
-                                .color(muted_fg.opacity(0.2)),
-                        )
-                    }),
-            )
-            .child(
-                div()
-                    .flex_1()
-                    .flex()
-                    .items_center()
-                    .overflow_x_hidden()
-                    .children(
-                        self.buffers
-                            .iter()
-                            .enumerate()
-                            .skip(offset)
-                            .map(|(idx, _)| {
-                                let is_active = idx == self.active_tab;
-                                let title = self
-                                    .tab_meta
-                                    .get(idx)
-                                    .map(|meta| meta.title.clone())
-                                    .unwrap_or_else(|| SharedString::from("Untitled"));
-
-                                div()
-                                    .id(ElementId::Name(format!("tab-{}", idx).into()))
-                                    .h_full()
-                                    .flex()
-                                    .flex_shrink_0()
-                                    .items_center()
-                                    .gap(px(6.0))
-                                    .px(px(14.0))
-                                    .cursor_pointer()
-                                    .text_size(px(13.0))
-                                    .border_r_1()
-                                    .border_color(border_color)
-                                    .when(is_active, |el| el.bg(editor_bg).text_color(active_fg))
-                                    .when(!is_active, |el| {
-                                        el.text_color(muted_fg)
-                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
-                                    })
-                                    .on_click(cx.listener(move |this, _, _, cx| {
-                                        this.active_tab = idx;
-                                        this.update_search_editor(cx);
-                                        cx.notify();
-                                    }))
-                                    .child(title)
-                                    .child(
-                                        div()
-                                            .id(ElementId::Name(
-                                                format!("tab-close-{}", idx).into(),
-                                            ))
-                                            .w(px(16.0))
-                                            .h(px(16.0))
-                                            .flex()
-                                            .items_center()
-                                            .justify_center()
-                                            .rounded(px(3.0))
-                                            .text_color(muted_fg)
-                                            .hover(|s| {
-                                                s.bg(hsla(0.0, 0.0, 1.0, 0.1)).text_color(active_fg)
-                                            })
-                                            .on_click(cx.listener(move |this, _, _, cx| {
-                                                this.close_tab_at(idx, cx);
-                                            }))
-                                            .child(Icon::new("x").size(px(12.0)).color(muted_fg)),
-                                    )
-                            }),
-                    )
-                    .child(
-                        div()
-                            .id("new-tab-btn")
-                            .h_full()
-                            .flex()
-                            .flex_shrink_0()
-                            .items_center()
-                            .px(px(6.0))
-                            .cursor_pointer()
-                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
-                            .on_click(cx.listener(|this, _, _, cx| {
-                                this.new_file(cx);
-                            }))
-                            .child(Icon::new("plus").size(px(14.0)).color(muted_fg)),
-                    ),
-            )
-            .child(
-                div()
-                    .id("tab-scroll-right")
-                    .h_full()
-                    .w(px(28.0))
-                    .flex()
-                    .flex_shrink_0()
-                    .items_center()
-                    .justify_center()
-                    .border_l_1()
-                    .border_color(border_color)
-                    .when(show_right, |el| {
-                        el.cursor_pointer()
-                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
-                            .on_click(cx.listener(|this, _, _, cx| {
-                                let max = this.buffers.len().saturating_sub(1);
-                                if this.tab_scroll_offset < max {
-                                    this.tab_scroll_offset += 1;
-                                }
-                                cx.notify();
-                            }))
-                            .child(Icon::new("chevron-right").size(px(14.0)).color(muted_fg))
-                    })
-                    .when(!show_right, |el| {
-                        el.child(
-                            Icon::new("chevron-right")
-                                .size(px(14.0))
-                                .color(muted_fg.opacity(0.2)),
-                        )
-                    }),
-            )
+    fn active_file_path(&self) -> Option<PathBuf> {
+        self.tab_meta
+            .get(self.active_tab)
+            .and_then(|meta| meta.file_path.clone())
     }
 
-    fn render_goto_line(&self, cx: &mut Context<Self>) -> impl IntoElement {
-        let ide = use_ide_theme();
-        let chrome = &ide.chrome;
-        let line_count = self
-            .buffers
-            .get(self.active_tab)
-            .map(|b| b.read(cx).line_count())
-            .unwrap_or(0);
+    fn reveal_in_file_manager(&mut self, path: Option<PathBuf>, cx: &mut Context<Self>) {
+        let Some(path) = path else {
+            self.notify(
+                "Save the file before revealing it".to_string(),
+                ToastSeverity::Info,
+                cx,
+            );
+            return;
+        };
+        if cfg!(target_os = "macos") {
+            if let Err(e) = std::process::Command::new("open")
+                .arg("-R")
+                .arg(&path)
+                .spawn()
+            {
+                self.notify(
+                    format!("Failed to reveal {}: {}", path.display(), e),
+                    ToastSeverity::Error,
+                    cx,
+                );
+            }
+        } else {
+            cx.reveal_path(&path);
+        }
+    }
 
-        div()
-            .w_full()
-            .flex()
-            .items_center()
-            .bg(chrome.panel_bg)
-            .border_b_1()
-            .border_color(chrome.header_border)
-            .px(px(12.0))
-            .py(px(6.0))
-            .gap(px(8.0))
-            .child(
-                div()
-                    .text_size(px(13.0))
-                    .text_color(chrome.text_secondary)
-                    .child("Go to Line:"),
-            )
-            .child(
-                div().w(px(100.0)).child(
-                    Input::new(&self.goto_line_input)
-                        .placeholder("Line #")
-                        .h(px(28.0))
-                        .text_size(px(13.0))
-                        .on_enter({
-                            let goto_input = self.goto_line_input.clone();
-                            let app_entity = cx.entity().clone();
-                            move |_, cx| {
-                                let text = goto_input.read(cx).content().to_string();
-                                if let Ok(line) = text.trim().parse::<usize>() {
-                                    app_entity.update(cx, |this, cx| {
-                                        if let Some(buffer) = this.buffers.get(this.active_tab) {
-                                            buffer.update(cx, |state, ecx| {
-                                                state.goto_line(line, ecx);
-                                            });
-                                        }
-                                    });
-                                }
-                            }
-                        }),
-                ),
-            )
-            .child(
-                div()
-                    .text_size(px(12.0))
-                    .text_color(chrome.text_secondary)
-                    .child(format!("/ {}", line_count)),
-            )
+    fn dismiss_external_open(&mut self) {
+        if !self.pending_external_open.is_empty() {
+            self.pending_external_open.remove(0);
+        }
     }
 
-    pub fn open_folder(&mut self, path: PathBuf, cx: &mut Context<Self>) {
-        let nodes = scan_directory(&path, 2);
-        self.expanded_paths = vec![path.clone()];
-        let git_path = path.clone();
-        self.workspace_root = Some(path.clone());
-        self.file_tree_nodes = nodes;
-        self.active_mode = ViewMode::Explorer;
-        self.panel_visible = true;
-        self.selected_tree_path = None;
-        self.rebuild_file_index(&path);
-        let review_path = path.clone();
-        self.git_state
-            .update(cx, |s, cx| s.set_workspace(git_path, cx));
-        self.review_state
-            .update(cx, |s, cx| s.set_workspace(review_path, cx));
-        self.lsp_registry.set_root(path);
-        self.start_lsp_poll(cx);
-        cx.notify();
+    fn open_externally(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if cfg!(target_os = "macos") {
+            if let Err(e) = std::process::Command::new("open").arg(&path).spawn() {
+                self.notify(
+                    format!("Failed to open {}: {}", path.display(), e),
+                    ToastSeverity::Error,
+                    cx,
+                );
+            }
+        } else {
+            cx.open_with_system(&path);
+        }
     }
 
-    fn rebuild_file_index(&mut self, root: &Path) {
-        let mut index = Vec::new();
-        fn walk_dir(
-            dir: &Path,
-            root: &Path,
-            out: &mut Vec<(PathBuf, String, String)>,
-            depth: usize,
-        ) {
-            if depth > 12 {
-                return;
+    fn open_in_terminal(
+        &mut self,
+        path: Option<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(path) = path else {
+            self.notify(
+                "Save the file before opening it in a terminal".to_string(),
+                ToastSeverity::Info,
+                cx,
+            );
+            return;
+        };
+        let dir = if path.is_dir() {
+            path
+        } else {
+            match path.parent() {
+                Some(parent) => parent.to_path_buf(),
+                None => return,
             }
-            let entries = match std::fs::read_dir(dir) {
-                Ok(e) => e,
-                Err(_) => return,
-            };
-            for entry in entries.flatten() {
-                let path = entry.path();
-                let name = path
-                    .file_name()
-                    .map(|n| n.to_string_lossy().to_string())
-                    .unwrap_or_default();
-                if name.starts_with('.') {
-                    continue;
-                }
-                if path.is_file() {
-                    let rel_dir = path
-                        .parent()
-                        .and_then(|p| p.strip_prefix(root).ok())
-                        .map(|p| p.to_string_lossy().to_string())
-                        .unwrap_or_default();
-                    out.push((path, name, rel_dir));
-                } else if path.is_dir() {
-                    if matches!(
-                        name.as_str(),
-                        "node_modules"
-                            | "target"
-                            | ".git"
-                            | "dist"
-                            | "build"
-                            | "__pycache__"
-                            | ".next"
-                    ) {
-                        continue;
-                    }
-                    walk_dir(&path, root, out, depth + 1);
-                }
+        };
+        self.new_terminal_in(dir, window, cx);
+    }
+
+    fn render_context_menu(
+        &self,
+        menu: &ContextMenuState,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let path = match &menu.target {
+            ContextMenuTarget::Tab(idx) => self
+                .tab_meta
+                .get(*idx)
+                .and_then(|meta| meta.file_path.clone()),
+            ContextMenuTarget::Path(path) => Some(path.clone()),
+            ContextMenuTarget::Terminal(target) => {
+                let items = self.terminal_menu_items(target, cx);
+                return self.render_menu(menu.position, items, cx);
             }
+            ContextMenuTarget::TreeSort => {
+                let items = self.tree_sort_menu_items(cx);
+                return self.render_menu(menu.position, items, cx);
+            }
+            ContextMenuTarget::TreeActions => {
+                let items = self.tree_action_menu_items(cx);
+                return self.render_menu(menu.position, items, cx);
+            }
+        };
+        let has_path = path.is_some();
+        let mut items = Vec::new();
+        if let ContextMenuTarget::Tab(idx) = menu.target {
+            let app = cx.entity().clone();
+            items.push(
+                ContextMenuItem::new("close-tab", "Close Tab").on_click(move |_, cx| {
+                    app.update(cx, |this, cx| this.close_tab_at(idx, cx));
+                }),
+            );
+            items.push(ContextMenuItem::separator());
         }
-        walk_dir(root, root, &mut index, 0);
-        self.file_index = Arc::new(index);
+        let app = cx.entity().clone();
+        let reveal_path = path.clone();
+        items.push(
+            ContextMenuItem::new("reveal", REVEAL_LABEL)
+                .disabled(!has_path)
+                .on_click(move |_, cx| {
+                    let path = reveal_path.clone();
+                    app.update(cx, |this, cx| this.reveal_in_file_manager(path, cx));
+                }),
+        );
+        let app = cx.entity().clone();
+        items.push(
+            ContextMenuItem::new("open-in-terminal", "Open in Terminal")
+                .disabled(!has_path)
+                .on_click({
+                    let path = path.clone();
+                    move |window, cx| {
+                        let path = path.clone();
+                        app.update(cx, |this, cx| this.open_in_terminal(path, window, cx));
+                    }
+                }),
+        );
+        items.push(ContextMenuItem::separator());
+        let full = path.as_ref().map(|p| p.to_string_lossy().to_string());
+        let relative = path.as_ref().map(|p| {
+            let root = self.root_for_path(p).map(|r| r.as_path());
+            crate::file_ops::relative_path(p, root)
+        });
+        for (id, label, text) in [
+            ("copy-path", "Copy Path", full),
+            ("copy-relative-path", "Copy Relative Path", relative),
+        ] {
+            items.push(
+                ContextMenuItem::new(id, label)
+                    .disabled(text.is_none())
+                    .on_click(move |_, cx| {
+                        if let Some(text) = &text {
+                            cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+                        }
+                    }),
+            );
+        }
+        let app = cx.entity().clone();
+        let is_file = path.as_ref().is_some_and(|p| p.is_file());
+        let duplicate_path = path.clone();
+        items.push(
+            ContextMenuItem::new("duplicate", "Duplicate")
+                .disabled(!is_file)
+                .on_click(move |_, cx| {
+                    if let Some(path) = duplicate_path.clone() {
+                        app.update(cx, |this, cx| this.duplicate_file(&path, cx));
+                    }
+                }),
+        );
+        if let ContextMenuTarget::Path(_) = menu.target {
+            let app = cx.entity().clone();
+            let deletable = path
+                .as_ref()
+                .is_some_and(|p| p.exists() && !self.workspace_roots.contains(p));
+            let label = match self.settings.delete_behavior {
+                crate::settings::DeleteBehavior::Trash => "Move to Trash",
+                _ => "Delete",
+            };
+            items.push(ContextMenuItem::separator());
+            items.push(
+                ContextMenuItem::new("delete", label)
+                    .disabled(!deletable)
+                    .on_click(move |_, cx| {
+                        if let Some(path) = path.clone() {
+                            app.update(cx, |this, cx| this.request_delete(path, cx));
+                        }
+                    }),
+            );
+        }
+        self.render_menu(menu.position, items, cx)
     }
 
-    fn trigger_content_search(&mut self, cx: &mut Context<Self>) {
-        self.search_version += 1;
-        let version = self.search_version;
-        let query = self.file_search_query.clone();
-        let index = self.file_index.clone();
-        cx.notify();
-
-        cx.spawn(async move |this, cx| {
-            Timer::after(Duration::from_millis(200)).await;
+    fn request_delete(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        use crate::settings::DeleteBehavior;
+        let behavior = self.settings.delete_behavior;
+        let trash = behavior == DeleteBehavior::Trash;
+        if behavior == DeleteBehavior::Confirm || !self.dirty_tabs_under(&path, cx).is_empty() {
+            self.confirm_delete_path = Some((path, trash));
+            cx.notify();
+        } else {
+            self.delete_path(path, trash, cx);
+        }
+    }
 
-            let still_current = cx
-                .update(|cx| {
-                    this.update(cx, |this, _| this.search_version == version)
-                        .unwrap_or(false)
-                })
-                .unwrap_or(false);
-            if !still_current {
-                return;
-            }
+    fn confirm_delete(&mut self, path: PathBuf, trash: bool, cx: &mut Context<Self>) {
+        if trash && !self.save_tabs_under(&path, cx) {
+            return;
+        }
+        self.delete_path(path, trash, cx);
+    }
 
-            let results = smol::unblock(move || search_content(&query, &index)).await;
+    fn dirty_tabs_under(&self, path: &Path, cx: &App) -> Vec<usize> {
+        self.buffers
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| {
+                let state = buffer.read(cx);
+                state.is_modified() && state.file_path().is_some_and(|p| p.starts_with(path))
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
 
-            let _ = cx.update(|cx| {
-                let _ = this.update(cx, |this, cx| {
-                    if this.search_version == version {
-                        this.file_search_results = results;
-                        cx.notify();
-                    }
-                });
+    fn save_tabs_under(&mut self, path: &Path, cx: &mut Context<Self>) -> bool {
+        for idx in self.dirty_tabs_under(path, cx) {
+            let buffer = self.buffers[idx].clone();
+            let failed = buffer.update(cx, |state, cx| {
+                let file = state.file_path().cloned()?;
+                (!state.save_to_file(&file, cx)).then_some(file)
             });
-        })
-        .detach();
+            if let Some(file) = failed {
+                self.notify(
+                    format!("Failed to save {}", file.display()),
+                    ToastSeverity::Error,
+                    cx,
+                );
+                return false;
+            }
+        }
+        true
     }
 
-    fn open_folder_dialog(&mut self, cx: &mut Context<Self>) {
-        let rx = cx.prompt_for_paths(PathPromptOptions {
-            files: false,
-            directories: true,
-            multiple: false,
-            prompt: None,
-        });
+    fn delete_path(&mut self, path: PathBuf, trash: bool, cx: &mut Context<Self>) {
+        let target = path.clone();
         cx.spawn(async move |this, cx| {
-            if let Ok(Ok(Some(paths))) = rx.await {
-                if let Some(path) = paths.into_iter().next() {
-                    let _ = cx.update(|cx| {
-                        let _ = this.update(cx, |this, cx| {
-                            this.open_folder(path, cx);
-                        });
-                    });
+            let result = smol::unblock(move || {
+                if trash {
+                    crate::file_ops::move_to_trash(&target)
+                } else {
+                    crate::file_ops::delete_path(&target)
                 }
-            }
+            })
+            .await;
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |this, cx| match result {
+                    Ok(()) => this.finish_delete(&path, trash, cx),
+                    Err(err) => this.notify(err.to_string(), ToastSeverity::Error, cx),
+                });
+            });
         })
         .detach();
     }
 
-    fn toggle_terminal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if self.active_mode == ViewMode::Terminal {
-            self.active_mode = ViewMode::Explorer;
-            self.panel_visible = true;
+    fn finish_delete(&mut self, path: &Path, trash: bool, cx: &mut Context<Self>) {
+        let open: Vec<usize> = self
+            .tab_meta
+            .iter()
+            .enumerate()
+            .filter(|(_, meta)| meta.file_path.as_ref().is_some_and(|p| p.starts_with(path)))
+            .map(|(idx, _)| idx)
+            .collect();
+        for idx in open.into_iter().rev() {
+            self.close_tab_at(idx, cx);
+        }
+        self.undo_history.forget(path);
+        self.undo_history.save();
+        self.selected_tree_path.take_if(|p| p.starts_with(path));
+        self.expanded_paths.retain(|p| !p.starts_with(path));
+        self.refresh_file_tree();
+        self.persist_expanded_paths();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let message = if trash {
+            format!("Moved {} to the trash", name)
         } else {
-            self.active_mode = ViewMode::Terminal;
-            self.panel_visible = true;
-            if self.terminals.is_empty() {
-                self.new_terminal(window, cx);
-                return;
+            format!("Deleted {}", name)
+        };
+        self.notify(message, ToastSeverity::Info, cx);
+    }
+
+    fn duplicate_file(&mut self, path: &Path, cx: &mut Context<Self>) {
+        match crate::file_ops::duplicate_file(path) {
+            Ok(copy) => {
+                self.refresh_file_tree();
+                self.selected_tree_path = Some(copy.clone());
+                self.open_paths(vec![copy], cx);
             }
+            Err(err) => self.notify(err.to_string(), ToastSeverity::Error, cx),
         }
-        cx.notify();
     }
 
-    fn new_terminal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let working_dir = self.current_working_directory();
-        let zoom = self.zoom_level;
-        let font = self.settings.terminal_font.clone();
-        let font_fallback = self.settings.terminal_font_fallback.clone();
-        let terminal = cx.new(|cx| TerminalView::new(cx).with_working_directory(working_dir));
-        terminal.update(cx, |t, cx| {
-            t.set_font_family(font);
-            t.set_font_fallback(font_fallback);
-            if (zoom - 1.0).abs() > f32::EPSILON {
-                t.set_font_size(13.0 * zoom);
-            }
-            let _ = t.start_with_polling(window, cx);
-        });
-        self.terminals.push(terminal);
-        self.active_terminal = self.terminals.len() - 1;
-        self.active_mode = ViewMode::Terminal;
-        self.panel_visible = true;
-        cx.notify();
+    fn refresh_file_tree(&mut self) {
+        self.file_tree_nodes = self.scan_workspace_roots();
+        self.restore_expanded_paths(self.expanded_paths.clone());
+        self.rebuild_file_index();
     }
 
-    fn close_terminal_at(&mut self, idx: usize, cx: &mut Context<Self>) {
-        if idx >= self.terminals.len() {
-            return;
-        }
-        let is_running = self.terminals[idx].read(cx).is_running();
-        if is_running {
-            self.confirm_close_terminal = Some(idx);
-            cx.notify();
-            return;
-        }
-        self.force_close_terminal_at(idx, cx);
+    fn tree_sort_menu_items(&self, cx: &mut Context<Self>) -> Vec<ContextMenuItem> {
+        let mut items: Vec<ContextMenuItem> = crate::settings::TreeSort::ALL
+            .into_iter()
+            .map(|sort| {
+                let app = cx.entity().clone();
+                let item = ContextMenuItem::new(sort.label(), sort.label());
+                let item = if self.settings.tree_sort == sort {
+                    item.icon("check")
+                } else {
+                    item
+                };
+                item.on_click(move |_, cx| {
+                    app.update(cx, |this, cx| {
+                        this.settings.tree_sort = sort;
+                        this.settings.save();
+                        cx.notify();
+                    });
+                })
+            })
+            .collect();
+        items.push(ContextMenuItem::separator());
+        let app = cx.entity().clone();
+        let folders_first = ContextMenuItem::new("folders-first", "Folders First");
+        let folders_first = if self.settings.tree_folders_first {
+            folders_first.icon("check")
+        } else {
+            folders_first
+        };
+        items.push(folders_first.on_click(move |_, cx| {
+            app.update(cx, |this, cx| {
+                this.settings.tree_folders_first = !this.settings.tree_folders_first;
+                this.settings.save();
+                cx.notify();
+            });
+        }));
+        items
     }
 
-    fn force_close_terminal_at(&mut self, idx: usize, cx: &mut Context<Self>) {
-        if idx >= self.terminals.len() {
-            return;
-        }
-        self.terminals[idx].update(cx, |t, _| t.stop());
-        self.terminals.remove(idx);
-        if self.terminals.is_empty() {
-            self.terminal_fullscreen = false;
-            self.active_terminal = 0;
-        } else if self.active_terminal >= self.terminals.len() {
-            self.active_terminal = self.terminals.len() - 1;
+    fn visible_tree(&self) -> (Vec<FileNode>, Vec<PathBuf>) {
+        if self.tree_filter_query.is_empty() {
+            (self.file_tree_nodes.clone(), self.expanded_paths.clone())
+        } else {
+            crate::explorer_tree::filter_tree(
+                &self.file_tree_nodes,
+                self.file_index.iter().map(|(path, _, _)| path.as_path()),
+                &self.tree_filter_query,
+            )
         }
-        cx.notify();
     }
 
-    fn zoom_in(&mut self, cx: &mut Context<Self>) {
-        self.set_zoom((self.zoom_level + 0.1).min(3.0), cx);
+    fn render_tree_filter(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let chrome = use_ide_theme().chrome;
+        let app_change = cx.entity().clone();
+        let app_clear = cx.entity().clone();
+        div().px(px(16.0)).py(px(6.0)).child(
+            Input::new(&self.tree_filter_input)
+                .placeholder("Filter by name...")
+                .when(!self.tree_filter_query.is_empty(), |input| {
+                    input.suffix(
+                        div()
+                            .id("clear-tree-filter")
+                            .cursor_pointer()
+                            .child(Icon::new("x").size(px(12.0)).color(chrome.text_secondary))
+                            .on_mouse_down(MouseButton::Left, move |_, _, cx| {
+                                app_clear.update(cx, |this, cx| {
+                                    this.tree_filter_query.clear();
+                                    this.tree_filter_input.update(cx, |input, cx| {
+                                        input.content = SharedString::default();
+                                        cx.notify();
+                                    });
+                                    cx.notify();
+                                });
+                            })
+                            .into_any_element(),
+                    )
+                })
+                .bg(chrome.editor_bg)
+                .rounded(px(8.0))
+                .text_size(px(12.0))
+                .on_change(move |text: SharedString, cx: &mut App| {
+                    app_change.update(cx, |this, cx| {
+                        this.tree_filter_query = text.trim().to_string();
+                        cx.notify();
+                    });
+                }),
+        )
+    }
+
+    fn render_tree_header_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let chrome = use_ide_theme().chrome;
+        let open_menu = |target: fn() -> ContextMenuTarget| {
+            cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                this.context_menu = Some(ContextMenuState {
+                    position: event.position,
+                    target: target(),
+                });
+                cx.stop_propagation();
+                cx.notify();
+            })
+        };
+        div()
+            .flex()
+            .items_center()
+            .gap(px(4.0))
+            .child(
+                div()
+                    .id("tree-sort")
+                    .flex()
+                    .items_center()
+                    .gap(px(2.0))
+                    .px(px(4.0))
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .text_xs()
+                    .text_color(chrome.text_secondary)
+                    .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                    .child(self.settings.tree_sort.label())
+                    .child(Icon::new("chevron-down").size(px(12.0)))
+                    .on_mouse_down(MouseButton::Left, open_menu(|| ContextMenuTarget::TreeSort)),
+            )
+            .child(
+                div()
+                    .id("tree-actions")
+                    .p(px(2.0))
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                    .child(
+                        Icon::new("ellipsis")
+                            .size(px(14.0))
+                            .color(chrome.text_secondary),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        open_menu(|| ContextMenuTarget::TreeActions),
+                    ),
+            )
+    }
+
+    fn tree_action_menu_items(&self, cx: &mut Context<Self>) -> Vec<ContextMenuItem> {
+        let app = cx.entity().clone();
+        let mut items = vec![
+            ContextMenuItem::new("collapse-all", "Collapse All").on_click(move |_, cx| {
+                app.update(cx, |this, cx| this.collapse_all_folders(cx));
+            }),
+            ContextMenuItem::separator(),
+        ];
+        for level in 1..=3 {
+            let app = cx.entity().clone();
+            let label = format!("Expand to Level {}", level);
+            items.push(
+                ContextMenuItem::new(label.clone(), label).on_click(move |_, cx| {
+                    app.update(cx, |this, cx| this.expand_to_level(level, cx));
+                }),
+            );
+        }
+        items
     }
 
-    fn zoom_out(&mut self, cx: &mut Context<Self>) {
-        self.set_zoom((self.zoom_level - 0.1).max(0.5), cx);
+    fn terminal_menu_items(
+        &self,
+        target: &TerminalMenuTarget,
+        cx: &mut Context<Self>,
+    ) -> Vec<ContextMenuItem> {
+        let app = cx.entity().clone();
+        match target.clone() {
+            TerminalMenuTarget::Url(url) => {
+                let copy = url.clone();
+                vec![
+                    ContextMenuItem::new("terminal-open-link", "Open Link").on_click(
+                        move |_, cx| app.update(cx, |this, cx| this.open_terminal_link(&url, cx)),
+                    ),
+                    ContextMenuItem::new("terminal-copy-link", "Copy Link").on_click(
+                        move |_, cx| cx.write_to_clipboard(ClipboardItem::new_string(copy.clone())),
+                    ),
+                ]
+            }
+            TerminalMenuTarget::Path(path) => {
+                let copy = path.display().to_string();
+                let reveal = path.clone();
+                let reveal_app = app.clone();
+                vec![
+                    ContextMenuItem::new("terminal-open-path", "Open").on_click(move |_, cx| {
+                        let path = path.clone();
+                        app.update(cx, |this, cx| {
+                            if path.is_dir() {
+                                this.reveal_in_explorer(path, cx);
+                            } else {
+                                this.open_paths(vec![path], cx);
+                            }
+                        });
+                    }),
+                    ContextMenuItem::new("terminal-copy-path", "Copy Path").on_click(
+                        move |_, cx| cx.write_to_clipboard(ClipboardItem::new_string(copy.clone())),
+                    ),
+                    ContextMenuItem::new("terminal-reveal-path", "Reveal in Explorer").on_click(
+                        move |_, cx| {
+                            let path = reveal.clone();
+                            reveal_app.update(cx, |this, cx| this.reveal_in_explorer(path, cx));
+                        },
+                    ),
+                ]
+            }
+            TerminalMenuTarget::Selection(text) => {
+                let copy = text.clone();
+                vec![
+                    ContextMenuItem::new("terminal-copy", "Copy").on_click(move |_, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(copy.clone()))
+                    }),
+                    ContextMenuItem::new("terminal-search-workspace", "Search in Workspace")
+                        .on_click(move |window, cx| {
+                            let query = text.clone();
+                            app.update(cx, |this, cx| this.search_in_workspace(&query, window, cx));
+                        }),
+                ]
+            }
+        }
     }
 
-    fn zoom_reset(&mut self, cx: &mut Context<Self>) {
-        self.set_zoom(1.0, cx);
+    fn open_terminal_link(&mut self, url: &str, cx: &mut Context<Self>) {
+        let workspace_path = url::Url::parse(url)
+            .ok()
+            .filter(|u| u.scheme() == "file")
+            .and_then(|u| u.to_file_path().ok())
+            .filter(|path| path.exists() && self.root_for_path(path).is_some());
+        match workspace_path {
+            Some(path) if path.is_dir() => self.reveal_in_explorer(path, cx),
+            Some(path) => self.open_paths(vec![path], cx),
+            None => cx.open_url(url),
+        }
     }
 
-    fn set_zoom(&mut self, level: f32, cx: &mut Context<Self>) {
-        self.zoom_level = level;
-        let editor_font_size = 14.0 * self.zoom_level;
-        for buffer in &self.buffers {
-            buffer.update(cx, |state, cx| {
-                state.set_font_size(editor_font_size, cx);
-            });
+    fn reveal_in_explorer(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        for dir in path.ancestors().skip(1) {
+            if self.root_for_path(dir).is_none() {
+                break;
+            }
+            if !self.expanded_paths.iter().any(|p| p == dir) {
+                self.expanded_paths.push(dir.to_path_buf());
+            }
         }
-        let terminal_font_size = 13.0 * self.zoom_level;
-        for terminal in &self.terminals {
-            terminal.update(cx, |t, _| {
-                t.set_font_size(terminal_font_size);
-            });
+        for dir in path.ancestors().collect::<Vec<_>>().into_iter().rev() {
+            load_children_if_needed(&mut self.file_tree_nodes, dir);
         }
+        self.selected_tree_path = Some(path);
+        self.active_mode = ViewMode::Explorer;
+        self.panel_visible = true;
         cx.notify();
     }
 
-    fn toggle_terminal_fullscreen(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        if self.terminals.is_empty() {
-            self.new_terminal(window, cx);
+    fn search_in_workspace(&mut self, text: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let query = text.lines().next().unwrap_or("").trim().to_string();
+        if query.is_empty() {
+            return;
         }
-        self.terminal_fullscreen = !self.terminal_fullscreen;
-        cx.notify();
+        self.active_mode = ViewMode::Explorer;
+        self.panel_visible = true;
+        self.file_search_input.update(cx, |input, cx| {
+            input.set_value(SharedString::from(query.clone()), window, cx);
+        });
+        self.file_search_query = query;
+        self.content_search_history.sync(&self.file_search_query);
+        self.trigger_content_search(cx);
+    }
+
+    fn render_menu(
+        &self,
+        position: Point<Pixels>,
+        items: Vec<ContextMenuItem>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let app = cx.entity().clone();
+        deferred(
+            ContextMenu::new(position)
+                .items(items)
+                .on_close(move |_, cx| {
+                    app.update(cx, |this, cx| {
+                        this.context_menu = None;
+                        cx.notify();
+                    });
+                }),
+        )
+        .with_priority(1)
     }
 
-
     fn current_working_directory(&self) -> PathBuf {
         if let Some(meta) = self.tab_meta.get(self.active_tab) {
             if let Some(path) = &meta.file_path {
@@ -2025,29 +8140,140 @@ impl AppState {
                 }
             }
         }
-        if let Some(root) = &self.workspace_root {
+        if let Some(root) = self.workspace_root() {
             return root.clone();
         }
         std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"))
     }
 
-    fn render_image_preview(path: &Path, ide: &IdeTheme) -> Div {
-        let path_str: SharedString = path.to_string_lossy().into_owned().into();
+    fn toggle_svg_source(&mut self, cx: &mut Context<Self>) {
+        let Some(meta) = self.tab_meta.get_mut(self.active_tab) else {
+            return;
+        };
+        if !meta.file_path.as_deref().is_some_and(is_svg_file) {
+            return;
+        }
+        meta.is_image = !meta.is_image;
+        self.update_search_editor(cx);
+        cx.notify();
+    }
+
+    fn render_svg_toggle(&self, showing_source: bool, cx: &mut Context<Self>) -> impl IntoElement {
+        let chrome = use_ide_theme().chrome;
+        div()
+            .id("svg-source-toggle")
+            .absolute()
+            .top(px(8.0))
+            .right(px(16.0))
+            .flex()
+            .items_center()
+            .gap(px(6.0))
+            .px(px(10.0))
+            .py(px(4.0))
+            .rounded(px(6.0))
+            .bg(chrome.panel_bg)
+            .border_1()
+            .border_color(chrome.header_border)
+            .text_size(px(12.0))
+            .text_color(chrome.text_secondary)
+            .cursor_pointer()
+            .hover(|s| s.text_color(chrome.bright))
+            .on_click(cx.listener(|this, _, _, cx| this.toggle_svg_source(cx)))
+            .child(
+                Icon::new(if showing_source { "image" } else { "file-code" })
+                    .size(px(14.0))
+                    .color(chrome.text_secondary),
+            )
+            .child(if showing_source { "Preview" } else { "Source" })
+    }
+
+    fn render_status_bar(&self, cx: &mut Context<Self>) -> Option<Div> {
+        let meta = self.tab_meta.get(self.active_tab)?;
+        if meta.is_image {
+            return None;
+        }
+        let buffer = self.buffers.get(self.active_tab)?;
+        let chrome = use_ide_theme().chrome;
+        let cursor = buffer.read(cx).cursor();
+        let missing_eol = meta.stats.missing_eol;
+        let col = if cursor.line == 0 && meta.has_bom {
+            cursor.col.saturating_sub(BOM.len_utf8())
+        } else {
+            cursor.col
+        };
+        let encoding = div()
+            .id("status-encoding")
+            .px(px(6.0))
+            .rounded(px(3.0))
+            .child(if meta.has_bom {
+                "UTF-8 with BOM"
+            } else {
+                "UTF-8"
+            })
+            .when(meta.has_bom, |el| {
+                el.cursor_pointer()
+                    .text_color(hsla(0.12, 0.9, 0.65, 1.0))
+                    .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                    .on_click(cx.listener(|this, _, window, cx| this.remove_bom(window, cx)))
+            });
+        let is_read_only = meta.read_only_content.is_some();
+        let flashing = is_read_only && self.read_only_flash.is_some();
+        let read_only = div()
+            .id("status-read-only")
+            .px(px(6.0))
+            .rounded(px(3.0))
+            .cursor_pointer()
+            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+            .when(is_read_only, |el| el.text_color(hsla(0.12, 0.9, 0.65, 1.0)))
+            .when(flashing, |el| el.bg(hsla(0.12, 0.9, 0.65, 0.2)))
+            .child(if is_read_only {
+                "Read-only"
+            } else {
+                "Editable"
+            })
+            .on_click(cx.listener(|this, _, _, cx| this.toggle_read_only(cx)));
+        Some(
+            div()
+                .w_full()
+                .h(px(22.0))
+                .flex()
+                .flex_shrink_0()
+                .items_center()
+                .justify_end()
+                .gap(px(12.0))
+                .px(px(12.0))
+                .border_t_1()
+                .border_color(hsla(0.0, 0.0, 1.0, 0.05))
+                .bg(chrome.panel_bg.opacity(0.5))
+                .text_size(px(11.0))
+                .text_color(chrome.text_secondary)
+                .children(self.lsp_registry.progress_label())
+                .child(read_only)
+                .children(
+                    self.active_multi_edit(cx)
+                        .map(|s| format!("{} cursors", s.edit.cursor_count())),
+                )
+                .child(document_stats(buffer.read(cx), meta.stats.len))
+                .when(missing_eol, |el| {
+                    el.child(
+                        div()
+                            .id("status-no-eol")
+                            .text_color(chrome.text_secondary.opacity(0.6))
+                            .child("No EOL"),
+                    )
+                })
+                .child(format!("Ln {}, Col {}", cursor.line + 1, col + 1))
+                .child(encoding),
+        )
+    }
+
+    fn render_image_preview(path: &Path, source: ImageSource, ide: &IdeTheme) -> Div {
         let file_name = path
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
         let file_size = std::fs::metadata(path)
-            .map(|m| {
-                let bytes = m.len();
-                if bytes < 1024 {
-                    format!("{} B", bytes)
-                } else if bytes < 1024 * 1024 {
-                    format!("{:.1} KB", bytes as f64 / 1024.0)
-                } else {
-                    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
-                }
-            })
+            .map(|m| format_file_size(m.len()))
             .unwrap_or_default();
 
         div()
@@ -2067,7 +8293,7 @@ impl AppState {
                     .gap(px(12.0))
                     .p(px(24.0))
                     .child(
-                        img(path_str)
+                        img(source)
                             .max_w(px(760.0))
                             .max_h(px(600.0))
                             .object_fit(ObjectFit::Contain),
@@ -2096,35 +8322,28 @@ impl AppState {
     fn render_symbol_outline(&self, cx: &mut Context<Self>) -> impl IntoElement {
         let ide = use_ide_theme();
 
-        let symbols: Vec<(String, String, usize)> =
-            if let Some(buffer) = self.buffers.get(self.active_tab) {
+        let symbols = self
+            .buffers
+            .get(self.active_tab)
+            .and_then(|buffer| {
                 let state = buffer.read(cx);
-                if let (Some(tree), content) = (state.syntax_tree(), state.content()) {
-                    let syms = extract_symbols(tree, &content, state.language());
-                    syms.into_iter()
-                        .map(|s| {
-                            let kind_label = format!("{:?}", s.kind);
-                            (s.name, kind_label, 0)
-                        })
-                        .collect()
-                } else {
-                    Vec::new()
-                }
-            } else {
-                Vec::new()
-            };
+                let tree = state.syntax_tree()?;
+                Some(extract_outline(tree, &state.content(), state.language()))
+            })
+            .unwrap_or_default();
 
         let filter = self.symbol_outline_filter.to_lowercase();
         let filtered: Vec<_> = symbols
             .into_iter()
-            .filter(|(name, _, _)| filter.is_empty() || name.to_lowercase().contains(&filter))
+            .filter(|s| filter.is_empty() || s.name.to_lowercase().contains(&filter))
             .collect();
 
         let app_entity = cx.entity().clone();
+        let first = filtered.first().map(|s| (s.line, s.col));
 
         let mut list = div().flex_col().gap(px(1.0));
-        for (name, kind, _line) in filtered {
-            let name_clone = name.clone();
+        for symbol in filtered {
+            let (line, col) = (symbol.line, symbol.col);
             let app_e = app_entity.clone();
             list = list.child(
                 div()
@@ -2136,36 +8355,22 @@ impl AppState {
                     .cursor_pointer()
                     .rounded(px(3.0))
                     .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
-                    .on_mouse_down(MouseButton::Left, move |_, _, cx| {
-                        let search_name = name_clone.clone();
+                    .on_mouse_down(MouseButton::Left, move |_, window, cx| {
                         app_e.update(cx, |this, cx| {
-                            if let Some(buffer) = this.buffers.get(this.active_tab).cloned() {
-                                let target_line = {
-                                    let state = buffer.read(cx);
-                                    let content = state.content();
-                                    content.find(&search_name).map(|pos| {
-                                        content[..pos].chars().filter(|&c| c == '\n').count()
-                                    })
-                                };
-                                if let Some(line) = target_line {
-                                    buffer.update(cx, |s, cx| s.goto_line(line, cx));
-                                }
-                            }
-                            this.symbol_outline_visible = false;
-                            cx.notify();
+                            this.jump_to_outline_symbol(line, col, window, cx);
                         });
                     })
                     .child(
                         div()
                             .text_size(px(11.0))
                             .text_color(ide.syntax.keyword.opacity(0.7))
-                            .child(kind),
+                            .child(symbol.kind.label()),
                     )
                     .child(
                         div()
                             .text_size(px(13.0))
                             .text_color(ide.chrome.bright)
-                            .child(name),
+                            .child(symbol.name),
                     ),
             );
         }
@@ -2188,6 +8393,16 @@ impl AppState {
             .flex_col()
             .gap(px(4.0))
             .text_size(px(13.0))
+            .occlude()
+            .on_mouse_down_out(cx.listener(|this, _, _, cx| {
+                this.symbol_outline_visible = false;
+                cx.notify();
+            }))
+            .capture_action(cx.listener(
+                |this, _: &adabraka_ui::components::input::Escape, window, cx| {
+                    this.close_symbol_outline(window, cx);
+                },
+            ))
             .child(
                 div()
                     .text_size(px(11.0))
@@ -2195,10 +8410,71 @@ impl AppState {
                     .pb(px(4.0))
                     .child("Symbol Outline"),
             )
+            .child(
+                Input::new(&self.symbol_outline_input)
+                    .placeholder("Filter symbols...")
+                    .h(px(26.0))
+                    .text_size(px(12.0))
+                    .on_change({
+                        let app = app_entity.clone();
+                        move |text: SharedString, cx: &mut App| {
+                            app.update(cx, |this, cx| {
+                                this.symbol_outline_filter = text.to_string();
+                                cx.notify();
+                            });
+                        }
+                    })
+                    .on_enter(move |_, cx: &mut App| {
+                        let Some((line, col)) = first else {
+                            return;
+                        };
+                        if let Some(window) = cx.active_window() {
+                            let app = app_entity.clone();
+                            let _ = window.update(cx, |_, window, cx| {
+                                app.update(cx, |this, cx| {
+                                    this.jump_to_outline_symbol(line, col, window, cx);
+                                });
+                            });
+                        }
+                    }),
+            )
             .child(list)
     }
 
-    fn render_welcome(&self, ide: &IdeTheme) -> impl IntoElement {
+    fn toggle_symbol_outline(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.symbol_outline_visible {
+            self.close_symbol_outline(window, cx);
+            return;
+        }
+        self.symbol_outline_visible = true;
+        self.symbol_outline_filter.clear();
+        self.symbol_outline_input.update(cx, |input, cx| {
+            input.set_value(SharedString::default(), window, cx);
+        });
+        let focus = self.symbol_outline_input.read(cx).focus_handle(cx);
+        window.focus(&focus);
+        cx.notify();
+    }
+
+    fn close_symbol_outline(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.symbol_outline_visible = false;
+        self.focus_active_editor(window, cx);
+    }
+
+    fn jump_to_outline_symbol(
+        &mut self,
+        line: usize,
+        col: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(buffer) = self.buffers.get(self.active_tab).cloned() {
+            buffer.update(cx, |s, cx| s.set_cursor_position(line, col, cx));
+        }
+        self.close_symbol_outline(window, cx);
+    }
+
+    fn render_welcome(&self, ide: &IdeTheme, cx: &mut Context<Self>) -> impl IntoElement {
         use adabraka_ui::animations::easings;
         use adabraka_ui::components::gradient_text::GradientText;
 
@@ -2268,6 +8544,160 @@ impl AppState {
             .child(title)
             .child(subtitle)
             .child(shortcuts)
+            .child(self.render_welcome_recent(ide, cx))
+    }
+
+    fn startup_folder_candidates(&self) -> Vec<PathBuf> {
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        let known = self
+            .workspace_roots
+            .iter()
+            .chain(&self.settings.favorite_folders)
+            .chain(&self.settings.recent_folders)
+            .chain(&self.settings.startup_folder);
+        for folder in known {
+            if folder.is_dir() && !candidates.contains(folder) {
+                candidates.push(folder.clone());
+            }
+        }
+        candidates
+    }
+
+    fn step_startup_folder(&mut self, delta: i32) {
+        let candidates = self.startup_folder_candidates();
+        if candidates.is_empty() {
+            return;
+        }
+        let current = self.settings.startup_folder.as_ref();
+        let idx = candidates.iter().position(|f| Some(f) == current);
+        let next = match idx {
+            Some(idx) => (idx as i32 + delta).rem_euclid(candidates.len() as i32) as usize,
+            None => 0,
+        };
+        self.settings.startup_folder = Some(candidates[next].clone());
+    }
+
+    fn choose_startup_folder_dialog(&mut self, cx: &mut Context<Self>) {
+        let rx = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            if let Ok(Ok(Some(paths))) = rx.await {
+                if let Some(path) = paths.into_iter().next() {
+                    let _ = cx.update(|cx| {
+                        let _ = this.update(cx, |this, cx| {
+                            this.settings.startup = crate::settings::StartupBehavior::Folder;
+                            this.settings.startup_folder = Some(path);
+                            this.settings.save();
+                            cx.notify();
+                        });
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn startup_label(&self) -> String {
+        let label = self.settings.startup.label();
+        let folder = self
+            .settings
+            .startup_folder
+            .as_ref()
+            .and_then(|f| f.file_name())
+            .filter(|_| self.settings.startup == crate::settings::StartupBehavior::Folder);
+        match folder {
+            Some(name) => format!("{}: {}", label, name.to_string_lossy()),
+            None => label.to_string(),
+        }
+    }
+
+    fn render_welcome_recent(&self, ide: &IdeTheme, cx: &mut Context<Self>) -> impl IntoElement {
+        let chrome = &ide.chrome;
+        let folders: Vec<PathBuf> = self
+            .settings
+            .recent_folders
+            .iter()
+            .take(5)
+            .cloned()
+            .collect();
+        let files: Vec<PathBuf> = self.settings.recent_files.iter().take(5).cloned().collect();
+        let section = |title: &'static str| {
+            div().flex().flex_col().gap(px(2.0)).w(px(260.0)).child(
+                div()
+                    .mb(px(4.0))
+                    .px(px(8.0))
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(chrome.text_secondary)
+                    .child(title),
+            )
+        };
+        let row = |id: String, path: &Path, icon: &'static str| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string());
+            let parent = path
+                .parent()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            div()
+                .id(SharedString::from(id))
+                .flex()
+                .items_center()
+                .gap(px(8.0))
+                .px(px(8.0))
+                .py(px(4.0))
+                .rounded(px(6.0))
+                .cursor_pointer()
+                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                .child(Icon::new(icon).size(px(14.0)).color(chrome.text_secondary))
+                .child(
+                    div()
+                        .text_size(px(13.0))
+                        .text_color(chrome.bright)
+                        .child(name),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .whitespace_nowrap()
+                        .text_size(px(11.0))
+                        .text_color(chrome.text_secondary.opacity(0.6))
+                        .child(parent),
+                )
+        };
+
+        let has_folders = !folders.is_empty();
+        let mut folder_section = section("RECENT FOLDERS");
+        for (i, folder) in folders.into_iter().enumerate() {
+            let el = row(format!("welcome-folder-{}", i), &folder, "folder");
+            let el = el.on_click(cx.listener(move |this, _, _, cx| {
+                this.open_folder(folder.clone(), cx);
+            }));
+            folder_section = folder_section.child(el);
+        }
+        let has_files = !files.is_empty();
+        let mut file_section = section("RECENT FILES");
+        for (i, file) in files.into_iter().enumerate() {
+            let el = row(format!("welcome-file-{}", i), &file, "file-text");
+            let el = el.on_click(cx.listener(move |this, _, _, cx| {
+                this.open_paths(vec![file.clone()], cx);
+            }));
+            file_section = file_section.child(el);
+        }
+        div()
+            .mt(px(32.0))
+            .flex()
+            .gap(px(32.0))
+            .when(has_folders, |d| d.child(folder_section))
+            .when(has_files, |d| d.child(file_section))
     }
 
     fn render_icon_sidebar(&self, cx: &mut Context<Self>) -> impl IntoElement {
@@ -2495,7 +8925,7 @@ impl AppState {
         let ide = use_ide_theme();
         let chrome = &ide.chrome;
 
-        if self.workspace_root.is_none() {
+        if self.workspace_roots.is_empty() {
             let app_entity_open = cx.entity().clone();
             return div()
                 .size_full()
@@ -2560,12 +8990,16 @@ impl AppState {
 
         let app_entity = cx.entity().clone();
         let app_entity2 = cx.entity().clone();
+        let app_entity_menu = cx.entity().clone();
         let app_entity_search = cx.entity().clone();
         let app_entity_clear = cx.entity().clone();
 
-        let mut tree = FileTree::new()
-            .nodes(self.file_tree_nodes.clone())
-            .expanded_paths(self.expanded_paths.clone());
+        let (tree_nodes, tree_expanded) = self.visible_tree();
+        let mut tree = ExplorerTree::new()
+            .nodes(tree_nodes.clone())
+            .sort(self.settings.tree_sort, self.settings.tree_folders_first)
+            .expanded_paths(tree_expanded.clone())
+            .git_status(self.explorer_git_status(cx));
         if let Some(path) = &self.selected_tree_path {
             tree = tree.selected_path(path.clone());
         }
@@ -2595,6 +9029,9 @@ impl AppState {
                 move |path, expanding, _, cx| {
                     let path = path.clone();
                     app_entity2.update(cx, |this, cx| {
+                        if !this.tree_filter_query.is_empty() {
+                            return;
+                        }
                         if expanding {
                             if !this.expanded_paths.contains(&path) {
                                 this.expanded_paths.push(path.clone());
@@ -2603,6 +9040,30 @@ impl AppState {
                         } else {
                             this.expanded_paths.retain(|p| p != &path);
                         }
+                        this.persist_expanded_paths();
+                        cx.notify();
+                    });
+                }
+            })
+            .on_collapse_recursive({
+                let app = cx.entity().clone();
+                move |path, _, cx| {
+                    app.update(cx, |this, cx| {
+                        if this.tree_filter_query.is_empty() {
+                            this.collapse_folder_recursive(path, cx);
+                        }
+                    });
+                }
+            })
+            .on_context_menu({
+                move |path, position, _, cx| {
+                    let path = path.clone();
+                    app_entity_menu.update(cx, |this, cx| {
+                        this.selected_tree_path = Some(path.clone());
+                        this.context_menu = Some(ContextMenuState {
+                            position,
+                            target: ContextMenuTarget::Path(path),
+                        });
                         cx.notify();
                     });
                 }
@@ -2614,6 +9075,13 @@ impl AppState {
             .flex_col()
             .child(
                 div()
+                    .key_context("ContentSearch")
+                    .on_action(cx.listener(|this, _: &HistoryPrevious, window, cx| {
+                        this.step_content_search_history(true, window, cx);
+                    }))
+                    .on_action(cx.listener(|this, _: &HistoryNext, window, cx| {
+                        this.step_content_search_history(false, window, cx);
+                    }))
                     .w_full()
                     .flex()
                     .flex_col()
@@ -2635,11 +9103,13 @@ impl AppState {
                                     .font_weight(FontWeight::SEMIBOLD)
                                     .text_color(chrome.text_secondary)
                                     .child("EXPLORER"),
-                            ),
+                            )
+                            .child(self.render_tree_header_controls(cx)),
                     )
                     .child({
                         let app_search = app_entity_search;
                         let app_clear = app_entity_clear;
+                        let app_enter = cx.entity().clone();
                         Input::new(&self.file_search_input)
                             .placeholder("Search files...")
                             .prefix(
@@ -2661,7 +9131,7 @@ impl AppState {
                                         .on_mouse_down(MouseButton::Left, move |_, _, cx| {
                                             app_clear.update(cx, |this, cx| {
                                                 this.file_search_query.clear();
-                                                this.file_search_results.clear();
+                                                this.clear_content_search(cx);
                                                 this.file_search_input.update(cx, |input, cx| {
                                                     input.content = SharedString::default();
                                                     cx.notify();
@@ -2678,20 +9148,22 @@ impl AppState {
                             .on_change(move |text: SharedString, cx: &mut App| {
                                 app_search.update(cx, |this, cx| {
                                     this.file_search_query = text.to_string();
+                                    this.content_search_history.sync(&this.file_search_query);
                                     if this.file_search_query.is_empty() {
-                                        this.file_search_results.clear();
-                                        this.search_version += 1;
-                                        cx.notify();
+                                        this.clear_content_search(cx);
                                     } else {
                                         this.trigger_content_search(cx);
                                     }
                                 });
                             })
+                            .on_enter(move |_, cx: &mut App| {
+                                app_enter.update(cx, |this, _| this.record_content_search());
+                            })
                     }),
             )
+            .child(self.render_tree_filter(cx))
             .child({
-                let visible_node_count =
-                    count_visible_nodes(&self.file_tree_nodes, &self.expanded_paths);
+                let visible_node_count = count_visible_nodes(&tree_nodes, &tree_expanded);
                 let total_content_h = visible_node_count as f32 * 28.0;
                 let explorer_handle = self.explorer_scroll_handle.clone();
                 let git_state_for_bar = self.git_state.clone();
@@ -2719,8 +9191,138 @@ impl AppState {
                         explorer_handle,
                         total_content_h,
                         git_state_for_bar,
+                        self.settings.scrollbar_visibility,
                     ))
             })
+            .when(self.settings.outline_docked, |el| {
+                el.child(self.render_docked_outline(cx))
+            })
+    }
+
+    fn render_docked_outline(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let buffer = self
+            .buffers
+            .get(self.active_tab)
+            .filter(|_| {
+                !self
+                    .tab_meta
+                    .get(self.active_tab)
+                    .is_some_and(|m| m.is_image)
+            })
+            .cloned();
+        let (entries, current) = match &buffer {
+            Some(buffer) => {
+                let state = buffer.read(cx);
+                let entries = state
+                    .syntax_tree()
+                    .map(|tree| extract_outline(tree, &state.content(), state.language()))
+                    .unwrap_or_default();
+                let current = outline_entry_at(&entries, state.cursor().line);
+                (entries, current)
+            }
+            None => (Vec::new(), None),
+        };
+
+        let mut list = div().flex().flex_col().gap(px(1.0)).px(px(8.0)).pb(px(8.0));
+        if entries.is_empty() {
+            list = list.child(
+                div()
+                    .px(px(8.0))
+                    .py(px(4.0))
+                    .text_size(px(12.0))
+                    .text_color(chrome.text_secondary.opacity(0.6))
+                    .child("No symbols"),
+            );
+        }
+        for (i, entry) in entries.into_iter().enumerate() {
+            let is_current = current == Some(i);
+            let target = buffer.clone();
+            list = list.child(
+                div()
+                    .id(ElementId::Name(format!("outline-entry-{}", i).into()))
+                    .flex()
+                    .items_center()
+                    .gap(px(8.0))
+                    .pl(px(8.0 + entry.depth as f32 * 12.0))
+                    .pr(px(8.0))
+                    .py(px(2.0))
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .when(is_current, |el| el.bg(chrome.accent.opacity(0.15)))
+                    .when(!is_current, |el| {
+                        el.hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                    })
+                    .on_click(cx.listener(move |_, _, window, cx| {
+                        if let Some(buffer) = &target {
+                            buffer.update(cx, |s, cx| {
+                                s.set_cursor_position(entry.line, entry.col, cx)
+                            });
+                            window.focus(&buffer.read(cx).focus_handle(cx));
+                        }
+                    }))
+                    .child(
+                        div()
+                            .text_size(px(10.0))
+                            .text_color(ide.syntax.keyword.opacity(0.7))
+                            .child(entry.kind.label()),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_ellipsis()
+                            .text_color(if is_current {
+                                chrome.bright
+                            } else {
+                                chrome.text_secondary
+                            })
+                            .child(entry.name),
+                    ),
+            );
+        }
+
+        div()
+            .flex_shrink_0()
+            .max_h(px(280.0))
+            .flex()
+            .flex_col()
+            .border_t_1()
+            .border_color(hsla(0.0, 0.0, 1.0, 0.05))
+            .child(
+                div()
+                    .px(px(16.0))
+                    .pt(px(10.0))
+                    .pb(px(6.0))
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(chrome.text_secondary)
+                    .child("OUTLINE"),
+            )
+            .child(
+                div()
+                    .id("docked-outline")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .child(list),
+            )
+    }
+
+    fn explorer_git_status(&self, cx: &App) -> HashMap<PathBuf, FileStatusKind> {
+        let gs = self.git_state.read(cx);
+        let Some(root) = gs.repo_path.as_ref() else {
+            return HashMap::new();
+        };
+        let mut status = HashMap::new();
+        for entry in &gs.file_entries {
+            let path = root.join(&entry.path);
+            if entry.staged && status.contains_key(&path) {
+                continue;
+            }
+            status.insert(path, entry.status);
+        }
+        status
     }
 
     fn render_file_search_results(&self, cx: &mut Context<Self>) -> impl IntoElement {
@@ -2733,17 +9335,15 @@ impl AppState {
         let results = &self.file_search_results;
         let searching = !self.file_search_query.is_empty() && results.is_empty();
 
-        let mut grouped: Vec<(PathBuf, String, String, Vec<(usize, String, usize, usize)>)> =
-            Vec::new();
+        type ResultGroup = (PathBuf, String, String, Vec<(ContentSearchResult, bool)>);
+        let mut grouped: Vec<ResultGroup> = Vec::new();
         for r in results {
+            let expanded = self
+                .expanded_search_results
+                .contains(&(r.path.clone(), r.line_number));
             if let Some(group) = grouped.last_mut() {
                 if group.0 == r.path {
-                    group.3.push((
-                        r.line_number,
-                        r.line_content.clone(),
-                        r.col_start,
-                        r.col_end,
-                    ));
+                    group.3.push((r.clone(), expanded));
                     continue;
                 }
             }
@@ -2751,14 +9351,36 @@ impl AppState {
                 r.path.clone(),
                 r.file_name.clone(),
                 r.dir_path.clone(),
-                vec![(
-                    r.line_number,
-                    r.line_content.clone(),
-                    r.col_start,
-                    r.col_end,
-                )],
+                vec![(r.clone(), expanded)],
             ));
         }
+        let context_row = move |(line_num, text): (usize, String)| {
+            div()
+                .w_full()
+                .flex()
+                .items_center()
+                .gap(px(6.0))
+                .h(px(18.0))
+                .pl(px(32.0))
+                .pr(px(12.0))
+                .child(
+                    div()
+                        .text_size(px(10.0))
+                        .text_color(chrome.text_secondary.opacity(0.35))
+                        .w(px(32.0))
+                        .flex_shrink_0()
+                        .child(format!("{}", line_num)),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .text_size(px(11.0))
+                        .text_color(chrome.text_secondary.opacity(0.5))
+                        .text_ellipsis()
+                        .overflow_x_hidden()
+                        .child(text),
+                )
+        };
 
         div()
             .w_full()
@@ -2820,10 +9442,32 @@ impl AppState {
                             .children(lines.into_iter().enumerate().map({
                                 let path = path.clone();
                                 let app_e = app_e.clone();
-                                move |(i, (line_num, line_content, _col_start, _col_end))| {
+                                move |(i, (result, expanded))| {
                                     let path = path.clone();
                                     let app_e = app_e.clone();
-                                    div()
+                                    let line_num = result.line_number;
+                                    let has_context = !result.context_before.is_empty()
+                                        || !result.context_after.is_empty();
+                                    let toggle_key = (path.clone(), line_num);
+                                    let text = result.line_content.clone();
+                                    let end = result.col_end.min(text.len());
+                                    let start = result.col_start.min(end);
+                                    let highlights = if text.is_char_boundary(start)
+                                        && text.is_char_boundary(end)
+                                        && start < end
+                                    {
+                                        vec![(
+                                            start..end,
+                                            HighlightStyle {
+                                                color: Some(chrome.bright),
+                                                font_weight: Some(FontWeight::SEMIBOLD),
+                                                ..Default::default()
+                                            },
+                                        )]
+                                    } else {
+                                        Vec::new()
+                                    };
+                                    let row = div()
                                         .id(SharedString::from(format!(
                                             "sr-{}-{}",
                                             path.display(),
@@ -2834,11 +9478,50 @@ impl AppState {
                                         .items_center()
                                         .gap(px(6.0))
                                         .h(px(22.0))
-                                        .pl(px(32.0))
+                                        .pl(px(16.0))
                                         .pr(px(12.0))
                                         .cursor_pointer()
                                         .rounded(px(4.0))
                                         .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                        .child(
+                                            div()
+                                                .id(SharedString::from(format!(
+                                                    "sr-toggle-{}-{}",
+                                                    path.display(),
+                                                    i
+                                                )))
+                                                .w(px(10.0))
+                                                .flex_shrink_0()
+                                                .when(has_context, |el| {
+                                                    let app_t = app_e.clone();
+                                                    el.child(
+                                                        Icon::new(if expanded {
+                                                            "chevron-down"
+                                                        } else {
+                                                            "chevron-right"
+                                                        })
+                                                        .size(px(10.0))
+                                                        .color(chrome.text_secondary),
+                                                    )
+                                                    .on_mouse_down(
+                                                        MouseButton::Left,
+                                                        move |_, _, cx| {
+                                                            cx.stop_propagation();
+                                                            app_t.update(cx, |this, cx| {
+                                                                let key = toggle_key.clone();
+                                                                if !this
+                                                                    .expanded_search_results
+                                                                    .remove(&key)
+                                                                {
+                                                                    this.expanded_search_results
+                                                                        .insert(key);
+                                                                }
+                                                                cx.notify();
+                                                            });
+                                                        },
+                                                    )
+                                                }),
+                                        )
                                         .child(
                                             div()
                                                 .text_size(px(10.0))
@@ -2854,11 +9537,15 @@ impl AppState {
                                                 .text_color(chrome.text_secondary)
                                                 .text_ellipsis()
                                                 .overflow_x_hidden()
-                                                .child(line_content),
+                                                .child(
+                                                    StyledText::new(text)
+                                                        .with_highlights(highlights),
+                                                ),
                                         )
                                         .on_mouse_down(MouseButton::Left, move |_, _, cx| {
                                             let path = path.clone();
                                             app_e.update(cx, |this, cx| {
+                                                this.record_content_search();
                                                 this.selected_tree_path = Some(path.clone());
                                                 if path.is_file() {
                                                     let already_open =
@@ -2897,11 +9584,92 @@ impl AppState {
                                                 }
                                                 cx.notify();
                                             });
-                                        })
+                                        });
+                                    let (before, after) = if expanded {
+                                        (result.context_before, result.context_after)
+                                    } else {
+                                        (Vec::new(), Vec::new())
+                                    };
+                                    div()
+                                        .w_full()
+                                        .flex()
+                                        .flex_col()
+                                        .children(before.into_iter().map(context_row))
+                                        .child(row)
+                                        .children(after.into_iter().map(context_row))
                                 }
                             }))
                     }),
             )
+            .when(!results.is_empty(), |el| {
+                let shown = results.len();
+                let summary = match self.file_search_total {
+                    Some(total) if total > shown => {
+                        format!("Showing {} of {} results", shown, total)
+                    }
+                    Some(total) => format!("{} result{}", total, if total == 1 { "" } else { "s" }),
+                    None if self.file_search_next.is_some() => {
+                        format!("Showing {} results, counting\u{2026}", shown)
+                    }
+                    None => format!("Showing {} results", shown),
+                };
+                el.child(
+                    div()
+                        .w_full()
+                        .flex()
+                        .items_center()
+                        .gap(px(8.0))
+                        .px(px(12.0))
+                        .py(px(6.0))
+                        .text_size(px(11.0))
+                        .text_color(chrome.text_secondary.opacity(0.7))
+                        .child(div().flex_1().child(summary))
+                        .child(
+                            div()
+                                .id("search-open-all")
+                                .px(px(8.0))
+                                .py(px(2.0))
+                                .rounded(px(4.0))
+                                .cursor_pointer()
+                                .text_color(accent)
+                                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                .child("Open All")
+                                .on_click(
+                                    cx.listener(|this, _, _, cx| this.open_all_search_matches(cx)),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .id("search-edit-results")
+                                .px(px(8.0))
+                                .py(px(2.0))
+                                .rounded(px(4.0))
+                                .cursor_pointer()
+                                .text_color(accent)
+                                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                .child("Edit")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.open_search_results_editor(cx)
+                                })),
+                        )
+                        .when(self.file_search_next.is_some(), |el| {
+                            el.child(
+                                div()
+                                    .id("search-load-more")
+                                    .px(px(8.0))
+                                    .py(px(2.0))
+                                    .rounded(px(4.0))
+                                    .cursor_pointer()
+                                    .text_color(accent)
+                                    .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                    .child("Load more")
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.load_more_content_results(cx)
+                                    })),
+                            )
+                        }),
+                )
+            })
             .when(searching, |el| {
                 el.child(
                     div()
@@ -2916,6 +9684,203 @@ impl AppState {
             })
     }
 
+    fn open_git_file_at_first_change(&mut self, file_idx: usize, cx: &mut Context<Self>) {
+        let gs = self.git_state.read(cx);
+        let (Some(root), Some(entry)) = (gs.repo_path.clone(), gs.file_entries.get(file_idx))
+        else {
+            return;
+        };
+        if entry.status == FileStatusKind::Deleted {
+            return;
+        }
+        let rel_path = entry.path.clone();
+        let path = root.join(&rel_path);
+        if !path.is_file() {
+            return;
+        }
+
+        self.active_mode = ViewMode::Explorer;
+        self.panel_visible = true;
+        self.selected_tree_path = Some(path.clone());
+        match self.tab_index_for_path(&path) {
+            Some(idx) => {
+                self.active_tab = idx;
+                self.update_search_editor(cx);
+            }
+            None => self.open_paths(vec![path.clone()], cx),
+        }
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let content = buffer.read(cx).content();
+
+        cx.spawn(async move |_, cx| {
+            let first_line = smol::unblock(move || {
+                let repo = GitService::open(&root).ok()?;
+                let Some(head) = GitService::read_head_content(&repo, &rel_path) else {
+                    return Some(0);
+                };
+                let changes = GitService::line_changes(&head, &content).ok()?;
+                changes.first().map(|c| c.start_line)
+            })
+            .await;
+
+            if let Some(line) = first_line {
+                let _ = cx.update(|cx| {
+                    buffer.update(cx, |state, cx| state.goto_line(line + 1, cx));
+                });
+            }
+        })
+        .detach();
+        cx.notify();
+    }
+
+    fn open_file_at_head(&mut self, cx: &mut Context<Self>) {
+        let active_path = self
+            .buffers
+            .get(self.active_tab)
+            .and_then(|b| b.read(cx).file_path().cloned());
+        let Some(path) =
+            active_path.or_else(|| self.selected_tree_path.clone().filter(|p| p.is_file()))
+        else {
+            return;
+        };
+        let Some(dir) = path.parent().map(Path::to_path_buf) else {
+            return;
+        };
+
+        cx.spawn(async move |this, cx| {
+            let file = path.clone();
+            let content = smol::unblock(move || {
+                let repo = GitService::open(&dir).ok()?;
+                let workdir = repo.workdir()?.to_path_buf();
+                let rel = file.strip_prefix(&workdir).ok()?;
+                GitService::read_head_content(&repo, &rel.to_string_lossy())
+            })
+            .await;
+
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |this, cx| match content {
+                    Some(content) => this.open_read_only_tab(&path, "HEAD", content, cx),
+                    None => this.notify(
+                        format!("{} is not tracked at HEAD", path.display()),
+                        ToastSeverity::Error,
+                        cx,
+                    ),
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn open_read_only_tab(
+        &mut self,
+        path: &Path,
+        label: &str,
+        content: String,
+        cx: &mut Context<Self>,
+    ) {
+        let completion_check = self.completion_state.clone();
+        let buffer = cx.new(|cx| {
+            let mut state = EditorState::new(cx);
+            state.set_overlay_active_check(move |cx| completion_check.read(cx).is_visible());
+            let language = match Language::from_path(path) {
+                Language::Plain => shebang_language(&content).unwrap_or(Language::Plain),
+                language => language,
+            };
+            state.set_language(language);
+            state.set_content(&content, cx);
+            state
+        });
+        let snapshot = buffer.read(cx).content();
+        let idx = self.buffers.len();
+        let name = path
+            .file_name()
+            .map(|n| format!("{} @ {}", n.to_string_lossy(), label))
+            .unwrap_or_else(|| label.to_string());
+        self.buffer_index.insert(buffer.entity_id(), idx);
+        self.tab_meta.push(TabMeta {
+            file_path: None,
+            title: Self::compose_tab_title(Some(&name), idx, false),
+            file_name: Some(name),
+            modified: false,
+            is_image: false,
+            deleted_on_disk: false,
+            read_only_content: Some(snapshot),
+            has_bom: false,
+            final_newline: true,
+            stats: ContentStats::of(buffer.read(cx)),
+        });
+        self.buffers.push(buffer.clone());
+        self.autosave.push();
+        self.active_tab = idx;
+        self.setup_overlay_check(&buffer, cx);
+        self.apply_editor_prefs(&buffer, cx);
+        cx.observe(&buffer, Self::on_buffer_changed).detach();
+        self.active_mode = ViewMode::Explorer;
+        self.clamp_tab_scroll();
+        self.update_search_editor(cx);
+        cx.notify();
+    }
+
+    fn copy_permalink(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffers.get(self.active_tab).cloned() else {
+            return;
+        };
+        let Some(path) = buffer.read(cx).file_path().cloned() else {
+            return;
+        };
+        let content = buffer.read(cx).content();
+        let cursor_line = buffer.read(cx).cursor().line;
+        let selection = buffer.update(cx, |state, cx| state.selected_text_range(false, window, cx));
+        let (start_line, end_line) = match selection {
+            Some(sel) if !sel.range.is_empty() => {
+                let line_at = |utf16: usize| {
+                    let mut offset = 0;
+                    let mut line = 0;
+                    for ch in content.chars() {
+                        if offset >= utf16 {
+                            break;
+                        }
+                        offset += ch.len_utf16();
+                        if ch == '\n' {
+                            line += 1;
+                        }
+                    }
+                    line
+                };
+                let start = line_at(sel.range.start);
+                let mut end = line_at(sel.range.end);
+                if end > start && content.encode_utf16().nth(sel.range.end - 1) == Some('\n' as u16)
+                {
+                    end -= 1;
+                }
+                (start, end)
+            }
+            _ => (cursor_line, cursor_line),
+        };
+        let root = self
+            .root_for_path(&path)
+            .cloned()
+            .unwrap_or_else(|| path.parent().map(Path::to_path_buf).unwrap_or_default());
+
+        cx.spawn(async move |_, cx| {
+            let result = smol::unblock(move || {
+                let repo = GitService::open(&root)?;
+                GitService::permalink(&repo, &path, start_line + 1, end_line + 1)
+            })
+            .await;
+
+            match result {
+                Ok(url) => {
+                    let _ = cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(url)));
+                }
+                Err(err) => eprintln!("[shiori] Permalink failed: {}", err.message()),
+            }
+        })
+        .detach();
+    }
+
     fn render_git_panel(&self, cx: &mut Context<Self>) -> impl IntoElement {
         let ide = use_ide_theme();
         let chrome = &ide.chrome;
@@ -2940,26 +9905,24 @@ impl AppState {
         changes.sort_by_key(|a| a.1.to_lowercase());
 
         let branch = gs.summary.branch.clone();
+        let repo_label = gs.repository_label();
         let commit_editor = gs.commit_editor.clone();
-
-        let status_letter = |status: FileStatusKind| -> &'static str {
-            match status {
-                FileStatusKind::Modified => "M",
-                FileStatusKind::Added => "A",
-                FileStatusKind::Deleted => "D",
-                FileStatusKind::Renamed => "R",
-                FileStatusKind::Untracked => "U",
+        let amend = gs.amend;
+        let amend_color = hsla(0.08, 0.85, 0.6, 1.0);
+        let commit_warnings = if self.settings.commit_lint_enabled {
+            let message = commit_editor.read(cx).content();
+            if message.trim().is_empty() && staged.is_empty() {
+                Vec::new()
+            } else {
+                GitService::lint_commit_message(&message, self.settings.commit_subject_limit)
             }
+        } else {
+            Vec::new()
         };
+        let show_prefixes = self.settings.conventional_commit_prefixes;
+        let prefix_menu_open = self.commit_prefix_menu_open;
 
-        let status_color = |status: FileStatusKind| -> Hsla {
-            match status {
-                FileStatusKind::Modified => hsla(0.12, 0.9, 0.65, 1.0),
-                FileStatusKind::Added | FileStatusKind::Untracked => ide.chrome.diff_add_text,
-                FileStatusKind::Deleted => ide.chrome.diff_del_text,
-                FileStatusKind::Renamed => hsla(0.58, 0.7, 0.65, 1.0),
-            }
-        };
+        let status_color = |status: FileStatusKind| -> Hsla { status_color(status, &ide.chrome) };
 
         let file_icon_for_path = |path: &str| -> &'static str {
             let ext = Path::new(path)
@@ -3070,41 +10033,189 @@ impl AppState {
                         .items_center()
                         .px(px(12.0))
                         .gap(px(6.0))
+                        .when_some(repo_label, |el, label| {
+                            el.child(
+                                div()
+                                    .id("git-repo-switch")
+                                    .flex()
+                                    .items_center()
+                                    .gap(px(4.0))
+                                    .px(px(4.0))
+                                    .rounded(px(4.0))
+                                    .cursor_pointer()
+                                    .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.git_state.update(cx, |gs, cx| {
+                                            gs.cycle_repository(cx);
+                                        });
+                                    }))
+                                    .child(Icon::new("folder").size(px(12.0)).color(chrome.accent))
+                                    .child(
+                                        div()
+                                            .text_size(px(12.0))
+                                            .text_color(chrome.bright)
+                                            .child(label),
+                                    ),
+                            )
+                        })
                         .child(
                             Icon::new("git-branch")
                                 .size(px(12.0))
                                 .color(chrome.text_secondary),
                         )
-                        .child(
-                            div()
-                                .text_size(px(12.0))
-                                .text_color(chrome.text_secondary)
-                                .child(branch),
-                        ),
-                )
-            })
-            .child(
-                div()
-                    .w_full()
-                    .flex_shrink_0()
-                    .px(px(8.0))
-                    .py(px(6.0))
-                    .child(
+                        .child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(chrome.text_secondary)
+                                .child(branch),
+                        ),
+                )
+            })
+            .child(
+                div()
+                    .w_full()
+                    .flex_shrink_0()
+                    .px(px(8.0))
+                    .py(px(6.0))
+                    .child(
+                        div()
+                            .w_full()
+                            .h(px(60.0))
+                            .rounded(px(12.0))
+                            .bg(chrome.editor_bg)
+                            .border_1()
+                            .border_color(if amend {
+                                amend_color.opacity(0.6)
+                            } else {
+                                hsla(0.0, 0.0, 1.0, 0.1)
+                            })
+                            .overflow_hidden()
+                            .cursor(CursorStyle::IBeam)
+                            .child(
+                                Editor::new(&commit_editor)
+                                    .show_line_numbers(false, cx)
+                                    .show_border(false),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("git-amend-toggle")
+                            .mt(px(6.0))
+                            .flex()
+                            .items_center()
+                            .gap(px(6.0))
+                            .cursor_pointer()
+                            .text_size(px(11.0))
+                            .text_color(if amend {
+                                amend_color
+                            } else {
+                                chrome.text_secondary
+                            })
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.git_state.update(cx, |gs, cx| gs.set_amend(!amend, cx));
+                            }))
+                            .child(
+                                div()
+                                    .w(px(14.0))
+                                    .h(px(14.0))
+                                    .flex()
+                                    .items_center()
+                                    .justify_center()
+                                    .rounded(px(3.0))
+                                    .border_1()
+                                    .border_color(if amend {
+                                        amend_color
+                                    } else {
+                                        hsla(0.0, 0.0, 1.0, 0.25)
+                                    })
+                                    .when(amend, |el| {
+                                        el.bg(amend_color).child(
+                                            Icon::new("check")
+                                                .size(px(10.0))
+                                                .color(hsla(0.0, 0.0, 1.0, 1.0)),
+                                        )
+                                    }),
+                            )
+                            .child(if amend {
+                                "Amending last commit (rewrites history)"
+                            } else {
+                                "Amend last commit"
+                            }),
+                    )
+                    .when(show_prefixes, |el| {
+                        el.child(
+                            div()
+                                .mt(px(6.0))
+                                .flex()
+                                .flex_wrap()
+                                .items_center()
+                                .gap(px(4.0))
+                                .child(
+                                    div()
+                                        .id("commit-prefix-toggle")
+                                        .flex()
+                                        .items_center()
+                                        .gap(px(4.0))
+                                        .px(px(6.0))
+                                        .py(px(2.0))
+                                        .rounded(px(4.0))
+                                        .cursor_pointer()
+                                        .text_size(px(11.0))
+                                        .text_color(chrome.text_secondary)
+                                        .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.commit_prefix_menu_open =
+                                                !this.commit_prefix_menu_open;
+                                            cx.notify();
+                                        }))
+                                        .child("Type")
+                                        .child(
+                                            Icon::new(if prefix_menu_open {
+                                                "chevron-down"
+                                            } else {
+                                                "chevron-right"
+                                            })
+                                            .size(px(10.0))
+                                            .color(chrome.text_secondary),
+                                        ),
+                                )
+                                .when(prefix_menu_open, |el| {
+                                    el.children(CONVENTIONAL_COMMIT_TYPES.iter().map(|kind| {
+                                        let kind = *kind;
+                                        div()
+                                            .id(ElementId::Name(
+                                                format!("commit-prefix-{}", kind).into(),
+                                            ))
+                                            .px(px(6.0))
+                                            .py(px(2.0))
+                                            .rounded(px(4.0))
+                                            .cursor_pointer()
+                                            .text_size(px(11.0))
+                                            .text_color(chrome.bright)
+                                            .bg(hsla(0.0, 0.0, 1.0, 0.06))
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.12)))
+                                            .on_click(cx.listener(move |this, _, _, cx| {
+                                                this.commit_prefix_menu_open = false;
+                                                this.git_state.update(cx, |gs, cx| {
+                                                    gs.apply_commit_prefix(kind, cx);
+                                                });
+                                            }))
+                                            .child(kind)
+                                    }))
+                                }),
+                        )
+                    })
+                    .children(commit_warnings.into_iter().map(|warning| {
                         div()
-                            .w_full()
-                            .h(px(60.0))
-                            .rounded(px(12.0))
-                            .bg(chrome.editor_bg)
-                            .border_1()
-                            .border_color(hsla(0.0, 0.0, 1.0, 0.1))
-                            .overflow_hidden()
-                            .cursor(CursorStyle::IBeam)
-                            .child(
-                                Editor::new(&commit_editor)
-                                    .show_line_numbers(false, cx)
-                                    .show_border(false),
-                            ),
-                    )
+                            .mt(px(4.0))
+                            .flex()
+                            .items_center()
+                            .gap(px(6.0))
+                            .text_size(px(11.0))
+                            .text_color(amend_color)
+                            .child(Icon::new("circle-alert").size(px(12.0)).color(amend_color))
+                            .child(warning)
+                    }))
                     .child(
                         div()
                             .id("git-commit-btn")
@@ -3116,7 +10227,7 @@ impl AppState {
                             .justify_center()
                             .gap(px(6.0))
                             .rounded(px(8.0))
-                            .bg(chrome.accent)
+                            .bg(if amend { amend_color } else { chrome.accent })
                             .text_color(hsla(0.0, 0.0, 1.0, 1.0))
                             .text_size(px(12.0))
                             .font_weight(FontWeight::SEMIBOLD)
@@ -3130,7 +10241,7 @@ impl AppState {
                                     .size(px(14.0))
                                     .color(hsla(0.0, 0.0, 1.0, 1.0)),
                             )
-                            .child("Commit"),
+                            .child(if amend { "Amend Commit" } else { "Commit" }),
                     ),
             )
             .child({
@@ -3230,7 +10341,11 @@ impl AppState {
                                 .text_size(px(12.0))
                                 .text_color(chrome.text_secondary)
                                 .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
-                                .on_click(cx.listener(move |this, _, _, cx| {
+                                .on_click(cx.listener(move |this, event: &ClickEvent, _, cx| {
+                                    if event.modifiers().secondary() {
+                                        this.open_git_file_at_first_change(file_idx, cx);
+                                        return;
+                                    }
                                     this.git_state.update(cx, |gs, cx| {
                                         gs.select_file(file_idx, cx);
                                     });
@@ -3399,7 +10514,11 @@ impl AppState {
                                 .text_size(px(12.0))
                                 .text_color(chrome.text_secondary)
                                 .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
-                                .on_click(cx.listener(move |this, _, _, cx| {
+                                .on_click(cx.listener(move |this, event: &ClickEvent, _, cx| {
+                                    if event.modifiers().secondary() {
+                                        this.open_git_file_at_first_change(file_idx, cx);
+                                        return;
+                                    }
                                     this.git_state.update(cx, |gs, cx| {
                                         gs.select_file(file_idx, cx);
                                     });
@@ -3753,6 +10872,7 @@ impl AppState {
                         fl_handle,
                         total_content_h,
                         git_state_bar,
+                        self.settings.scrollbar_visibility,
                     ))
             })
     }
@@ -3900,6 +11020,33 @@ impl AppState {
                                                     ),
                                             ),
                                     )
+                                    .when(!running, |el| {
+                                        el.child(
+                                            div()
+                                                .id(ElementId::Name(
+                                                    format!("term-restart-{}", idx).into(),
+                                                ))
+                                                .w(px(22.0))
+                                                .h(px(22.0))
+                                                .flex()
+                                                .items_center()
+                                                .justify_center()
+                                                .rounded(px(4.0))
+                                                .cursor_pointer()
+                                                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.1)))
+                                                .on_click(cx.listener(
+                                                    move |this, _, window, cx| {
+                                                        cx.stop_propagation();
+                                                        this.restart_terminal_at(idx, window, cx);
+                                                    },
+                                                ))
+                                                .child(
+                                                    Icon::new("refresh-cw")
+                                                        .size(px(13.0))
+                                                        .color(chrome.dim),
+                                                ),
+                                        )
+                                    })
                                     .child(
                                         div()
                                             .id(ElementId::Name(format!("term-close-{}", idx).into()))
@@ -3924,10 +11071,104 @@ impl AppState {
                         tl_handle,
                         total_content_h,
                         cx.entity().clone(),
+                        self.settings.scrollbar_visibility,
                     ))
             })
     }
 
+    fn render_terminal_tab_strip(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if !self.settings.terminal_tab_strip || self.terminals.is_empty() {
+            return None;
+        }
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let muted_fg = chrome.text_secondary;
+        let active_fg = chrome.bright;
+        let border_color = hsla(0.0, 0.0, 1.0, 0.05);
+
+        Some(
+            div()
+                .id("terminal-tab-strip")
+                .w_full()
+                .h(px(32.0))
+                .flex()
+                .flex_shrink_0()
+                .items_center()
+                .overflow_x_scroll()
+                .border_b_1()
+                .border_color(border_color)
+                .children(self.terminals.iter().enumerate().map(|(idx, term)| {
+                    let is_active = idx == self.active_terminal;
+                    let title = term.read(cx).title();
+                    let running = term.read(cx).is_running();
+
+                    div()
+                        .id(ElementId::Name(format!("term-tab-{}", idx).into()))
+                        .h_full()
+                        .flex()
+                        .flex_shrink_0()
+                        .items_center()
+                        .gap(px(6.0))
+                        .px(px(12.0))
+                        .cursor_pointer()
+                        .text_size(px(12.0))
+                        .border_r_1()
+                        .border_color(border_color)
+                        .when(is_active, |el| {
+                            el.bg(chrome.editor_bg).text_color(active_fg)
+                        })
+                        .when(!is_active, |el| {
+                            el.text_color(muted_fg)
+                                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                        })
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.active_terminal = idx;
+                            if let Some(term) = this.terminals.get(idx) {
+                                window.focus(&term.read(cx).focus_handle(cx));
+                            }
+                            cx.notify();
+                        }))
+                        .child(div().w(px(6.0)).h(px(6.0)).rounded_full().bg(if running {
+                            gpui::rgb(0x4ade80)
+                        } else {
+                            gpui::rgb(0x6b7280)
+                        }))
+                        .child(div().max_w(px(200.0)).text_ellipsis().child(title))
+                        .child(
+                            div()
+                                .id(ElementId::Name(format!("term-tab-close-{}", idx).into()))
+                                .w(px(16.0))
+                                .h(px(16.0))
+                                .flex()
+                                .items_center()
+                                .justify_center()
+                                .rounded(px(3.0))
+                                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.1)))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    cx.stop_propagation();
+                                    this.close_terminal_at(idx, cx);
+                                }))
+                                .child(Icon::new("x").size(px(12.0)).color(muted_fg)),
+                        )
+                }))
+                .child(
+                    div()
+                        .id("term-tab-new")
+                        .h_full()
+                        .flex()
+                        .flex_shrink_0()
+                        .items_center()
+                        .px(px(8.0))
+                        .cursor_pointer()
+                        .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.new_terminal(window, cx);
+                        }))
+                        .child(Icon::new("plus").size(px(14.0)).color(muted_fg)),
+                ),
+        )
+    }
+
     fn render_settings_view(&self, cx: &mut Context<Self>) -> impl IntoElement {
         let ide = use_ide_theme();
         let chrome = &ide.chrome;
@@ -4001,92 +11242,756 @@ impl AppState {
                         });
                     }
                     for terminal in &this.terminals {
-                        terminal.update(cx, |tv, _cx| {
-                            tv.apply_ide_theme();
-                        });
+                        terminal.update(cx, |tv, _cx| {
+                            tv.apply_ide_theme();
+                        });
+                    }
+                    cx.notify();
+                }))
+                .child(preview)
+                .child(
+                    div()
+                        .w_full()
+                        .px(px(10.0))
+                        .py(px(8.0))
+                        .bg(chrome.panel_bg)
+                        .flex()
+                        .flex_col()
+                        .gap(px(2.0))
+                        .child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap(px(6.0))
+                                .child(
+                                    div()
+                                        .text_size(px(13.0))
+                                        .font_weight(FontWeight::SEMIBOLD)
+                                        .text_color(chrome.bright)
+                                        .child(theme.name),
+                                )
+                                .when(is_current, |el| {
+                                    el.child(Icon::new("check").size(px(14.0)).color(chrome.accent))
+                                }),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(11.0))
+                                .text_color(chrome.text_secondary)
+                                .child(theme.description),
+                        ),
+                );
+
+            grid = grid.child(card);
+        }
+
+        div()
+            .id("settings-view")
+            .size_full()
+            .flex()
+            .flex_col()
+            .overflow_y_scroll()
+            .p(px(24.0))
+            .gap(px(20.0))
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(4.0))
+                    .child(
+                        div()
+                            .text_size(px(20.0))
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(chrome.bright)
+                            .child("Appearance"),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(13.0))
+                            .text_color(chrome.text_secondary)
+                            .child("Customize the look and feel of the editor"),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(12.0))
+                    .child(
+                        div()
+                            .text_xs()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(chrome.text_secondary)
+                            .child("COLOR THEMES"),
+                    )
+                    .child(grid),
+            )
+            .child(self.render_font_settings(cx))
+            .child(self.render_editor_settings(cx))
+            .child(self.render_git_settings(cx))
+            .child(self.render_terminal_settings(cx))
+            .child(self.render_lsp_settings(cx))
+    }
+
+    fn render_editor_settings(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+
+        div()
+            .max_w(px(500.0))
+            .flex()
+            .flex_col()
+            .gap(px(8.0))
+            .child(
+                div()
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(chrome.text_secondary)
+                    .child("EDITOR"),
+            )
+            .child(self.render_stepper_row(
+                "startup-behavior",
+                "On Startup",
+                "What to show at launch: the welcome screen, your last session, an untitled file, or a folder",
+                self.startup_label(),
+                |this, delta, _| {
+                    this.settings.startup = this.settings.startup.cycle(delta);
+                    if this.settings.startup == crate::settings::StartupBehavior::Folder
+                        && this.settings.startup_folder.is_none()
+                    {
+                        this.settings.startup_folder = this
+                            .workspace_root()
+                            .or(this.settings.recent_folders.first())
+                            .cloned();
+                    }
+                },
+                cx,
+            ))
+            .when(
+                self.settings.startup == crate::settings::StartupBehavior::Folder,
+                |d| {
+                    d.child(self.render_stepper_row(
+                        "startup-folder",
+                        "Startup Folder",
+                        "Cycle through open, favorite and recent folders, or run Choose Startup Folder... for any other",
+                        self.settings
+                            .startup_folder
+                            .as_ref()
+                            .map(|f| f.display().to_string())
+                            .unwrap_or_else(|| "None".into()),
+                        |this, delta, _| this.step_startup_folder(delta),
+                        cx,
+                    ))
+                },
+            )
+            .child(self.render_stepper_row(
+                "search-history-limit",
+                "Search History",
+                "Number of recent queries remembered per search field",
+                self.settings.search_history_limit.to_string(),
+                |this, delta, cx| {
+                    let limit =
+                        (this.settings.search_history_limit as i32 + delta * 10).clamp(0, 500);
+                    this.settings.search_history_limit = limit as usize;
+                    let limit = this.settings.search_history_limit;
+                    this.settings.search_history.truncate(limit);
+                    this.settings.replace_history.truncate(limit);
+                    this.settings.content_search_history.truncate(limit);
+                    this.content_search_history.set_limit(limit);
+                    this.search_bar
+                        .update(cx, |bar, _| bar.set_history_limit(limit));
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "search-max-results",
+                "Search Results Per Page",
+                "Matches loaded at a time in content search before offering to load more",
+                self.settings.search_max_results.to_string(),
+                |this, delta, _| {
+                    let limit =
+                        (this.settings.search_max_results as i32 + delta * 100).clamp(100, 5000);
+                    this.settings.search_max_results = limit as usize;
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "search-max-file-size",
+                "Search File Size Limit",
+                "Files larger than this many megabytes are skipped by content search",
+                format!("{} MB", self.settings.search_max_file_size_mb),
+                |this, delta, _| {
+                    let size =
+                        (this.settings.search_max_file_size_mb as i64 + delta as i64).clamp(1, 64);
+                    this.settings.search_max_file_size_mb = size as u64;
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "search-context-lines",
+                "Search Context Lines",
+                "Lines shown around a content search match when its row is expanded",
+                self.settings.search_context_lines.to_string(),
+                |this, delta, _| {
+                    let lines = (this.settings.search_context_lines as i32 + delta).clamp(0, 10);
+                    this.settings.search_context_lines = lines as usize;
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "indent-tab-size",
+                "Tab Size",
+                "Default indentation width; per-language values live in language_indent",
+                self.settings.indent.tab_size.to_string(),
+                |this, delta, _| {
+                    let size = (this.settings.indent.tab_size as i32 + delta).clamp(1, 8);
+                    this.settings.indent.tab_size = size as usize;
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "indent-hard-tabs",
+                "Indent with Tabs",
+                "Insert tab characters instead of spaces by default",
+                self.settings.indent.hard_tabs,
+                |this, _| this.settings.indent.hard_tabs = !this.settings.indent.hard_tabs,
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "strip-bom-on-save",
+                "Strip BOM on Save",
+                "Remove a UTF-8 byte order mark when saving; otherwise it is preserved",
+                self.settings.strip_bom_on_save,
+                |this, _| this.settings.strip_bom_on_save = !this.settings.strip_bom_on_save,
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "insert-final-newline",
+                "Insert Final Newline",
+                "End files with a newline when saving; otherwise the file's original ending is kept",
+                self.settings.insert_final_newline,
+                |this, _| this.settings.insert_final_newline = !this.settings.insert_final_newline,
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "delete-behavior",
+                "Deleting Files",
+                "What Delete in the file tree does: move to the trash, ask first, or delete at once",
+                self.settings.delete_behavior.label().to_string(),
+                |this, delta, _| this.settings.delete_behavior = this.settings.delete_behavior.cycle(delta),
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "outline-docked",
+                "Dock Outline",
+                "Show the symbol outline below the file tree in the explorer",
+                self.settings.outline_docked,
+                |this, _| this.settings.outline_docked = !this.settings.outline_docked,
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "paste-normalize-indent",
+                "Normalize Pasted Indentation",
+                "Re-indent multi-line pastes to the cursor line using the current indent settings",
+                self.settings.paste_normalize_indent,
+                |this, _| {
+                    this.settings.paste_normalize_indent = !this.settings.paste_normalize_indent
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "search-sticky",
+                "Keep Search Open",
+                "Escape in the search field returns to the editor and keeps matches highlighted",
+                self.settings.search_sticky,
+                |this, cx| {
+                    this.settings.search_sticky = !this.settings.search_sticky;
+                    let sticky = this.settings.search_sticky;
+                    this.search_bar.update(cx, |bar, _| bar.sticky = sticky);
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "indent-guides",
+                "Indent Guides",
+                "Draw vertical guides at each indentation level",
+                self.settings.indent_guides,
+                |this, _| this.settings.indent_guides = !this.settings.indent_guides,
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "indent-guide-active",
+                "Highlight Active Guide",
+                "Draw the guide for the scope containing the cursor more brightly",
+                self.settings.indent_guide_active_highlight,
+                |this, _| {
+                    this.settings.indent_guide_active_highlight =
+                        !this.settings.indent_guide_active_highlight
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "bracket-pair-guides",
+                "Bracket Pair Guides",
+                "Connect the brackets of the block containing the cursor with a guide",
+                self.settings.bracket_pair_guides,
+                |this, _| this.settings.bracket_pair_guides = !this.settings.bracket_pair_guides,
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "gutter-click-selects",
+                "Select Lines from Gutter",
+                "Click or drag across line numbers to select whole lines",
+                self.settings.gutter_click_selects_lines,
+                |this, _| {
+                    this.settings.gutter_click_selects_lines =
+                        !this.settings.gutter_click_selects_lines
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "preferred-line-length",
+                "Line Length",
+                "Column that Rewrap Comment wraps text to",
+                self.settings.preferred_line_length.to_string(),
+                |this, delta, _| {
+                    let width =
+                        (this.settings.preferred_line_length as i32 + delta * 10).clamp(40, 200);
+                    this.settings.preferred_line_length = width as usize;
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "editor-line-height",
+                "Line Spacing",
+                "Editor line height as a multiple of the font size",
+                format!("{:.1}", self.settings.editor_line_height),
+                |this, delta, cx| {
+                    let spacing = this.settings.editor_line_height + delta as f32 * 0.1;
+                    this.settings.editor_line_height =
+                        (spacing * 10.0).round().clamp(10.0, 30.0) / 10.0;
+                    this.set_zoom(ZoomTarget::Editor, this.editor_zoom, cx);
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "editor-padding-left",
+                "Left Padding",
+                "Space between the editor edge and the gutter, in pixels",
+                format!("{}", self.settings.editor_padding_left),
+                |this, delta, _| {
+                    this.settings.editor_padding_left =
+                        (this.settings.editor_padding_left + delta as f32 * 4.0).clamp(0.0, 64.0);
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "editor-padding-top",
+                "Top Padding",
+                "Space above the first line of the editor, in pixels",
+                format!("{}", self.settings.editor_padding_top),
+                |this, delta, _| {
+                    this.settings.editor_padding_top =
+                        (this.settings.editor_padding_top + delta as f32 * 4.0).clamp(0.0, 64.0);
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "undo-history-limit",
+                "Undo History",
+                "Saved versions kept per file so undo can step back after reopening it; 0 turns it off and clears the saved history",
+                self.settings.undo_history_limit.to_string(),
+                |this, delta, _| {
+                    let limit = (this.settings.undo_history_limit as i32 + delta * 5).clamp(0, 100);
+                    this.settings.undo_history_limit = limit as usize;
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "undo-grouping",
+                "Undo Grouping",
+                "Per Word (default) undoes typing a word at a time; saves, jumps and commands always start a new step",
+                self.settings.undo_grouping.label().to_string(),
+                |this, delta, _| this.settings.undo_grouping = this.settings.undo_grouping.cycle(delta),
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "undo-max-entries",
+                "Undo Depth",
+                "Undo steps kept per open file; the oldest are dropped first",
+                self.settings.undo_max_entries.to_string(),
+                |this, delta, _| {
+                    let limit = (this.settings.undo_max_entries as i32 + delta * 50).clamp(50, 5000);
+                    this.settings.undo_max_entries = limit as usize;
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "line-numbers",
+                "Line Numbers",
+                "Absolute, relative to the cursor, or hybrid with the current line absolute",
+                self.settings.line_numbers.label().to_string(),
+                |this, delta, _| {
+                    this.settings.line_numbers = this.settings.line_numbers.cycle(delta)
+                },
+                cx,
+            ))
+            .children(GutterItem::ALL.map(|item| {
+                let position = self
+                    .settings
+                    .gutter_items
+                    .iter()
+                    .position(|i| *i == item)
+                    .map_or("Off".to_string(), |p| (p + 1).to_string());
+                self.render_stepper_row(
+                    item.key(),
+                    item.label(),
+                    "Position in the gutter from the left; step past the last slot to hide",
+                    position,
+                    move |this, delta, _| {
+                        this.settings.gutter_items =
+                            move_item(&this.settings.gutter_items, item, delta)
+                    },
+                    cx,
+                )
+            }))
+            .child(self.render_stepper_row(
+                "tab-overflow",
+                "Tab Overflow",
+                "How to reach tabs that do not fit: scroll arrows or a dropdown list of all tabs",
+                self.settings.tab_overflow.label().to_string(),
+                |this, delta, _| this.settings.tab_overflow = this.settings.tab_overflow.cycle(delta),
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "scrollbar-visibility",
+                "Scrollbars",
+                "When panel scrollbars are shown: always, on hover, while scrolling, or never",
+                self.settings.scrollbar_visibility.label().to_string(),
+                |this, delta, _| {
+                    this.settings.scrollbar_visibility =
+                        this.settings.scrollbar_visibility.cycle(delta);
+                },
+                cx,
+            ))
+    }
+
+    fn render_git_settings(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+
+        div()
+            .max_w(px(500.0))
+            .flex()
+            .flex_col()
+            .gap(px(8.0))
+            .child(
+                div()
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(chrome.text_secondary)
+                    .child("GIT"),
+            )
+            .child(self.render_toggle_row(
+                "commit-lint-toggle",
+                "Commit Message Lint",
+                "Warn about empty messages, long subjects and missing blank lines",
+                self.settings.commit_lint_enabled,
+                |this, _| this.settings.commit_lint_enabled = !this.settings.commit_lint_enabled,
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "commit-subject-limit",
+                "Subject Line Limit",
+                "Maximum characters in the first line before warning",
+                self.settings.commit_subject_limit.to_string(),
+                |this, delta, _| {
+                    let limit =
+                        (this.settings.commit_subject_limit as i32 + delta * 2).clamp(20, 200);
+                    this.settings.commit_subject_limit = limit as usize;
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "commit-prefix-toggle-setting",
+                "Conventional Commit Types",
+                "Show quick type prefixes (feat, fix, docs...) in the git panel",
+                self.settings.conventional_commit_prefixes,
+                |this, _| {
+                    this.settings.conventional_commit_prefixes =
+                        !this.settings.conventional_commit_prefixes
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "diff-ignore-whitespace",
+                "Ignore Whitespace",
+                "Hide changes that only add, remove or reindent whitespace",
+                self.settings.diff_ignore_whitespace,
+                |this, cx| {
+                    this.settings.diff_ignore_whitespace = !this.settings.diff_ignore_whitespace;
+                    this.sync_diff_config(cx);
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "diff-ignore-blank-lines",
+                "Ignore Blank Lines",
+                "Hide hunks that only add or remove empty lines",
+                self.settings.diff_ignore_blank_lines,
+                |this, cx| {
+                    this.settings.diff_ignore_blank_lines = !this.settings.diff_ignore_blank_lines;
+                    this.sync_diff_config(cx);
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "diff-word-level",
+                "Word-Level Diff",
+                "Highlight the changed words inside modified lines",
+                self.settings.diff_word_level,
+                |this, cx| {
+                    this.settings.diff_word_level = !this.settings.diff_word_level;
+                    this.sync_diff_config(cx);
+                },
+                cx,
+            ))
+    }
+
+    fn render_terminal_settings(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+
+        div()
+            .max_w(px(500.0))
+            .flex()
+            .flex_col()
+            .gap(px(8.0))
+            .child(
+                div()
+                    .text_xs()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(chrome.text_secondary)
+                    .child("TERMINAL"),
+            )
+            .child(self.render_toggle_row(
+                "terminal-line-wrap",
+                "Wrap Long Lines",
+                "Reflow wrapped output on resize instead of truncating it at the edge",
+                self.settings.terminal_line_wrap,
+                |this, cx| {
+                    this.settings.terminal_line_wrap = !this.settings.terminal_line_wrap;
+                    let wrap = this.settings.terminal_line_wrap;
+                    for terminal in &this.terminals {
+                        terminal.update(cx, |tv, _| tv.set_line_wrap(wrap));
+                    }
+                },
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "terminal-tab-strip",
+                "Terminal Tabs",
+                "Show a tab strip above the terminal for switching between sessions",
+                self.settings.terminal_tab_strip,
+                |this, _| this.settings.terminal_tab_strip = !this.settings.terminal_tab_strip,
+                cx,
+            ))
+            .child(self.render_toggle_row(
+                "terminal-confirm-paste",
+                "Confirm Multi-line Paste",
+                "Ask before pasting several lines into a shell that would run each one",
+                self.settings.terminal_confirm_multiline_paste,
+                |this, cx| {
+                    this.settings.terminal_confirm_multiline_paste =
+                        !this.settings.terminal_confirm_multiline_paste;
+                    let confirm = this.settings.terminal_confirm_multiline_paste;
+                    for terminal in &this.terminals {
+                        terminal.update(cx, |tv, _| tv.set_confirm_multiline_paste(confirm));
                     }
-                    cx.notify();
-                }))
-                .child(preview)
-                .child(
-                    div()
-                        .w_full()
-                        .px(px(10.0))
-                        .py(px(8.0))
-                        .bg(chrome.panel_bg)
-                        .flex()
-                        .flex_col()
-                        .gap(px(2.0))
-                        .child(
+                },
+                cx,
+            ))
+            .child(self.render_stepper_row(
+                "terminal-scrollback",
+                "Scrollback",
+                "Lines of history kept per terminal; the oldest output is dropped past this",
+                self.settings.terminal_scrollback.to_string(),
+                |this, delta, cx| {
+                    let lines = (this.settings.terminal_scrollback as i64 + delta as i64 * 5_000)
+                        .clamp(1_000, crate::terminal_state::MAX_SCROLLBACK as i64);
+                    this.settings.terminal_scrollback = lines as usize;
+                    for terminal in &this.terminals {
+                        terminal.update(cx, |tv, _| tv.set_scrollback_limit(lines as usize));
+                    }
+                },
+                cx,
+            ))
+    }
+
+    fn render_toggle_row(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        description: &'static str,
+        enabled: bool,
+        on_toggle: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+
+        div()
+            .w_full()
+            .flex()
+            .items_center()
+            .justify_between()
+            .p(px(12.0))
+            .rounded(px(8.0))
+            .bg(chrome.panel_bg)
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(px(2.0))
+                    .child(
+                        div()
+                            .text_size(px(13.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(chrome.bright)
+                            .child(label),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(chrome.text_secondary)
+                            .child(description),
+                    ),
+            )
+            .child(
+                div()
+                    .id(id)
+                    .w(px(40.0))
+                    .h(px(22.0))
+                    .rounded(px(11.0))
+                    .cursor_pointer()
+                    .flex()
+                    .items_center()
+                    .when(enabled, |el| {
+                        el.bg(chrome.accent).child(
                             div()
-                                .flex()
-                                .items_center()
-                                .gap(px(6.0))
-                                .child(
-                                    div()
-                                        .text_size(px(13.0))
-                                        .font_weight(FontWeight::SEMIBOLD)
-                                        .text_color(chrome.bright)
-                                        .child(theme.name),
-                                )
-                                .when(is_current, |el| {
-                                    el.child(Icon::new("check").size(px(14.0)).color(chrome.accent))
-                                }),
+                                .ml(px(20.0))
+                                .w(px(18.0))
+                                .h(px(18.0))
+                                .rounded_full()
+                                .bg(gpui::white()),
                         )
-                        .child(
+                    })
+                    .when(!enabled, |el| {
+                        el.bg(hsla(0.0, 0.0, 1.0, 0.15)).child(
                             div()
-                                .text_size(px(11.0))
-                                .text_color(chrome.text_secondary)
-                                .child(theme.description),
-                        ),
-                );
+                                .ml(px(2.0))
+                                .w(px(18.0))
+                                .h(px(18.0))
+                                .rounded_full()
+                                .bg(chrome.text_secondary),
+                        )
+                    })
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        on_toggle(this, cx);
+                        this.settings.save();
+                        cx.notify();
+                    })),
+            )
+    }
 
-            grid = grid.child(card);
-        }
+    fn render_stepper_row(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        description: &'static str,
+        value: String,
+        on_step: impl Fn(&mut Self, i32, &mut Context<Self>) + Clone + 'static,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let on_dec = on_step.clone();
+        let on_inc = on_step;
+        let step_btn = |btn_id: String, icon: &'static str| {
+            div()
+                .id(ElementId::Name(btn_id.into()))
+                .w(px(24.0))
+                .h(px(24.0))
+                .flex()
+                .items_center()
+                .justify_center()
+                .rounded(px(4.0))
+                .cursor_pointer()
+                .bg(hsla(0.0, 0.0, 1.0, 0.06))
+                .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.12)))
+                .child(Icon::new(icon).size(px(12.0)).color(chrome.text_secondary))
+        };
 
         div()
-            .id("settings-view")
-            .size_full()
+            .w_full()
             .flex()
-            .flex_col()
-            .overflow_y_scroll()
-            .p(px(24.0))
-            .gap(px(20.0))
+            .items_center()
+            .justify_between()
+            .p(px(12.0))
+            .rounded(px(8.0))
+            .bg(chrome.panel_bg)
             .child(
                 div()
                     .flex()
                     .flex_col()
-                    .gap(px(4.0))
+                    .gap(px(2.0))
                     .child(
                         div()
-                            .text_size(px(20.0))
-                            .font_weight(FontWeight::BOLD)
+                            .text_size(px(13.0))
+                            .font_weight(FontWeight::MEDIUM)
                             .text_color(chrome.bright)
-                            .child("Appearance"),
+                            .child(label),
                     )
                     .child(
                         div()
-                            .text_size(px(13.0))
+                            .text_size(px(12.0))
                             .text_color(chrome.text_secondary)
-                            .child("Customize the look and feel of the editor"),
+                            .child(description),
                     ),
             )
             .child(
                 div()
                     .flex()
-                    .flex_col()
-                    .gap(px(12.0))
+                    .items_center()
+                    .gap(px(8.0))
+                    .child(
+                        step_btn(format!("{}-dec", id), "minus").on_click(cx.listener(
+                            move |this, _, _, cx| {
+                                on_dec(this, -1, cx);
+                                this.settings.save();
+                                cx.notify();
+                            },
+                        )),
+                    )
                     .child(
                         div()
-                            .text_xs()
-                            .font_weight(FontWeight::SEMIBOLD)
-                            .text_color(chrome.text_secondary)
-                            .child("COLOR THEMES"),
+                            .min_w(px(36.0))
+                            .flex()
+                            .justify_center()
+                            .text_size(px(13.0))
+                            .text_color(chrome.bright)
+                            .child(value),
                     )
-                    .child(grid),
+                    .child(
+                        step_btn(format!("{}-inc", id), "plus").on_click(cx.listener(
+                            move |this, _, _, cx| {
+                                on_inc(this, 1, cx);
+                                this.settings.save();
+                                cx.notify();
+                            },
+                        )),
+                    ),
             )
-            .child(self.render_font_settings(cx))
-            .child(self.render_lsp_settings(cx))
     }
 
     fn enumerate_mono_fonts(cx: &App) -> Vec<String> {
@@ -4279,25 +12184,45 @@ impl AppState {
                                 .bg(chrome.text_secondary),
                         )
                     })
-                    .on_click(cx.listener(move |this, _, _, cx| {
-                        this.settings.lsp_enabled = !this.settings.lsp_enabled;
-                        this.settings.save();
-                        if this.settings.lsp_enabled {
-                            if let Some(root) = this.workspace_root.clone() {
-                                this.lsp_registry.set_root(root);
-                            }
-                            for buffer in this.buffers.clone() {
-                                this.lsp_notify_did_open(&buffer, cx);
-                            }
-                            this.start_lsp_poll(cx);
-                        } else {
-                            this.lsp_registry.stop_all();
-                            this.lsp_poll_task = None;
-                        }
-                        cx.notify();
-                    })),
+                    .on_click(cx.listener(|this, _, _, cx| this.toggle_lsp_enabled(cx))),
             );
 
+        let project_lsp = self.project_settings.contains_key("lsp_enabled");
+        let scope_row = (project_lsp || self.lsp_scope_prompt.is_some()).then(|| {
+            let link = |id: &'static str, label: &'static str| {
+                div()
+                    .id(id)
+                    .cursor_pointer()
+                    .text_color(chrome.accent)
+                    .hover(|s| s.text_color(chrome.bright))
+                    .child(label)
+            };
+            let row = div()
+                .flex()
+                .items_center()
+                .gap(px(12.0))
+                .px(px(12.0))
+                .text_size(px(12.0))
+                .text_color(chrome.text_secondary);
+            if project_lsp {
+                row.child("Saved for this project only").child(
+                    link("lsp-use-global", "Use global setting")
+                        .on_click(cx.listener(|this, _, _, cx| this.use_global_lsp_choice(cx))),
+                )
+            } else {
+                row.child("Changed for all projects")
+                    .child(link("lsp-project-only", "Only for this project").on_click(
+                        cx.listener(|this, _, _, cx| this.keep_lsp_choice_for_project(cx)),
+                    ))
+                    .child(link("lsp-keep-global", "Keep global").on_click(cx.listener(
+                        |this, _, _, cx| {
+                            this.lsp_scope_prompt = None;
+                            cx.notify();
+                        },
+                    )))
+            }
+        });
+
         let mut lang_rows = div().flex().flex_col().gap(px(4.0));
 
         let mut sorted_keys: Vec<_> = self.settings.language_servers.keys().cloned().collect();
@@ -4344,6 +12269,9 @@ impl AppState {
 
             let lang_key_toggle = lang_key.clone();
             let lang_key_restart = lang_key.clone();
+            let install = (lsp_enabled && config.enabled && !installed)
+                .then(|| self.settings.install_command_for(lang_key))
+                .flatten();
 
             let row = div()
                 .w_full()
@@ -4366,9 +12294,17 @@ impl AppState {
                 .child(
                     div()
                         .flex_1()
+                        .flex()
+                        .flex_col()
                         .text_size(px(12.0))
                         .text_color(chrome.text_secondary)
-                        .child(config.command.clone()),
+                        .child(config.command.clone())
+                        .children(install.as_ref().map(|cmd| {
+                            div()
+                                .text_size(px(11.0))
+                                .text_color(chrome.text_secondary.opacity(0.7))
+                                .child(format!("Install: {}", cmd))
+                        })),
                 )
                 .child(
                     div()
@@ -4377,6 +12313,34 @@ impl AppState {
                         .text_color(status_color)
                         .child(status_text),
                 )
+                .when_some(install, |el, cmd| {
+                    let copy_cmd = cmd.clone();
+                    el.child(
+                        div()
+                            .id(SharedString::from(format!("copy-install-{}", lang_key)))
+                            .text_size(px(11.0))
+                            .text_color(chrome.text_secondary)
+                            .cursor_pointer()
+                            .hover(|s| s.text_color(chrome.bright))
+                            .child("Copy")
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                cx.write_to_clipboard(ClipboardItem::new_string(copy_cmd.clone()));
+                                this.notify("Install command copied", ToastSeverity::Info, cx);
+                            })),
+                    )
+                    .child(
+                        div()
+                            .id(SharedString::from(format!("run-install-{}", lang_key)))
+                            .text_size(px(11.0))
+                            .text_color(chrome.accent)
+                            .cursor_pointer()
+                            .hover(|s| s.text_color(chrome.bright))
+                            .child("Install")
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.run_in_new_terminal(&cmd, window, cx);
+                            })),
+                    )
+                })
                 .when(lsp_enabled && is_active, |el| {
                     el.child(
                         div()
@@ -4452,6 +12416,55 @@ impl AppState {
                     ),
             )
             .child(toggle_row)
+            .children(scope_row)
+            .child(self.render_stepper_row(
+                "completion-max-items",
+                "Completion Items",
+                "Maximum completions listed at once; keep typing to narrow the rest",
+                match self.settings.completion_max_items {
+                    0 => "All".to_string(),
+                    n => n.to_string(),
+                },
+                |this, delta, cx| {
+                    let max =
+                        (this.settings.completion_max_items as i32 + delta * 25).clamp(0, 500);
+                    this.settings.completion_max_items = max as usize;
+                    this.completion_state
+                        .update(cx, |s, _| s.set_max_items(max as usize));
+                },
+                cx,
+            ))
+            .when(lsp_enabled, |el| {
+                el.child(self.render_toggle_row(
+                    "hover-enabled",
+                    "Hover Popups",
+                    "Show documentation when the pointer or cursor rests on a symbol",
+                    self.settings.hover_enabled,
+                    |this, cx| {
+                        this.settings.hover_enabled = !this.settings.hover_enabled;
+                        if !this.settings.hover_enabled {
+                            this.hover_task = None;
+                            this.mouse_hover_word = None;
+                            this.dismiss_hover(cx);
+                        }
+                    },
+                    cx,
+                ))
+            })
+            .when(lsp_enabled && self.settings.hover_enabled, |el| {
+                el.child(self.render_stepper_row(
+                    "hover-delay",
+                    "Hover Delay",
+                    "Milliseconds to wait before showing a hover popup",
+                    format!("{} ms", self.settings.hover_delay_ms),
+                    |this, delta, _| {
+                        let delay = (this.settings.hover_delay_ms as i64 + delta as i64 * 100)
+                            .clamp(0, 3000);
+                        this.settings.hover_delay_ms = delay as u64;
+                    },
+                    cx,
+                ))
+            })
             .when(lsp_enabled, |el| {
                 el.child(
                     div()
@@ -4496,10 +12509,107 @@ impl AppState {
         cx.notify();
     }
 
-    fn create_commands(&self, cx: &Context<Self>) -> Vec<Command> {
-        let app = cx.entity().clone();
+    fn create_commands(&self, cx: &Context<Self>) -> Vec<Command> {
+        let app = cx.entity().clone();
+
+        let mut commands = Vec::new();
+
+        let a = app.clone();
+        commands.push(
+            Command::new("show-hover", "Show Hover")
+                .category("Editor")
+                .shortcut("⌘K ⌘I")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.request_cursor_hover(Duration::ZERO, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("next-problem", "Go to Next Problem")
+                .category("Editor")
+                .shortcut("F8")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.go_to_problem(true, window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("previous-problem", "Go to Previous Problem")
+                .category("Editor")
+                .shortcut("⇧F8")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.go_to_problem(false, window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("format-document", "Format Document")
+                .category("Editor")
+                .shortcut("⇧⌥F")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.format_active(false, window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("trigger-completion", "Trigger Completion")
+                .category("Editor")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.trigger_completion(cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("rewrap-comment", "Rewrap Comment")
+                .category("Editor")
+                .shortcut("⌥Q")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.rewrap_comment_at_cursor(window, cx));
+                }),
+        );
 
-        let mut commands = Vec::new();
+        let a = app.clone();
+        commands.push(
+            Command::new("copy-with-highlighting", "Copy with Highlighting")
+                .category("Editor")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.copy_with_highlighting(window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("insert-snippet", "Insert Snippet")
+                .category("Editor")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.open_snippet_picker(cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("expand-selection", "Expand Selection")
+                .category("Editor")
+                .shortcut("⌥↑")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.expand_selection(window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("shrink-selection", "Shrink Selection")
+                .category("Editor")
+                .shortcut("⌥↓")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.shrink_selection(window, cx));
+                }),
+        );
 
         let a = app.clone();
         commands.push(
@@ -4580,18 +12690,145 @@ impl AppState {
                 }),
         );
 
+        let a = app.clone();
+        commands.push(
+            Command::new("choose-startup-folder", "Choose Startup Folder...")
+                .category("File")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| {
+                        this.choose_startup_folder_dialog(cx);
+                    });
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("open-workspace", "Open Workspace...")
+                .category("File")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| {
+                        this.open_workspace_dialog(cx);
+                    });
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("save-workspace-as", "Save Workspace As...")
+                .category("File")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| {
+                        this.save_workspace_as_dialog(cx);
+                    });
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("add-folder-to-workspace", "Add Folder to Workspace")
+                .category("File")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| {
+                        this.add_folder_dialog(cx);
+                    });
+                }),
+        );
+
+        if self.workspace_roots.len() > 1 {
+            let a = app.clone();
+            commands.push(
+                Command::new(
+                    "remove-folder-from-workspace",
+                    "Remove Folder from Workspace",
+                )
+                .category("File")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| {
+                        this.remove_active_workspace_root(cx);
+                    });
+                }),
+            );
+        }
+
+        let active_is_svg = self
+            .tab_meta
+            .get(self.active_tab)
+            .and_then(|m| m.file_path.as_deref())
+            .is_some_and(is_svg_file);
+        if active_is_svg {
+            let a = app.clone();
+            commands.push(
+                Command::new("toggle-svg-source", "Toggle SVG Source")
+                    .category("View")
+                    .on_select(move |_, cx| {
+                        a.update(cx, |this, cx| this.toggle_svg_source(cx));
+                    }),
+            );
+        }
+
+        let a = app.clone();
+        commands.push(
+            Command::new("switch-workspace", "Switch Workspace...")
+                .category("File")
+                .shortcut("⌥⌘O")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| {
+                        this.open_workspace_switcher(window, cx);
+                    });
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("reveal-in-finder", REVEAL_LABEL)
+                .category("File")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| {
+                        let path = this.active_file_path();
+                        this.reveal_in_file_manager(path, cx);
+                    });
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("open-in-terminal", "Open in Terminal")
+                .category("File")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| {
+                        let path = this.active_file_path();
+                        this.open_in_terminal(path, window, cx);
+                    });
+                }),
+        );
+
         let a = app.clone();
         commands.push(
             Command::new("save-file", "Save File")
                 .category("File")
                 .shortcut("⌘S")
-                .on_select(move |_, cx| {
+                .on_select(move |window, cx| {
                     a.update(cx, |this, cx| {
-                        this.save_active(cx);
+                        this.save_active(window, cx);
                     });
                 }),
         );
 
+        if self
+            .tab_meta
+            .get(self.active_tab)
+            .is_some_and(|m| m.has_bom)
+        {
+            let a = app.clone();
+            commands.push(
+                Command::new("remove-bom", "Remove BOM")
+                    .category("File")
+                    .on_select(move |window, cx| {
+                        a.update(cx, |this, cx| this.remove_bom(window, cx));
+                    }),
+            );
+        }
+
         let a = app.clone();
         commands.push(
             Command::new("close-tab", "Close Tab")
@@ -4612,6 +12849,8 @@ impl AppState {
                 .on_select(move |_, cx| {
                     a.update(cx, |this, cx| {
                         this.goto_line_visible = true;
+                        this.goto_line_error = false;
+                        this.goto_line_origin = None;
                         cx.notify();
                     });
                 }),
@@ -4653,15 +12892,35 @@ impl AppState {
                 }),
         );
 
+        let a = app.clone();
+        commands.push(
+            Command::new("goto-definition", "Go to Definition")
+                .category("Navigation")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.goto_definition(cx));
+                }),
+        );
+
         let a = app.clone();
         commands.push(
             Command::new("symbol-outline", "Symbol Outline")
                 .category("Navigation")
                 .shortcut("⌘⇧K")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.toggle_symbol_outline(window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("toggle-docked-outline", "Toggle Docked Outline")
+                .category("View")
                 .on_select(move |_, cx| {
                     a.update(cx, |this, cx| {
-                        this.symbol_outline_visible = !this.symbol_outline_visible;
-                        this.symbol_outline_filter.clear();
+                        this.settings.outline_docked = !this.settings.outline_docked;
+                        this.settings.save();
+                        this.active_mode = ViewMode::Explorer;
+                        this.panel_visible = true;
                         cx.notify();
                     });
                 }),
@@ -4688,7 +12947,7 @@ impl AppState {
         let a = app.clone();
         commands.push(
             Command::new("toggle-terminal", "Toggle Terminal")
-                .category("View")
+                .category("Terminal")
                 .shortcut("⌘`")
                 .on_select(move |window, cx| {
                     a.update(cx, |this, cx| {
@@ -4700,7 +12959,7 @@ impl AppState {
         let a = app.clone();
         commands.push(
             Command::new("new-terminal", "New Terminal")
-                .category("View")
+                .category("Terminal")
                 .on_select(move |window, cx| {
                     a.update(cx, |this, cx| {
                         this.new_terminal(window, cx);
@@ -4708,10 +12967,19 @@ impl AppState {
                 }),
         );
 
+        let a = app.clone();
+        commands.push(
+            Command::new("toggle-terminal-fullscreen", "Toggle Terminal Fullscreen")
+                .category("Terminal")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.toggle_terminal_fullscreen(window, cx));
+                }),
+        );
+
         let a = app.clone();
         commands.push(
             Command::new("toggle-search", "Toggle Search")
-                .category("View")
+                .category("Search")
                 .shortcut("⌘F")
                 .on_select(move |_, cx| {
                     a.update(cx, |this, cx| {
@@ -4724,7 +12992,7 @@ impl AppState {
         let a = app.clone();
         commands.push(
             Command::new("search-replace", "Search & Replace")
-                .category("View")
+                .category("Search")
                 .shortcut("⌘H")
                 .on_select(move |_, cx| {
                     a.update(cx, |this, cx| {
@@ -4734,6 +13002,78 @@ impl AppState {
                 }),
         );
 
+        let a = app.clone();
+        commands.push(
+            Command::new("toggle-read-only", "Toggle Read-Only")
+                .category("Editor")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.toggle_read_only(cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("select-all-matches", "Select All Matches")
+                .category("Search")
+                .shortcut("⌥Enter")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.select_all_matches(window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("cycle-line-numbers", "Cycle Line Numbers")
+                .category("Editor")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.cycle_line_numbers(cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("wrap-with-tag", "Wrap with Tag")
+                .category("Editor")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.wrap_with_tag(window, cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new("open-all-matches", "Open All Search Matches")
+                .category("Search")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.open_all_search_matches(cx));
+                }),
+        );
+
+        let a = app.clone();
+        commands.push(
+            Command::new(
+                "edit-search-results",
+                "Open Search Results as Editable Buffer",
+            )
+            .category("Search")
+            .on_select(move |_, cx| {
+                a.update(cx, |this, cx| this.open_search_results_editor(cx));
+            }),
+        );
+
+        for (id, name, forward) in [
+            ("find-next", "Find Next Match", true),
+            ("find-previous", "Find Previous Match", false),
+        ] {
+            let a = app.clone();
+            commands.push(
+                Command::new(id, name)
+                    .category("Search")
+                    .on_select(move |_, cx| {
+                        a.update(cx, |this, cx| this.find_in_active(forward, cx));
+                    }),
+            );
+        }
+
         let a = app.clone();
         commands.push(
             Command::new("toggle-git", "Toggle Git View")
@@ -4768,6 +13108,49 @@ impl AppState {
                 }),
         );
 
+        let a = app.clone();
+        commands.push(
+            Command::new("open-file-at-head", "Open File at HEAD")
+                .category("Git")
+                .on_select(move |_, cx| {
+                    a.update(cx, |this, cx| this.open_file_at_head(cx));
+                }),
+        );
+
+        for (id, name, forward) in [
+            ("git-next-file", "Next Changed File", true),
+            ("git-prev-file", "Previous Changed File", false),
+        ] {
+            let a = app.clone();
+            commands.push(
+                Command::new(id, name)
+                    .category("Git")
+                    .on_select(move |_, cx| {
+                        a.update(cx, |this, cx| {
+                            this.active_mode = ViewMode::Git;
+                            this.panel_visible = true;
+                            this.git_state.update(cx, |s, cx| {
+                                if forward {
+                                    s.select_next_file(cx)
+                                } else {
+                                    s.select_prev_file(cx)
+                                }
+                            });
+                            cx.notify();
+                        });
+                    }),
+            );
+        }
+
+        let a = app.clone();
+        commands.push(
+            Command::new("copy-permalink", "Copy GitHub Permalink")
+                .category("Git")
+                .on_select(move |window, cx| {
+                    a.update(cx, |this, cx| this.copy_permalink(window, cx));
+                }),
+        );
+
         let a = app.clone();
         commands.push(
             Command::new("install-cli", "Install CLI Command")
@@ -4798,7 +13181,7 @@ impl AppState {
                 .shortcut("⌘+")
                 .category("View")
                 .on_select(move |_, cx| {
-                    a.update(cx, |this, cx| this.zoom_in(cx));
+                    a.update(cx, |this, cx| this.zoom_in(ZoomTarget::Editor, cx));
                 }),
         );
 
@@ -4808,7 +13191,7 @@ impl AppState {
                 .shortcut("⌘−")
                 .category("View")
                 .on_select(move |_, cx| {
-                    a.update(cx, |this, cx| this.zoom_out(cx));
+                    a.update(cx, |this, cx| this.zoom_out(ZoomTarget::Editor, cx));
                 }),
         );
 
@@ -4818,10 +13201,38 @@ impl AppState {
                 .shortcut("⌘0")
                 .category("View")
                 .on_select(move |_, cx| {
-                    a.update(cx, |this, cx| this.zoom_reset(cx));
+                    a.update(cx, |this, cx| this.zoom_reset(ZoomTarget::Editor, cx));
                 }),
         );
 
+        for (id, label, delta) in [
+            ("terminal-zoom-in", "Zoom In Terminal", 1),
+            ("terminal-zoom-out", "Zoom Out Terminal", -1),
+            ("terminal-zoom-reset", "Reset Terminal Zoom", 0),
+        ] {
+            let a = app.clone();
+            commands.push(
+                Command::new(id, label)
+                    .category("View")
+                    .on_select(move |_, cx| {
+                        a.update(cx, |this, cx| match delta {
+                            1 => this.zoom_in(ZoomTarget::Terminal, cx),
+                            -1 => this.zoom_out(ZoomTarget::Terminal, cx),
+                            _ => this.zoom_reset(ZoomTarget::Terminal, cx),
+                        });
+                    }),
+            );
+        }
+
+        for command in &mut commands {
+            let action = crate::keymap::COMMAND_ACTIONS
+                .iter()
+                .find(|(id, _)| *id == command.id.as_ref())
+                .map(|(_, action)| *action);
+            if let Some(shortcut) = action.and_then(|a| crate::keymap::shortcut_for_action(a, cx)) {
+                command.shortcut = Some(shortcut.into());
+            }
+        }
         commands
     }
 
@@ -4864,25 +13275,30 @@ impl AppState {
             source.display()
         );
 
-        cx.spawn(async move |_, _cx| {
+        cx.spawn(async move |this, cx| {
             let result = std::process::Command::new("osascript")
                 .arg("-e")
                 .arg(&script)
                 .output();
 
-            match result {
-                Ok(output) if output.status.success() => {
-                    eprintln!("[shiori] CLI installed to /usr/local/bin/shiori");
-                }
+            let outcome = match result {
+                Ok(output) if output.status.success() => Some((
+                    "CLI installed to /usr/local/bin/shiori".to_string(),
+                    ToastSeverity::Success,
+                )),
                 Ok(output) => {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    if !stderr.contains("User canceled") {
-                        eprintln!("[shiori] CLI install failed: {}", stderr);
-                    }
-                }
-                Err(err) => {
-                    eprintln!("[shiori] CLI install error: {}", err);
+                    (!stderr.contains("User canceled")).then(|| {
+                        (
+                            format!("CLI install failed: {}", stderr.trim()),
+                            ToastSeverity::Error,
+                        )
+                    })
                 }
+                Err(err) => Some((format!("CLI install error: {}", err), ToastSeverity::Error)),
+            };
+            if let Some((message, severity)) = outcome {
+                let _ = this.update(cx, |this, cx| this.notify(message, severity, cx));
             }
         })
         .detach();
@@ -4896,7 +13312,7 @@ impl Focusable for AppState {
 }
 
 impl Render for AppState {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let ide = use_ide_theme();
         let chrome = &ide.chrome;
 
@@ -4907,10 +13323,22 @@ impl Render for AppState {
         let is_terminal_mode = self.active_mode == ViewMode::Terminal;
         let show_left_panel = self.panel_visible && self.active_mode != ViewMode::Settings;
 
+        let (indent_guide, indent_guide_active) = if !self.settings.indent_guides {
+            (gpui::transparent_black(), gpui::transparent_black())
+        } else if self.settings.indent_guide_active_highlight {
+            (ide.editor.indent_guide, ide.editor.indent_guide_active)
+        } else {
+            (ide.editor.indent_guide, ide.editor.indent_guide)
+        };
+        let editor_padding_left = px(self.settings.editor_padding_left.max(0.0));
+        let editor_padding_top = px(self.settings.editor_padding_top.max(0.0));
+        let wide_gutter = needs_wide_gutter(&self.settings.gutter_items);
         let build_editor = |buffer: &Entity<EditorState>, cx: &mut App| {
             let syn = ide.syntax.clone();
             Editor::new(buffer)
-                .show_line_numbers(true, cx)
+                .pl(editor_padding_left)
+                .pt(editor_padding_top)
+                .show_line_numbers(wide_gutter, cx)
                 .show_border(false)
                 .cursor_color(ide.editor.cursor)
                 .selection_color(ide.editor.selection)
@@ -4921,7 +13349,7 @@ impl Render for AppState {
                 .current_line_color(ide.editor.current_line)
                 .bracket_match_color(ide.editor.bracket_match)
                 .word_highlight_color(ide.editor.word_highlight)
-                .indent_guide_colors(ide.editor.indent_guide, ide.editor.indent_guide_active)
+                .indent_guide_colors(indent_guide, indent_guide_active)
                 .fold_marker_color(ide.editor.fold_marker)
                 .syntax_color_fn(move |name| syn.color_for_capture(name))
                 .bg(gpui::transparent_black())
@@ -4956,7 +13384,12 @@ impl Render for AppState {
         } else if is_git_mode {
             div()
                 .size_full()
-                .child(GitView::new(self.git_state.clone(), self.review_state.clone(), self.zoom_level))
+                .child(GitView::new(
+                    self.git_state.clone(),
+                    self.review_state.clone(),
+                    self.editor_zoom,
+                    self.settings.scrollbar_visibility,
+                ))
                 .into_any_element()
         } else if is_terminal_mode {
             let active_terminal = self.terminals.get(self.active_terminal).cloned();
@@ -4965,6 +13398,7 @@ impl Render for AppState {
                     .size_full()
                     .flex()
                     .flex_col()
+                    .children(self.render_terminal_tab_strip(cx))
                     .child(div().flex_1().overflow_hidden().child(term))
                     .into_any_element()
             } else {
@@ -4977,16 +13411,93 @@ impl Render for AppState {
                     .text_size(px(14.0))
                     .child("No terminal sessions")
                     .into_any_element()
-            }
-        } else {
-            let right_pane_content: AnyElement = if !has_tabs {
-                self.render_welcome(&ide).into_any_element()
-            } else if let Some(image_path) = &active_image_path {
-                Self::render_image_preview(image_path, &ide).into_any_element()
-            } else if let Some(buffer) = self.buffers.get(self.active_tab) {
-                build_editor(buffer, cx).into_any_element()
+            }
+        } else {
+            let right_pane_content: AnyElement = if !has_tabs {
+                self.render_welcome(&ide, cx).into_any_element()
+            } else if let Some(image_path) = &active_image_path {
+                if is_svg_file(image_path) {
+                    let source = self
+                        .buffers
+                        .get(self.active_tab)
+                        .map(|b| b.read(cx).content().into_bytes())
+                        .unwrap_or_default();
+                    let image = Arc::new(Image::from_bytes(ImageFormat::Svg, source));
+                    Self::render_image_preview(image_path, image.into(), &ide)
+                        .relative()
+                        .child(self.render_svg_toggle(false, cx))
+                        .into_any_element()
+                } else {
+                    Self::render_image_preview(image_path, image_path.clone().into(), &ide)
+                        .into_any_element()
+                }
+            } else if let Some(buffer) = self.buffers.get(self.active_tab) {
+                let is_svg = self
+                    .tab_meta
+                    .get(self.active_tab)
+                    .and_then(|m| m.file_path.as_deref())
+                    .is_some_and(is_svg_file);
+                if self.pending_view_restore.contains_key(&buffer.entity_id()) {
+                    let app = cx.entity().downgrade();
+                    let restore = buffer.clone();
+                    window.on_next_frame(move |_, cx| {
+                        let _ = app.update(cx, |this, cx| this.apply_view_restore(&restore, cx));
+                    });
+                }
+                let viewport = self.editor_viewport.clone();
+                div()
+                    .id("editor-pane")
+                    .relative()
+                    .size_full()
+                    .capture_action(cx.listener(|this, _: &EditorUndo, window, cx| {
+                        this.grouped_undo(false, window, cx)
+                    }))
+                    .capture_action(cx.listener(|this, _: &EditorRedo, window, cx| {
+                        this.grouped_undo(true, window, cx)
+                    }))
+                    .capture_action(cx.listener(|this, _: &EditorTab, window, cx| {
+                        this.insert_indent(window, cx)
+                    }))
+                    .capture_action(cx.listener(|this, _: &EditorEnter, window, cx| {
+                        this.insert_newline_with_indent(window, cx)
+                    }))
+                    .on_action(cx.listener(|this, _: &Outdent, window, cx| {
+                        this.outdent_selection(window, cx)
+                    }))
+                    .capture_action(cx.listener(|this, _: &EditorPaste, window, cx| {
+                        this.paste_preserving_indent(window, cx)
+                    }))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, event: &MouseDownEvent, window, cx| {
+                            this.multi_edit = None;
+                            if event.click_count == 2 {
+                                this.select_word_at(event.position, window, cx);
+                            }
+                        }),
+                    )
+                    .on_mouse_move(cx.listener(Self::on_editor_mouse_move))
+                    .on_hover(cx.listener(|this, hovered: &bool, _, cx| {
+                        this.on_editor_hover_changed(*hovered, cx)
+                    }))
+                    .child(build_editor(buffer, cx))
+                    .children(self.render_gutter(buffer, cx))
+                    .children(self.render_git_gutter(buffer, cx))
+                    .children(self.render_gutter_line_select(buffer, cx))
+                    .children(self.render_goto_line_highlight(buffer))
+                    .children(self.render_bracket_guide(buffer, cx))
+                    .children(self.render_multi_edit_cursors(buffer, cx))
+                    .child(
+                        canvas(move |bounds, _, _| viewport.set(bounds), |_, _, _, _| {})
+                            .absolute()
+                            .top_0()
+                            .left_0()
+                            .size_full(),
+                    )
+                    .when(is_svg, |el| el.child(self.render_svg_toggle(true, cx)))
+                    .into_any_element()
             } else {
-                self.render_welcome(&ide).into_any_element()
+                self.render_welcome(&ide, cx).into_any_element()
             };
 
             let tab_bar_row = if !self.terminal_fullscreen {
@@ -5029,12 +13540,20 @@ impl Render for AppState {
                                     .child("\u{203A}"),
                             );
                         }
-                        let target_line = *line;
+                        let target_line = *line + 1;
                         let app_bc = app_entity_bc.clone();
+                        let is_current = i + 1 == crumbs.len();
                         row = row.child(
                             div()
                                 .cursor_pointer()
+                                .px(px(4.0))
+                                .rounded(px(3.0))
                                 .text_color(chrome.text_secondary)
+                                .when(is_current, |el| {
+                                    el.text_color(chrome.bright)
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .bg(chrome.accent.opacity(0.12))
+                                })
                                 .hover(|s| s.text_color(chrome.bright))
                                 .child(name.clone())
                                 .on_mouse_down(MouseButton::Left, move |_, _, cx| {
@@ -5064,13 +13583,15 @@ impl Render for AppState {
                 .flex_col()
                 .children(tab_bar_row)
                 .children(breadcrumb_bar)
+                .children(self.render_whitespace_notice(cx))
                 .child(
                     div()
                         .flex_1()
                         .overflow_hidden()
                         .cursor(CursorStyle::IBeam)
                         .child(right_pane_content),
-                );
+                )
+                .children(self.render_status_bar(cx));
 
             if self.terminal_fullscreen || is_terminal_mode {
                 let active_terminal = self.terminals.get(self.active_terminal).cloned();
@@ -5078,6 +13599,7 @@ impl Render for AppState {
                     .size_full()
                     .flex()
                     .flex_col()
+                    .children(self.render_terminal_tab_strip(cx))
                     .child(div().flex_1().overflow_hidden().children(active_terminal))
                     .into_any_element()
             } else {
@@ -5115,8 +13637,8 @@ impl Render for AppState {
         div()
             .key_context("ShioriApp")
             .track_focus(&self.focus_handle)
-            .on_action(cx.listener(|this, _: &SaveFile, _, cx| {
-                this.save_active(cx);
+            .on_action(cx.listener(|this, _: &SaveFile, window, cx| {
+                this.save_active(window, cx);
             }))
             .on_action(cx.listener(|this, _: &CloseTab, window, cx| {
                 if this.terminal_fullscreen && !this.terminals.is_empty() {
@@ -5145,14 +13667,23 @@ impl Render for AppState {
                     this.close_terminal_at(this.active_terminal, cx);
                 }
             }))
-            .on_action(cx.listener(|this, _: &ZoomIn, _, cx| {
-                this.zoom_in(cx);
+            .on_action(cx.listener(|this, _: &ZoomIn, window, cx| {
+                this.zoom_in(this.zoom_target(window, cx), cx);
             }))
-            .on_action(cx.listener(|this, _: &ZoomOut, _, cx| {
-                this.zoom_out(cx);
+            .on_action(cx.listener(|this, _: &ZoomOut, window, cx| {
+                this.zoom_out(this.zoom_target(window, cx), cx);
             }))
-            .on_action(cx.listener(|this, _: &ZoomReset, _, cx| {
-                this.zoom_reset(cx);
+            .on_action(cx.listener(|this, _: &ZoomReset, window, cx| {
+                this.zoom_reset(this.zoom_target(window, cx), cx);
+            }))
+            .on_action(cx.listener(|this, _: &TerminalZoomIn, _, cx| {
+                this.zoom_in(ZoomTarget::Terminal, cx);
+            }))
+            .on_action(cx.listener(|this, _: &TerminalZoomOut, _, cx| {
+                this.zoom_out(ZoomTarget::Terminal, cx);
+            }))
+            .on_action(cx.listener(|this, _: &TerminalZoomReset, _, cx| {
+                this.zoom_reset(ZoomTarget::Terminal, cx);
             }))
             .on_action(cx.listener(|this, _: &OpenFile, _, cx| {
                 this.open_file_dialog(cx);
@@ -5211,6 +13742,12 @@ impl Render for AppState {
                 window.focus(&fh);
                 cx.notify();
             }))
+            .on_action(cx.listener(|this, _: &crate::search_bar::FindNext, _, cx| {
+                this.find_in_active(true, cx);
+            }))
+            .on_action(cx.listener(|this, _: &crate::search_bar::FindPrevious, _, cx| {
+                this.find_in_active(false, cx);
+            }))
             .on_action(cx.listener(|this, _: &CloseSearch, _, cx| {
                 if this.search_visible || this.goto_line_visible {
                     this.close_search_internal(cx);
@@ -5219,6 +13756,8 @@ impl Render for AppState {
             .on_action(cx.listener(|this, _: &GotoLine, window, cx| {
                 this.search_visible = false;
                 this.goto_line_visible = true;
+                this.goto_line_error = false;
+                this.goto_line_origin = None;
                 if let Some(buffer) = this.buffers.get(this.active_tab) {
                     let line_str = (buffer.read(cx).cursor().line + 1).to_string();
                     this.goto_line_input.update(cx, |state, cx| {
@@ -5230,6 +13769,7 @@ impl Render for AppState {
                 cx.notify();
             }))
             .on_action(cx.listener(|this, _: &CloseGotoLine, _, cx| {
+                this.revert_goto_preview(cx);
                 this.goto_line_visible = false;
                 cx.notify();
             }))
@@ -5263,14 +13803,43 @@ impl Render for AppState {
                 }
                 cx.notify();
             }))
-            .on_action(cx.listener(|this, _: &ToggleSymbolOutline, _, cx| {
-                this.symbol_outline_visible = !this.symbol_outline_visible;
-                this.symbol_outline_filter.clear();
-                cx.notify();
+            .on_action(cx.listener(|this, _: &ToggleSymbolOutline, window, cx| {
+                this.toggle_symbol_outline(window, cx);
             }))
             .on_action(cx.listener(|this, _: &ToggleCommandPalette, window, cx| {
                 this.toggle_command_palette(window, cx);
             }))
+            .on_action(cx.listener(|this, _: &SwitchWorkspace, window, cx| {
+                if this.workspace_switcher_open {
+                    this.close_workspace_switcher(window, cx);
+                } else {
+                    this.open_workspace_switcher(window, cx);
+                }
+            }))
+            .on_action(cx.listener(|this, _: &RewrapComment, window, cx| {
+                this.rewrap_comment_at_cursor(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &CopyWithHighlighting, window, cx| {
+                this.copy_with_highlighting(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &InsertSnippet, _, cx| {
+                this.open_snippet_picker(cx);
+            }))
+            .on_action(cx.listener(|this, _: &GoToNextProblem, window, cx| {
+                this.go_to_problem(true, window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &GoToPreviousProblem, window, cx| {
+                this.go_to_problem(false, window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &FormatDocument, window, cx| {
+                this.format_active(false, window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ExpandSelection, window, cx| {
+                this.expand_selection(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &ShrinkSelection, window, cx| {
+                this.shrink_selection(window, cx);
+            }))
             .on_action(cx.listener(|this, _: &FoldToggle, _, cx| {
                 if let Some(buffer) = this.buffers.get(this.active_tab).cloned() {
                     let line = buffer.read(cx).cursor().line;
@@ -5305,6 +13874,9 @@ impl Render for AppState {
             .on_action(cx.listener(|this, _: &GotoDefinition, _, cx| {
                 this.goto_definition(cx);
             }))
+            .on_action(cx.listener(|this, _: &ShowHover, _, cx| {
+                this.request_cursor_hover(Duration::ZERO, cx);
+            }))
             .on_action(cx.listener(|this, _: &CompletionUp, _, cx| {
                 if this.completion_state.read(cx).is_visible() {
                     this.completion_move_up(cx);
@@ -5319,16 +13891,20 @@ impl Render for AppState {
                     cx.propagate();
                 }
             }))
-            .on_action(cx.listener(|this, _: &CompletionAccept, _, cx| {
+            .on_action(cx.listener(|this, _: &CompletionAccept, window, cx| {
                 if this.completion_state.read(cx).is_visible() {
-                    this.apply_completion(cx);
+                    this.apply_completion(window, cx);
                 } else {
                     cx.propagate();
                 }
             }))
-            .on_action(cx.listener(|this, _: &CompletionDismiss, _, cx| {
+            .on_action(cx.listener(|this, _: &CompletionDismiss, window, cx| {
                 if this.completion_state.read(cx).is_visible() {
                     this.completion_dismiss(cx);
+                } else if this.hover_info.is_some() {
+                    this.close_hover(cx);
+                } else if this.symbol_outline_visible {
+                    this.close_symbol_outline(window, cx);
                 } else if this.search_visible || this.goto_line_visible {
                     this.close_search_internal(cx);
                 } else if this.panel_visible {
@@ -5348,14 +13924,14 @@ impl Render for AppState {
                     this.completion_move_down(cx);
                 }
             }))
-            .on_action(cx.listener(|this, _: &EditorTab, _, cx| {
+            .on_action(cx.listener(|this, _: &EditorTab, window, cx| {
                 if this.completion_state.read(cx).is_visible() {
-                    this.apply_completion(cx);
+                    this.apply_completion(window, cx);
                 }
             }))
-            .on_action(cx.listener(|this, _: &EditorEnter, _, cx| {
+            .on_action(cx.listener(|this, _: &EditorEnter, window, cx| {
                 if this.completion_state.read(cx).is_visible() {
-                    this.apply_completion(cx);
+                    this.apply_completion(window, cx);
                 }
             }))
             .on_drop::<ExternalPaths>(cx.listener(|this, paths: &ExternalPaths, _, cx| {
@@ -5437,52 +14013,522 @@ impl Render for AppState {
                     .filter(|_| self.command_palette_open),
                 |el, palette| el.child(palette.clone()),
             )
+            .when(self.workspace_switcher_open, |el| {
+                el.child(self.render_workspace_switcher(cx))
+            })
+            .when_some(self.tab_list_anchor, |el, position| {
+                el.child(self.render_tab_list(position, cx))
+            })
             .child({
                 let app_entity = cx.entity().clone();
                 let mut menu = CompletionMenu::new(self.completion_state.clone());
                 if let Some(buffer) = self.buffers.get(self.active_tab) {
                     menu = menu.editor_state(buffer.clone());
                 }
-                menu.on_accept(move |_, cx| {
+                menu = menu.code_font(self.settings.editor_font.clone());
+                menu.on_accept(move |window, cx| {
                     app_entity.update(cx, |this, cx| {
-                        this.apply_completion(cx);
+                        this.apply_completion(window, cx);
                     });
                 })
             })
-            .when_some(self.hover_info.clone(), |el, (contents, anchor)| {
+            .when_some(self.hunk_popover.as_ref().map(|p| p.position), |el, position| {
+                el.child(self.render_hunk_popover(position, cx))
+            })
+            .when_some(self.context_menu.as_ref(), |el, menu| {
+                el.child(self.render_context_menu(menu, cx))
+            })
+            .when_some(self.hover_info.as_ref(), |el, hover| {
                 let chrome = use_ide_theme().chrome;
+                let pinned = hover.pinned;
+                let app = cx.entity().clone();
+                let app2 = cx.entity().clone();
+                let language = self
+                    .buffers
+                    .get(self.active_tab)
+                    .map(|b| b.read(cx).language())
+                    .unwrap_or(Language::Plain);
+                let code_font = SharedString::from(self.settings.editor_font.clone());
+                el.child(
+                    deferred(
+                        anchored()
+                            .position(hover.anchor)
+                            .snap_to_window_with_margin(px(8.0))
+                            .child(
+                                div()
+                                    .id("hover-popup")
+                                    .occlude()
+                                    .relative()
+                                    .mt(px(4.0))
+                                    .max_w(px(500.0))
+                                    .max_h(px(300.0))
+                                    .flex()
+                                    .flex_col()
+                                    .bg(chrome.panel_bg)
+                                    .border_1()
+                                    .border_color(if pinned {
+                                        chrome.accent.opacity(0.6)
+                                    } else {
+                                        chrome.header_border
+                                    })
+                                    .rounded(px(8.0))
+                                    .shadow_lg()
+                                    .overflow_hidden()
+                                    .text_size(px(13.0))
+                                    .text_color(chrome.bright)
+                                    .on_mouse_down_out(move |_, _, cx| {
+                                        app.update(cx, |this, cx| this.close_hover(cx));
+                                    })
+                                    .child(
+                                        div()
+                                            .id("hover-popup-scroll")
+                                            .flex_1()
+                                            .min_h_0()
+                                            .overflow_y_scroll()
+                                            .track_scroll(&self.hover_scroll_handle)
+                                            .p(px(10.0))
+                                            .pr(px(28.0))
+                                            .child(render_markdown(
+                                                &hover.contents,
+                                                language,
+                                                code_font,
+                                            )),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("hover-pin")
+                                            .absolute()
+                                            .top(px(6.0))
+                                            .right(px(6.0))
+                                            .p(px(3.0))
+                                            .rounded(px(4.0))
+                                            .cursor_pointer()
+                                            .when(pinned, |el| el.bg(chrome.accent.opacity(0.2)))
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.08)))
+                                            .child(Icon::new("pin").size(px(12.0)).color(
+                                                if pinned {
+                                                    chrome.accent
+                                                } else {
+                                                    chrome.text_secondary
+                                                },
+                                            ))
+                                            .on_click(move |_, _, cx| {
+                                                app2.update(cx, |this, cx| {
+                                                    this.toggle_hover_pin(cx)
+                                                });
+                                            }),
+                                    ),
+                            ),
+                    )
+                    .with_priority(1),
+                )
+            })
+            .when_some(self.confirm_close_terminal, |el, _idx| {
+                let ide = use_ide_theme();
+                let chrome = &ide.chrome;
+                let app = cx.entity().clone();
+                let app2 = cx.entity().clone();
+                let app3 = cx.entity().clone();
+                el.child(
+                    deferred(
+                        Dialog::new()
+                            .width(px(400.0))
+                            .bg(chrome.panel_bg)
+                            .text_color(chrome.bright)
+                            .header(
+                                div()
+                                    .p(px(16.0))
+                                    .pb(px(8.0))
+                                    .text_size(px(15.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(chrome.bright)
+                                    .child("Close Terminal?"),
+                            )
+                            .content(
+                                div()
+                                    .px(px(16.0))
+                                    .pb(px(16.0))
+                                    .text_size(px(13.0))
+                                    .text_color(chrome.text_secondary)
+                                    .child("This terminal has a running process. Closing it will terminate the process."),
+                            )
+                            .footer(
+                                div()
+                                    .flex()
+                                    .justify_end()
+                                    .gap(px(8.0))
+                                    .p(px(16.0))
+                                    .pt(px(0.0))
+                                    .child(
+                                        div()
+                                            .id("cancel-close-term")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .text_color(chrome.text_secondary)
+                                            .border_1()
+                                            .border_color(chrome.header_border)
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                            .on_click(move |_, _, cx| {
+                                                app2.update(cx, |this, cx| {
+                                                    this.confirm_close_terminal = None;
+                                                    cx.notify();
+                                                });
+                                            })
+                                            .child("Cancel"),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("confirm-close-term")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .bg(hsla(0.0, 0.7, 0.5, 1.0))
+                                            .text_color(gpui::white())
+                                            .hover(|s| s.bg(hsla(0.0, 0.7, 0.45, 1.0)))
+                                            .on_click(move |_, _, cx| {
+                                                app3.update(cx, |this, cx| {
+                                                    if let Some(i) = this.confirm_close_terminal.take() {
+                                                        this.force_close_terminal_at(i, cx);
+                                                    }
+                                                    cx.notify();
+                                                });
+                                            })
+                                            .child("Close Terminal"),
+                                    ),
+                            )
+                            .on_backdrop_click(move |_, cx| {
+                                app.update(cx, |this, cx| {
+                                    this.confirm_close_terminal = None;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .with_priority(2),
+                )
+            })
+            .when_some(self.confirm_delete_path.clone(), |el, (path, trash)| {
+                let ide = use_ide_theme();
+                let chrome = &ide.chrome;
+                let app = cx.entity().clone();
+                let app2 = cx.entity().clone();
+                let app3 = cx.entity().clone();
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let dirty = self.dirty_tabs_under(&path, cx).len();
+                let (title, action) = match (trash, dirty) {
+                    (true, _) => ("Unsaved Changes", "Save & Move to Trash"),
+                    (false, 0) => ("Delete Permanently?", "Delete"),
+                    (false, _) => ("Delete Permanently?", "Discard & Delete"),
+                };
+                let mut message = if trash {
+                    format!("\"{}\" will be moved to the trash.", file_name)
+                } else {
+                    format!(
+                        "\"{}\" will be deleted from disk. This cannot be undone.",
+                        file_name
+                    )
+                };
+                match dirty {
+                    0 => {}
+                    1 => message.push_str(" 1 open file has unsaved changes."),
+                    n => message.push_str(&format!(" {} open files have unsaved changes.", n)),
+                }
+                el.child(
+                    deferred(
+                        Dialog::new()
+                            .width(px(400.0))
+                            .bg(chrome.panel_bg)
+                            .text_color(chrome.bright)
+                            .header(
+                                div()
+                                    .p(px(16.0))
+                                    .pb(px(8.0))
+                                    .text_size(px(15.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(chrome.bright)
+                                    .child(title),
+                            )
+                            .content(
+                                div()
+                                    .px(px(16.0))
+                                    .pb(px(16.0))
+                                    .text_size(px(13.0))
+                                    .text_color(chrome.text_secondary)
+                                    .child(message),
+                            )
+                            .footer(
+                                div()
+                                    .flex()
+                                    .justify_end()
+                                    .gap(px(8.0))
+                                    .p(px(16.0))
+                                    .pt(px(0.0))
+                                    .child(
+                                        div()
+                                            .id("cancel-delete-path")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .text_color(chrome.text_secondary)
+                                            .border_1()
+                                            .border_color(chrome.header_border)
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                            .on_click(move |_, _, cx| {
+                                                app2.update(cx, |this, cx| {
+                                                    this.confirm_delete_path = None;
+                                                    cx.notify();
+                                                });
+                                            })
+                                            .child("Cancel"),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("confirm-delete-path")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .bg(hsla(0.0, 0.7, 0.5, 1.0))
+                                            .text_color(gpui::white())
+                                            .hover(|s| s.bg(hsla(0.0, 0.7, 0.45, 1.0)))
+                                            .on_click(move |_, _, cx| {
+                                                app3.update(cx, |this, cx| {
+                                                    if let Some((path, trash)) =
+                                                        this.confirm_delete_path.take()
+                                                    {
+                                                        this.confirm_delete(path, trash, cx);
+                                                    }
+                                                    cx.notify();
+                                                });
+                                            })
+                                            .child(action),
+                                    ),
+                            )
+                            .on_backdrop_click(move |_, cx| {
+                                app.update(cx, |this, cx| {
+                                    this.confirm_delete_path = None;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .with_priority(2),
+                )
+            })
+            .when_some(self.confirm_deleted_file.clone(), |el, path| {
+                let ide = use_ide_theme();
+                let chrome = &ide.chrome;
+                let app = cx.entity().clone();
+                let app2 = cx.entity().clone();
+                let app3 = cx.entity().clone();
+                let keep_path = path.clone();
+                let close_path = path.clone();
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
                 el.child(
                     deferred(
-                        anchored()
-                            .position(anchor)
-                            .snap_to_window_with_margin(px(8.0))
-                            .child(
+                        Dialog::new()
+                            .width(px(400.0))
+                            .bg(chrome.panel_bg)
+                            .text_color(chrome.bright)
+                            .header(
                                 div()
-                                    .mt(px(4.0))
-                                    .max_w(px(500.0))
-                                    .max_h(px(300.0))
-                                    .bg(chrome.panel_bg)
-                                    .border_1()
-                                    .border_color(chrome.header_border)
-                                    .rounded(px(8.0))
-                                    .shadow_lg()
-                                    .overflow_hidden()
-                                    .p(px(10.0))
+                                    .p(px(16.0))
+                                    .pb(px(8.0))
+                                    .text_size(px(15.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(chrome.bright)
+                                    .child("File Deleted on Disk"),
+                            )
+                            .content(
+                                div()
+                                    .px(px(16.0))
+                                    .pb(px(16.0))
                                     .text_size(px(13.0))
+                                    .text_color(chrome.text_secondary)
+                                    .child(format!(
+                                        "\"{}\" was deleted outside of Shiori. Autosave is paused for this tab.",
+                                        file_name
+                                    )),
+                            )
+                            .footer(
+                                div()
+                                    .flex()
+                                    .justify_end()
+                                    .gap(px(8.0))
+                                    .p(px(16.0))
+                                    .pt(px(0.0))
+                                    .child(
+                                        div()
+                                            .id("keep-deleted-file")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .text_color(chrome.text_secondary)
+                                            .border_1()
+                                            .border_color(chrome.header_border)
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                            .on_click(move |_, _, cx| {
+                                                app2.update(cx, |this, cx| {
+                                                    this.keep_deleted_tab(&keep_path, cx);
+                                                });
+                                            })
+                                            .child("Keep in Editor"),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("close-deleted-file")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .bg(hsla(0.0, 0.7, 0.5, 1.0))
+                                            .text_color(gpui::white())
+                                            .hover(|s| s.bg(hsla(0.0, 0.7, 0.45, 1.0)))
+                                            .on_click(move |_, _, cx| {
+                                                app3.update(cx, |this, cx| {
+                                                    this.close_deleted_tab(&close_path, cx);
+                                                });
+                                            })
+                                            .child("Close"),
+                                    ),
+                            )
+                            .on_backdrop_click(move |_, cx| {
+                                app.update(cx, |this, cx| {
+                                    this.confirm_deleted_file = None;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .with_priority(2),
+                )
+            })
+            .when_some(self.pending_open_folder.clone(), |el, path| {
+                let ide = use_ide_theme();
+                let chrome = &ide.chrome;
+                let app = cx.entity().clone();
+                let app2 = cx.entity().clone();
+                let app3 = cx.entity().clone();
+                let add_path = path.clone();
+                let folder_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string());
+                el.child(
+                    deferred(
+                        Dialog::new()
+                            .width(px(400.0))
+                            .bg(chrome.panel_bg)
+                            .text_color(chrome.bright)
+                            .header(
+                                div()
+                                    .p(px(16.0))
+                                    .pb(px(8.0))
+                                    .text_size(px(15.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
                                     .text_color(chrome.bright)
-                                    .overflow_hidden()
-                                    .child(contents),
-                            ),
+                                    .child("Open Folder"),
+                            )
+                            .content(
+                                div()
+                                    .px(px(16.0))
+                                    .pb(px(16.0))
+                                    .text_size(px(13.0))
+                                    .text_color(chrome.text_secondary)
+                                    .child(format!(
+                                        "Add \"{}\" to the current workspace, or replace the workspace with it?",
+                                        folder_name
+                                    )),
+                            )
+                            .footer(
+                                div()
+                                    .flex()
+                                    .justify_end()
+                                    .gap(px(8.0))
+                                    .p(px(16.0))
+                                    .pt(px(0.0))
+                                    .child(
+                                        div()
+                                            .id("open-folder-replace")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .text_color(chrome.text_secondary)
+                                            .border_1()
+                                            .border_color(chrome.header_border)
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                            .on_click(move |_, _, cx| {
+                                                app2.update(cx, |this, cx| {
+                                                    this.pending_open_folder = None;
+                                                    this.open_folder(path.clone(), cx);
+                                                });
+                                            })
+                                            .child("Replace Workspace"),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("open-folder-add")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .bg(chrome.accent)
+                                            .text_color(gpui::white())
+                                            .hover(|s| s.opacity(0.9))
+                                            .on_click(move |_, _, cx| {
+                                                app3.update(cx, |this, cx| {
+                                                    this.pending_open_folder = None;
+                                                    this.add_workspace_root(add_path.clone(), cx);
+                                                });
+                                            })
+                                            .child("Add to Workspace"),
+                                    ),
+                            )
+                            .on_backdrop_click(move |_, cx| {
+                                app.update(cx, |this, cx| {
+                                    this.pending_open_folder = None;
+                                    cx.notify();
+                                });
+                            }),
                     )
-                    .with_priority(1),
+                    .with_priority(2),
                 )
             })
-            .when_some(self.confirm_close_terminal, |el, _idx| {
+            .when_some(self.pending_external_open.first().cloned(), |el, path| {
                 let ide = use_ide_theme();
                 let chrome = &ide.chrome;
                 let app = cx.entity().clone();
                 let app2 = cx.entity().clone();
                 let app3 = cx.entity().clone();
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string());
+                let file_size = std::fs::metadata(&path)
+                    .map(|m| format!(" ({})", format_file_size(m.len())))
+                    .unwrap_or_default();
+                let (title, reason) = if is_too_large(&path) {
+                    ("Large File", "is too large to open in the editor")
+                } else {
+                    ("Binary File", "is not a text file")
+                };
                 el.child(
                     deferred(
                         Dialog::new()
@@ -5496,7 +14542,7 @@ impl Render for AppState {
                                     .text_size(px(15.0))
                                     .font_weight(FontWeight::SEMIBOLD)
                                     .text_color(chrome.bright)
-                                    .child("Close Terminal?"),
+                                    .child(title),
                             )
                             .content(
                                 div()
@@ -5504,7 +14550,10 @@ impl Render for AppState {
                                     .pb(px(16.0))
                                     .text_size(px(13.0))
                                     .text_color(chrome.text_secondary)
-                                    .child("This terminal has a running process. Closing it will terminate the process."),
+                                    .child(format!(
+                                        "\"{}\"{} {}. Open it with the default application instead?",
+                                        file_name, file_size, reason
+                                    )),
                             )
                             .footer(
                                 div()
@@ -5515,7 +14564,7 @@ impl Render for AppState {
                                     .pt(px(0.0))
                                     .child(
                                         div()
-                                            .id("cancel-close-term")
+                                            .id("open-external-cancel")
                                             .px(px(14.0))
                                             .py(px(6.0))
                                             .rounded(px(6.0))
@@ -5527,7 +14576,7 @@ impl Render for AppState {
                                             .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
                                             .on_click(move |_, _, cx| {
                                                 app2.update(cx, |this, cx| {
-                                                    this.confirm_close_terminal = None;
+                                                    this.dismiss_external_open();
                                                     cx.notify();
                                                 });
                                             })
@@ -5535,29 +14584,28 @@ impl Render for AppState {
                                     )
                                     .child(
                                         div()
-                                            .id("confirm-close-term")
+                                            .id("open-external-confirm")
                                             .px(px(14.0))
                                             .py(px(6.0))
                                             .rounded(px(6.0))
                                             .text_size(px(13.0))
                                             .cursor_pointer()
-                                            .bg(hsla(0.0, 0.7, 0.5, 1.0))
+                                            .bg(chrome.accent)
                                             .text_color(gpui::white())
-                                            .hover(|s| s.bg(hsla(0.0, 0.7, 0.45, 1.0)))
+                                            .hover(|s| s.opacity(0.9))
                                             .on_click(move |_, _, cx| {
                                                 app3.update(cx, |this, cx| {
-                                                    if let Some(i) = this.confirm_close_terminal.take() {
-                                                        this.force_close_terminal_at(i, cx);
-                                                    }
+                                                    this.dismiss_external_open();
+                                                    this.open_externally(path.clone(), cx);
                                                     cx.notify();
                                                 });
                                             })
-                                            .child("Close Terminal"),
+                                            .child("Open with Default App"),
                                     ),
                             )
                             .on_backdrop_click(move |_, cx| {
                                 app.update(cx, |this, cx| {
-                                    this.confirm_close_terminal = None;
+                                    this.dismiss_external_open();
                                     cx.notify();
                                 });
                             }),
@@ -5565,5 +14613,141 @@ impl Render for AppState {
                     .with_priority(2),
                 )
             })
+            .when(self.confirm_quit, |el| {
+                let ide = use_ide_theme();
+                let chrome = &ide.chrome;
+                let app = cx.entity().clone();
+                let app2 = cx.entity().clone();
+                let app3 = cx.entity().clone();
+                let app4 = cx.entity().clone();
+                let (unsaved, running) = self.quit_blockers(cx);
+                let has_unsaved = !unsaved.is_empty();
+
+                let mut details = div()
+                    .px(px(16.0))
+                    .pb(px(16.0))
+                    .flex()
+                    .flex_col()
+                    .gap(px(6.0))
+                    .text_size(px(13.0))
+                    .text_color(chrome.text_secondary);
+                if has_unsaved {
+                    details = details
+                        .child(format!("{} file(s) with unsaved changes:", unsaved.len()))
+                        .children(unsaved.into_iter().take(8).map(|name| {
+                            div()
+                                .pl(px(12.0))
+                                .text_color(chrome.bright)
+                                .child(format!("• {}", name))
+                        }));
+                }
+                if running > 0 {
+                    details = details.child(format!(
+                        "{} running terminal process(es) will be terminated.",
+                        running
+                    ));
+                }
+
+                el.child(
+                    deferred(
+                        Dialog::new()
+                            .width(px(440.0))
+                            .bg(chrome.panel_bg)
+                            .text_color(chrome.bright)
+                            .header(
+                                div()
+                                    .p(px(16.0))
+                                    .pb(px(8.0))
+                                    .text_size(px(15.0))
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(chrome.bright)
+                                    .child("Quit Shiori?"),
+                            )
+                            .content(details)
+                            .footer(
+                                div()
+                                    .flex()
+                                    .justify_end()
+                                    .gap(px(8.0))
+                                    .p(px(16.0))
+                                    .pt(px(0.0))
+                                    .child(
+                                        div()
+                                            .id("cancel-quit")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .text_color(chrome.text_secondary)
+                                            .border_1()
+                                            .border_color(chrome.header_border)
+                                            .hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                                            .on_click(move |_, _, cx| {
+                                                app2.update(cx, |this, cx| {
+                                                    this.confirm_quit = false;
+                                                    cx.notify();
+                                                });
+                                            })
+                                            .child("Cancel"),
+                                    )
+                                    .child(
+                                        div()
+                                            .id("quit-anyway")
+                                            .px(px(14.0))
+                                            .py(px(6.0))
+                                            .rounded(px(6.0))
+                                            .text_size(px(13.0))
+                                            .cursor_pointer()
+                                            .bg(hsla(0.0, 0.7, 0.5, 1.0))
+                                            .text_color(gpui::white())
+                                            .hover(|s| s.bg(hsla(0.0, 0.7, 0.45, 1.0)))
+                                            .on_click(move |_, window, cx| {
+                                                app3.update(cx, |this, cx| {
+                                                    this.quit_now(false, window, cx)
+                                                });
+                                            })
+                                            .child("Quit Anyway"),
+                                    )
+                                    .when(has_unsaved, |el| {
+                                        el.child(
+                                            div()
+                                                .id("save-and-quit")
+                                                .px(px(14.0))
+                                                .py(px(6.0))
+                                                .rounded(px(6.0))
+                                                .text_size(px(13.0))
+                                                .cursor_pointer()
+                                                .bg(chrome.accent)
+                                                .text_color(gpui::white())
+                                                .hover(|s| s.opacity(0.9))
+                                                .on_click(move |_, window, cx| {
+                                                    app4.update(cx, |this, cx| {
+                                                        this.quit_now(true, window, cx)
+                                                    });
+                                                })
+                                                .child("Save & Quit"),
+                                        )
+                                    }),
+                            )
+                            .on_backdrop_click(move |_, cx| {
+                                app.update(cx, |this, cx| {
+                                    this.confirm_quit = false;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .with_priority(3),
+                )
+            })
+            .when(!self.toasts.is_empty(), |el| {
+                let app = cx.entity().clone();
+                el.child(
+                    deferred(render_toasts(&self.toasts, move |id, cx| {
+                        app.update(cx, |this, cx| this.dismiss_toast(id, cx));
+                    }))
+                    .with_priority(4),
+                )
+            })
     }
 }
@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PairEdit {
+    Insert { text: String, caret: usize },
+    Skip(usize),
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+pub fn typed_edit(
+    line: &str,
+    col: usize,
+    typed: &str,
+    pairs: &[(String, String)],
+    builtin: &[(&str, &str)],
+) -> Option<PairEdit> {
+    let (before, after) = line.split_at(col.min(line.len()));
+    if pairs.iter().any(|(_, close)| close == typed) && after.starts_with(typed) {
+        return Some(PairEdit::Skip(typed.len()));
+    }
+    let raw = PairEdit::Insert {
+        text: typed.to_string(),
+        caret: typed.len(),
+    };
+    let typed_line = format!("{}{}", before, typed);
+    if let Some((open, close)) = pairs
+        .iter()
+        .filter(|(open, _)| !open.is_empty() && typed_line.ends_with(open.as_str()))
+        .max_by_key(|(open, _)| open.len())
+    {
+        let prev = before.chars().next_back();
+        let next = after.chars().next();
+        let unbalanced = open == close && before.matches(open.as_str()).count() % 2 == 1;
+        if next.is_some_and(is_word_char)
+            || open == close && (prev.is_some_and(is_word_char) || unbalanced)
+        {
+            return Some(raw);
+        }
+        return Some(PairEdit::Insert {
+            text: format!("{}{}", typed, close),
+            caret: typed.len(),
+        });
+    }
+    builtin
+        .iter()
+        .any(|(open, close)| *open == typed || *close == typed)
+        .then_some(raw)
+}
+
+pub fn backspace_range(
+    line: &str,
+    col: usize,
+    pairs: &[(String, String)],
+    builtin: &[(&str, &str)],
+) -> Option<Range<usize>> {
+    if col == 0 || col > line.len() {
+        return None;
+    }
+    let (before, after) = line.split_at(col);
+    if let Some((open, close)) = pairs.iter().find(|(open, close)| {
+        !open.is_empty() && before.ends_with(open.as_str()) && after.starts_with(close.as_str())
+    }) {
+        return Some(col - open.len()..col + close.len());
+    }
+    let prev = before.chars().next_back()?;
+    builtin
+        .iter()
+        .any(|(open, close)| before.ends_with(open) && after.starts_with(close))
+        .then(|| col - prev.len_utf8()..col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backspace_range, typed_edit, PairEdit};
+
+    const BUILTIN: [(&str, &str); 2] = [("(", ")"), ("'", "'")];
+
+    fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|(o, c)| (o.to_string(), c.to_string()))
+            .collect()
+    }
+
+    fn insert(text: &str, caret: usize) -> Option<PairEdit> {
+        Some(PairEdit::Insert {
+            text: text.into(),
+            caret,
+        })
+    }
+
+    #[test]
+    fn test_typed_edit_respects_language_pairs() {
+        let rust = pairs(&[("(", ")"), ("<", ">")]);
+        assert_eq!(typed_edit("foo", 3, "(", &rust, &BUILTIN), insert("()", 1));
+        assert_eq!(typed_edit("Vec", 3, "<", &rust, &BUILTIN), insert("<>", 1));
+        let skip = Some(PairEdit::Skip(1));
+        assert_eq!(typed_edit("f()", 2, ")", &rust, &BUILTIN), skip);
+        assert_eq!(typed_edit("&", 1, "'", &rust, &BUILTIN), insert("'", 1));
+        assert_eq!(typed_edit("x", 0, "(", &rust, &BUILTIN), insert("(", 1));
+        assert_eq!(typed_edit("a", 1, "|", &rust, &BUILTIN), None);
+
+        let html = pairs(&[("'", "'"), ("<!--", "-->")]);
+        assert_eq!(typed_edit("don", 3, "'", &html, &BUILTIN), insert("'", 1));
+        let comment = insert("--->", 1);
+        assert_eq!(typed_edit("<!-", 3, "-", &html, &BUILTIN), comment);
+
+        assert_eq!(backspace_range("f<>", 2, &rust, &BUILTIN), Some(1..3));
+        assert_eq!(backspace_range("''", 1, &rust, &BUILTIN), Some(0..1));
+        assert_eq!(backspace_range("ab", 1, &rust, &BUILTIN), None);
+    }
+}
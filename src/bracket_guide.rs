@@ -0,0 +1,48 @@
+use tree_sitter::{Point, Tree};
+
+const PAIRS: [(&str, &str); 3] = [("{", "}"), ("(", ")"), ("[", "]")];
+
+pub fn enclosing_block(tree: &Tree, line: usize, col: usize) -> Option<(Point, Point)> {
+    let at = Point::new(line, col);
+    let mut node = tree.root_node().descendant_for_point_range(at, at)?;
+    loop {
+        let count = node.child_count();
+        if count >= 2 {
+            let open = node.child(0)?;
+            let close = node.child(count - 1)?;
+            let paired = PAIRS
+                .iter()
+                .any(|(o, c)| open.kind() == *o && close.kind() == *c);
+            let start = open.start_position();
+            let end = close.start_position();
+            let inside = at >= start && at <= close.end_position();
+            if paired && inside && start.row < end.row {
+                return Some((start, end));
+            }
+        }
+        node = node.parent()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::enclosing_block;
+    use adabraka_ui::components::editor::Language;
+    use tree_sitter::{Parser, Point};
+
+    #[test]
+    fn test_enclosing_block_skips_single_line_pairs() {
+        let source = "fn a() {\n    if x {\n        y(1);\n    }\n}\n";
+        let mut parser = Parser::new();
+        let language = Language::Rust.tree_sitter_language().unwrap();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let inner = Some((Point::new(1, 9), Point::new(3, 4)));
+        assert_eq!(enclosing_block(&tree, 2, 10), inner);
+        assert_eq!(enclosing_block(&tree, 3, 4), inner);
+        let outer = Some((Point::new(0, 7), Point::new(4, 0)));
+        assert_eq!(enclosing_block(&tree, 1, 2), outer);
+        assert_eq!(enclosing_block(&tree, 5, 0), None);
+    }
+}
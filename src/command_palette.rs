@@ -0,0 +1,407 @@
+use crate::ide_theme::use_ide_theme;
+use adabraka_ui::components::input::{Input, InputEvent, InputState};
+use adabraka_ui::overlays::command_palette::{
+    CloseCommand, Command, NavigateDown, NavigateUp, SelectCommand,
+};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use std::rc::Rc;
+
+type CloseHandler = Rc<dyn Fn(&mut Window, &mut App)>;
+
+pub struct RankedCommand {
+    pub index: usize,
+    pub score: i32,
+    pub positions: Vec<usize>,
+}
+
+fn is_word_start(chars: &[(usize, char)], j: usize) -> bool {
+    j == 0
+        || !chars[j - 1].1.is_alphanumeric()
+        || chars[j - 1].1.is_lowercase() && chars[j].1.is_uppercase()
+}
+
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|(_, c)| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let (m, n) = (query.len(), chars.len());
+    if m > n {
+        return None;
+    }
+
+    let mut score = vec![vec![None::<i32>; n]; m];
+    let mut back = vec![vec![0usize; n]; m];
+    for i in 0..m {
+        for j in i..n {
+            if lower[j] != query[i] {
+                continue;
+            }
+            let bonus = if is_word_start(&chars, j) { 10 } else { 1 };
+            if i == 0 {
+                score[i][j] = Some(bonus - (j as i32).min(5));
+                continue;
+            }
+            for k in (i - 1)..j {
+                let Some(prev) = score[i - 1][k] else {
+                    continue;
+                };
+                let step = if k + 1 == j {
+                    bonus + 8
+                } else {
+                    bonus - ((j - k - 1) as i32).min(5)
+                };
+                if score[i][j].is_none_or(|s| prev + step > s) {
+                    score[i][j] = Some(prev + step);
+                    back[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let (mut j, best) = (0..n)
+        .filter_map(|j| score[m - 1][j].map(|s| (j, s)))
+        .max_by_key(|(_, s)| *s)?;
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = chars[j].0;
+        j = back[i][j];
+    }
+    Some((best, positions))
+}
+
+fn category_of(command: &Command) -> SharedString {
+    command
+        .category
+        .clone()
+        .unwrap_or_else(|| SharedString::from("Other"))
+}
+
+fn rank_command(command: &Command, query: &str) -> Option<(i32, Vec<usize>)> {
+    if let Some(found) = fuzzy_match(query, &command.name) {
+        return Some(found);
+    }
+    let category = category_of(command);
+    let offset = category.len() + 1;
+    let (score, positions) = fuzzy_match(query, &format!("{} {}", category, command.name))?;
+    let positions = positions
+        .into_iter()
+        .filter_map(|p| p.checked_sub(offset))
+        .collect();
+    Some((score - 5, positions))
+}
+
+pub fn rank_commands(commands: &[Command], query: &str) -> Vec<(SharedString, Vec<RankedCommand>)> {
+    let mut groups: Vec<(SharedString, Vec<RankedCommand>)> = Vec::new();
+    for (index, command) in commands.iter().enumerate() {
+        let Some((score, positions)) = rank_command(command, query) else {
+            continue;
+        };
+        let category = category_of(command);
+        let ranked = RankedCommand {
+            index,
+            score,
+            positions,
+        };
+        match groups.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, items)) => items.push(ranked),
+            None => groups.push((category, vec![ranked])),
+        }
+    }
+    for (_, items) in &mut groups {
+        items.sort_by_key(|r| std::cmp::Reverse(r.score));
+    }
+    groups.sort_by_key(|(_, items)| std::cmp::Reverse(items.first().map_or(0, |r| r.score)));
+    groups
+}
+
+pub struct CommandPalette {
+    commands: Vec<Command>,
+    groups: Vec<(SharedString, Vec<RankedCommand>)>,
+    selected: usize,
+    input: Entity<InputState>,
+    scroll_handle: ScrollHandle,
+    on_close: Option<CloseHandler>,
+}
+
+impl CommandPalette {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>, commands: Vec<Command>) -> Self {
+        let input = cx.new(|cx| InputState::new(cx).placeholder("Type a command or search..."));
+        cx.subscribe_in(
+            &input,
+            window,
+            |this, input, event: &InputEvent, window, cx| match event {
+                InputEvent::Change => {
+                    let query = input.read(cx).content().to_string();
+                    this.groups = rank_commands(&this.commands, &query);
+                    this.selected = 0;
+                    this.scroll_handle.scroll_to_item(0);
+                    cx.notify();
+                }
+                InputEvent::Enter => this.execute_selected(window, cx),
+                InputEvent::Blur => this.close(window, cx),
+                _ => {}
+            },
+        )
+        .detach();
+        let groups = rank_commands(&commands, "");
+        Self {
+            commands,
+            groups,
+            selected: 0,
+            input,
+            scroll_handle: ScrollHandle::new(),
+            on_close: None,
+        }
+    }
+
+    pub fn on_close<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_close = Some(Rc::new(handler));
+        self
+    }
+
+    fn item_count(&self) -> usize {
+        self.groups.iter().map(|(_, items)| items.len()).sum()
+    }
+
+    fn selected_command(&self) -> Option<&Command> {
+        self.groups
+            .iter()
+            .flat_map(|(_, items)| items)
+            .nth(self.selected)
+            .and_then(|ranked| self.commands.get(ranked.index))
+    }
+
+    fn move_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let count = self.item_count();
+        if count == 0 {
+            return;
+        }
+        self.selected = (self.selected as isize + delta).clamp(0, count as isize - 1) as usize;
+        let mut row = 0;
+        let mut remaining = self.selected;
+        for (_, items) in &self.groups {
+            row += 1;
+            if remaining < items.len() {
+                row += remaining;
+                break;
+            }
+            row += items.len();
+            remaining -= items.len();
+        }
+        self.scroll_handle.scroll_to_item(row);
+        cx.notify();
+    }
+
+    fn execute_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(handler) = self.selected_command().and_then(|c| c.on_select.clone()) else {
+            return;
+        };
+        handler(window, cx);
+        self.close(window, cx);
+    }
+
+    fn close(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(handler) = self.on_close.clone() {
+            handler(window, cx);
+        }
+    }
+}
+
+impl Focusable for CommandPalette {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.input.read(cx).focus_handle(cx)
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let ide = use_ide_theme();
+        let chrome = &ide.chrome;
+        let highlight = HighlightStyle {
+            color: Some(chrome.accent),
+            font_weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        };
+
+        let mut list = div()
+            .id("command-palette-list")
+            .flex_1()
+            .flex()
+            .flex_col()
+            .p(px(6.0))
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle);
+        if self.groups.is_empty() {
+            list = list.child(
+                div()
+                    .px(px(12.0))
+                    .py(px(16.0))
+                    .text_size(px(12.0))
+                    .text_color(chrome.text_secondary)
+                    .child("No commands found"),
+            );
+        }
+        let mut flat = 0;
+        for (category, items) in &self.groups {
+            list = list.child(
+                div()
+                    .px(px(10.0))
+                    .pt(px(8.0))
+                    .pb(px(4.0))
+                    .text_size(px(10.0))
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .text_color(chrome.text_secondary)
+                    .child(category.to_uppercase()),
+            );
+            for ranked in items {
+                let idx = flat;
+                flat += 1;
+                let command = &self.commands[ranked.index];
+                let is_selected = idx == self.selected;
+                let name = command.name.clone();
+                let highlights: Vec<_> = ranked
+                    .positions
+                    .iter()
+                    .filter_map(|&p| {
+                        let len = name[p..].chars().next()?.len_utf8();
+                        Some((p..p + len, highlight))
+                    })
+                    .collect();
+                list = list.child(
+                    div()
+                        .id(ElementId::Name(format!("palette-command-{}", idx).into()))
+                        .flex()
+                        .items_center()
+                        .justify_between()
+                        .gap(px(12.0))
+                        .px(px(10.0))
+                        .py(px(6.0))
+                        .rounded(px(6.0))
+                        .cursor_pointer()
+                        .when(is_selected, |el| el.bg(chrome.accent.opacity(0.15)))
+                        .when(!is_selected, |el| {
+                            el.hover(|s| s.bg(hsla(0.0, 0.0, 1.0, 0.05)))
+                        })
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.selected = idx;
+                            this.execute_selected(window, cx);
+                        }))
+                        .child(
+                            div()
+                                .text_size(px(13.0))
+                                .text_color(chrome.bright)
+                                .child(StyledText::new(name).with_highlights(highlights)),
+                        )
+                        .children(command.shortcut.clone().map(|shortcut| {
+                            div()
+                                .flex_shrink_0()
+                                .px(px(6.0))
+                                .py(px(2.0))
+                                .rounded(px(4.0))
+                                .bg(hsla(0.0, 0.0, 1.0, 0.06))
+                                .text_size(px(11.0))
+                                .text_color(chrome.text_secondary)
+                                .child(shortcut)
+                        })),
+                );
+            }
+        }
+
+        div()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .flex()
+            .justify_center()
+            .pt(px(80.0))
+            .bg(hsla(0.0, 0.0, 0.0, 0.3))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, window, cx| this.close(window, cx)),
+            )
+            .child(
+                div()
+                    .key_context("CommandPalette")
+                    .on_action(cx.listener(|this, _: &NavigateUp, _, cx| {
+                        this.move_selection(-1, cx);
+                    }))
+                    .on_action(cx.listener(|this, _: &NavigateDown, _, cx| {
+                        this.move_selection(1, cx);
+                    }))
+                    .on_action(cx.listener(|this, _: &SelectCommand, window, cx| {
+                        this.execute_selected(window, cx);
+                    }))
+                    .on_action(cx.listener(|this, _: &CloseCommand, window, cx| {
+                        this.close(window, cx);
+                    }))
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(600.0))
+                    .max_h(px(500.0))
+                    .flex()
+                    .flex_col()
+                    .bg(chrome.panel_bg)
+                    .border_1()
+                    .border_color(chrome.header_border)
+                    .rounded(px(8.0))
+                    .shadow_lg()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .px(px(12.0))
+                            .py(px(10.0))
+                            .border_b_1()
+                            .border_color(chrome.header_border)
+                            .child(Input::new(&self.input)),
+                    )
+                    .child(list),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_match, rank_commands};
+    use adabraka_ui::overlays::command_palette::Command;
+
+    #[test]
+    fn test_fuzzy_match_prefers_word_starts() {
+        let (_, positions) = fuzzy_match("tgit", "Toggle Git View").unwrap();
+        assert_eq!(positions, vec![0, 7, 8, 9]);
+        assert!(fuzzy_match("xyz", "Toggle Git View").is_none());
+        let (word_starts, _) = fuzzy_match("fd", "Format Document").unwrap();
+        let (scattered, _) = fuzzy_match("fd", "Unfolded").unwrap();
+        assert!(word_starts > scattered);
+    }
+
+    #[test]
+    fn test_rank_commands_groups_by_category() {
+        let commands = vec![
+            Command::new("a", "Toggle Sidebar").category("View"),
+            Command::new("b", "Toggle Git View").category("View"),
+            Command::new("c", "Open File at HEAD").category("Git"),
+        ];
+        let groups = rank_commands(&commands, "tgit");
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].1[0].index, 1);
+
+        let groups = rank_commands(&commands, "git head");
+        assert_eq!(groups[0].0.as_ref(), "Git");
+        let all = rank_commands(&commands, "");
+        assert_eq!(all.iter().map(|(_, items)| items.len()).sum::<usize>(), 3);
+    }
+}
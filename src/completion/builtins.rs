@@ -0,0 +1,263 @@
+use super::state::CompletionItem;
+use super::SymbolKind;
+use adabraka_ui::components::editor::Language;
+
+fn keywords_for(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "false", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+            "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+            "unsafe", "use", "where", "while",
+        ],
+        Language::Python => &[
+            "and", "as", "assert", "async", "await", "break", "continue", "del", "elif", "else",
+            "except", "False", "finally", "from", "global", "import", "is", "lambda", "None",
+            "nonlocal", "not", "or", "pass", "raise", "return", "True", "with", "yield",
+        ],
+        Language::JavaScript | Language::TypeScript => &[
+            "async",
+            "await",
+            "break",
+            "case",
+            "catch",
+            "const",
+            "continue",
+            "default",
+            "delete",
+            "else",
+            "export",
+            "extends",
+            "false",
+            "finally",
+            "import",
+            "instanceof",
+            "let",
+            "new",
+            "null",
+            "return",
+            "static",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "true",
+            "typeof",
+            "undefined",
+            "var",
+            "void",
+            "yield",
+        ],
+        Language::Go => &[
+            "break",
+            "case",
+            "chan",
+            "const",
+            "continue",
+            "default",
+            "defer",
+            "else",
+            "fallthrough",
+            "go",
+            "goto",
+            "import",
+            "interface",
+            "map",
+            "nil",
+            "package",
+            "range",
+            "return",
+            "select",
+            "struct",
+            "switch",
+            "type",
+            "var",
+        ],
+        Language::C | Language::Cpp => &[
+            "break", "case", "char", "const", "continue", "default", "double", "else", "enum",
+            "extern", "float", "goto", "int", "long", "return", "short", "signed", "sizeof",
+            "static", "struct", "switch", "typedef", "union", "unsigned", "void", "volatile",
+        ],
+        Language::Java => &[
+            "abstract",
+            "boolean",
+            "break",
+            "case",
+            "catch",
+            "continue",
+            "default",
+            "else",
+            "extends",
+            "final",
+            "finally",
+            "implements",
+            "import",
+            "instanceof",
+            "interface",
+            "new",
+            "null",
+            "package",
+            "private",
+            "protected",
+            "public",
+            "return",
+            "static",
+            "super",
+            "switch",
+            "this",
+            "throw",
+            "throws",
+            "void",
+        ],
+        Language::Ruby => &[
+            "begin", "break", "case", "class", "def", "do", "else", "elsif", "end", "ensure",
+            "false", "module", "next", "nil", "rescue", "return", "self", "then", "true", "unless",
+            "until", "when", "yield",
+        ],
+        Language::Lua => &[
+            "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "local",
+            "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+        ],
+        Language::Bash => &[
+            "case", "do", "done", "elif", "else", "esac", "export", "fi", "function", "local",
+            "readonly", "return", "then", "until",
+        ],
+        _ => &[],
+    }
+}
+
+fn snippets_for(language: Language) -> &'static [(&'static str, &'static str, &'static str)] {
+    match language {
+        Language::Rust => &[
+            ("fn", "function", "fn ${1:name}($2) {\n\t$0\n}"),
+            ("for", "for loop", "for ${1:item} in ${2:iter} {\n\t$0\n}"),
+            ("if", "if block", "if ${1:condition} {\n\t$0\n}"),
+            (
+                "if let",
+                "if let block",
+                "if let ${1:Some(value)} = ${2:expr} {\n\t$0\n}",
+            ),
+            (
+                "match",
+                "match expression",
+                "match ${1:expr} {\n\t${2:_} => $0,\n}",
+            ),
+            ("struct", "struct definition", "struct ${1:Name} {\n\t$0\n}"),
+            ("impl", "impl block", "impl ${1:Type} {\n\t$0\n}"),
+            (
+                "test",
+                "test function",
+                "#[test]\nfn ${1:name}() {\n\t$0\n}",
+            ),
+        ],
+        Language::Python => &[
+            ("def", "function", "def ${1:name}($2):\n\t${0:pass}"),
+            (
+                "class",
+                "class definition",
+                "class ${1:Name}:\n\tdef __init__(self$2):\n\t\t${0:pass}",
+            ),
+            (
+                "for",
+                "for loop",
+                "for ${1:item} in ${2:items}:\n\t${0:pass}",
+            ),
+            ("if", "if block", "if ${1:condition}:\n\t${0:pass}"),
+            (
+                "try",
+                "try/except",
+                "try:\n\t$1\nexcept ${2:Exception} as ${3:e}:\n\t${0:raise}",
+            ),
+            (
+                "main",
+                "main guard",
+                "if __name__ == \"__main__\":\n\t${0:main()}",
+            ),
+        ],
+        Language::JavaScript | Language::TypeScript => &[
+            ("function", "function", "function ${1:name}($2) {\n\t$0\n}"),
+            (
+                "for",
+                "for loop",
+                "for (let ${1:i} = 0; $1 < ${2:n}; $1++) {\n\t$0\n}",
+            ),
+            (
+                "forof",
+                "for...of loop",
+                "for (const ${1:item} of ${2:items}) {\n\t$0\n}",
+            ),
+            ("if", "if block", "if (${1:condition}) {\n\t$0\n}"),
+            (
+                "class",
+                "class definition",
+                "class ${1:Name} {\n\tconstructor($2) {\n\t\t$0\n\t}\n}",
+            ),
+            (
+                "arrow",
+                "arrow function",
+                "const ${1:name} = ($2) => {\n\t$0\n};",
+            ),
+        ],
+        Language::Go => &[
+            ("func", "function", "func ${1:name}($2) {\n\t$0\n}"),
+            (
+                "for",
+                "for loop",
+                "for ${1:i} := 0; $1 < ${2:n}; $1++ {\n\t$0\n}",
+            ),
+            (
+                "forr",
+                "for range loop",
+                "for ${1:_}, ${2:v} := range ${3:items} {\n\t$0\n}",
+            ),
+            ("if", "if block", "if ${1:condition} {\n\t$0\n}"),
+            (
+                "iferr",
+                "error check",
+                "if err != nil {\n\treturn ${0:err}\n}",
+            ),
+        ],
+        Language::C | Language::Cpp => &[
+            (
+                "for",
+                "for loop",
+                "for (int ${1:i} = 0; $1 < ${2:n}; $1++) {\n\t$0\n}",
+            ),
+            ("if", "if block", "if (${1:condition}) {\n\t$0\n}"),
+            ("while", "while loop", "while (${1:condition}) {\n\t$0\n}"),
+            (
+                "main",
+                "main function",
+                "int main(int argc, char **argv) {\n\t$0\n\treturn 0;\n}",
+            ),
+        ],
+        _ => &[],
+    }
+}
+
+pub fn builtin_items(language: Language) -> Vec<CompletionItem> {
+    let snippets = snippets_for(language);
+    let keywords = keywords_for(language)
+        .iter()
+        .filter(|kw| !snippets.iter().any(|(prefix, _, _)| prefix == *kw))
+        .map(|kw| CompletionItem {
+            label: kw.to_string(),
+            kind: SymbolKind::Keyword,
+            insert_text: kw.to_string(),
+            detail: None,
+            documentation: None,
+            snippet: false,
+        });
+    snippets
+        .iter()
+        .map(|(prefix, description, body)| CompletionItem {
+            label: prefix.to_string(),
+            kind: SymbolKind::Snippet,
+            insert_text: body.to_string(),
+            detail: Some(description.to_string()),
+            documentation: None,
+            snippet: true,
+        })
+        .chain(keywords)
+        .collect()
+}
@@ -1,8 +1,8 @@
 use super::state::CompletionState;
 use super::SymbolKind;
-use crate::ide_theme::use_ide_theme;
-use adabraka_ui::components::editor::EditorState;
-use adabraka_ui::components::icon::Icon;
+use crate::ide_theme::{use_ide_theme, IdeTheme};
+use crate::markdown::render_markdown;
+use adabraka_ui::components::editor::{EditorState, Language};
 use adabraka_ui::components::scrollable::scrollable_vertical;
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
@@ -11,10 +11,14 @@ use std::rc::Rc;
 const MAX_VISIBLE_ITEMS: usize = 8;
 const ITEM_HEIGHT: f32 = 28.0;
 const MENU_WIDTH: f32 = 280.0;
+const FOOTER_HEIGHT: f32 = 24.0;
+const DOCS_WIDTH: f32 = 340.0;
+const DOCS_MAX_HEIGHT: f32 = 300.0;
 
 pub struct CompletionMenu {
     state: Entity<CompletionState>,
     editor_state: Option<Entity<EditorState>>,
+    code_font: SharedString,
     on_accept: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
 }
 
@@ -23,10 +27,16 @@ impl CompletionMenu {
         Self {
             state,
             editor_state: None,
+            code_font: "JetBrains Mono".into(),
             on_accept: None,
         }
     }
 
+    pub fn code_font(mut self, font: impl Into<SharedString>) -> Self {
+        self.code_font = font.into();
+        self
+    }
+
     pub fn editor_state(mut self, editor: Entity<EditorState>) -> Self {
         self.editor_state = Some(editor);
         self
@@ -45,6 +55,7 @@ impl IntoElement for CompletionMenu {
         CompletionMenuElement {
             state: self.state,
             editor_state: self.editor_state,
+            code_font: self.code_font,
             on_accept: self.on_accept,
         }
     }
@@ -53,6 +64,7 @@ impl IntoElement for CompletionMenu {
 pub struct CompletionMenuElement {
     state: Entity<CompletionState>,
     editor_state: Option<Entity<EditorState>>,
+    code_font: SharedString,
     on_accept: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
 }
 
@@ -96,18 +108,35 @@ impl Element for CompletionMenuElement {
             return (layout_id, None);
         }
 
-        let chrome = use_ide_theme().chrome;
+        let ide = use_ide_theme();
+        let chrome = ide.chrome.clone();
         let anchor = self
             .editor_state
             .as_ref()
             .and_then(|es| es.read(cx).cursor_screen_position(px(20.0)))
             .unwrap_or_else(|| state.anchor_position());
         let selected_idx = state.selected_display_index();
+        let hidden_count = state.hidden_count();
+        let language = self
+            .editor_state
+            .as_ref()
+            .map(|es| es.read(cx).language())
+            .unwrap_or(Language::Plain);
+        let docs = state.selected_item().and_then(|item| {
+            let detail = item.detail.as_ref().filter(|d| !d.is_empty());
+            let mut text = String::new();
+            if let Some(detail) = detail {
+                text.push_str(&format!("```\n{}\n```\n\n", detail));
+            }
+            if let Some(doc) = &item.documentation {
+                text.push_str(doc);
+            }
+            (item.documentation.is_some() || detail.is_some_and(|d| d.len() > 30)).then_some(text)
+        });
         let on_accept = self.on_accept.clone();
 
         let items: Vec<_> = state
             .filtered_items()
-            .take(50)
             .map(|(display_idx, item)| {
                 let is_selected = display_idx == selected_idx;
                 let label = item.label.clone();
@@ -143,7 +172,7 @@ impl Element for CompletionMenuElement {
                             handler(window, cx);
                         }
                     })
-                    .child(render_kind_icon(kind, &chrome))
+                    .child(render_kind_icon(kind, &ide))
                     .child(
                         div()
                             .flex_1()
@@ -166,59 +195,109 @@ impl Element for CompletionMenuElement {
             .collect();
 
         let item_count = items.len();
-        let menu_height = (item_count.min(MAX_VISIBLE_ITEMS) as f32 * ITEM_HEIGHT) + 8.0;
+        let footer_height = if hidden_count > 0 { FOOTER_HEIGHT } else { 0.0 };
+        let menu_height =
+            (item_count.min(MAX_VISIBLE_ITEMS) as f32 * ITEM_HEIGHT) + 8.0 + footer_height;
 
         let state_for_keys = state_entity.clone();
         let on_accept_key = on_accept.clone();
 
+        let list = div()
+            .id("completion-menu-inner")
+            .key_context("CompletionMenu")
+            .occlude()
+            .mt(px(4.0))
+            .w(px(MENU_WIDTH))
+            .max_h(px(menu_height))
+            .bg(chrome.panel_bg)
+            .border_1()
+            .border_color(chrome.header_border)
+            .rounded(px(8.0))
+            .shadow_lg()
+            .overflow_hidden()
+            .on_key_down({
+                let state = state_for_keys.clone();
+                let on_accept = on_accept_key.clone();
+                move |event: &KeyDownEvent, window, cx| match event.keystroke.key.as_str() {
+                    "up" => {
+                        state.update(cx, |s, cx| s.move_up(cx));
+                        cx.stop_propagation();
+                    }
+                    "down" => {
+                        state.update(cx, |s, cx| s.move_down(cx));
+                        cx.stop_propagation();
+                    }
+                    "tab" | "enter" => {
+                        if let Some(ref handler) = on_accept {
+                            handler(window, cx);
+                        }
+                        cx.stop_propagation();
+                    }
+                    "escape" => {
+                        state.update(cx, |s, cx| s.dismiss(cx));
+                        cx.stop_propagation();
+                    }
+                    _ => {}
+                }
+            })
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .child(scrollable_vertical(div().py(px(4.0)).children(items))),
+            )
+            .when(hidden_count > 0, |el| {
+                el.child(
+                    div()
+                        .h(px(FOOTER_HEIGHT))
+                        .flex_shrink_0()
+                        .flex()
+                        .items_center()
+                        .px(px(8.0))
+                        .border_t_1()
+                        .border_color(chrome.header_border)
+                        .text_size(px(11.0))
+                        .text_color(chrome.text_secondary)
+                        .child(format!("{} more, keep typing to narrow", hidden_count)),
+                )
+            });
+
         let mut menu = deferred(
             anchored()
                 .position(anchor)
                 .snap_to_window_with_margin(px(8.0))
                 .child(
                     div()
-                        .id("completion-menu-inner")
-                        .key_context("CompletionMenu")
-                        .occlude()
-                        .mt(px(4.0))
-                        .w(px(MENU_WIDTH))
-                        .max_h(px(menu_height))
-                        .bg(chrome.panel_bg)
-                        .border_1()
-                        .border_color(chrome.header_border)
-                        .rounded(px(8.0))
-                        .shadow_lg()
-                        .overflow_hidden()
-                        .on_key_down({
-                            let state = state_for_keys.clone();
-                            let on_accept = on_accept_key.clone();
-                            move |event: &KeyDownEvent, window, cx| match event
-                                .keystroke
-                                .key
-                                .as_str()
-                            {
-                                "up" => {
-                                    state.update(cx, |s, cx| s.move_up(cx));
-                                    cx.stop_propagation();
-                                }
-                                "down" => {
-                                    state.update(cx, |s, cx| s.move_down(cx));
-                                    cx.stop_propagation();
-                                }
-                                "tab" | "enter" => {
-                                    if let Some(ref handler) = on_accept {
-                                        handler(window, cx);
-                                    }
-                                    cx.stop_propagation();
-                                }
-                                "escape" => {
-                                    state.update(cx, |s, cx| s.dismiss(cx));
-                                    cx.stop_propagation();
-                                }
-                                _ => {}
-                            }
-                        })
-                        .child(scrollable_vertical(div().py(px(4.0)).children(items))),
+                        .flex()
+                        .items_start()
+                        .gap(px(4.0))
+                        .child(list)
+                        .when_some(docs, |el, docs| {
+                            el.child(
+                                div()
+                                    .id("completion-docs")
+                                    .occlude()
+                                    .mt(px(4.0))
+                                    .w(px(DOCS_WIDTH))
+                                    .max_h(px(DOCS_MAX_HEIGHT))
+                                    .overflow_y_scroll()
+                                    .p(px(10.0))
+                                    .bg(chrome.panel_bg)
+                                    .border_1()
+                                    .border_color(chrome.header_border)
+                                    .rounded(px(8.0))
+                                    .shadow_lg()
+                                    .text_size(px(12.0))
+                                    .text_color(chrome.bright)
+                                    .child(render_markdown(
+                                        &docs,
+                                        language,
+                                        self.code_font.clone(),
+                                    )),
+                            )
+                        }),
                 ),
         )
         .with_priority(2)
@@ -261,22 +340,39 @@ impl Element for CompletionMenuElement {
     }
 }
 
-fn render_kind_icon(kind: SymbolKind, chrome: &crate::ide_theme::ChromeColors) -> impl IntoElement {
-    let icon_color = match kind {
-        SymbolKind::Function | SymbolKind::Method => chrome.accent,
-        SymbolKind::Variable | SymbolKind::Field => chrome.bright,
-        SymbolKind::Struct | SymbolKind::Class => chrome.bright,
-        SymbolKind::Enum => chrome.diff_del_text,
-        SymbolKind::Const => chrome.accent,
-        SymbolKind::Type => chrome.accent,
-        SymbolKind::Module => chrome.text_secondary,
-    };
+fn kind_style(kind: SymbolKind, ide: &IdeTheme) -> (&'static str, Hsla) {
+    let glyphs = &ide.completion_glyphs;
+    let colors = &ide.completion;
+    match kind {
+        SymbolKind::Function => (glyphs.function, colors.function),
+        SymbolKind::Method => (glyphs.method, colors.method),
+        SymbolKind::Variable => (glyphs.variable, colors.variable),
+        SymbolKind::Field => (glyphs.field, colors.field),
+        SymbolKind::Module => (glyphs.module, colors.module),
+        SymbolKind::Struct => (glyphs.structure, colors.structure),
+        SymbolKind::Class => (glyphs.class, colors.class),
+        SymbolKind::Enum => (glyphs.enumeration, colors.enumeration),
+        SymbolKind::Const => (glyphs.constant, colors.constant),
+        SymbolKind::Type => (glyphs.type_name, colors.type_name),
+        SymbolKind::Keyword => (glyphs.keyword, colors.keyword),
+        SymbolKind::Snippet => (glyphs.snippet, colors.snippet),
+    }
+}
+
+fn render_kind_icon(kind: SymbolKind, ide: &IdeTheme) -> impl IntoElement {
+    let (glyph, color) = kind_style(kind, ide);
 
     div()
         .w(px(16.0))
         .h(px(16.0))
         .flex()
+        .flex_shrink_0()
         .items_center()
         .justify_center()
-        .child(Icon::new(kind.icon_name()).size(px(14.0)).color(icon_color))
+        .rounded(px(3.0))
+        .bg(color.opacity(0.15))
+        .text_size(px(10.0))
+        .font_weight(FontWeight::BOLD)
+        .text_color(color)
+        .child(glyph)
 }
@@ -1,7 +1,9 @@
+mod builtins;
 mod menu;
 mod state;
 mod symbols;
 
+pub use builtins::builtin_items;
 pub use menu::CompletionMenu;
 pub use state::{CompletionItem, CompletionState};
-pub use symbols::{extract_symbols, Symbol, SymbolKind};
+pub use symbols::{extract_outline, extract_symbols, outline_entry_at, Symbol, SymbolKind};
@@ -7,6 +7,8 @@ pub struct CompletionItem {
     pub kind: SymbolKind,
     pub insert_text: String,
     pub detail: Option<String>,
+    pub documentation: Option<String>,
+    pub snippet: bool,
 }
 
 impl From<Symbol> for CompletionItem {
@@ -16,6 +18,8 @@ impl From<Symbol> for CompletionItem {
             kind: sym.kind,
             insert_text: sym.name,
             detail: None,
+            documentation: None,
+            snippet: false,
         }
     }
 }
@@ -23,12 +27,14 @@ impl From<Symbol> for CompletionItem {
 pub struct CompletionState {
     items: Vec<CompletionItem>,
     filtered_indices: Vec<usize>,
+    max_items: usize,
     selected_index: usize,
     visible: bool,
     filter_prefix: String,
     trigger_col: usize,
     trigger_line: usize,
     anchor_position: Point<Pixels>,
+    incomplete: bool,
 }
 
 impl CompletionState {
@@ -36,12 +42,14 @@ impl CompletionState {
         Self {
             items: Vec::new(),
             filtered_indices: Vec::new(),
+            max_items: 0,
             selected_index: 0,
             visible: false,
             filter_prefix: String::new(),
             trigger_col: 0,
             trigger_line: 0,
             anchor_position: Point::default(),
+            incomplete: false,
         }
     }
 
@@ -59,11 +67,46 @@ impl CompletionState {
         self.anchor_position = anchor;
         self.filter_prefix.clear();
         self.selected_index = 0;
+        self.incomplete = false;
         self.update_filtered();
         self.visible = !self.filtered_indices.is_empty();
         cx.notify();
     }
 
+    pub fn set_max_items(&mut self, max_items: usize) {
+        self.max_items = max_items;
+    }
+
+    fn visible_count(&self) -> usize {
+        if self.max_items == 0 {
+            self.filtered_indices.len()
+        } else {
+            self.filtered_indices.len().min(self.max_items)
+        }
+    }
+
+    pub fn hidden_count(&self) -> usize {
+        self.filtered_indices.len() - self.visible_count()
+    }
+
+    pub fn merge(&mut self, items: Vec<CompletionItem>, cx: &mut Context<Self>) {
+        for item in items {
+            if !self
+                .items
+                .iter()
+                .any(|existing| existing.label == item.label)
+            {
+                self.items.push(item);
+            }
+        }
+        self.update_filtered();
+        if self.selected_index >= self.visible_count() {
+            self.selected_index = 0;
+        }
+        self.visible = !self.filtered_indices.is_empty();
+        cx.notify();
+    }
+
     pub fn set_filter(&mut self, prefix: &str, cx: &mut Context<Self>) {
         self.filter_prefix = prefix.to_string();
         self.update_filtered();
@@ -122,7 +165,7 @@ impl CompletionState {
         if self.selected_index > 0 {
             self.selected_index -= 1;
         } else {
-            self.selected_index = self.filtered_indices.len() - 1;
+            self.selected_index = self.visible_count() - 1;
         }
         cx.notify();
     }
@@ -131,7 +174,7 @@ impl CompletionState {
         if self.filtered_indices.is_empty() {
             return;
         }
-        if self.selected_index < self.filtered_indices.len() - 1 {
+        if self.selected_index < self.visible_count() - 1 {
             self.selected_index += 1;
         } else {
             self.selected_index = 0;
@@ -150,6 +193,7 @@ impl CompletionState {
         self.items.clear();
         self.filtered_indices.clear();
         self.filter_prefix.clear();
+        self.incomplete = false;
         cx.notify();
     }
 
@@ -157,8 +201,20 @@ impl CompletionState {
         self.visible
     }
 
+    pub fn has_items(&self) -> bool {
+        !self.items.is_empty()
+    }
+
+    pub fn set_incomplete(&mut self, incomplete: bool) {
+        self.incomplete = incomplete;
+    }
+
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+
     pub fn filtered_items(&self) -> impl Iterator<Item = (usize, &CompletionItem)> {
-        self.filtered_indices
+        self.filtered_indices[..self.visible_count()]
             .iter()
             .enumerate()
             .filter_map(|(display_idx, &item_idx)| {
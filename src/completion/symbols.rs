@@ -20,21 +20,11 @@ pub enum SymbolKind {
     Module,
     Class,
     Method,
+    Keyword,
+    Snippet,
 }
 
 impl SymbolKind {
-    pub fn icon_name(&self) -> &'static str {
-        match self {
-            SymbolKind::Function | SymbolKind::Method => "function",
-            SymbolKind::Variable | SymbolKind::Field => "variable",
-            SymbolKind::Struct | SymbolKind::Class => "box",
-            SymbolKind::Enum => "list",
-            SymbolKind::Const => "lock",
-            SymbolKind::Type => "type",
-            SymbolKind::Module => "folder",
-        }
-    }
-
     pub fn label(&self) -> &'static str {
         match self {
             SymbolKind::Function => "fn",
@@ -47,6 +37,8 @@ impl SymbolKind {
             SymbolKind::Const => "const",
             SymbolKind::Type => "type",
             SymbolKind::Module => "mod",
+            SymbolKind::Keyword => "keyword",
+            SymbolKind::Snippet => "snippet",
         }
     }
 }
@@ -94,6 +86,79 @@ pub fn extract_symbols(tree: &Tree, source: &str, language: Language) -> Vec<Sym
     symbols
 }
 
+#[derive(Debug, Clone)]
+pub struct OutlineEntry {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub line: usize,
+    pub col: usize,
+    pub end_line: usize,
+    pub depth: usize,
+}
+
+pub fn extract_outline(tree: &Tree, source: &str, language: Language) -> Vec<OutlineEntry> {
+    let query_src = symbol_query_for_language(language);
+    if query_src.is_empty() {
+        return Vec::new();
+    }
+    let Ok(query) = Query::new(&tree.language(), query_src) else {
+        return Vec::new();
+    };
+
+    let mut cursor = QueryCursor::new();
+    let source_bytes = source.as_bytes();
+    let mut entries = Vec::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), source_bytes);
+    while let Some(m) = matches.next() {
+        for cap in m.captures {
+            let kind = kind_from_capture(query.capture_names()[cap.index as usize]);
+            if matches!(kind, SymbolKind::Variable | SymbolKind::Field) {
+                continue;
+            }
+            let Ok(name) = cap.node.utf8_text(source_bytes) else {
+                continue;
+            };
+            let start = cap.node.start_position();
+            let line = start.row;
+            let mut decl = cap.node;
+            while let Some(parent) = decl.parent() {
+                if parent.parent().is_none() || parent.start_position().row != line {
+                    break;
+                }
+                decl = parent;
+            }
+            entries.push(OutlineEntry {
+                name: name.to_string(),
+                kind,
+                line,
+                col: start.column,
+                end_line: decl.end_position().row,
+                depth: 0,
+            });
+        }
+    }
+
+    entries.sort_by_key(|e| (e.line, std::cmp::Reverse(e.end_line)));
+    let mut open: Vec<usize> = Vec::new();
+    for entry in &mut entries {
+        while open.last().is_some_and(|&end| entry.line > end) {
+            open.pop();
+        }
+        entry.depth = open.len();
+        open.push(entry.end_line);
+    }
+    entries
+}
+
+pub fn outline_entry_at(entries: &[OutlineEntry], line: usize) -> Option<usize> {
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.line <= line && line <= e.end_line)
+        .max_by_key(|(i, e)| (e.depth, *i))
+        .map(|(i, _)| i)
+}
+
 fn kind_from_capture(capture: &str) -> SymbolKind {
     match capture {
         "function" | "fn" => SymbolKind::Function,
@@ -171,3 +236,36 @@ fn symbol_query_for_language(lang: Language) -> &'static str {
         _ => "",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_outline, outline_entry_at, SymbolKind};
+    use adabraka_ui::components::editor::Language;
+
+    #[test]
+    fn test_outline_nests_and_locates_symbols() {
+        let source = "struct Point {\n    x: i32,\n}\n\nimpl Point {\n    fn new() -> Self {\n        let y = 1;\n        Point { x: y }\n    }\n}\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&Language::Rust.tree_sitter_language().unwrap())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let outline = extract_outline(&tree, source, Language::Rust);
+        let summary: Vec<_> = outline
+            .iter()
+            .map(|e| (e.name.as_str(), e.kind, e.line, e.col, e.end_line, e.depth))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Point", SymbolKind::Struct, 0, 7, 2, 0),
+                ("Point", SymbolKind::Type, 4, 5, 9, 0),
+                ("new", SymbolKind::Function, 5, 7, 8, 1),
+            ]
+        );
+        assert_eq!(outline_entry_at(&outline, 1), Some(0));
+        assert_eq!(outline_entry_at(&outline, 3), None);
+        assert_eq!(outline_entry_at(&outline, 6), Some(2));
+        assert_eq!(outline_entry_at(&outline, 9), Some(1));
+    }
+}
@@ -1,5 +1,6 @@
 use adabraka_ui::components::editor::{highlight_color_for_capture, Language};
 use gpui::Hsla;
+use std::ops::Range;
 use tree_sitter::{Parser, Query, QueryCursor, StreamingIterator};
 
 #[derive(Clone, Debug)]
@@ -10,6 +11,14 @@ pub struct HighlightRun {
 }
 
 pub fn compute_line_highlights(content: &str, language: Language) -> Vec<Vec<HighlightRun>> {
+    compute_line_highlights_with(content, language, highlight_color_for_capture)
+}
+
+pub fn compute_line_highlights_with(
+    content: &str,
+    language: Language,
+    color_for: impl Fn(&str) -> Hsla,
+) -> Vec<Vec<HighlightRun>> {
     let ts_lang = match language.tree_sitter_language() {
         Some(l) => l,
         None => {
@@ -57,7 +66,7 @@ pub fn compute_line_highlights(content: &str, language: Language) -> Vec<Vec<Hig
     while let Some(m) = matches.next() {
         for capture in m.captures {
             let capture_name = &query.capture_names()[capture.index as usize];
-            let color = highlight_color_for_capture(capture_name);
+            let color = color_for(capture_name);
             let node = capture.node;
             let start_byte = node.start_byte();
             let end_byte = node.end_byte();
@@ -97,6 +106,99 @@ pub fn compute_line_highlights(content: &str, language: Language) -> Vec<Vec<Hig
     result
 }
 
+const MAX_WORD_DIFF_TOKENS: usize = 400;
+
+pub fn word_diff_ranges(
+    old: &str,
+    new: &str,
+    ignore_whitespace: bool,
+) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let keep = |t: &Range<usize>, text: &str| {
+        !(ignore_whitespace && text[t.clone()].chars().all(char::is_whitespace))
+    };
+    let old_tokens: Vec<Range<usize>> =
+        tokenize(old).into_iter().filter(|t| keep(t, old)).collect();
+    let new_tokens: Vec<Range<usize>> =
+        tokenize(new).into_iter().filter(|t| keep(t, new)).collect();
+
+    if old_tokens.len() > MAX_WORD_DIFF_TOKENS || new_tokens.len() > MAX_WORD_DIFF_TOKENS {
+        return (Vec::new(), Vec::new());
+    }
+
+    let n = old_tokens.len();
+    let m = new_tokens.len();
+    let mut lcs = vec![vec![0u16; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[old_tokens[i].clone()] == new[new_tokens[j].clone()] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_changed = Vec::new();
+    let mut new_changed = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[old_tokens[i].clone()] == new[new_tokens[j].clone()] {
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            new_changed.push(new_tokens[j].clone());
+            j += 1;
+        } else {
+            old_changed.push(old_tokens[i].clone());
+            i += 1;
+        }
+    }
+
+    (
+        merge_ranges(old_changed, old),
+        merge_ranges(new_changed, new),
+    )
+}
+
+fn tokenize(text: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut start: Option<(usize, u8)> = None;
+    for (i, ch) in text.char_indices() {
+        let class = if ch.is_alphanumeric() || ch == '_' {
+            1
+        } else if ch.is_whitespace() {
+            2
+        } else {
+            0
+        };
+        if let Some((s, c)) = start {
+            if c == class && class != 0 {
+                continue;
+            }
+            tokens.push(s..i);
+        }
+        start = Some((i, class));
+    }
+    if let Some((s, _)) = start {
+        tokens.push(s..text.len());
+    }
+    tokens
+}
+
+fn merge_ranges(ranges: Vec<Range<usize>>, text: &str) -> Vec<Range<usize>> {
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        if let Some(last) = merged.last_mut() {
+            if text[last.end..range.start].chars().all(char::is_whitespace) {
+                last.end = range.end;
+                continue;
+            }
+        }
+        merged.push(range);
+    }
+    merged
+}
+
 fn compute_line_offsets(content: &str) -> Vec<usize> {
     let mut offsets = vec![0];
     for (i, ch) in content.char_indices() {
@@ -106,3 +208,37 @@ fn compute_line_offsets(content: &str) -> Vec<usize> {
     }
     offsets
 }
+
+#[cfg(test)]
+mod tests {
+    use super::word_diff_ranges;
+
+    fn changed<'a>(old: &'a str, new: &'a str, ignore: bool) -> (Vec<&'a str>, Vec<&'a str>) {
+        let (o, n) = word_diff_ranges(old, new, ignore);
+        (
+            o.into_iter().map(|r| &old[r]).collect(),
+            n.into_iter().map(|r| &new[r]).collect(),
+        )
+    }
+
+    #[test]
+    fn test_word_diff_ranges() {
+        assert_eq!(
+            changed("let x = 1;", "let mut x = 1;", false),
+            (vec![], vec!["mut "])
+        );
+        assert_eq!(
+            changed("let mut x = 1;", "let x = 1;", false),
+            (vec!["mut "], vec![])
+        );
+        assert_eq!(
+            changed("foo(bar, 2)", "foo(baz, 3)", false),
+            (vec!["bar", "2"], vec!["baz", "3"])
+        );
+        assert_eq!(
+            changed("naïve café", "naïve cafe", false),
+            (vec!["café"], vec!["cafe"])
+        );
+        assert_eq!(changed("a  b", "a b", true), (vec![], vec![]));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::settings::IndentConfig;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorConfigIndent {
+    pub hard_tabs: Option<bool>,
+    pub indent_size: Option<usize>,
+    pub tab_width: Option<usize>,
+}
+
+impl EditorConfigIndent {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn apply(&self, mut config: IndentConfig) -> IndentConfig {
+        if let Some(hard_tabs) = self.hard_tabs {
+            config.hard_tabs = hard_tabs;
+        }
+        if let Some(size) = self.indent_size.or(self.tab_width) {
+            config.tab_size = size.max(1);
+        }
+        config
+    }
+
+    fn or(self, other: Self) -> Self {
+        Self {
+            hard_tabs: self.hard_tabs.or(other.hard_tabs),
+            indent_size: self.indent_size.or(other.indent_size),
+            tab_width: self.tab_width.or(other.tab_width),
+        }
+    }
+}
+
+pub fn indent_for_file(path: &Path) -> EditorConfigIndent {
+    let mut found = EditorConfigIndent::default();
+    for dir in path.ancestors().skip(1) {
+        let Ok(text) = std::fs::read_to_string(dir.join(".editorconfig")) else {
+            continue;
+        };
+        let Ok(rel) = path.strip_prefix(dir) else {
+            continue;
+        };
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let (indent, root) = parse_editorconfig(&text, &rel);
+        found = found.or(indent);
+        if root {
+            break;
+        }
+    }
+    found
+}
+
+fn parse_editorconfig(text: &str, rel_path: &str) -> (EditorConfigIndent, bool) {
+    let name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+    let mut indent = EditorConfigIndent::default();
+    let mut root = false;
+    let mut section: Option<bool> = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let matched = match glob.strip_prefix('/') {
+                Some(glob) => glob_match(glob, rel_path),
+                None if glob.contains('/') => glob_match(glob, rel_path),
+                None => glob_match(glob, name),
+            };
+            section = Some(matched);
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_lowercase();
+        match (section, key.as_str()) {
+            (None, "root") => root = value == "true",
+            (Some(true), "indent_style") => match value.as_str() {
+                "tab" => indent.hard_tabs = Some(true),
+                "space" => indent.hard_tabs = Some(false),
+                _ => {}
+            },
+            (Some(true), "indent_size") => indent.indent_size = value.parse().ok(),
+            (Some(true), "tab_width") => indent.tab_width = value.parse().ok(),
+            _ => {}
+        }
+    }
+    (indent, root)
+}
+
+fn glob_match(glob: &str, text: &str) -> bool {
+    if let (Some(open), Some(close)) = (glob.find('{'), glob.find('}')) {
+        if open < close {
+            let (head, tail) = (&glob[..open], &glob[close + 1..]);
+            return glob[open + 1..close]
+                .split(',')
+                .any(|alt| glob_match(&format!("{}{}{}", head, alt, tail), text));
+        }
+    }
+    wildcard_match(glob.as_bytes(), text.as_bytes())
+}
+
+fn wildcard_match(glob: &[u8], text: &[u8]) -> bool {
+    match glob {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|i| wildcard_match(rest, &text[i..])),
+        [b'*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != b'/')
+            .any(|i| wildcard_match(rest, &text[i..])),
+        [b'?', rest @ ..] => {
+            text.first().is_some_and(|&c| c != b'/') && wildcard_match(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && wildcard_match(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_editorconfig, EditorConfigIndent};
+
+    #[test]
+    fn test_parse_editorconfig_sections() {
+        let text = "root = true\n\n[*]\nindent_style = space\nindent_size = 4\n\n\
+                    [*.{go,mk}]\nindent_style = tab\ntab_width = 8\n\n\
+                    [docs/**.md]\nindent_size = 2\n";
+        let (go, root) = parse_editorconfig(text, "cmd/main.go");
+        assert!(root);
+        assert_eq!(
+            go,
+            EditorConfigIndent {
+                hard_tabs: Some(true),
+                indent_size: Some(4),
+                tab_width: Some(8),
+            }
+        );
+        assert_eq!(
+            parse_editorconfig(text, "docs/a/b.md").0.indent_size,
+            Some(2)
+        );
+        assert_eq!(parse_editorconfig(text, "a.md").0.indent_size, Some(4));
+        assert!(!parse_editorconfig("[*.rs]\nindent_size = 2", "a.py").1);
+        assert!(parse_editorconfig("[*.rs]\nindent_size = 2", "a.py")
+            .0
+            .is_empty());
+    }
+}
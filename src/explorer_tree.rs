@@ -0,0 +1,483 @@
+use crate::command_palette::fuzzy_match;
+use crate::git_service::FileStatusKind;
+use crate::git_view::{status_color, status_letter};
+use crate::ide_theme::use_ide_theme;
+use crate::settings::TreeSort;
+use adabraka_ui::components::icon::Icon;
+use adabraka_ui::navigation::file_tree::FileNode;
+use adabraka_ui::theme::use_theme;
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const ROW_HEIGHT: f32 = 28.0;
+
+type SelectHandler = Arc<dyn Fn(&PathBuf, &mut Window, &mut App) + Send + Sync>;
+type ToggleHandler = Arc<dyn Fn(&PathBuf, bool, &mut Window, &mut App) + Send + Sync>;
+type ContextMenuHandler = Arc<dyn Fn(&PathBuf, Point<Pixels>, &mut Window, &mut App) + Send + Sync>;
+
+#[derive(IntoElement)]
+pub struct ExplorerTree {
+    nodes: Vec<FileNode>,
+    selected_path: Option<PathBuf>,
+    expanded_paths: Vec<PathBuf>,
+    git_status: HashMap<PathBuf, FileStatusKind>,
+    changed_dirs: HashSet<PathBuf>,
+    sort: TreeSort,
+    folders_first: bool,
+    on_select: Option<SelectHandler>,
+    on_toggle: Option<ToggleHandler>,
+    on_collapse_recursive: Option<SelectHandler>,
+    on_context_menu: Option<ContextMenuHandler>,
+}
+
+impl ExplorerTree {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            selected_path: None,
+            expanded_paths: Vec::new(),
+            git_status: HashMap::new(),
+            changed_dirs: HashSet::new(),
+            sort: TreeSort::default(),
+            folders_first: true,
+            on_select: None,
+            on_toggle: None,
+            on_collapse_recursive: None,
+            on_context_menu: None,
+        }
+    }
+
+    pub fn nodes(mut self, nodes: Vec<FileNode>) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    pub fn sort(mut self, sort: TreeSort, folders_first: bool) -> Self {
+        self.sort = sort;
+        self.folders_first = folders_first;
+        self
+    }
+
+    pub fn selected_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.selected_path = Some(path.into());
+        self
+    }
+
+    pub fn expanded_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.expanded_paths = paths;
+        self
+    }
+
+    pub fn git_status(mut self, status: HashMap<PathBuf, FileStatusKind>) -> Self {
+        self.changed_dirs = status
+            .keys()
+            .flat_map(|path| path.ancestors().skip(1).map(|p| p.to_path_buf()))
+            .collect();
+        self.git_status = status;
+        self
+    }
+
+    pub fn on_select<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&PathBuf, &mut Window, &mut App) + Send + Sync + 'static,
+    {
+        self.on_select = Some(Arc::new(handler));
+        self
+    }
+
+    pub fn on_toggle<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&PathBuf, bool, &mut Window, &mut App) + Send + Sync + 'static,
+    {
+        self.on_toggle = Some(Arc::new(handler));
+        self
+    }
+
+    pub fn on_collapse_recursive<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&PathBuf, &mut Window, &mut App) + Send + Sync + 'static,
+    {
+        self.on_collapse_recursive = Some(Arc::new(handler));
+        self
+    }
+
+    pub fn on_context_menu<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&PathBuf, Point<Pixels>, &mut Window, &mut App) + Send + Sync + 'static,
+    {
+        self.on_context_menu = Some(Arc::new(handler));
+        self
+    }
+}
+
+fn sort_file_nodes(nodes: &mut [FileNode], sort: TreeSort, folders_first: bool) {
+    let group = |node: &FileNode| folders_first && !node.is_directory();
+    match sort {
+        TreeSort::Name => nodes.sort_by_cached_key(|n| (group(n), n.name.to_lowercase())),
+        TreeSort::NameCaseSensitive => nodes.sort_by_cached_key(|n| (group(n), n.name.clone())),
+        TreeSort::Extension => nodes.sort_by_cached_key(|n| {
+            let ext = n.extension().filter(|_| !n.is_directory());
+            let ext = ext.unwrap_or_default().to_lowercase();
+            (group(n), ext, n.name.to_lowercase())
+        }),
+        TreeSort::Modified => nodes.sort_by_cached_key(|n| {
+            let modified = Reverse(n.modified.clone());
+            (group(n), modified, n.name.to_lowercase())
+        }),
+    }
+    for node in nodes.iter_mut() {
+        if !node.children.is_empty() {
+            sort_file_nodes(&mut node.children, sort, folders_first);
+        }
+    }
+}
+
+pub fn filter_tree<'a>(
+    nodes: &[FileNode],
+    files: impl IntoIterator<Item = &'a Path>,
+    query: &str,
+) -> (Vec<FileNode>, Vec<PathBuf>) {
+    let mut filter = TreeFilter {
+        query,
+        loaded: HashMap::new(),
+        children: HashMap::new(),
+        expanded: Vec::new(),
+    };
+    filter.add_loaded(nodes);
+    for file in files {
+        let Some(top) = nodes.iter().find(|n| file.starts_with(&n.path)) else {
+            continue;
+        };
+        for (child, parent) in file.ancestors().zip(file.ancestors().skip(1)) {
+            if child == top.path {
+                break;
+            }
+            let siblings = filter.children.entry(parent.to_path_buf()).or_default();
+            if !siblings.insert(child.to_path_buf()) {
+                break;
+            }
+        }
+    }
+    let filtered = nodes
+        .iter()
+        .filter_map(|n| filter.build(&n.path, false))
+        .collect();
+    (filtered, filter.expanded)
+}
+
+struct TreeFilter<'a> {
+    query: &'a str,
+    loaded: HashMap<&'a Path, &'a FileNode>,
+    children: HashMap<PathBuf, BTreeSet<PathBuf>>,
+    expanded: Vec<PathBuf>,
+}
+
+impl<'a> TreeFilter<'a> {
+    fn add_loaded(&mut self, nodes: &'a [FileNode]) {
+        for node in nodes {
+            self.loaded.insert(&node.path, node);
+            if node.is_directory() {
+                let children = self.children.entry(node.path.clone()).or_default();
+                children.extend(node.children.iter().map(|c| c.path.clone()));
+                self.add_loaded(&node.children);
+            }
+        }
+    }
+
+    fn build(&mut self, path: &Path, keep_all: bool) -> Option<FileNode> {
+        let mut node = match self.loaded.get(path) {
+            Some(node) => FileNode {
+                path: node.path.clone(),
+                name: node.name.clone(),
+                kind: node.kind,
+                children: Vec::new(),
+                size: node.size,
+                modified: node.modified.clone(),
+                is_hidden: node.is_hidden,
+                has_unloaded_children: false,
+            },
+            None => {
+                let node = if self.children.contains_key(path) {
+                    FileNode::directory(path)
+                } else {
+                    FileNode::file(path)
+                };
+                let hidden = node.name.starts_with('.');
+                node.hidden(hidden)
+            }
+        };
+        if node.is_hidden {
+            return None;
+        }
+        let matched = keep_all || fuzzy_match(self.query, &node.name).is_some();
+        let keep_children = matched && node.is_directory();
+        let child_paths: Vec<PathBuf> = self
+            .children
+            .get(path)
+            .map(|c| c.iter().cloned().collect())
+            .unwrap_or_default();
+        node.children = child_paths
+            .iter()
+            .filter_map(|child| self.build(child, keep_children))
+            .collect();
+        node.has_unloaded_children = false;
+        if node.children.is_empty() && !matched {
+            return None;
+        }
+        if !keep_all && !node.children.is_empty() {
+            self.expanded.push(node.path.clone());
+        }
+        Some(node)
+    }
+}
+
+fn flatten_nodes<'a>(
+    nodes: &'a [FileNode],
+    expanded: &HashSet<PathBuf>,
+    level: usize,
+    out: &mut Vec<(&'a FileNode, usize)>,
+) {
+    for node in nodes {
+        if node.is_hidden {
+            continue;
+        }
+        out.push((node, level));
+        let has_children = !node.children.is_empty() || node.has_unloaded_children;
+        if has_children && expanded.contains(&node.path) {
+            flatten_nodes(&node.children, expanded, level + 1, out);
+        }
+    }
+}
+
+impl RenderOnce for ExplorerTree {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let theme = use_theme();
+        let ide = use_ide_theme();
+        let expanded: HashSet<PathBuf> = self.expanded_paths.into_iter().collect();
+        let mut nodes = self.nodes;
+        sort_file_nodes(&mut nodes, self.sort, self.folders_first);
+        let mut flat = Vec::new();
+        flatten_nodes(&nodes, &expanded, 0, &mut flat);
+
+        let mut rows = Vec::with_capacity(flat.len());
+        for (node, level) in flat {
+            let path = node.path.clone();
+            let is_selected = self.selected_path.as_ref() == Some(&path);
+            let is_expanded = expanded.contains(&path);
+            let is_dir = node.is_directory();
+            let has_children = !node.children.is_empty() || node.has_unloaded_children;
+            let status = self.git_status.get(&path).copied();
+            let dir_changed = is_dir && self.changed_dirs.contains(&path);
+            let status_fg = status.map(|s| status_color(s, &ide.chrome));
+
+            let name_color = if is_selected {
+                theme.tokens.accent_foreground
+            } else if let Some(color) = status_fg {
+                color
+            } else if dir_changed {
+                hsla(0.12, 0.9, 0.65, 1.0).opacity(0.8)
+            } else {
+                theme.tokens.foreground
+            };
+            let icon_color = if is_selected {
+                theme.tokens.accent_foreground
+            } else {
+                node.file_icon_color(&theme)
+            };
+
+            let on_select = self.on_select.clone();
+            let on_toggle = self.on_toggle.clone();
+            let on_collapse_recursive = self.on_collapse_recursive.clone();
+            let on_context_menu = self.on_context_menu.clone();
+            let menu_path = path.clone();
+            rows.push(
+                div()
+                    .id(SharedString::from(path.to_string_lossy().to_string()))
+                    .w_full()
+                    .h(px(ROW_HEIGHT))
+                    .flex()
+                    .items_center()
+                    .mx(px(8.0))
+                    .px(px(8.0))
+                    .pl(px(level as f32 * 16.0 + 8.0))
+                    .rounded(px(8.0))
+                    .cursor_pointer()
+                    .when(is_selected, |d| d.bg(theme.tokens.accent))
+                    .when(!is_selected, |d| {
+                        d.hover(|s| s.bg(theme.tokens.accent.opacity(0.5)))
+                    })
+                    .on_mouse_down(MouseButton::Right, move |event, window, cx| {
+                        if let Some(handler) = &on_context_menu {
+                            handler(&menu_path, event.position, window, cx);
+                        }
+                    })
+                    .on_click(move |event, window, cx| {
+                        if let Some(handler) = &on_select {
+                            handler(&path, window, cx);
+                        }
+                        if is_dir && event.modifiers().alt {
+                            if let Some(handler) = &on_collapse_recursive {
+                                handler(&path, window, cx);
+                            }
+                        } else if is_dir {
+                            if let Some(handler) = &on_toggle {
+                                handler(&path, !is_expanded, window, cx);
+                            }
+                        }
+                    })
+                    .child(
+                        div()
+                            .w(px(16.0))
+                            .h(px(16.0))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .when(has_children, |d| {
+                                d.child(
+                                    Icon::new(if is_expanded {
+                                        "chevron-down"
+                                    } else {
+                                        "chevron-right"
+                                    })
+                                    .size(px(12.0))
+                                    .color(theme.tokens.muted_foreground),
+                                )
+                            }),
+                    )
+                    .child(
+                        div().ml(px(6.0)).child(
+                            Icon::new(node.file_icon(is_expanded))
+                                .size(px(16.0))
+                                .color(icon_color),
+                        ),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .ml(px(6.0))
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .text_size(px(13.0))
+                            .font_family(theme.tokens.font_family.clone())
+                            .text_color(name_color)
+                            .when(status == Some(FileStatusKind::Deleted), |d| {
+                                d.line_through()
+                            })
+                            .child(node.name.clone()),
+                    )
+                    .when_some(status, |d, status| {
+                        d.child(
+                            div()
+                                .flex_shrink_0()
+                                .ml(px(6.0))
+                                .text_size(px(11.0))
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .text_color(if is_selected {
+                                    theme.tokens.accent_foreground
+                                } else {
+                                    status_color(status, &ide.chrome)
+                                })
+                                .child(status_letter(status)),
+                        )
+                    })
+                    .when(dir_changed && status.is_none(), |d| {
+                        d.child(
+                            div()
+                                .flex_shrink_0()
+                                .ml(px(6.0))
+                                .size(px(6.0))
+                                .rounded_full()
+                                .bg(name_color.opacity(0.7)),
+                        )
+                    }),
+            );
+        }
+
+        div().flex().flex_col().w_full().children(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_tree, sort_file_nodes};
+    use crate::settings::TreeSort;
+    use adabraka_ui::navigation::file_tree::FileNode;
+    use std::path::PathBuf;
+
+    fn names(nodes: &[FileNode]) -> Vec<&str> {
+        nodes.iter().map(|n| n.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_file_nodes_modes() {
+        let mut nodes = vec![
+            FileNode::file("/w/a.rs").with_modified("2"),
+            FileNode::file("/w/B.toml").with_modified("3"),
+            FileNode::directory("/w/src").with_modified("1"),
+            FileNode::file("/w/c.md").with_modified("1"),
+        ];
+        sort_file_nodes(&mut nodes, TreeSort::Name, true);
+        assert_eq!(names(&nodes), ["src", "a.rs", "B.toml", "c.md"]);
+        sort_file_nodes(&mut nodes, TreeSort::Extension, true);
+        assert_eq!(names(&nodes), ["src", "c.md", "a.rs", "B.toml"]);
+        sort_file_nodes(&mut nodes, TreeSort::Modified, false);
+        assert_eq!(names(&nodes), ["B.toml", "a.rs", "c.md", "src"]);
+        sort_file_nodes(&mut nodes, TreeSort::Name, false);
+        assert_eq!(names(&nodes), ["a.rs", "B.toml", "c.md", "src"]);
+        sort_file_nodes(&mut nodes, TreeSort::NameCaseSensitive, false);
+        assert_eq!(names(&nodes), ["B.toml", "a.rs", "c.md", "src"]);
+    }
+
+    #[test]
+    fn test_filter_tree_keeps_ancestors_of_matches() {
+        let nodes = vec![
+            FileNode::directory("/w/src").with_children(vec![
+                FileNode::file("/w/src/main.rs"),
+                FileNode::directory("/w/src/ui")
+                    .with_children(vec![FileNode::file("/w/src/ui/menu.rs")]),
+            ]),
+            FileNode::directory("/w/docs").with_children(vec![FileNode::file("/w/docs/guide.md")]),
+            FileNode::file("/w/.env").hidden(true),
+        ];
+        let (filtered, expanded) = filter_tree(&nodes, [], "mn");
+        assert_eq!(names(&filtered), ["src"]);
+        assert_eq!(names(&filtered[0].children), ["main.rs", "ui"]);
+        assert_eq!(names(&filtered[0].children[1].children), ["menu.rs"]);
+        let expected: Vec<PathBuf> = vec!["/w/src/ui".into(), "/w/src".into()];
+        assert_eq!(expanded, expected);
+        assert!(filter_tree(&nodes, [], "env").0.is_empty());
+    }
+
+    #[test]
+    fn test_filter_tree_searches_unloaded_folders() {
+        let nodes = vec![
+            FileNode::directory("/w/src").with_unloaded_children(true),
+            FileNode::file("/w/README.md"),
+        ];
+        let files: Vec<PathBuf> = vec![
+            "/w/src/lib.rs".into(),
+            "/w/src/deep/menu.rs".into(),
+            "/w/src/deep/util.rs".into(),
+        ];
+        let paths = || files.iter().map(PathBuf::as_path);
+        let (filtered, expanded) = filter_tree(&nodes, paths(), "menu");
+        assert_eq!(names(&filtered), ["src"]);
+        assert_eq!(names(&filtered[0].children), ["deep"]);
+        assert_eq!(names(&filtered[0].children[0].children), ["menu.rs"]);
+        let expected: Vec<PathBuf> = vec!["/w/src/deep".into(), "/w/src".into()];
+        assert_eq!(expanded, expected);
+
+        let (filtered, expanded) = filter_tree(&nodes, paths(), "deep");
+        assert_eq!(names(&filtered[0].children), ["deep"]);
+        assert_eq!(
+            names(&filtered[0].children[0].children),
+            ["menu.rs", "util.rs"]
+        );
+        assert_eq!(expanded, expected);
+    }
+}
@@ -0,0 +1,67 @@
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+static LOCATION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:[A-Za-z]:)?[\w.~/\\-]+:(\d+)(?::(\d+))?").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileLocation {
+    pub range: Range<usize>,
+    pub path: String,
+    pub line: usize,
+    pub col: Option<usize>,
+}
+
+pub fn find_locations(text: &str) -> Vec<FileLocation> {
+    let char_index = |byte: usize| text[..byte].chars().count();
+    LOCATION
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            let line = caps.get(1)?;
+            let path = &text[whole.start()..line.start() - 1];
+            let prev = text[..whole.start()].chars().next_back();
+            let attached = prev.is_some_and(|c| c == ':' || c.is_alphanumeric());
+            if attached || path.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let line = line.as_str().parse().ok().filter(|n| *n > 0)?;
+            let col = caps.get(2).and_then(|c| c.as_str().parse().ok());
+            Some(FileLocation {
+                range: char_index(whole.start())..char_index(whole.end()),
+                path: path.to_string(),
+                line,
+                col,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_locations, FileLocation};
+
+    #[test]
+    fn test_find_locations_in_compiler_output() {
+        let found = find_locations("  --> src/app.rs:417:12 and ./lib.rs:3, url http://x:80");
+        let expected = vec![
+            FileLocation {
+                range: 6..23,
+                path: "src/app.rs".into(),
+                line: 417,
+                col: Some(12),
+            },
+            FileLocation {
+                range: 28..38,
+                path: "./lib.rs".into(),
+                line: 3,
+                col: None,
+            },
+        ];
+        assert_eq!(found, expected);
+        assert_eq!(find_locations("ünï main.rs:1")[0].range, 4..13);
+        assert!(find_locations("at 12:30 today").is_empty());
+    }
+}
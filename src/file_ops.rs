@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FileOpError {
+    #[error("Failed to duplicate {0}: {1}")]
+    Duplicate(PathBuf, std::io::Error),
+    #[error("Failed to delete {0}: {1}")]
+    Delete(PathBuf, std::io::Error),
+    #[error("Failed to move {0} to the trash: {1}")]
+    Trash(PathBuf, String),
+}
+
+pub fn copy_name(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let suffix = if n <= 1 {
+        " copy".to_string()
+    } else {
+        format!(" copy {}", n)
+    };
+    let name = match path.extension() {
+        Some(ext) => format!("{}{}.{}", stem, suffix, ext.to_string_lossy()),
+        None => format!("{}{}", stem, suffix),
+    };
+    path.with_file_name(name)
+}
+
+pub fn duplicate_file(path: &Path) -> Result<PathBuf, FileOpError> {
+    let target = (1..)
+        .map(|n| copy_name(path, n))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| copy_name(path, 1));
+    fs::copy(path, &target).map_err(|err| FileOpError::Duplicate(path.to_path_buf(), err))?;
+    Ok(target)
+}
+
+pub fn delete_path(path: &Path) -> Result<(), FileOpError> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.map_err(|err| FileOpError::Delete(path.to_path_buf(), err))
+}
+
+#[cfg(target_os = "macos")]
+fn trash_command(path: &Path) -> Command {
+    const SCRIPT: &str = "tell application \"Finder\" to delete POSIX file (item 1 of argv)";
+    let mut command = Command::new("osascript");
+    command
+        .args(["-e", "on run argv"])
+        .args(["-e", SCRIPT])
+        .args(["-e", "end run"])
+        .arg(path);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn trash_command(path: &Path) -> Command {
+    const SCRIPT: &str = "Add-Type -AssemblyName Microsoft.VisualBasic; \
+        $p = $env:SHIORI_TRASH_PATH; \
+        if (Test-Path -PathType Container $p) { \
+            [Microsoft.VisualBasic.FileIO.FileSystem]::DeleteDirectory($p, 'OnlyErrorDialogs', 'SendToRecycleBin') \
+        } else { \
+            [Microsoft.VisualBasic.FileIO.FileSystem]::DeleteFile($p, 'OnlyErrorDialogs', 'SendToRecycleBin') \
+        }";
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT])
+        .env("SHIORI_TRASH_PATH", path);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn trash_command(path: &Path) -> Command {
+    let mut command = Command::new("gio");
+    command.arg("trash").arg(path);
+    command
+}
+
+pub fn move_to_trash(path: &Path) -> Result<(), FileOpError> {
+    let trash_error = |message: String| FileOpError::Trash(path.to_path_buf(), message);
+    let output = trash_command(path)
+        .output()
+        .map_err(|err| trash_error(err.to_string()))?;
+    if output.status.success() && !path.exists() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(trash_error(if stderr.is_empty() {
+            "the trash is not available".to_string()
+        } else {
+            stderr
+        }))
+    }
+}
+
+pub fn relative_path(path: &Path, root: Option<&Path>) -> String {
+    root.and_then(|root| path.strip_prefix(root).ok())
+        .filter(|rel| !rel.as_os_str().is_empty())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{copy_name, relative_path};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_copy_name_keeps_extension() {
+        let copy = |path: &str, n| copy_name(Path::new(path), n);
+        assert_eq!(copy("/w/foo.rs", 1), PathBuf::from("/w/foo copy.rs"));
+        assert_eq!(copy("/w/foo.rs", 3), PathBuf::from("/w/foo copy 3.rs"));
+        assert_eq!(copy("/w/Makefile", 1), PathBuf::from("/w/Makefile copy"));
+        assert_eq!(copy("/w/.env", 2), PathBuf::from("/w/.env copy 2"));
+        let root = Some(Path::new("/w"));
+        assert_eq!(relative_path(Path::new("/w/src/a.rs"), root), "src/a.rs");
+        assert_eq!(relative_path(Path::new("/x/a.rs"), root), "/x/a.rs");
+    }
+}
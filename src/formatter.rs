@@ -0,0 +1,148 @@
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use thiserror::Error;
+
+use crate::lsp::types::TextEdit;
+use crate::settings::FormatterConfig;
+
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("Failed to run {0}: {1}")]
+    Spawn(String, std::io::Error),
+    #[error("{0}")]
+    Failed(String),
+}
+
+pub fn run_formatter(
+    config: &FormatterConfig,
+    input: &str,
+    file: Option<&Path>,
+) -> Result<String, FormatError> {
+    let file_arg = file.map(|p| p.display().to_string()).unwrap_or_default();
+    let mut cmd = Command::new(&config.command);
+    cmd.args(config.args.iter().map(|a| a.replace("{file}", &file_arg)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = file.and_then(Path::parent) {
+        cmd.current_dir(dir);
+    }
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| FormatError::Spawn(config.command.clone(), e))?;
+
+    let mut stdin = child.stdin.take();
+    let input = input.to_string();
+    let writer = thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    let output = child
+        .wait_with_output()
+        .map_err(|e| FormatError::Spawn(config.command.clone(), e))?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(FormatError::Failed(if stderr.is_empty() {
+            format!("{} exited with {}", config.command, output.status)
+        } else {
+            stderr
+        }));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| FormatError::Failed(format!("{} produced invalid UTF-8", config.command)))
+}
+
+fn offset_for(text: &str, line_starts: &[usize], line: u32, col_utf16: u32) -> usize {
+    let Some(&start) = line_starts.get(line as usize) else {
+        return text.len();
+    };
+    let mut utf16 = 0;
+    for (i, c) in text[start..].char_indices() {
+        if utf16 >= col_utf16 as usize || c == '\n' {
+            return start + i;
+        }
+        utf16 += c.len_utf16();
+    }
+    text.len()
+}
+
+pub fn apply_text_edits(text: &str, edits: &[TextEdit]) -> String {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let mut ranges: Vec<(Range<usize>, &str)> = edits
+        .iter()
+        .map(|e| {
+            let start = offset_for(text, &line_starts, e.start_line, e.start_col);
+            let end = offset_for(text, &line_starts, e.end_line, e.end_col).max(start);
+            (start..end, e.new_text.as_str())
+        })
+        .collect();
+    ranges.sort_by_key(|(r, _)| std::cmp::Reverse(r.start));
+    let mut out = text.to_string();
+    for (range, new_text) in ranges {
+        out.replace_range(range, new_text);
+    }
+    out
+}
+
+pub fn changed_range(old: &str, new: &str) -> Option<(Range<usize>, Range<usize>)> {
+    if old == new {
+        return None;
+    }
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old.len().min(new.len()), |((i, _), _)| i);
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+    Some((prefix..old.len() - suffix, prefix..new.len() - suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_text_edits, changed_range};
+    use crate::lsp::types::TextEdit;
+
+    #[test]
+    fn test_apply_text_edits_uses_utf16_columns() {
+        let text = "let é =  1;\nfn  x() {}\n";
+        let edits = vec![
+            TextEdit {
+                start_line: 0,
+                start_col: 7,
+                end_line: 0,
+                end_col: 9,
+                new_text: " ".into(),
+            },
+            TextEdit {
+                start_line: 1,
+                start_col: 2,
+                end_line: 1,
+                end_col: 4,
+                new_text: " ".into(),
+            },
+        ];
+        assert_eq!(apply_text_edits(text, &edits), "let é = 1;\nfn x() {}\n");
+    }
+
+    #[test]
+    fn test_changed_range_trims_common_ends() {
+        assert_eq!(changed_range("a  b", "a  b"), None);
+        assert_eq!(changed_range("fn  x()", "fn x()"), Some((3..4, 3..3)));
+        assert_eq!(changed_range("aé", "aéb"), Some((3..3, 3..4)));
+    }
+}
@@ -1,15 +1,19 @@
-use crate::diff_highlighter::{compute_line_highlights, HighlightRun};
+use crate::diff_highlighter::{compute_line_highlights, word_diff_ranges, HighlightRun};
 use crate::git_service::{
-    DiffLine, DiffLineKind, FileDiff, FileStatusKind, GitFileEntry, GitService, GitSummary,
+    DiffConfig, DiffLine, DiffLineKind, FileDiff, FileStatusKind, GitFileEntry, GitService,
+    GitSummary, LineChange,
 };
 use adabraka_ui::components::editor::{EditorState, Language};
 use gpui::UniformListScrollHandle;
 use gpui::*;
 use smol::Timer;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 const POLL_INTERVAL: Duration = Duration::from_secs(3);
+const REPO_SCAN_DEPTH: usize = 4;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DiffViewMode {
@@ -23,10 +27,14 @@ pub struct DiffRow {
     pub right: Option<DiffLine>,
     pub left_highlights: Vec<HighlightRun>,
     pub right_highlights: Vec<HighlightRun>,
+    pub left_changes: Vec<Range<usize>>,
+    pub right_changes: Vec<Range<usize>>,
 }
 
 pub struct GitState {
     pub repo_path: Option<PathBuf>,
+    roots: Vec<PathBuf>,
+    pub repositories: Vec<PathBuf>,
     pub file_entries: Vec<GitFileEntry>,
     pub selected_file_index: usize,
     pub active_diff: Option<FileDiff>,
@@ -37,11 +45,19 @@ pub struct GitState {
     polling_task: Option<Task<()>>,
     pub loading: bool,
     pub error_message: Option<String>,
+    pub notice: Option<String>,
     pub diff_view_mode: DiffViewMode,
+    pub diff_config: DiffConfig,
     old_line_highlights: Vec<Vec<HighlightRun>>,
     new_line_highlights: Vec<Vec<HighlightRun>>,
     pub diff_scroll_handle: UniformListScrollHandle,
     pub file_list_scroll_handle: ScrollHandle,
+    pub line_changes: HashMap<PathBuf, Vec<LineChange>>,
+    head_contents: HashMap<PathBuf, Option<String>>,
+    pub generation: u64,
+    pub amend: bool,
+    amend_message: Option<String>,
+    commit_drafts: HashMap<PathBuf, String>,
 }
 
 impl GitState {
@@ -49,6 +65,8 @@ impl GitState {
         let commit_editor = cx.new(EditorState::new);
         Self {
             repo_path: None,
+            roots: Vec::new(),
+            repositories: Vec::new(),
             file_entries: Vec::new(),
             selected_file_index: 0,
             active_diff: None,
@@ -59,11 +77,19 @@ impl GitState {
             polling_task: None,
             loading: false,
             error_message: None,
+            notice: None,
             diff_view_mode: DiffViewMode::Split,
+            diff_config: DiffConfig::default(),
             old_line_highlights: Vec::new(),
             new_line_highlights: Vec::new(),
             diff_scroll_handle: UniformListScrollHandle::new(),
             file_list_scroll_handle: ScrollHandle::new(),
+            line_changes: HashMap::new(),
+            head_contents: HashMap::new(),
+            generation: 0,
+            amend: false,
+            amend_message: None,
+            commit_drafts: HashMap::new(),
         }
     }
 
@@ -76,6 +102,7 @@ impl GitState {
             self.aligned_rows = Self::build_aligned_rows(
                 diff,
                 self.diff_view_mode,
+                self.diff_config,
                 &self.old_line_highlights,
                 &self.new_line_highlights,
             );
@@ -83,6 +110,15 @@ impl GitState {
         cx.notify();
     }
 
+    pub fn set_diff_config(&mut self, config: DiffConfig, cx: &mut Context<Self>) {
+        if self.diff_config == config {
+            return;
+        }
+        self.diff_config = config;
+        self.load_selected_diff(cx);
+        cx.notify();
+    }
+
     pub fn set_diff_split_pct(&mut self, pct: f32, cx: &mut Context<Self>) {
         self.diff_split_pct = pct.clamp(0.2, 0.8);
         cx.notify();
@@ -179,11 +215,138 @@ impl GitState {
     }
 
     pub fn set_workspace(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.repo_path = None;
+        self.repositories.clear();
+        self.set_roots(vec![path.clone()], cx);
+        self.switch_repository(path, cx);
+    }
+
+    pub fn set_roots(&mut self, roots: Vec<PathBuf>, cx: &mut Context<Self>) {
+        self.roots = roots.clone();
+        self.repositories
+            .retain(|repo| roots.iter().any(|root| repo.starts_with(root)));
+        let outside = self
+            .repo_path
+            .as_ref()
+            .is_some_and(|repo| !roots.iter().any(|root| repo.starts_with(root)));
+        if let Some(primary) = roots.first().filter(|_| outside) {
+            self.switch_repository(primary.clone(), cx);
+        }
+
+        cx.spawn(async move |this, cx| {
+            let scan = roots.clone();
+            let found =
+                smol::unblock(move || GitService::discover_repositories(&scan, REPO_SCAN_DEPTH))
+                    .await;
+
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |state, cx| {
+                    if state.roots != roots {
+                        return;
+                    }
+                    for repo in found {
+                        if !state.repositories.contains(&repo) {
+                            state.repositories.push(repo);
+                        }
+                    }
+                    state.repositories.sort();
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    pub fn switch_repository(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if self.repo_path.as_ref() == Some(&path) {
+            return;
+        }
+        let draft = self.commit_editor.read(cx).content();
+        let prefill = self.amend_message.take();
+        if let Some(previous) = self.repo_path.take() {
+            let trimmed = draft.trim_end();
+            if trimmed.is_empty() || prefill.as_deref() == Some(trimmed) {
+                self.commit_drafts.remove(&previous);
+            } else {
+                self.commit_drafts.insert(previous, draft);
+            }
+        }
+        let restored = self.commit_drafts.remove(&path).unwrap_or_default();
+        self.commit_editor
+            .update(cx, |editor, cx| editor.set_content(&restored, cx));
         self.repo_path = Some(path);
+        self.file_entries.clear();
+        self.selected_file_index = 0;
+        self.summary = GitSummary::default();
+        self.amend = false;
+        self.polling_task = None;
+        self.load_selected_diff(cx);
         self.refresh(cx);
         self.start_polling(cx);
     }
 
+    pub fn focus_path(&mut self, file: &Path, cx: &mut Context<Self>) {
+        let Some(root) = self
+            .roots
+            .iter()
+            .filter(|root| file.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .cloned()
+        else {
+            return;
+        };
+        let file = file.to_path_buf();
+        cx.spawn(async move |this, cx| {
+            let repo = smol::unblock(move || {
+                GitService::repository_root_for(&file)
+                    .filter(|repo| repo.starts_with(&root))
+                    .unwrap_or(root)
+            })
+            .await;
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |state, cx| {
+                    if !state.repositories.contains(&repo) {
+                        state.repositories.push(repo.clone());
+                        state.repositories.sort();
+                    }
+                    state.switch_repository(repo, cx);
+                });
+            });
+        })
+        .detach();
+    }
+
+    pub fn cycle_repository(&mut self, cx: &mut Context<Self>) {
+        if self.repositories.len() < 2 {
+            return;
+        }
+        let current = self
+            .repositories
+            .iter()
+            .position(|repo| Some(repo) == self.repo_path.as_ref());
+        let next = current.map_or(0, |idx| (idx + 1) % self.repositories.len());
+        self.switch_repository(self.repositories[next].clone(), cx);
+    }
+
+    pub fn repository_label(&self) -> Option<String> {
+        if self.repositories.len() < 2 {
+            return None;
+        }
+        let repo = self.repo_path.as_ref()?;
+        let relative = self
+            .roots
+            .iter()
+            .filter_map(|root| repo.strip_prefix(root).ok())
+            .find(|rel| !rel.as_os_str().is_empty());
+        Some(match relative {
+            Some(rel) => rel.display().to_string(),
+            None => repo
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| repo.display().to_string()),
+        })
+    }
+
     pub fn start_polling(&mut self, cx: &mut Context<Self>) {
         if self.polling_task.is_some() {
             return;
@@ -215,6 +378,7 @@ impl GitState {
                             state.file_entries = entries;
                             state.summary = summary;
                             if changed {
+                                state.invalidate_head_contents();
                                 if state.selected_file_index >= state.file_entries.len() {
                                     state.selected_file_index =
                                         state.file_entries.len().saturating_sub(1);
@@ -255,7 +419,11 @@ impl GitState {
 
             let _ = cx.update(|cx| {
                 let _ = this.update(cx, |state, cx| {
+                    if state.repo_path.as_ref() != Some(&repo_path) {
+                        return;
+                    }
                     state.loading = false;
+                    state.invalidate_head_contents();
                     if let Some((entries, summary)) = result {
                         state.file_entries = entries;
                         state.summary = summary;
@@ -271,6 +439,91 @@ impl GitState {
         .detach();
     }
 
+    fn invalidate_head_contents(&mut self) {
+        self.head_contents.clear();
+        self.generation += 1;
+    }
+
+    pub fn update_line_changes(&mut self, path: PathBuf, content: String, cx: &mut Context<Self>) {
+        let repo_path = match &self.repo_path {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        let cached = self.head_contents.get(&path).cloned();
+
+        cx.spawn(async move |this, cx| {
+            let file = path.clone();
+            let result = smol::unblock(move || {
+                let head = match cached {
+                    Some(head) => head,
+                    None => {
+                        let dir = file.parent().unwrap_or(&repo_path);
+                        let repo = GitService::open(dir).ok()?;
+                        let workdir = repo.workdir()?.to_path_buf();
+                        let rel = file.strip_prefix(&workdir).ok()?;
+                        GitService::read_head_content(&repo, &rel.to_string_lossy())
+                    }
+                };
+                let changes = match &head {
+                    Some(old) => GitService::line_changes(old, &content).unwrap_or_default(),
+                    None => Vec::new(),
+                };
+                Some((head, changes))
+            })
+            .await;
+
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |state, cx| {
+                    if let Some((head, changes)) = result {
+                        state.head_contents.insert(path.clone(), head);
+                        state.line_changes.insert(path, changes);
+                    } else {
+                        state.line_changes.remove(&path);
+                    }
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    pub fn head_content(&self, path: &Path) -> Option<&str> {
+        self.head_contents.get(path)?.as_deref()
+    }
+
+    pub fn stage_hunk(
+        &mut self,
+        path: PathBuf,
+        change: LineChange,
+        content: String,
+        cx: &mut Context<Self>,
+    ) {
+        let repo_path = match &self.repo_path {
+            Some(p) => p.clone(),
+            None => return,
+        };
+
+        cx.spawn(async move |this, cx| {
+            let result = smol::unblock(move || {
+                let repo = GitService::open(path.parent().unwrap_or(&repo_path))?;
+                let workdir = repo.workdir().map(Path::to_path_buf).unwrap_or_default();
+                let rel = path.strip_prefix(&workdir).unwrap_or(&path);
+                GitService::stage_hunk(&repo, &rel.to_string_lossy(), &content, &change)
+            })
+            .await;
+
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |state, cx| {
+                    if let Err(e) = result {
+                        state.error_message = Some(format!("Stage hunk failed: {}", e));
+                    }
+                    state.refresh(cx);
+                });
+            });
+        })
+        .detach();
+    }
+
     pub fn select_file(&mut self, idx: usize, cx: &mut Context<Self>) {
         if idx >= self.file_entries.len() {
             return;
@@ -320,6 +573,7 @@ impl GitState {
         let path = entry.path.clone();
         let staged = entry.staged;
         let is_untracked = entry.status == FileStatusKind::Untracked;
+        let config = self.diff_config;
 
         cx.spawn(async move |this, cx| {
             let p = repo_path.clone();
@@ -329,9 +583,9 @@ impl GitState {
                 let diff = if is_untracked {
                     GitService::file_diff_untracked(&repo, &file_path).ok()
                 } else if staged {
-                    GitService::file_diff_staged(&repo, &file_path).ok()
+                    GitService::file_diff_staged(&repo, &file_path, config).ok()
                 } else {
-                    GitService::file_diff_workdir(&repo, &file_path).ok()
+                    GitService::file_diff_workdir(&repo, &file_path, config).ok()
                 };
 
                 let old_content = if is_untracked {
@@ -369,6 +623,7 @@ impl GitState {
                             state.aligned_rows = Self::build_aligned_rows(
                                 &diff,
                                 state.diff_view_mode,
+                                state.diff_config,
                                 &state.old_line_highlights,
                                 &state.new_line_highlights,
                             );
@@ -390,12 +645,62 @@ impl GitState {
     fn build_aligned_rows(
         diff: &FileDiff,
         mode: DiffViewMode,
+        config: DiffConfig,
         old_highlights: &[Vec<HighlightRun>],
         new_highlights: &[Vec<HighlightRun>],
     ) -> Vec<DiffRow> {
-        match mode {
+        let mut rows = match mode {
             DiffViewMode::Split => Self::build_split_rows(diff, old_highlights, new_highlights),
             DiffViewMode::Unified => Self::build_unified_rows(diff, old_highlights, new_highlights),
+        };
+        if config.word_diff {
+            match mode {
+                DiffViewMode::Split => Self::apply_split_word_diff(&mut rows, config),
+                DiffViewMode::Unified => Self::apply_unified_word_diff(&mut rows, config),
+            }
+        }
+        rows
+    }
+
+    fn apply_split_word_diff(rows: &mut [DiffRow], config: DiffConfig) {
+        for row in rows.iter_mut() {
+            if let (Some(left), Some(right)) = (&row.left, &row.right) {
+                if left.kind == DiffLineKind::Deletion && right.kind == DiffLineKind::Addition {
+                    let (old, new) =
+                        word_diff_ranges(&left.content, &right.content, config.ignore_whitespace);
+                    row.left_changes = old;
+                    row.right_changes = new;
+                }
+            }
+        }
+    }
+
+    fn apply_unified_word_diff(rows: &mut [DiffRow], config: DiffConfig) {
+        let kind_at = |rows: &[DiffRow], i: usize| rows[i].left.as_ref().map(|l| l.kind);
+        let mut i = 0;
+        while i < rows.len() {
+            let del_start = i;
+            while i < rows.len() && kind_at(rows, i) == Some(DiffLineKind::Deletion) {
+                i += 1;
+            }
+            let add_start = i;
+            while i < rows.len() && kind_at(rows, i) == Some(DiffLineKind::Addition) {
+                i += 1;
+            }
+            let pairs = (add_start - del_start).min(i - add_start);
+            for k in 0..pairs {
+                let old_text = rows[del_start + k].left.as_ref().map(|l| l.content.clone());
+                let new_text = rows[add_start + k].left.as_ref().map(|l| l.content.clone());
+                if let (Some(old_text), Some(new_text)) = (old_text, new_text) {
+                    let (old, new) =
+                        word_diff_ranges(&old_text, &new_text, config.ignore_whitespace);
+                    rows[del_start + k].left_changes = old;
+                    rows[add_start + k].left_changes = new;
+                }
+            }
+            if i == del_start {
+                i += 1;
+            }
         }
     }
 
@@ -445,6 +750,8 @@ impl GitState {
                             right: Some(line.clone()),
                             left_highlights: hl.clone(),
                             right_highlights: hl,
+                            left_changes: Vec::new(),
+                            right_changes: Vec::new(),
                         });
                     }
                     DiffLineKind::Deletion => {
@@ -483,6 +790,8 @@ impl GitState {
                 right,
                 left_highlights: left_hl,
                 right_highlights: right_hl,
+                left_changes: Vec::new(),
+                right_changes: Vec::new(),
             });
         }
         del_buf.clear();
@@ -504,6 +813,8 @@ impl GitState {
                     right: None,
                     left_highlights: hl,
                     right_highlights: Vec::new(),
+                    left_changes: Vec::new(),
+                    right_changes: Vec::new(),
                 });
             }
         }
@@ -555,6 +866,68 @@ impl GitState {
         self.active_diff.as_ref().map(|d| d.path.as_str())
     }
 
+    pub fn set_amend(&mut self, amend: bool, cx: &mut Context<Self>) {
+        self.amend = amend;
+        if !amend {
+            if let Some(prefill) = self.amend_message.take() {
+                if self.commit_editor.read(cx).content().trim_end() == prefill {
+                    self.commit_editor
+                        .update(cx, |editor, cx| editor.set_content("", cx));
+                }
+            }
+            cx.notify();
+            return;
+        }
+
+        let repo_path = match &self.repo_path {
+            Some(p) => p.clone(),
+            None => return,
+        };
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let message = smol::unblock(move || {
+                let repo = GitService::open(&repo_path).ok()?;
+                GitService::head_commit_message(&repo)
+            })
+            .await;
+
+            let _ = cx.update(|cx| {
+                let _ = this.update(cx, |state, cx| {
+                    let Some(message) = message else {
+                        state.amend = false;
+                        state.error_message = Some("No previous commit to amend".to_string());
+                        cx.notify();
+                        return;
+                    };
+                    if !state.amend {
+                        return;
+                    }
+                    if state.commit_editor.read(cx).content().trim().is_empty() {
+                        state
+                            .commit_editor
+                            .update(cx, |editor, cx| editor.set_content(&message, cx));
+                        state.amend_message = Some(message);
+                    }
+                    cx.notify();
+                });
+            });
+        })
+        .detach();
+    }
+
+    pub fn apply_commit_prefix(&mut self, kind: &str, cx: &mut Context<Self>) {
+        let message = self.commit_editor.read(cx).content();
+        let rest = GitService::strip_conventional_prefix(message.trim_end_matches('\n'));
+        let updated = format!("{}: {}", kind, rest);
+        let col = kind.len() + 2;
+        self.commit_editor.update(cx, |editor, cx| {
+            editor.set_content(&updated, cx);
+            editor.set_cursor_position(0, col, cx);
+        });
+        cx.notify();
+    }
+
     pub fn do_commit(&mut self, cx: &mut Context<Self>) {
         let message = self.commit_editor.read(cx).content();
         let message = message.trim().to_string();
@@ -565,7 +938,7 @@ impl GitState {
         }
 
         let has_staged = self.file_entries.iter().any(|e| e.staged);
-        if !has_staged {
+        if !has_staged && !self.amend {
             self.error_message = Some("No staged changes to commit".to_string());
             cx.notify();
             return;
@@ -580,13 +953,18 @@ impl GitState {
         cx.notify();
 
         let commit_editor = self.commit_editor.clone();
+        let amend = self.amend;
 
         cx.spawn(async move |this, cx| {
             let p = repo_path.clone();
             let msg = message.clone();
             let result = smol::unblock(move || {
                 let repo = GitService::open(&p)?;
-                GitService::commit(&repo, &msg)
+                if amend {
+                    GitService::amend(&repo, &msg)
+                } else {
+                    GitService::commit(&repo, &msg)
+                }
             })
             .await;
 
@@ -594,8 +972,16 @@ impl GitState {
                 let _ = this.update(cx, |state, cx| {
                     state.loading = false;
                     match result {
-                        Ok(_oid) => {
+                        Ok(oid) => {
                             state.error_message = None;
+                            let short: String = oid.to_string().chars().take(7).collect();
+                            state.notice = Some(if amend {
+                                format!("Amended commit {}", short)
+                            } else {
+                                format!("Committed {}", short)
+                            });
+                            state.amend = false;
+                            state.amend_message = None;
                             commit_editor.update(cx, |editor, cx| {
                                 editor.set_content("", cx);
                             });
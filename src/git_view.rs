@@ -1,16 +1,20 @@
 use crate::diff_highlighter::HighlightRun;
-use crate::git_service::{DiffLineKind, FileStatusKind};
+use crate::git_service::{DiffLine, DiffLineKind, FileStatusKind};
 use crate::git_state::{DiffRow, DiffViewMode, GitState};
-use crate::ide_theme::use_ide_theme;
+use crate::ide_theme::{use_ide_theme, ChromeColors};
 use crate::review_state::{CommentSide, CommentStatus, ReviewState};
+use crate::settings::ScrollbarVisibility;
 use adabraka_ui::components::icon::Icon;
 use adabraka_ui::components::input::{Input, InputSize, InputState};
 use adabraka_ui::theme::use_theme;
 use gpui::prelude::FluentBuilder as _;
 use gpui::UniformListScrollHandle;
 use gpui::*;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct ScrollbarThumbDrag<T: 'static> {
@@ -25,18 +29,50 @@ impl<T: 'static> Render for ScrollbarThumbDrag<T> {
     }
 }
 
+const SCROLLBAR_FADE_MS: u64 = 1500;
+
+thread_local! {
+    static SCROLLBAR_ACTIVITY: RefCell<HashMap<SharedString, (f32, usize)>> =
+        RefCell::new(HashMap::new());
+}
+
+fn scrollbar_generation(id: &SharedString, offset: Option<f32>) -> usize {
+    SCROLLBAR_ACTIVITY.with(|activity| {
+        let mut activity = activity.borrow_mut();
+        let entry = activity.entry(id.clone()).or_insert((0.0, 0));
+        if let Some(offset) = offset {
+            if offset != entry.0 {
+                entry.0 = offset;
+                entry.1 += 1;
+            }
+        }
+        entry.1
+    })
+}
+
+fn bump_scrollbar_activity(id: &SharedString) {
+    SCROLLBAR_ACTIVITY.with(|activity| {
+        activity
+            .borrow_mut()
+            .entry(id.clone())
+            .or_insert((0.0, 0))
+            .1 += 1;
+    });
+}
+
 pub fn render_vertical_scrollbar<T: 'static>(
     id: impl Into<SharedString>,
     scroll_handle: ScrollHandle,
     total_content_h: f32,
     notifier: Entity<T>,
+    visibility: ScrollbarVisibility,
 ) -> AnyElement {
     let id: SharedString = id.into();
     let theme = use_theme();
     let viewport_h = scroll_handle.bounds().size.height;
     let total_h = px(total_content_h);
 
-    if viewport_h <= px(0.0) || total_h <= viewport_h {
+    if viewport_h <= px(0.0) || total_h <= viewport_h || visibility == ScrollbarVisibility::Hidden {
         return div().into_any_element();
     }
 
@@ -58,6 +94,57 @@ pub fn render_vertical_scrollbar<T: 'static>(
     let total_h_f32 = total_content_h;
 
     let thumb_id: SharedString = format!("{}-thumb", id).into();
+    let generation = match visibility {
+        ScrollbarVisibility::OnScroll => scrollbar_generation(&id, Some(f32::from(scroll_offset))),
+        ScrollbarVisibility::OnHover => scrollbar_generation(&id, None),
+        _ => 0,
+    };
+    let fades = visibility != ScrollbarVisibility::Always;
+    let id_for_hover = id.clone();
+    let id_for_drag = id.clone();
+    let state_for_hover = notifier.clone();
+
+    let thumb = div()
+        .id(ElementId::Name(thumb_id))
+        .absolute()
+        .left(px(2.0))
+        .right(px(2.0))
+        .top(relative(thumb_top_pct / 100.0))
+        .h(relative(thumb_h_pct / 100.0))
+        .bg(theme.tokens.muted_foreground.opacity(0.4))
+        .rounded(px(3.0))
+        .cursor(CursorStyle::PointingHand)
+        .hover(|s| s.bg(theme.tokens.muted_foreground.opacity(0.7)))
+        .on_mouse_down(MouseButton::Left, |_, _, cx| {
+            cx.stop_propagation();
+        })
+        .on_drag(
+            ScrollbarThumbDrag {
+                scroll_handle: scroll_handle.clone(),
+                notifier: notifier.clone(),
+                total_content_h,
+            },
+            |drag: &ScrollbarThumbDrag<T>, _, _, cx| {
+                cx.new(|_| ScrollbarThumbDrag {
+                    scroll_handle: drag.scroll_handle.clone(),
+                    notifier: drag.notifier.clone(),
+                    total_content_h: drag.total_content_h,
+                })
+            },
+        );
+    let thumb = if !fades {
+        thumb.into_any_element()
+    } else if generation == 0 {
+        thumb.opacity(0.0).into_any_element()
+    } else {
+        thumb
+            .with_animation(
+                ElementId::Name(format!("{}-fade-{}", id, generation).into()),
+                Animation::new(Duration::from_millis(SCROLLBAR_FADE_MS)),
+                |el, delta| el.opacity((1.0 - (delta - 0.6).max(0.0) / 0.4).clamp(0.0, 1.0)),
+            )
+            .into_any_element()
+    };
 
     div()
         .id(ElementId::Name(id))
@@ -67,6 +154,12 @@ pub fn render_vertical_scrollbar<T: 'static>(
         .bottom_0()
         .w(px(12.0))
         .cursor(CursorStyle::PointingHand)
+        .when(visibility == ScrollbarVisibility::OnHover, |el| {
+            el.on_mouse_move(move |_, _, cx| {
+                bump_scrollbar_activity(&id_for_hover);
+                state_for_hover.update(cx, |_, cx| cx.notify());
+            })
+        })
         .on_mouse_down(
             MouseButton::Left,
             move |event: &MouseDownEvent, _window, cx| {
@@ -96,39 +189,13 @@ pub fn render_vertical_scrollbar<T: 'static>(
             if max_scroll > px(0.0) {
                 let new_offset_y = -(max_scroll * ratio);
                 handle_for_drag.set_offset(point(handle_for_drag.offset().x, new_offset_y));
+                if fades {
+                    bump_scrollbar_activity(&id_for_drag);
+                }
                 state_for_drag.update(cx, |_, cx| cx.notify());
             }
         })
-        .child(
-            div()
-                .id(ElementId::Name(thumb_id))
-                .absolute()
-                .left(px(2.0))
-                .right(px(2.0))
-                .top(relative(thumb_top_pct / 100.0))
-                .h(relative(thumb_h_pct / 100.0))
-                .bg(theme.tokens.muted_foreground.opacity(0.4))
-                .rounded(px(3.0))
-                .cursor(CursorStyle::PointingHand)
-                .hover(|s| s.bg(theme.tokens.muted_foreground.opacity(0.7)))
-                .on_mouse_down(MouseButton::Left, |_, _, cx| {
-                    cx.stop_propagation();
-                })
-                .on_drag(
-                    ScrollbarThumbDrag {
-                        scroll_handle: scroll_handle.clone(),
-                        notifier: notifier.clone(),
-                        total_content_h,
-                    },
-                    |drag: &ScrollbarThumbDrag<T>, _, _, cx| {
-                        cx.new(|_| ScrollbarThumbDrag {
-                            scroll_handle: drag.scroll_handle.clone(),
-                            notifier: drag.notifier.clone(),
-                            total_content_h: drag.total_content_h,
-                        })
-                    },
-                ),
-        )
+        .child(thumb)
         .into_any_element()
 }
 
@@ -174,6 +241,36 @@ impl Render for DiffSplitDrag {
     }
 }
 
+pub fn status_letter(status: FileStatusKind) -> &'static str {
+    match status {
+        FileStatusKind::Modified => "M",
+        FileStatusKind::Added => "A",
+        FileStatusKind::Deleted => "D",
+        FileStatusKind::Renamed => "R",
+        FileStatusKind::Untracked => "U",
+    }
+}
+
+pub fn status_color(status: FileStatusKind, chrome: &ChromeColors) -> Hsla {
+    match status {
+        FileStatusKind::Modified => hsla(0.12, 0.9, 0.65, 1.0),
+        FileStatusKind::Added | FileStatusKind::Untracked => chrome.diff_add_text,
+        FileStatusKind::Deleted => chrome.diff_del_text,
+        FileStatusKind::Renamed => hsla(0.58, 0.7, 0.65, 1.0),
+    }
+}
+
+fn no_newline_marker(line: Option<&DiffLine>, color: Hsla) -> Option<Div> {
+    line.filter(|l| l.no_newline).map(|_| {
+        div()
+            .pl(px(8.0))
+            .flex_shrink_0()
+            .text_size(px(11.0))
+            .text_color(color)
+            .child("\\ No newline at end of file")
+    })
+}
+
 fn build_text_runs(
     content: &str,
     highlights: &[HighlightRun],
@@ -260,6 +357,40 @@ fn build_text_runs(
     runs
 }
 
+fn apply_change_backgrounds(
+    runs: Vec<TextRun>,
+    changes: &[Range<usize>],
+    background: Hsla,
+) -> Vec<TextRun> {
+    if changes.is_empty() {
+        return runs;
+    }
+
+    let mut out = Vec::with_capacity(runs.len() + changes.len() * 2);
+    let mut pos = 0;
+    for run in runs {
+        let run_end = pos + run.len;
+        let mut start = pos;
+        while start < run_end {
+            let change = changes.iter().find(|c| c.end > start && c.start < run_end);
+            let (end, highlighted) = match change {
+                Some(c) if c.start <= start => (c.end.min(run_end), true),
+                Some(c) => (c.start, false),
+                None => (run_end, false),
+            };
+            let mut piece = run.clone();
+            piece.len = end - start;
+            if highlighted {
+                piece.background_color = Some(background);
+            }
+            out.push(piece);
+            start = end;
+        }
+        pos = run_end;
+    }
+    out
+}
+
 const BASE_LINE_HEIGHT: f32 = 20.0;
 const BASE_GUTTER_WIDTH: f32 = 44.0;
 const HEADER_HEIGHT: f32 = 32.0;
@@ -524,14 +655,21 @@ pub struct GitView {
     state: Entity<GitState>,
     review_state: Entity<ReviewState>,
     zoom: f32,
+    scrollbar_visibility: ScrollbarVisibility,
 }
 
 impl GitView {
-    pub fn new(state: Entity<GitState>, review_state: Entity<ReviewState>, zoom: f32) -> Self {
+    pub fn new(
+        state: Entity<GitState>,
+        review_state: Entity<ReviewState>,
+        zoom: f32,
+        scrollbar_visibility: ScrollbarVisibility,
+    ) -> Self {
         Self {
             state,
             review_state,
             zoom,
+            scrollbar_visibility,
         }
     }
 
@@ -625,7 +763,8 @@ impl GitView {
                                     .flex()
                                     .items_center()
                                     .px(px(8.0))
-                                    .child(styled),
+                                    .child(styled)
+                                    .children(no_newline_marker(Some(line), muted_fg)),
                             )
                             .into_any_element()
                     })
@@ -663,6 +802,8 @@ impl GitView {
                 let default_color = chrome.bright;
                 let muted_fg = chrome.text_secondary.opacity(0.5);
                 let border_color = chrome.header_border.opacity(0.3);
+                let word_add_bg = chrome.diff_add_text.opacity(0.3);
+                let word_del_bg = chrome.diff_del_text.opacity(0.3);
 
                 range
                     .map(|row_idx| {
@@ -725,8 +866,11 @@ impl GitView {
                             .unwrap_or(false);
 
                         let left_styled = if !left_content.is_empty() {
-                            let text_runs =
-                                build_text_runs(&left_content, &row.left_highlights, default_color);
+                            let text_runs = apply_change_backgrounds(
+                                build_text_runs(&left_content, &row.left_highlights, default_color),
+                                &row.left_changes,
+                                word_del_bg,
+                            );
                             StyledText::new(SharedString::from(left_content.clone()))
                                 .with_runs(text_runs)
                                 .into_any_element()
@@ -735,10 +879,14 @@ impl GitView {
                         };
 
                         let right_styled = if !right_content.is_empty() {
-                            let text_runs = build_text_runs(
-                                &right_content,
-                                &row.right_highlights,
-                                default_color,
+                            let text_runs = apply_change_backgrounds(
+                                build_text_runs(
+                                    &right_content,
+                                    &row.right_highlights,
+                                    default_color,
+                                ),
+                                &row.right_changes,
+                                word_add_bg,
                             );
                             StyledText::new(SharedString::from(right_content.clone()))
                                 .with_runs(text_runs)
@@ -801,7 +949,11 @@ impl GitView {
                                             .flex()
                                             .items_center()
                                             .px(px(8.0))
-                                            .child(left_styled),
+                                            .child(left_styled)
+                                            .children(no_newline_marker(
+                                                row.left.as_ref(),
+                                                muted_fg,
+                                            )),
                                     ),
                             )
                             .child(div().w(px(1.0)).h(line_h).bg(border_color))
@@ -833,7 +985,11 @@ impl GitView {
                                             .flex()
                                             .items_center()
                                             .px(px(8.0))
-                                            .child(right_styled),
+                                            .child(right_styled)
+                                            .children(no_newline_marker(
+                                                row.right.as_ref(),
+                                                muted_fg,
+                                            )),
                                     ),
                             )
                             .into_any_element()
@@ -949,8 +1105,16 @@ impl GitView {
 
                         let content = line.content.clone();
                         let styled_content = if !content.is_empty() {
-                            let text_runs =
-                                build_text_runs(&content, &row.left_highlights, default_color);
+                            let word_bg = if line.kind == DiffLineKind::Addition {
+                                chrome.diff_add_text.opacity(0.3)
+                            } else {
+                                chrome.diff_del_text.opacity(0.3)
+                            };
+                            let text_runs = apply_change_backgrounds(
+                                build_text_runs(&content, &row.left_highlights, default_color),
+                                &row.left_changes,
+                                word_bg,
+                            );
                             StyledText::new(SharedString::from(content.clone()))
                                 .with_runs(text_runs)
                                 .into_any_element()
@@ -1021,7 +1185,8 @@ impl GitView {
                                     .flex()
                                     .items_center()
                                     .px(px(8.0))
-                                    .child(styled_content),
+                                    .child(styled_content)
+                                    .children(no_newline_marker(Some(line), muted_fg)),
                             )
                             .into_any_element()
                     })
@@ -1124,7 +1289,8 @@ impl GitView {
                                     .flex()
                                     .items_center()
                                     .px(px(8.0))
-                                    .child(styled),
+                                    .child(styled)
+                                    .children(no_newline_marker(Some(line), muted_fg)),
                             )
                             .into_any_element()
                     })
@@ -1545,6 +1711,7 @@ impl GitView {
                             base_handle,
                             total_h,
                             git_state_bar,
+                            self.scrollbar_visibility,
                         )
                     })
                     .children(overlay)
@@ -0,0 +1,73 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GotoTarget {
+    pub line: usize,
+    pub col: Option<usize>,
+}
+
+fn parse_number(text: &str) -> Option<usize> {
+    text.trim().parse::<usize>().ok().filter(|n| *n > 0)
+}
+
+pub fn parse_goto_target(
+    input: &str,
+    current_line: usize,
+    line_count: usize,
+) -> Option<GotoTarget> {
+    let input = input.trim().trim_end_matches(':');
+    let last = line_count.max(1);
+    if let Some(percent) = input.strip_suffix('%') {
+        let percent = percent
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|p| (0.0..=100.0).contains(p))?;
+        let line = ((last as f64 * percent / 100.0).round() as usize).clamp(1, last);
+        return Some(GotoTarget {
+            line: line - 1,
+            col: None,
+        });
+    }
+    let (line, col) = match input.strip_prefix(':') {
+        Some(col) if !col.contains(':') => (current_line + 1, Some(col)),
+        rest => {
+            let rest = rest.unwrap_or(input);
+            let (line, col) = match rest.split_once(':') {
+                Some((line, col)) => (line, Some(col)),
+                None => (rest, None),
+            };
+            (parse_number(line)?, col)
+        }
+    };
+    let col = match col {
+        Some(col) => Some(parse_number(col)? - 1),
+        None => None,
+    };
+    Some(GotoTarget {
+        line: line.min(last) - 1,
+        col,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_goto_target, GotoTarget};
+
+    fn target(line: usize, col: Option<usize>) -> Option<GotoTarget> {
+        Some(GotoTarget { line, col })
+    }
+
+    #[test]
+    fn test_parse_goto_target_forms() {
+        assert_eq!(parse_goto_target("42", 0, 100), target(41, None));
+        assert_eq!(parse_goto_target("42:10", 0, 100), target(41, Some(9)));
+        assert_eq!(parse_goto_target(":42:10:", 0, 100), target(41, Some(9)));
+        assert_eq!(parse_goto_target(":7", 4, 100), target(4, Some(6)));
+        assert_eq!(parse_goto_target("50%", 0, 200), target(99, None));
+        assert_eq!(parse_goto_target("0%", 0, 200), target(0, None));
+        assert_eq!(parse_goto_target("500", 0, 20), target(19, None));
+        assert_eq!(parse_goto_target("", 0, 20), None);
+        assert_eq!(parse_goto_target("0", 0, 20), None);
+        assert_eq!(parse_goto_target("12:x", 0, 20), None);
+        assert_eq!(parse_goto_target("150%", 0, 20), None);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::settings::GutterItem;
+
+pub const GUTTER_WIDTH: f32 = 80.0;
+
+fn slot_width(item: GutterItem) -> f32 {
+    match item {
+        GutterItem::Diagnostics | GutterItem::GitChanges => 8.0,
+        GutterItem::LineNumbers => 48.0,
+        GutterItem::Folds => 16.0,
+    }
+}
+
+pub fn gutter_slots(items: &[GutterItem]) -> Vec<(GutterItem, f32)> {
+    let mut slots: Vec<(GutterItem, f32)> = Vec::new();
+    let mut x = 0.0;
+    for &item in items {
+        if slots.iter().any(|(i, _)| *i == item) {
+            continue;
+        }
+        slots.push((item, x));
+        x += slot_width(item);
+    }
+    slots
+}
+
+pub fn slot_x(items: &[GutterItem], item: GutterItem) -> Option<f32> {
+    gutter_slots(items)
+        .into_iter()
+        .find(|(i, _)| *i == item)
+        .map(|(_, x)| x)
+}
+
+pub fn is_native(items: &[GutterItem]) -> bool {
+    gutter_slots(items)
+        .iter()
+        .map(|(i, _)| *i)
+        .eq(GutterItem::ALL)
+}
+
+pub fn needs_wide_gutter(items: &[GutterItem]) -> bool {
+    items.iter().any(|i| *i != GutterItem::GitChanges)
+}
+
+pub fn move_item(items: &[GutterItem], item: GutterItem, delta: i32) -> Vec<GutterItem> {
+    let mut items: Vec<GutterItem> = gutter_slots(items).into_iter().map(|(i, _)| i).collect();
+    match items.iter().position(|i| *i == item) {
+        Some(idx) => {
+            let target = idx as i32 + delta;
+            if target >= items.len() as i32 {
+                items.remove(idx);
+            } else if target >= 0 {
+                let item = items.remove(idx);
+                items.insert(target as usize, item);
+            }
+        }
+        None if delta < 0 => items.push(item),
+        None => {}
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gutter_slots, is_native, move_item};
+    use crate::settings::GutterItem;
+
+    #[test]
+    fn test_gutter_slots_and_reordering() {
+        let native = GutterItem::ALL.to_vec();
+        let xs: Vec<f32> = gutter_slots(&native).iter().map(|(_, x)| *x).collect();
+        assert_eq!(xs, vec![0.0, 8.0, 56.0, 64.0]);
+        assert!(is_native(&native));
+
+        let moved = move_item(&native, GutterItem::Folds, -3);
+        assert_eq!(moved[0], GutterItem::Folds);
+        assert!(!is_native(&moved));
+        assert_eq!(gutter_slots(&moved)[1], (GutterItem::Diagnostics, 16.0));
+
+        let hidden = move_item(&native, GutterItem::Folds, 1);
+        assert_eq!(hidden.len(), 3);
+        assert_eq!(move_item(&hidden, GutterItem::Folds, 1), hidden);
+        assert_eq!(move_item(&hidden, GutterItem::Folds, -1), native);
+    }
+}
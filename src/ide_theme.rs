@@ -9,6 +9,8 @@ pub struct IdeTheme {
     pub syntax: SyntaxColors,
     pub terminal: TerminalColors,
     pub chrome: ChromeColors,
+    pub completion: CompletionKindColors,
+    pub completion_glyphs: CompletionKindGlyphs,
 }
 
 #[derive(Clone, Debug)]
@@ -96,6 +98,57 @@ pub struct ChromeColors {
     pub review_comment_indicator: Hsla,
 }
 
+#[derive(Clone, Debug)]
+pub struct CompletionKindColors {
+    pub function: Hsla,
+    pub method: Hsla,
+    pub variable: Hsla,
+    pub field: Hsla,
+    pub module: Hsla,
+    pub structure: Hsla,
+    pub class: Hsla,
+    pub enumeration: Hsla,
+    pub constant: Hsla,
+    pub type_name: Hsla,
+    pub keyword: Hsla,
+    pub snippet: Hsla,
+}
+
+#[derive(Clone, Debug)]
+pub struct CompletionKindGlyphs {
+    pub function: &'static str,
+    pub method: &'static str,
+    pub variable: &'static str,
+    pub field: &'static str,
+    pub module: &'static str,
+    pub structure: &'static str,
+    pub class: &'static str,
+    pub enumeration: &'static str,
+    pub constant: &'static str,
+    pub type_name: &'static str,
+    pub keyword: &'static str,
+    pub snippet: &'static str,
+}
+
+impl Default for CompletionKindGlyphs {
+    fn default() -> Self {
+        Self {
+            function: "ƒ",
+            method: "m",
+            variable: "x",
+            field: "·",
+            module: "{}",
+            structure: "S",
+            class: "C",
+            enumeration: "E",
+            constant: "π",
+            type_name: "T",
+            keyword: "k",
+            snippet: "✂",
+        }
+    }
+}
+
 static IDE_THEME: LazyLock<Mutex<IdeTheme>> = LazyLock::new(|| Mutex::new(island_dark()));
 
 pub fn use_ide_theme() -> IdeTheme {
@@ -309,6 +362,21 @@ pub fn island_dark() -> IdeTheme {
             review_comment_bg: hsla_from_hex(0x2a2520),
             review_comment_indicator: hsla_from_hex(0xfbbf24),
         },
+        completion: CompletionKindColors {
+            function: hsla_from_hex(0x60a5fa),
+            method: hsla_from_hex(0x60a5fa),
+            variable: hsla_from_hex(0xbdc6d4),
+            field: hsla_from_hex(0x93c5fd),
+            module: hsla_from_hex(0x67e8f9),
+            structure: hsla_from_hex(0x67e8f9),
+            class: hsla_from_hex(0x67e8f9),
+            enumeration: hsla_from_hex(0x22d3ee),
+            constant: hsla_from_hex(0xfbbf24),
+            type_name: hsla_from_hex(0x67e8f9),
+            keyword: hsla_from_hex(0xc084fc),
+            snippet: hsla_from_hex(0xa78bfa),
+        },
+        completion_glyphs: CompletionKindGlyphs::default(),
     }
 }
 
@@ -415,6 +483,21 @@ pub fn dracula() -> IdeTheme {
             review_comment_bg: hsla_from_hex(0x3a3545),
             review_comment_indicator: hsla_from_hex(0xf1fa8c),
         },
+        completion: CompletionKindColors {
+            function: hsla_from_hex(0x50fa7b),
+            method: hsla_from_hex(0x50fa7b),
+            variable: hsla_from_hex(0xf8f8f2),
+            field: hsla_from_hex(0x66d9ef),
+            module: hsla_from_hex(0x8be9fd),
+            structure: hsla_from_hex(0x8be9fd),
+            class: hsla_from_hex(0x8be9fd),
+            enumeration: hsla_from_hex(0x8be9fd),
+            constant: hsla_from_hex(0xbd93f9),
+            type_name: hsla_from_hex(0x8be9fd),
+            keyword: hsla_from_hex(0xff79c6),
+            snippet: hsla_from_hex(0x50fa7b),
+        },
+        completion_glyphs: CompletionKindGlyphs::default(),
     }
 }
 
@@ -521,6 +604,21 @@ pub fn nord() -> IdeTheme {
             review_comment_bg: hsla_from_hex(0x3b3a40),
             review_comment_indicator: hsla_from_hex(0xebcb8b),
         },
+        completion: CompletionKindColors {
+            function: hsla_from_hex(0x88c0d0),
+            method: hsla_from_hex(0x88c0d0),
+            variable: hsla_from_hex(0xd8dee9),
+            field: hsla_from_hex(0x88c0d0),
+            module: hsla_from_hex(0x8fbcbb),
+            structure: hsla_from_hex(0x8fbcbb),
+            class: hsla_from_hex(0x8fbcbb),
+            enumeration: hsla_from_hex(0x8fbcbb),
+            constant: hsla_from_hex(0xb48ead),
+            type_name: hsla_from_hex(0x8fbcbb),
+            keyword: hsla_from_hex(0x81a1c1),
+            snippet: hsla_from_hex(0x5e81ac),
+        },
+        completion_glyphs: CompletionKindGlyphs::default(),
     }
 }
 
@@ -627,6 +725,21 @@ pub fn monokai_vivid() -> IdeTheme {
             review_comment_bg: hsla_from_hex(0x33301e),
             review_comment_indicator: hsla_from_hex(0xe6db74),
         },
+        completion: CompletionKindColors {
+            function: hsla_from_hex(0xa6e22e),
+            method: hsla_from_hex(0xa6e22e),
+            variable: hsla_from_hex(0xf8f8f0),
+            field: hsla_from_hex(0x66d9ef),
+            module: hsla_from_hex(0x66d9ef),
+            structure: hsla_from_hex(0x66d9ef),
+            class: hsla_from_hex(0x66d9ef),
+            enumeration: hsla_from_hex(0x66d9ef),
+            constant: hsla_from_hex(0xae81ff),
+            type_name: hsla_from_hex(0x66d9ef),
+            keyword: hsla_from_hex(0xf92672),
+            snippet: hsla_from_hex(0xa6e22e),
+        },
+        completion_glyphs: CompletionKindGlyphs::default(),
     }
 }
 
@@ -733,6 +846,21 @@ pub fn github_dark() -> IdeTheme {
             review_comment_bg: hsla_from_hex(0x1c1e24),
             review_comment_indicator: hsla_from_hex(0xe3b341),
         },
+        completion: CompletionKindColors {
+            function: hsla_from_hex(0xd2a8ff),
+            method: hsla_from_hex(0xd2a8ff),
+            variable: hsla_from_hex(0xffa657),
+            field: hsla_from_hex(0x7ee787),
+            module: hsla_from_hex(0xffa657),
+            structure: hsla_from_hex(0x79c0ff),
+            class: hsla_from_hex(0x79c0ff),
+            enumeration: hsla_from_hex(0x79c0ff),
+            constant: hsla_from_hex(0x79c0ff),
+            type_name: hsla_from_hex(0x79c0ff),
+            keyword: hsla_from_hex(0xff7b72),
+            snippet: hsla_from_hex(0xd2a8ff),
+        },
+        completion_glyphs: CompletionKindGlyphs::default(),
     }
 }
 
@@ -839,6 +967,21 @@ pub fn cyberpunk() -> IdeTheme {
             review_comment_bg: hsla_from_hex(0x0f1a2e),
             review_comment_indicator: hsla_from_hex(0xfcee0a),
         },
+        completion: CompletionKindColors {
+            function: hsla_from_hex(0xff2e97),
+            method: hsla_from_hex(0xff2e97),
+            variable: hsla_from_hex(0x8badc9),
+            field: hsla_from_hex(0x00d4ff),
+            module: hsla_from_hex(0x00d4ff),
+            structure: hsla_from_hex(0x00d4ff),
+            class: hsla_from_hex(0x00d4ff),
+            enumeration: hsla_from_hex(0x00d4ff),
+            constant: hsla_from_hex(0xfcee0a),
+            type_name: hsla_from_hex(0x00d4ff),
+            keyword: hsla_from_hex(0xfcee0a),
+            snippet: hsla_from_hex(0xd557ff),
+        },
+        completion_glyphs: CompletionKindGlyphs::default(),
     }
 }
 
@@ -945,6 +1088,21 @@ pub fn github_light() -> IdeTheme {
             review_comment_bg: hsla_from_hex(0xfff8c5),
             review_comment_indicator: hsla_from_hex(0x9a6700),
         },
+        completion: CompletionKindColors {
+            function: hsla_from_hex(0x8250df),
+            method: hsla_from_hex(0x8250df),
+            variable: hsla_from_hex(0x24292f),
+            field: hsla_from_hex(0x0550ae),
+            module: hsla_from_hex(0xcf222e),
+            structure: hsla_from_hex(0x0550ae),
+            class: hsla_from_hex(0x8250df),
+            enumeration: hsla_from_hex(0x0550ae),
+            constant: hsla_from_hex(0x0550ae),
+            type_name: hsla_from_hex(0x0550ae),
+            keyword: hsla_from_hex(0xcf222e),
+            snippet: hsla_from_hex(0x8250df),
+        },
+        completion_glyphs: CompletionKindGlyphs::default(),
     }
 }
 
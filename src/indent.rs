@@ -0,0 +1,282 @@
+use crate::settings::IndentConfig;
+
+pub fn indent_unit(config: IndentConfig) -> String {
+    if config.hard_tabs {
+        "\t".into()
+    } else {
+        " ".repeat(config.tab_size.max(1))
+    }
+}
+
+pub fn newline_with_indent(before: &str, after: &str, unit: &str) -> (String, usize) {
+    let base = &before[..before.len() - before.trim_start().len()];
+    let opener = before.trim_end().as_bytes().last().copied();
+    let increase = matches!(opener, Some(b'{' | b'(' | b'[' | b':'));
+    let closer = after.trim_start().as_bytes().first().copied();
+    let between_pair = matches!(
+        (opener, closer),
+        (Some(b'{'), Some(b'}')) | (Some(b'('), Some(b')')) | (Some(b'['), Some(b']'))
+    );
+
+    let mut text = format!("\n{}", base);
+    if increase {
+        text.push_str(unit);
+    }
+    let caret = text.len();
+    if between_pair {
+        text.push('\n');
+        text.push_str(base);
+    }
+    (text, caret)
+}
+
+pub fn indent_lines(text: &str, unit: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", unit, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn outdent_lines(text: &str, tab_size: usize) -> String {
+    let tab_size = tab_size.max(1);
+    text.split('\n')
+        .map(|line| match line.strip_prefix('\t') {
+            Some(rest) => rest,
+            None => {
+                let spaces = line.len() - line.trim_start_matches(' ').len();
+                &line[spaces.min(tab_size)..]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn outdent_for_closer(before: &str, unit: &str, tab_size: usize) -> Option<String> {
+    if before.is_empty() || !before.trim().is_empty() {
+        return None;
+    }
+    let unit_width = if unit == "\t" {
+        tab_size.max(1)
+    } else {
+        unit.len().max(1)
+    };
+    let width = indent_width(before, tab_size).saturating_sub(unit_width);
+    Some(render_indent(width, unit, tab_size))
+}
+
+fn is_suspicious(c: char) -> bool {
+    (c.is_control() && !matches!(c, '\n' | '\t' | '\r'))
+        || matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+pub fn strip_control_chars(text: &str) -> (String, usize) {
+    let removed = text.chars().filter(|&c| is_suspicious(c)).count();
+    if removed == 0 {
+        return (text.to_string(), 0);
+    }
+    (
+        text.chars().filter(|&c| !is_suspicious(c)).collect(),
+        removed,
+    )
+}
+
+fn indent_width(line: &str, tab_size: usize) -> usize {
+    let tab_size = tab_size.max(1);
+    line.chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .fold(0, |col, c| {
+            if c == '\t' {
+                col + tab_size - col % tab_size
+            } else {
+                col + 1
+            }
+        })
+}
+
+fn render_indent(width: usize, unit: &str, tab_size: usize) -> String {
+    let unit_width = if unit == "\t" {
+        tab_size.max(1)
+    } else {
+        unit.len().max(1)
+    };
+    format!(
+        "{}{}",
+        unit.repeat(width / unit_width),
+        " ".repeat(width % unit_width)
+    )
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WhitespaceIssues {
+    pub mixed_indent: bool,
+    pub mixed_eol: bool,
+}
+
+impl WhitespaceIssues {
+    pub fn any(&self) -> bool {
+        self.mixed_indent || self.mixed_eol
+    }
+}
+
+pub fn detect_whitespace_issues(text: &str) -> WhitespaceIssues {
+    let (mut tabs, mut spaces, mut crlf, mut lf) = (0, 0, 0, 0);
+    for line in text.split_inclusive('\n') {
+        if line.ends_with("\r\n") {
+            crlf += 1;
+        } else if line.ends_with('\n') {
+            lf += 1;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('\t') {
+            tabs += 1;
+        } else if line.starts_with("  ") {
+            spaces += 1;
+        }
+    }
+    WhitespaceIssues {
+        mixed_indent: tabs > 0 && spaces > 0,
+        mixed_eol: crlf > 0 && lf > 0,
+    }
+}
+
+pub fn normalize_whitespace(text: &str, unit: &str, tab_size: usize) -> String {
+    let crlf = text.matches("\r\n").count();
+    let eol = if crlf * 2 > text.matches('\n').count() {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let (body, has_eol) = match line.strip_suffix('\n') {
+            Some(body) => (body.strip_suffix('\r').unwrap_or(body), true),
+            None => (line, false),
+        };
+        let content = body.trim_start_matches([' ', '\t']);
+        if !content.is_empty() {
+            out.push_str(&render_indent(indent_width(body, tab_size), unit, tab_size));
+        }
+        out.push_str(content);
+        if has_eol {
+            out.push_str(eol);
+        }
+    }
+    out
+}
+
+pub fn reindent_paste(text: &str, before: &str, unit: &str, tab_size: usize) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    if lines.len() < 2 {
+        return text.to_string();
+    }
+    let base = &before[..before.len() - before.trim_start().len()];
+    let at_line_start = before.trim().is_empty();
+    let first = usize::from(!at_line_start);
+    let min = lines[first..]
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| indent_width(l, tab_size))
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let body = line.trim_start_matches([' ', '\t']);
+            if i < first {
+                return line.to_string();
+            }
+            if body.is_empty() {
+                return String::new();
+            }
+            let relative = indent_width(line, tab_size) - min;
+            let prefix = if i == 0 { "" } else { base };
+            format!(
+                "{}{}{}",
+                prefix,
+                render_indent(relative, unit, tab_size),
+                body
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        detect_whitespace_issues, indent_lines, newline_with_indent, normalize_whitespace,
+        outdent_for_closer, outdent_lines, reindent_paste, strip_control_chars, WhitespaceIssues,
+    };
+
+    #[test]
+    fn test_newline_keeps_tabs_and_indents_blocks() {
+        assert_eq!(
+            newline_with_indent("\tx := 1", "", "\t"),
+            ("\n\t".into(), 2)
+        );
+        assert_eq!(
+            newline_with_indent("\tif x {", "}", "\t"),
+            ("\n\t\t\n\t".into(), 3)
+        );
+        assert_eq!(
+            newline_with_indent("  foo(", "", "  "),
+            ("\n    ".into(), 5)
+        );
+    }
+
+    #[test]
+    fn test_indent_and_outdent_lines() {
+        assert_eq!(indent_lines("a\n\n  b", "\t"), "\ta\n\n\t  b");
+        assert_eq!(outdent_lines("\ta\n      b\n  c\nd", 4), "a\n  b\nc\nd");
+        assert_eq!(
+            outdent_for_closer("        ", "    ", 4),
+            Some("    ".into())
+        );
+        assert_eq!(outdent_for_closer("\t\t", "\t", 4), Some("\t".into()));
+        assert_eq!(outdent_for_closer("  ", "    ", 4), Some("".into()));
+        assert_eq!(outdent_for_closer("  x", "  ", 4), None);
+    }
+
+    #[test]
+    fn test_reindent_paste_rebases_block_on_cursor_indent() {
+        let pasted = "\tif x {\n\t\ty();\n\n\t}";
+        assert_eq!(
+            reindent_paste(pasted, "    ", "    ", 4),
+            "if x {\n        y();\n\n    }"
+        );
+        assert_eq!(
+            reindent_paste("foo(\n      a,\n    )", "  let v = ", "  ", 4),
+            "foo(\n    a,\n  )"
+        );
+        assert_eq!(
+            strip_control_chars("ok\u{1b}[31m\u{202e}\tend\n"),
+            ("ok[31m\tend\n".into(), 2)
+        );
+    }
+
+    #[test]
+    fn test_detects_and_normalizes_mixed_whitespace() {
+        let text = "fn a() {\r\n\tx();\n    y();\r\n}\r\n";
+        assert_eq!(
+            detect_whitespace_issues(text),
+            WhitespaceIssues {
+                mixed_indent: true,
+                mixed_eol: true,
+            }
+        );
+        assert_eq!(
+            normalize_whitespace(text, "  ", 4),
+            "fn a() {\r\n    x();\r\n    y();\r\n}\r\n"
+        );
+        assert!(!detect_whitespace_issues(" * doc\n\tbody\n").any());
+    }
+}
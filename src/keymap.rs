@@ -0,0 +1,166 @@
+use gpui::{App, KeyBinding, KeyBindingContextPredicate, Keystroke};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const APP_CONTEXT: &str = "ShioriApp";
+
+pub const COMMAND_ACTIONS: &[(&str, &str)] = &[
+    ("show-hover", "ShowHover"),
+    ("next-problem", "GoToNextProblem"),
+    ("previous-problem", "GoToPreviousProblem"),
+    ("format-document", "FormatDocument"),
+    ("rewrap-comment", "RewrapComment"),
+    ("copy-with-highlighting", "CopyWithHighlighting"),
+    ("insert-snippet", "InsertSnippet"),
+    ("expand-selection", "ExpandSelection"),
+    ("shrink-selection", "ShrinkSelection"),
+    ("trigger-completion", "TriggerCompletion"),
+    ("goto-definition", "GotoDefinition"),
+    ("fold-all", "FoldAll"),
+    ("unfold-all", "UnfoldAll"),
+    ("toggle-fold", "FoldToggle"),
+    ("new-file", "NewFile"),
+    ("open-file", "OpenFile"),
+    ("open-folder", "OpenFolder"),
+    ("switch-workspace", "SwitchWorkspace"),
+    ("save-file", "SaveFile"),
+    ("close-tab", "CloseTab"),
+    ("goto-line", "GotoLine"),
+    ("next-tab", "NextTab"),
+    ("prev-tab", "PrevTab"),
+    ("symbol-outline", "ToggleSymbolOutline"),
+    ("toggle-sidebar", "ToggleSidebar"),
+    ("toggle-terminal", "ToggleTerminal"),
+    ("toggle-terminal-fullscreen", "ToggleTerminalFullscreen"),
+    ("new-terminal", "NewTerminal"),
+    ("close-terminal", "CloseTerminal"),
+    ("toggle-search", "ToggleSearch"),
+    ("search-replace", "ToggleSearchReplace"),
+    ("select-all-matches", "search_bar::SelectAllMatches"),
+    ("find-next", "search_bar::FindNext"),
+    ("find-previous", "search_bar::FindPrevious"),
+    ("toggle-git", "ToggleGitView"),
+    ("git-next-file", "GitNextFile"),
+    ("git-prev-file", "GitPrevFile"),
+    ("zoom-in", "ZoomIn"),
+    ("zoom-out", "ZoomOut"),
+    ("zoom-reset", "ZoomReset"),
+    ("terminal-zoom-in", "TerminalZoomIn"),
+    ("terminal-zoom-out", "TerminalZoomOut"),
+    ("terminal-zoom-reset", "TerminalZoomReset"),
+];
+
+fn qualified_action_name(action: &str) -> String {
+    if action.contains("::") {
+        action.to_string()
+    } else {
+        format!("shiori::{}", action)
+    }
+}
+
+pub fn user_key_bindings(
+    bindings: &HashMap<String, String>,
+    cx: &App,
+) -> (Vec<KeyBinding>, Vec<String>) {
+    let context = KeyBindingContextPredicate::parse(APP_CONTEXT)
+        .ok()
+        .map(Rc::new);
+    let mut loaded = Vec::new();
+    let mut errors = Vec::new();
+    let mut entries: Vec<(&String, &String)> = bindings.iter().collect();
+    entries.sort();
+    for (action, keys) in entries {
+        let built = match cx.build_action(&qualified_action_name(action), None) {
+            Ok(built) => built,
+            Err(_) => {
+                errors.push(format!("Unknown action in keybindings: {}", action));
+                continue;
+            }
+        };
+        match KeyBinding::load(
+            keys,
+            built,
+            context.clone(),
+            false,
+            None,
+            cx.keyboard_mapper().as_ref(),
+        ) {
+            Ok(binding) => loaded.push(binding),
+            Err(err) => errors.push(format!("Invalid keybinding for {}: {}", action, err)),
+        }
+    }
+    (loaded, errors)
+}
+
+pub fn shortcut_for_action(action: &str, cx: &App) -> Option<String> {
+    let action = cx.build_action(&qualified_action_name(action), None).ok()?;
+    let keymap = cx.key_bindings();
+    let keymap = keymap.borrow();
+    let binding = keymap.bindings_for_action(action.as_ref()).last()?;
+    Some(
+        binding
+            .keystrokes()
+            .iter()
+            .map(|k| format_keystroke(k.inner()))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+pub fn format_keystroke(keystroke: &Keystroke) -> String {
+    let modifiers = &keystroke.modifiers;
+    let mut out = String::new();
+    for (held, symbol) in [
+        (modifiers.control, "⌃"),
+        (modifiers.alt, "⌥"),
+        (modifiers.shift, "⇧"),
+        (modifiers.platform, "⌘"),
+    ] {
+        if held {
+            out.push_str(symbol);
+        }
+    }
+    let key = match keystroke.key.as_str() {
+        "up" => "↑".to_string(),
+        "down" => "↓".to_string(),
+        "left" => "←".to_string(),
+        "right" => "→".to_string(),
+        "backspace" => "⌫".to_string(),
+        "escape" => "Esc".to_string(),
+        "-" => "−".to_string(),
+        key if key.chars().count() == 1 => key.to_uppercase(),
+        key if key.starts_with('f') && key[1..].parse::<u8>().is_ok() => key.to_uppercase(),
+        key => {
+            let mut chars = key.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    };
+    out.push_str(&key);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_keystroke;
+    use gpui::Keystroke;
+
+    fn format(keys: &str) -> String {
+        keys.split_whitespace()
+            .map(|k| format_keystroke(&Keystroke::parse(k).unwrap()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_format_keystroke_chords_and_named_keys() {
+        assert_eq!(format("cmd-k cmd-0"), "⌘K ⌘0");
+        assert_eq!(format("shift-alt-f"), "⌥⇧F");
+        assert_eq!(format("ctrl-shift-tab"), "⌃⇧Tab");
+        assert_eq!(format("shift-f8"), "⇧F8");
+        assert_eq!(format("alt-up"), "⌥↑");
+        assert_eq!(format("cmd--"), "⌘−");
+    }
+}
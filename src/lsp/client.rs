@@ -1,53 +1,97 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde_json::{json, Value};
 
 use super::config::ServerConfig;
-use super::transport::{LspTransport, TransportError};
+use super::transport::{parse_diagnostic_items, LspTransport, TransportError};
 use super::types::{
-    FileDiagnostics, HoverInfo, LocationInfo, LspCompletionItem, LspCompletionKind,
+    Diagnostic, FileDiagnostics, HoverInfo, LocationInfo, LspCompletionItem, LspCompletionKind,
+    LspCompletionList, ProgressEvent, TextEdit, WorkProgress,
 };
 
 pub struct LspClient {
     transport: LspTransport,
     root_uri: String,
+    workspace_folders: Vec<Value>,
     server_capabilities: Option<Value>,
+    trigger_characters: Vec<String>,
+    pull_diagnostics: bool,
+    server_name: String,
+    progress: Vec<(String, WorkProgress)>,
+}
+
+fn workspace_folder(path: &Path) -> Value {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    json!({ "uri": path_to_uri(path), "name": name })
 }
 
 impl LspClient {
-    pub fn start(config: &ServerConfig, root_path: &Path) -> Result<Self, TransportError> {
+    pub fn start(config: &ServerConfig, roots: &[PathBuf]) -> Result<Self, TransportError> {
+        let root_path = roots.first().ok_or(TransportError::ServerExited)?;
         let transport = LspTransport::spawn(config)?;
-        let root_uri = url::Url::from_file_path(root_path)
-            .map(|u| u.to_string())
-            .unwrap_or_else(|_| format!("file://{}", root_path.display()));
+        let root_uri = path_to_uri(root_path);
+        let server_name = Path::new(&config.command)
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| config.command.clone());
 
         Ok(Self {
             transport,
             root_uri,
+            workspace_folders: roots.iter().map(|r| workspace_folder(r)).collect(),
             server_capabilities: None,
+            trigger_characters: Vec::new(),
+            pull_diagnostics: false,
+            server_name,
+            progress: Vec::new(),
         })
     }
 
+    pub fn did_change_workspace_folders(
+        &self,
+        added: &[PathBuf],
+        removed: &[PathBuf],
+    ) -> Result<(), TransportError> {
+        let params = json!({
+            "event": {
+                "added": added.iter().map(|p| workspace_folder(p)).collect::<Vec<_>>(),
+                "removed": removed.iter().map(|p| workspace_folder(p)).collect::<Vec<_>>(),
+            }
+        });
+        self.transport
+            .send_notification("workspace/didChangeWorkspaceFolders", params)
+    }
+
     pub fn initialize(&mut self) -> Result<(), TransportError> {
         let params = json!({
             "processId": std::process::id(),
             "rootUri": self.root_uri,
+            "workspaceFolders": self.workspace_folders,
             "capabilities": {
                 "textDocument": {
                     "completion": {
                         "completionItem": {
                             "snippetSupport": false,
                             "labelDetailsSupport": true,
+                            "documentationFormat": ["markdown", "plaintext"],
                         },
                         "contextSupport": true,
                     },
                     "hover": {
-                        "contentFormat": ["plaintext", "markdown"],
+                        "contentFormat": ["markdown", "plaintext"],
                     },
                     "publishDiagnostics": {
                         "relatedInformation": false,
                     },
+                    "diagnostic": {
+                        "dynamicRegistration": false,
+                        "relatedDocumentSupport": false,
+                    },
                     "definition": {},
+                    "formatting": {},
                     "synchronization": {
                         "didSave": true,
                         "willSave": false,
@@ -55,7 +99,10 @@ impl LspClient {
                     },
                 },
                 "workspace": {
-                    "workspaceFolders": false,
+                    "workspaceFolders": true,
+                },
+                "window": {
+                    "workDoneProgress": true,
                 },
             },
             "initializationOptions": Value::Null,
@@ -65,6 +112,25 @@ impl LspClient {
         match rx.recv_timeout(std::time::Duration::from_secs(30)) {
             Ok(response) => {
                 self.server_capabilities = response.get("result").cloned();
+                self.trigger_characters = self
+                    .server_capabilities
+                    .as_ref()
+                    .and_then(|r| r.pointer("/capabilities/completionProvider/triggerCharacters"))
+                    .and_then(|v| v.as_array())
+                    .map(|chars| {
+                        chars
+                            .iter()
+                            .filter_map(|c| c.as_str())
+                            .filter(|c| !c.is_empty())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                self.pull_diagnostics = self
+                    .server_capabilities
+                    .as_ref()
+                    .and_then(|r| r.pointer("/capabilities/diagnosticProvider"))
+                    .is_some_and(|v| !v.is_null() && v != &Value::Bool(false));
                 self.transport.send_notification("initialized", json!({}))?;
                 Ok(())
             }
@@ -132,18 +198,28 @@ impl LspClient {
         )
     }
 
+    pub fn trigger_characters(&self) -> &[String] {
+        &self.trigger_characters
+    }
+
     pub fn completion(
         &self,
         path: &Path,
         line: u32,
         col: u32,
-    ) -> Result<flume::Receiver<Value>, TransportError> {
+        trigger: Option<&str>,
+    ) -> Result<(i64, flume::Receiver<Value>), TransportError> {
         let uri = path_to_uri(path);
-        self.transport.send_request(
+        let context = match trigger {
+            Some(ch) => json!({ "triggerKind": 2, "triggerCharacter": ch }),
+            None => json!({ "triggerKind": 1 }),
+        };
+        self.transport.send_tracked_request(
             "textDocument/completion",
             json!({
                 "textDocument": { "uri": uri },
                 "position": { "line": line, "character": col },
+                "context": context,
             }),
         )
     }
@@ -153,9 +229,9 @@ impl LspClient {
         path: &Path,
         line: u32,
         col: u32,
-    ) -> Result<flume::Receiver<Value>, TransportError> {
+    ) -> Result<(i64, flume::Receiver<Value>), TransportError> {
         let uri = path_to_uri(path);
-        self.transport.send_request(
+        self.transport.send_tracked_request(
             "textDocument/hover",
             json!({
                 "textDocument": { "uri": uri },
@@ -180,6 +256,81 @@ impl LspClient {
         )
     }
 
+    pub fn supports_formatting(&self) -> bool {
+        self.server_capabilities
+            .as_ref()
+            .and_then(|r| r.pointer("/capabilities/documentFormattingProvider"))
+            .is_some_and(|v| !v.is_null() && v != &Value::Bool(false))
+    }
+
+    pub fn formatting(
+        &self,
+        path: &Path,
+        tab_size: usize,
+        insert_spaces: bool,
+    ) -> Result<flume::Receiver<Value>, TransportError> {
+        let uri = path_to_uri(path);
+        self.transport.send_request(
+            "textDocument/formatting",
+            json!({
+                "textDocument": { "uri": uri },
+                "options": { "tabSize": tab_size, "insertSpaces": insert_spaces },
+            }),
+        )
+    }
+
+    pub fn parse_text_edits(response: &Value) -> Option<Vec<TextEdit>> {
+        let result = response.get("result")?;
+        if result.is_null() {
+            return Some(Vec::new());
+        }
+        result
+            .as_array()?
+            .iter()
+            .map(|edit| {
+                let range = edit.get("range")?;
+                let pos =
+                    |key: &str, field: &str| range.get(key)?.get(field)?.as_u64().map(|v| v as u32);
+                Some(TextEdit {
+                    start_line: pos("start", "line")?,
+                    start_col: pos("start", "character")?,
+                    end_line: pos("end", "line")?,
+                    end_col: pos("end", "character")?,
+                    new_text: edit.get("newText")?.as_str()?.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn supports_pull_diagnostics(&self) -> bool {
+        self.pull_diagnostics
+    }
+
+    pub fn document_diagnostics(
+        &self,
+        path: &Path,
+    ) -> Result<flume::Receiver<Value>, TransportError> {
+        let uri = path_to_uri(path);
+        self.transport.send_request(
+            "textDocument/diagnostic",
+            json!({
+                "textDocument": { "uri": uri },
+            }),
+        )
+    }
+
+    pub fn parse_document_diagnostics(response: &Value) -> Option<Vec<Diagnostic>> {
+        let result = response.get("result")?;
+        if result.get("kind")?.as_str()? != "full" {
+            return None;
+        }
+        Some(parse_diagnostic_items(result.get("items")?.as_array()?))
+    }
+
+    pub fn cancel_request(&self, id: i64) {
+        self.transport.cancel_request(id);
+    }
+
     pub fn shutdown(&mut self) -> Result<(), TransportError> {
         let rx = self.transport.send_request("shutdown", Value::Null)?;
         let _ = rx.recv_timeout(std::time::Duration::from_secs(5));
@@ -192,14 +343,65 @@ impl LspClient {
         self.transport.stop();
     }
 
+    pub fn has_exited(&self) -> bool {
+        self.transport.has_exited()
+    }
+
     pub fn diagnostics_rx(&self) -> &flume::Receiver<FileDiagnostics> {
         self.transport.diagnostics_rx()
     }
 
-    pub fn parse_completion_response(response: &Value) -> Vec<LspCompletionItem> {
+    pub fn poll_progress(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.transport.progress_rx().try_recv() {
+            changed = true;
+            match event {
+                ProgressEvent::Begin {
+                    token,
+                    title,
+                    message,
+                    percentage,
+                } => {
+                    self.progress.retain(|(t, _)| *t != token);
+                    self.progress.push((
+                        token,
+                        WorkProgress {
+                            title,
+                            message,
+                            percentage,
+                        },
+                    ));
+                }
+                ProgressEvent::Report {
+                    token,
+                    message,
+                    percentage,
+                } => {
+                    if let Some((_, progress)) = self.progress.iter_mut().find(|(t, _)| *t == token)
+                    {
+                        if message.is_some() {
+                            progress.message = message;
+                        }
+                        if percentage.is_some() {
+                            progress.percentage = percentage;
+                        }
+                    }
+                }
+                ProgressEvent::End { token } => self.progress.retain(|(t, _)| *t != token),
+            }
+        }
+        changed
+    }
+
+    pub fn progress_label(&self) -> Option<String> {
+        let (_, progress) = self.progress.last()?;
+        Some(format!("{}: {}", self.server_name, progress.label()))
+    }
+
+    pub fn parse_completion_response(response: &Value) -> LspCompletionList {
         let result = match response.get("result") {
             Some(r) => r,
-            None => return Vec::new(),
+            None => return LspCompletionList::default(),
         };
 
         let items = if let Some(arr) = result.as_array() {
@@ -207,10 +409,14 @@ impl LspClient {
         } else if let Some(arr) = result.get("items").and_then(|i| i.as_array()) {
             arr
         } else {
-            return Vec::new();
+            return LspCompletionList::default();
         };
+        let is_incomplete = result
+            .get("isIncomplete")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
-        items
+        let items = items
             .iter()
             .filter_map(|item| {
                 let label = item.get("label")?.as_str()?.to_string();
@@ -219,6 +425,12 @@ impl LspClient {
                     .and_then(|d| d.as_str())
                     .map(String::from);
 
+                let documentation = item
+                    .get("documentation")
+                    .and_then(|d| d.as_str().or_else(|| d.get("value")?.as_str()))
+                    .filter(|d| !d.is_empty())
+                    .map(String::from);
+
                 let insert_text = item
                     .get("insertText")
                     .and_then(|t| t.as_str())
@@ -240,11 +452,16 @@ impl LspClient {
                 Some(LspCompletionItem {
                     label,
                     detail,
+                    documentation,
                     insert_text,
                     kind,
                 })
             })
-            .collect()
+            .collect();
+        LspCompletionList {
+            items,
+            is_incomplete,
+        }
     }
 
     pub fn parse_hover_response(response: &Value) -> Option<HoverInfo> {
@@ -261,9 +478,13 @@ impl LspClient {
         } else if let Some(arr) = contents.as_array() {
             arr.iter()
                 .filter_map(|item| {
-                    item.as_str()
-                        .map(String::from)
-                        .or_else(|| item.get("value").and_then(|v| v.as_str()).map(String::from))
+                    item.as_str().map(String::from).or_else(|| {
+                        let value = item.get("value")?.as_str()?;
+                        Some(match item.get("language").and_then(|l| l.as_str()) {
+                            Some(lang) => format!("```{}\n{}\n```", lang, value),
+                            None => value.to_string(),
+                        })
+                    })
                 })
                 .collect::<Vec<_>>()
                 .join("\n\n")
@@ -17,7 +17,7 @@ struct PendingOpen {
 
 pub struct LspRegistry {
     clients: HashMap<Language, LspClient>,
-    root_path: Option<PathBuf>,
+    roots: Vec<PathBuf>,
     failed_languages: HashMap<Language, std::time::Instant>,
     pending_starts: HashMap<Language, ()>,
     ready_rx: flume::Receiver<(Language, Result<LspClient, String>)>,
@@ -32,7 +32,7 @@ impl LspRegistry {
         let (ready_tx, ready_rx) = flume::unbounded();
         Self {
             clients: HashMap::new(),
-            root_path: None,
+            roots: Vec::new(),
             failed_languages: HashMap::new(),
             pending_starts: HashMap::new(),
             ready_rx,
@@ -41,11 +41,29 @@ impl LspRegistry {
         }
     }
 
-    pub fn set_root(&mut self, path: PathBuf) {
-        self.root_path = Some(path);
+    pub fn set_roots(&mut self, roots: Vec<PathBuf>) {
+        let added: Vec<PathBuf> = roots
+            .iter()
+            .filter(|r| !self.roots.contains(r))
+            .cloned()
+            .collect();
+        let removed: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .filter(|r| !roots.contains(r))
+            .cloned()
+            .collect();
+        self.roots = roots;
+        if added.is_empty() && removed.is_empty() {
+            return;
+        }
+        for client in self.clients.values() {
+            let _ = client.did_change_workspace_folders(&added, &removed);
+        }
     }
 
-    pub fn poll_ready(&mut self) {
+    pub fn poll_ready(&mut self) -> Vec<(Language, String)> {
+        let mut failures = Vec::new();
         while let Ok((language, result)) = self.ready_rx.try_recv() {
             self.pending_starts.remove(&language);
             match result {
@@ -59,13 +77,32 @@ impl LspRegistry {
                         }
                     }
                 }
-                Err(_) => {
+                Err(err) => {
                     self.failed_languages
                         .insert(language, std::time::Instant::now());
                     self.queued_opens.remove(&language);
+                    failures.push((language, err));
                 }
             }
         }
+        failures
+    }
+
+    pub fn reap_exited(&mut self) -> Vec<Language> {
+        let exited: Vec<Language> = self
+            .clients
+            .iter()
+            .filter(|(_, client)| client.has_exited())
+            .map(|(language, _)| *language)
+            .collect();
+        for language in &exited {
+            if let Some(mut client) = self.clients.remove(language) {
+                client.stop();
+            }
+            self.failed_languages
+                .insert(*language, std::time::Instant::now());
+        }
+        exited
     }
 
     pub fn ensure_client_for(&mut self, language: Language, settings: &ShioriSettings) {
@@ -80,10 +117,10 @@ impl LspRegistry {
             self.failed_languages.remove(&language);
         }
 
-        let root = match self.root_path.clone() {
-            Some(r) => r,
-            None => return,
-        };
+        if self.roots.is_empty() {
+            return;
+        }
+        let roots = self.roots.clone();
         let config = match self.resolve_config(language, settings) {
             Some(c) => c,
             None => return,
@@ -92,7 +129,7 @@ impl LspRegistry {
 
         let tx = self.ready_tx.clone();
         thread::spawn(move || {
-            let result = match LspClient::start(&config, &root) {
+            let result = match LspClient::start(&config, &roots) {
                 Ok(mut client) => match client.initialize() {
                     Ok(()) => Ok(client),
                     Err(e) => {
@@ -120,6 +157,20 @@ impl LspRegistry {
         all
     }
 
+    pub fn poll_progress(&mut self) -> bool {
+        let mut changed = false;
+        for client in self.clients.values_mut() {
+            changed |= client.poll_progress();
+        }
+        changed
+    }
+
+    pub fn progress_label(&self) -> Option<String> {
+        self.clients
+            .values()
+            .find_map(|client| client.progress_label())
+    }
+
     pub fn notify_did_open(
         &mut self,
         language: Language,
@@ -7,7 +7,7 @@ use std::thread;
 use serde_json::Value;
 
 use super::config::ServerConfig;
-use super::types::FileDiagnostics;
+use super::types::{FileDiagnostics, ProgressEvent};
 
 #[derive(Debug)]
 pub enum TransportError {
@@ -34,6 +34,7 @@ pub struct LspTransport {
     reader_thread: Option<thread::JoinHandle<()>>,
     _response_rx: flume::Receiver<Value>,
     diagnostics_rx: flume::Receiver<FileDiagnostics>,
+    progress_rx: flume::Receiver<ProgressEvent>,
     pending_requests: Arc<Mutex<HashMap<i64, flume::Sender<Value>>>>,
     next_id: Arc<Mutex<i64>>,
     is_running: Arc<Mutex<bool>>,
@@ -74,9 +75,11 @@ impl LspTransport {
 
         let (response_tx, response_rx) = flume::unbounded();
         let (diagnostics_tx, diagnostics_rx) = flume::unbounded();
+        let (progress_tx, progress_rx) = flume::unbounded();
 
         let pending_clone = pending_requests.clone();
         let running_clone = is_running.clone();
+        let writer_clone = writer.clone();
 
         let reader_thread = thread::spawn(move || {
             let mut reader = BufReader::new(stdout);
@@ -110,6 +113,23 @@ impl LspTransport {
                                     }
                                 }
                             }
+                            if method == "$/progress" {
+                                if let Some(event) = msg.get("params").and_then(parse_progress) {
+                                    let _ = progress_tx.send(event);
+                                }
+                                continue;
+                            }
+                            if method == "window/workDoneProgress/create" {
+                                if let Some(id) = msg.get("id") {
+                                    let reply = serde_json::json!({
+                                        "jsonrpc": "2.0",
+                                        "id": id,
+                                        "result": Value::Null,
+                                    });
+                                    let _ = write_message_to(&writer_clone, &reply);
+                                }
+                                continue;
+                            }
                         }
 
                         let _ = response_tx.send(msg);
@@ -125,6 +145,7 @@ impl LspTransport {
             reader_thread: Some(reader_thread),
             _response_rx: response_rx,
             diagnostics_rx,
+            progress_rx,
             pending_requests,
             next_id: Arc::new(Mutex::new(1)),
             is_running,
@@ -136,6 +157,14 @@ impl LspTransport {
         method: &str,
         params: Value,
     ) -> Result<flume::Receiver<Value>, TransportError> {
+        self.send_tracked_request(method, params).map(|(_, rx)| rx)
+    }
+
+    pub fn send_tracked_request(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<(i64, flume::Receiver<Value>), TransportError> {
         let id = {
             let mut next = self.next_id.lock().unwrap();
             let id = *next;
@@ -154,7 +183,13 @@ impl LspTransport {
         });
 
         self.write_message(&msg)?;
-        Ok(rx)
+        Ok((id, rx))
+    }
+
+    pub fn cancel_request(&self, id: i64) {
+        if self.pending_requests.lock().unwrap().remove(&id).is_some() {
+            let _ = self.send_notification("$/cancelRequest", serde_json::json!({ "id": id }));
+        }
     }
 
     pub fn send_notification(&self, method: &str, params: Value) -> Result<(), TransportError> {
@@ -167,25 +202,24 @@ impl LspTransport {
     }
 
     fn write_message(&self, msg: &Value) -> Result<(), TransportError> {
-        let body =
-            serde_json::to_string(msg).map_err(|e| TransportError::ParseError(e.to_string()))?;
-        let header = format!("Content-Length: {}\r\n\r\n", body.len());
-
-        let mut writer = self.writer.lock().unwrap();
-        writer
-            .write_all(header.as_bytes())
-            .map_err(TransportError::WriteFailed)?;
-        writer
-            .write_all(body.as_bytes())
-            .map_err(TransportError::WriteFailed)?;
-        writer.flush().map_err(TransportError::WriteFailed)?;
-        Ok(())
+        write_message_to(&self.writer, msg)
     }
 
     pub fn diagnostics_rx(&self) -> &flume::Receiver<FileDiagnostics> {
         &self.diagnostics_rx
     }
 
+    pub fn progress_rx(&self) -> &flume::Receiver<ProgressEvent> {
+        &self.progress_rx
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.child
+            .lock()
+            .map(|mut child| matches!(child.try_wait(), Ok(Some(_))))
+            .unwrap_or(false)
+    }
+
     pub fn stop(&mut self) {
         *self.is_running.lock().unwrap() = false;
         if let Ok(mut child) = self.child.lock() {
@@ -237,6 +271,52 @@ fn read_message(reader: &mut BufReader<impl Read>) -> Result<Value, TransportErr
     serde_json::from_slice(&body).map_err(|e| TransportError::ParseError(e.to_string()))
 }
 
+fn write_message_to(
+    writer: &Mutex<Box<dyn Write + Send>>,
+    msg: &Value,
+) -> Result<(), TransportError> {
+    let body = serde_json::to_string(msg).map_err(|e| TransportError::ParseError(e.to_string()))?;
+    let header = format!("Content-Length: {}\r\n\r\n", body.len());
+
+    let mut writer = writer.lock().unwrap();
+    writer
+        .write_all(header.as_bytes())
+        .map_err(TransportError::WriteFailed)?;
+    writer
+        .write_all(body.as_bytes())
+        .map_err(TransportError::WriteFailed)?;
+    writer.flush().map_err(TransportError::WriteFailed)?;
+    Ok(())
+}
+
+fn parse_progress(params: &Value) -> Option<ProgressEvent> {
+    let token = params.get("token")?.to_string();
+    let value = params.get("value")?;
+    let message = value
+        .get("message")
+        .and_then(|m| m.as_str())
+        .map(String::from);
+    let percentage = value
+        .get("percentage")
+        .and_then(|p| p.as_u64())
+        .map(|p| p.min(100) as u32);
+    match value.get("kind")?.as_str()? {
+        "begin" => Some(ProgressEvent::Begin {
+            token,
+            title: value.get("title")?.as_str()?.to_string(),
+            message,
+            percentage,
+        }),
+        "report" => Some(ProgressEvent::Report {
+            token,
+            message,
+            percentage,
+        }),
+        "end" => Some(ProgressEvent::End { token }),
+        _ => None,
+    }
+}
+
 fn parse_diagnostics(params: &Value) -> Option<FileDiagnostics> {
     let uri = params.get("uri")?.as_str()?;
     let path = url::Url::parse(uri)
@@ -244,6 +324,11 @@ fn parse_diagnostics(params: &Value) -> Option<FileDiagnostics> {
         .and_then(|u| u.to_file_path().ok())?;
 
     let diags_arr = params.get("diagnostics")?.as_array()?;
+    let diagnostics = parse_diagnostic_items(diags_arr);
+    Some(FileDiagnostics { path, diagnostics })
+}
+
+pub(super) fn parse_diagnostic_items(diags_arr: &[Value]) -> Vec<super::types::Diagnostic> {
     let mut diagnostics = Vec::with_capacity(diags_arr.len());
 
     for diag in diags_arr {
@@ -276,5 +361,5 @@ fn parse_diagnostics(params: &Value) -> Option<FileDiagnostics> {
         }
     }
 
-    Some(FileDiagnostics { path, diagnostics })
+    diagnostics
 }
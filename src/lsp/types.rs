@@ -8,7 +8,7 @@ pub enum DiagnosticSeverity {
     Hint,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Diagnostic {
     pub range_start_line: u32,
     pub range_start_col: u32,
@@ -34,10 +34,17 @@ pub struct LocationInfo {
 pub struct LspCompletionItem {
     pub label: String,
     pub detail: Option<String>,
+    pub documentation: Option<String>,
     pub insert_text: String,
     pub kind: LspCompletionKind,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct LspCompletionList {
+    pub items: Vec<LspCompletionItem>,
+    pub is_incomplete: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LspCompletionKind {
     Function,
@@ -83,3 +90,51 @@ pub struct FileDiagnostics {
     pub path: PathBuf,
     pub diagnostics: Vec<Diagnostic>,
 }
+
+#[derive(Debug, Clone)]
+pub enum ProgressEvent {
+    Begin {
+        token: String,
+        title: String,
+        message: Option<String>,
+        percentage: Option<u32>,
+    },
+    Report {
+        token: String,
+        message: Option<String>,
+        percentage: Option<u32>,
+    },
+    End {
+        token: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkProgress {
+    pub title: String,
+    pub message: Option<String>,
+    pub percentage: Option<u32>,
+}
+
+impl WorkProgress {
+    pub fn label(&self) -> String {
+        let mut label = self.title.clone();
+        if let Some(message) = self.message.as_deref().filter(|m| !m.is_empty()) {
+            label.push(' ');
+            label.push_str(message);
+        }
+        if let Some(pct) = self.percentage {
+            label.push_str(&format!(" {}%", pct));
+        }
+        label
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+    pub start_line: u32,
+    pub start_col: u32,
+    pub end_line: u32,
+    pub end_col: u32,
+    pub new_text: String,
+}
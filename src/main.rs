@@ -1,19 +1,42 @@
 mod ansi_parser;
 mod app;
+mod auto_pair;
 mod autosave;
+mod bracket_guide;
+mod command_palette;
 mod completion;
 mod diff_highlighter;
+mod editorconfig;
+mod explorer_tree;
+mod file_location;
+mod file_ops;
+mod formatter;
 mod git_service;
 mod git_state;
 mod git_view;
+mod goto_line;
+mod gutter;
 mod ide_theme;
+mod indent;
+mod keymap;
 mod lsp;
+mod markdown;
+mod multi_edit;
 mod pty_service;
 mod review_state;
+mod rewrap;
+mod rich_copy;
 mod search_bar;
+mod selection_expand;
 mod settings;
+mod snippet;
+mod snippet_library;
 mod terminal_state;
 mod terminal_view;
+mod toast;
+mod undo_group;
+mod undo_history;
+mod workspace_file;
 
 use adabraka_ui::navigation::app_menu::{
     edit_menu, file_menu, view_menu, window_menu, StandardMacMenuBar,
@@ -111,27 +134,44 @@ fn main() {
                     window_background: WindowBackgroundAppearance::Opaque,
                     ..Default::default()
                 },
-                |_, cx| {
-                    cx.new(|cx| {
+                |window, cx| {
+                    let view = cx.new(|cx| {
                         let mut state = AppState::new(cx);
+                        let launched_bare = paths_for_window.is_empty();
                         let mut file_paths = Vec::new();
                         let mut folder_path = None;
+                        let mut workspace_path = None;
                         for path in paths_for_window {
-                            if path.is_dir() {
+                            if workspace_file::is_workspace_file(&path) {
+                                workspace_path = Some(path);
+                            } else if path.is_dir() {
                                 folder_path = Some(path);
                             } else {
                                 file_paths.push(path);
                             }
                         }
-                        if let Some(folder) = folder_path {
+                        if let Some(workspace) = workspace_path {
+                            state.open_workspace_file(workspace, cx);
+                        } else if let Some(folder) = folder_path {
                             state.open_folder(folder, cx);
                         }
                         if !file_paths.is_empty() {
                             state.open_paths(file_paths, cx);
                         }
+                        if launched_bare {
+                            state.apply_startup_behavior(cx);
+                        }
                         state.check_cli_install(cx);
                         state
-                    })
+                    });
+                    view.update(cx, |state, cx| state.observe_window_activation(window, cx));
+                    let guard = view.downgrade();
+                    window.on_window_should_close(cx, move |_, cx| {
+                        guard
+                            .update(cx, |state, cx| state.should_close_window(cx))
+                            .unwrap_or(true)
+                    });
+                    view
                 },
             )
             .unwrap();
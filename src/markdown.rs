@@ -0,0 +1,443 @@
+use crate::diff_highlighter::compute_line_highlights;
+use crate::ide_theme::{use_ide_theme, ChromeColors};
+use adabraka_ui::components::editor::Language;
+use gpui::*;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    Heading(String),
+    Paragraph(String),
+    ListItem {
+        marker: String,
+        depth: usize,
+        text: String,
+    },
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+    Rule,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct InlineFlags {
+    bold: bool,
+    italic: bool,
+    code: bool,
+    link: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct InlineText {
+    pub text: String,
+    runs: Vec<(Range<usize>, InlineFlags)>,
+    pub links: Vec<(Range<usize>, String)>,
+}
+
+fn is_fence(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    trimmed
+        .strip_prefix("```")
+        .or_else(|| trimmed.strip_prefix("~~~"))
+        .map(str::trim)
+}
+
+fn list_marker(line: &str) -> Option<(usize, String, &str)> {
+    let indent = line.len() - line.trim_start().len();
+    let trimmed = line.trim_start();
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(bullet) {
+            return Some((indent / 2, "•".into(), rest));
+        }
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        let rest = &trimmed[digits..];
+        if let Some(rest) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") ")) {
+            return Some((indent / 2, format!("{}.", &trimmed[..digits]), rest));
+        }
+    }
+    None
+}
+
+fn is_rule(line: &str) -> bool {
+    let trimmed: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    trimmed.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&c| trimmed.chars().all(|t| t == c))
+}
+
+fn parse_blocks(source: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut lines = source.lines();
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(paragraph.join(" ")));
+            paragraph.clear();
+        }
+    };
+
+    while let Some(line) = lines.next() {
+        if let Some(language) = is_fence(line) {
+            flush(&mut paragraph, &mut blocks);
+            let mut code = Vec::new();
+            for code_line in lines.by_ref() {
+                if is_fence(code_line).is_some() {
+                    break;
+                }
+                code.push(code_line);
+            }
+            blocks.push(Block::Code {
+                language: (!language.is_empty()).then(|| language.to_string()),
+                code: code.join("\n"),
+            });
+        } else if line.trim().is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some(heading) = line
+            .trim_start()
+            .strip_prefix('#')
+            .map(|h| h.trim_start_matches('#'))
+            .filter(|h| h.is_empty() || h.starts_with(' '))
+        {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading(heading.trim().to_string()));
+        } else if is_rule(line) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+        } else if let Some((depth, marker, rest)) = list_marker(line) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::ListItem {
+                marker,
+                depth,
+                text: rest.trim().to_string(),
+            });
+        } else if let Some(Block::ListItem { text, .. }) = blocks
+            .last_mut()
+            .filter(|_| paragraph.is_empty() && line.starts_with(' '))
+        {
+            text.push(' ');
+            text.push_str(line.trim());
+        } else {
+            paragraph.push(line.trim());
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+impl InlineText {
+    fn switch(&mut self, run_start: &mut usize, flags: &mut InlineFlags, new: InlineFlags) {
+        let end = self.text.len();
+        if end > *run_start {
+            self.runs.push((*run_start..end, *flags));
+        }
+        *run_start = end;
+        *flags = new;
+    }
+}
+
+pub fn parse_inline(source: &str) -> InlineText {
+    let mut out = InlineText::default();
+    let mut flags = InlineFlags::default();
+    let mut run_start = 0;
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '\\' && next.is_some_and(|n| n.is_ascii_punctuation()) {
+            out.text.push(next.unwrap_or_default());
+            i += 2;
+        } else if c == '`' {
+            let close = chars[i + 1..].iter().position(|&ch| ch == '`');
+            match close {
+                Some(len) => {
+                    let code: String = chars[i + 1..i + 1 + len].iter().collect();
+                    let mut code_flags = flags;
+                    code_flags.code = true;
+                    let prev = flags;
+                    out.switch(&mut run_start, &mut flags, code_flags);
+                    out.text.push_str(&code);
+                    out.switch(&mut run_start, &mut flags, prev);
+                    i += len + 2;
+                }
+                None => {
+                    out.text.push(c);
+                    i += 1;
+                }
+            }
+        } else if (c == '*' || c == '_') && next == Some(c) {
+            let mut new = flags;
+            new.bold = !flags.bold;
+            out.switch(&mut run_start, &mut flags, new);
+            i += 2;
+        } else if (c == '*' && (flags.italic || next.is_some_and(|n| !n.is_whitespace())))
+            || (c == '_'
+                && (flags.italic || i == 0 || !chars[i - 1].is_alphanumeric())
+                && (!flags.italic || next.is_none_or(|n| !n.is_alphanumeric())))
+        {
+            let mut new = flags;
+            new.italic = !flags.italic;
+            out.switch(&mut run_start, &mut flags, new);
+            i += 1;
+        } else if c == '[' {
+            let rest: String = chars[i + 1..].iter().collect();
+            let parsed = rest.find("](").and_then(|label_end| {
+                let url_part = &rest[label_end + 2..];
+                url_part
+                    .find(')')
+                    .map(|url_end| (label_end, url_part[..url_end].to_string(), url_end))
+            });
+            match parsed {
+                Some((label_end, url, url_end)) => {
+                    let label = parse_inline(&rest[..label_end]).text;
+                    let mut link_flags = flags;
+                    link_flags.link = true;
+                    let prev = flags;
+                    out.switch(&mut run_start, &mut flags, link_flags);
+                    let start = out.text.len();
+                    out.text.push_str(&label);
+                    out.links.push((start..out.text.len(), url));
+                    out.switch(&mut run_start, &mut flags, prev);
+                    i += 1 + rest[..label_end + 2 + url_end + 1].chars().count();
+                }
+                None => {
+                    out.text.push(c);
+                    i += 1;
+                }
+            }
+        } else {
+            out.text.push(c);
+            i += 1;
+        }
+    }
+    let end = out.text.len();
+    if end > run_start {
+        out.runs.push((run_start..end, flags));
+    }
+    out
+}
+
+fn fence_language(tag: Option<&str>, default_language: Language) -> Language {
+    let Some(tag) = tag else {
+        return default_language;
+    };
+    let ext = match tag.to_lowercase().as_str() {
+        "rust" => "rs",
+        "typescript" => "ts",
+        "javascript" => "js",
+        "python" => "py",
+        "ruby" => "rb",
+        "golang" => "go",
+        "bash" | "shell" | "sh" | "zsh" | "console" => "sh",
+        "c++" => "cpp",
+        other => return Language::from_extension(other),
+    };
+    Language::from_extension(ext)
+}
+
+fn inline_highlights(
+    inline: &InlineText,
+    chrome: &ChromeColors,
+) -> Vec<(Range<usize>, HighlightStyle)> {
+    inline
+        .runs
+        .iter()
+        .filter(|(_, f)| *f != InlineFlags::default())
+        .map(|(range, f)| {
+            let mut style = HighlightStyle::default();
+            if f.bold {
+                style.font_weight = Some(FontWeight::BOLD);
+            }
+            if f.italic {
+                style.font_style = Some(FontStyle::Italic);
+            }
+            if f.code {
+                style.color = Some(chrome.accent);
+                style.background_color = Some(hsla(0.0, 0.0, 1.0, 0.06));
+            }
+            if f.link {
+                style.color = Some(chrome.accent);
+                style.underline = Some(UnderlineStyle {
+                    thickness: px(1.0),
+                    color: Some(chrome.accent),
+                    wavy: false,
+                });
+            }
+            (range.clone(), style)
+        })
+        .collect()
+}
+
+fn render_inline(source: &str, idx: usize, chrome: &ChromeColors) -> AnyElement {
+    let inline = parse_inline(source);
+    let highlights = inline_highlights(&inline, chrome);
+    let styled = StyledText::new(inline.text).with_highlights(highlights);
+    if inline.links.is_empty() {
+        return styled.into_any_element();
+    }
+    let (ranges, urls): (Vec<_>, Vec<_>) = inline.links.into_iter().unzip();
+    InteractiveText::new(
+        ElementId::NamedInteger("markdown-inline".into(), idx as u64),
+        styled,
+    )
+    .on_click(ranges, move |link, _, cx| {
+        if let Some(url) = urls.get(link) {
+            cx.open_url(url);
+        }
+    })
+    .into_any_element()
+}
+
+fn render_code_block(code: &str, language: Language, code_font: &SharedString) -> Div {
+    let highlights = compute_line_highlights(code, language);
+    div()
+        .w_full()
+        .flex()
+        .flex_col()
+        .px(px(8.0))
+        .py(px(6.0))
+        .rounded(px(4.0))
+        .bg(hsla(0.0, 0.0, 0.0, 0.2))
+        .font_family(code_font.clone())
+        .text_size(px(12.0))
+        .children(code.lines().enumerate().map(|(idx, line)| {
+            let runs: Vec<(Range<usize>, HighlightStyle)> = highlights
+                .get(idx)
+                .into_iter()
+                .flatten()
+                .filter(|run| run.start + run.len <= line.len())
+                .map(|run| {
+                    (
+                        run.start..run.start + run.len,
+                        HighlightStyle {
+                            color: Some(run.color),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect();
+            let mut sorted = runs;
+            sorted.sort_by_key(|(r, _)| r.start);
+            let mut last_end = 0;
+            sorted.retain(|(r, _)| {
+                let keep = r.start >= last_end;
+                if keep {
+                    last_end = r.end;
+                }
+                keep
+            });
+            let text = if line.is_empty() {
+                " ".to_string()
+            } else {
+                line.to_string()
+            };
+            div()
+                .whitespace_nowrap()
+                .child(StyledText::new(text).with_highlights(sorted))
+        }))
+}
+
+pub fn render_markdown(source: &str, default_language: Language, code_font: SharedString) -> Div {
+    let chrome = use_ide_theme().chrome;
+    div()
+        .flex()
+        .flex_col()
+        .gap(px(6.0))
+        .children(
+            parse_blocks(source)
+                .into_iter()
+                .enumerate()
+                .map(|(idx, block)| match block {
+                    Block::Heading(text) => div()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .child(render_inline(&text, idx, &chrome)),
+                    Block::Paragraph(text) => div().child(render_inline(&text, idx, &chrome)),
+                    Block::ListItem {
+                        marker,
+                        depth,
+                        text,
+                    } => div()
+                        .flex()
+                        .gap(px(6.0))
+                        .pl(px(depth as f32 * 12.0))
+                        .child(
+                            div()
+                                .flex_shrink_0()
+                                .text_color(chrome.text_secondary)
+                                .child(marker),
+                        )
+                        .child(div().flex_1().child(render_inline(&text, idx, &chrome))),
+                    Block::Code { language, code } => render_code_block(
+                        &code,
+                        fence_language(language.as_deref(), default_language),
+                        &code_font,
+                    ),
+                    Block::Rule => div().h(px(1.0)).w_full().bg(chrome.header_border),
+                }),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_blocks, parse_inline, Block, InlineFlags};
+
+    #[test]
+    fn test_parse_inline_styles() {
+        let inline = parse_inline("call `foo()` with **care**, see [docs](https://example.com)");
+        assert_eq!(inline.text, "call foo() with care, see docs");
+        assert!(inline.runs.contains(&(
+            5..10,
+            InlineFlags {
+                code: true,
+                ..Default::default()
+            }
+        )));
+        assert!(inline.runs.contains(&(
+            16..20,
+            InlineFlags {
+                bold: true,
+                ..Default::default()
+            }
+        )));
+        assert_eq!(
+            inline.links,
+            vec![(26..30, "https://example.com".to_string())]
+        );
+        assert_eq!(parse_inline("snake_case_name").text, "snake_case_name");
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        let blocks = parse_blocks(
+            "# Title\n\nfirst\nline\n\n- one\n- two\n\n```rust\nfn main() {}\n```\n---",
+        );
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Heading("Title".into()),
+                Block::Paragraph("first line".into()),
+                Block::ListItem {
+                    marker: "•".into(),
+                    depth: 0,
+                    text: "one".into()
+                },
+                Block::ListItem {
+                    marker: "•".into(),
+                    depth: 0,
+                    text: "two".into()
+                },
+                Block::Code {
+                    language: Some("rust".into()),
+                    code: "fn main() {}".into()
+                },
+                Block::Rule,
+            ]
+        );
+    }
+}
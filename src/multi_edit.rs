@@ -0,0 +1,195 @@
+use regex::RegexBuilder;
+use std::ops::Range;
+
+pub const MAX_CURSORS: usize = 1000;
+
+pub fn find_matches(
+    text: &str,
+    query: &str,
+    use_regex: bool,
+    case_sensitive: bool,
+) -> Result<Vec<Range<usize>>, regex::Error> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = if use_regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let re = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()?;
+    Ok(re
+        .find_iter(text)
+        .map(|m| m.range())
+        .filter(|r| !r.is_empty())
+        .collect())
+}
+
+pub struct MultiEdit {
+    regions: Vec<Range<usize>>,
+    carets: Vec<usize>,
+    primary: usize,
+    selected: bool,
+}
+
+impl MultiEdit {
+    pub fn new(regions: Vec<Range<usize>>, primary: usize) -> Self {
+        let carets = regions.iter().map(|r| r.end).collect();
+        Self {
+            primary: primary.min(regions.len().saturating_sub(1)),
+            regions,
+            carets,
+            selected: true,
+        }
+    }
+
+    pub fn cursor_count(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn primary_selection(&self) -> Range<usize> {
+        if self.selected {
+            self.regions[self.primary].clone()
+        } else {
+            let caret = self.carets[self.primary];
+            caret..caret
+        }
+    }
+
+    pub fn secondary_selections(&self) -> Vec<Range<usize>> {
+        (0..self.regions.len())
+            .filter(|i| *i != self.primary)
+            .map(|i| {
+                if self.selected {
+                    self.regions[i].clone()
+                } else {
+                    self.carets[i]..self.carets[i]
+                }
+            })
+            .collect()
+    }
+
+    pub fn span(&self) -> Range<usize> {
+        let first = self.regions.first().map(|r| r.start).unwrap_or(0);
+        let last = self.regions.last().map(|r| r.end).unwrap_or(0);
+        first..last
+    }
+
+    fn apply(
+        &mut self,
+        doc: &str,
+        edit: impl Fn(&str, usize) -> (String, usize),
+    ) -> Option<(Range<usize>, String)> {
+        let span = self.span();
+        let mut out = String::new();
+        let mut regions = Vec::with_capacity(self.regions.len());
+        let mut carets = Vec::with_capacity(self.regions.len());
+        let mut cursor = span.start;
+        for (region, caret) in self.regions.iter().zip(&self.carets) {
+            out.push_str(&doc[cursor..region.start]);
+            let (text, rel) = edit(&doc[region.clone()], caret - region.start);
+            let start = span.start + out.len();
+            out.push_str(&text);
+            regions.push(start..start + text.len());
+            carets.push(start + rel);
+            cursor = region.end;
+        }
+        self.selected = false;
+        let changed = out != doc[span.clone()];
+        self.regions = regions;
+        self.carets = carets;
+        changed.then_some((span, out))
+    }
+
+    pub fn insert(&mut self, doc: &str, text: &str) -> Option<(Range<usize>, String)> {
+        let selected = self.selected;
+        self.apply(doc, |region, caret| {
+            if selected {
+                (text.to_string(), text.len())
+            } else {
+                let mut out = region[..caret].to_string();
+                out.push_str(text);
+                out.push_str(&region[caret..]);
+                (out, caret + text.len())
+            }
+        })
+    }
+
+    pub fn delete(&mut self, doc: &str, forward: bool) -> Option<(Range<usize>, String)> {
+        let selected = self.selected;
+        self.apply(doc, |region, caret| {
+            if selected {
+                return (String::new(), 0);
+            }
+            let (from, to) = if forward {
+                let next = region[caret..].chars().next().map_or(0, char::len_utf8);
+                (caret, caret + next)
+            } else {
+                let prev = region[..caret]
+                    .chars()
+                    .next_back()
+                    .map_or(0, char::len_utf8);
+                (caret - prev, caret)
+            };
+            (format!("{}{}", &region[..from], &region[to..]), from)
+        })
+    }
+
+    pub fn move_caret(&mut self, doc: &str, forward: bool) {
+        for (region, caret) in self.regions.iter().zip(self.carets.iter_mut()) {
+            *caret = match (self.selected, forward) {
+                (true, false) => region.start,
+                (true, true) => region.end,
+                (false, true) => doc[*caret..region.end]
+                    .chars()
+                    .next()
+                    .map_or(*caret, |c| *caret + c.len_utf8()),
+                (false, false) => doc[region.start..*caret]
+                    .chars()
+                    .next_back()
+                    .map_or(*caret, |c| *caret - c.len_utf8()),
+            };
+        }
+        self.selected = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_matches, MultiEdit};
+
+    #[test]
+    fn test_find_matches_literal_and_regex() {
+        let text = "Foo foo f.o";
+        assert_eq!(
+            find_matches(text, "foo", false, false).unwrap(),
+            vec![0..3, 4..7]
+        );
+        assert_eq!(find_matches(text, "f.o", false, true).unwrap(), vec![8..11]);
+        assert_eq!(find_matches(text, "f.o", true, true).unwrap().len(), 2);
+        assert!(find_matches(text, "(", true, true).is_err());
+        assert!(find_matches(text, "x*", true, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_multi_edit_types_into_every_region() {
+        let mut doc = "let a = a + a;".to_string();
+        let mut edit = MultiEdit::new(vec![4..5, 8..9, 12..13], 1);
+        assert_eq!(edit.secondary_selections(), vec![4..5, 12..13]);
+        for text in ["b", "c"] {
+            let (range, text) = edit.insert(&doc, text).unwrap();
+            doc.replace_range(range, &text);
+        }
+        assert_eq!(doc, "let bc = bc + bc;");
+        assert_eq!(edit.primary_selection(), 11..11);
+        assert_eq!(edit.secondary_selections(), vec![6..6, 16..16]);
+
+        edit.move_caret(&doc, false);
+        let (range, text) = edit.delete(&doc, false).unwrap();
+        doc.replace_range(range, &text);
+        assert_eq!(doc, "let c = c + c;");
+        assert!(edit.delete(&doc, false).is_none());
+    }
+}
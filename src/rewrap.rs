@@ -0,0 +1,460 @@
+use std::ops::Range;
+use tree_sitter::{Node, Tree};
+
+const LINE_LEADERS: [&str; 6] = ["///", "//!", "//", "--", ";;", "#"];
+
+fn line_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in text.split('\n') {
+        lines.push(offset..offset + line.len());
+        offset += line.len() + 1;
+    }
+    lines
+}
+
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn line_leader(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("#!") || trimmed.starts_with("#[") {
+        return None;
+    }
+    let leader = LINE_LEADERS.iter().find(|l| trimmed.starts_with(*l))?;
+    Some(format!("{}{}", indent_of(line), leader))
+}
+
+fn comment_node_at(tree: &Tree, pos: usize) -> Option<Node<'_>> {
+    let mut node = tree.root_node().descendant_for_byte_range(pos, pos)?;
+    loop {
+        if node.kind().contains("comment") {
+            let mut outer = node;
+            while let Some(parent) = outer.parent().filter(|p| p.kind().contains("comment")) {
+                outer = parent;
+            }
+            return Some(outer);
+        }
+        node = node.parent()?;
+    }
+}
+
+fn is_list_item(body: &str) -> Option<usize> {
+    for bullet in ["- ", "* ", "+ "] {
+        if body.starts_with(bullet) {
+            return Some(bullet.len());
+        }
+    }
+    let digits = body.chars().take_while(|c| c.is_ascii_digit()).count();
+    (digits > 0 && body[digits..].starts_with(". ")).then_some(digits + 2)
+}
+
+fn wrap_bodies(bodies: &[&str], width: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+    let mut head = String::new();
+    let mut hang = 0;
+    let mut verbatim = false;
+
+    let flush = |out: &mut Vec<String>, words: &mut Vec<&str>, head: &mut String, hang: usize| {
+        if words.is_empty() && head.is_empty() {
+            return;
+        }
+        let mut line = std::mem::take(head);
+        let mut line_len = line.chars().count();
+        let mut first = line.is_empty() || line.ends_with(' ');
+        for word in words.drain(..) {
+            let word_len = word.chars().count();
+            if !first && line_len + 1 + word_len > width {
+                out.push(std::mem::take(&mut line));
+                line = " ".repeat(hang);
+                line_len = hang;
+                first = true;
+            }
+            if !first {
+                line.push(' ');
+                line_len += 1;
+            }
+            line.push_str(word);
+            line_len += word_len;
+            first = false;
+        }
+        out.push(line);
+    };
+
+    for body in bodies {
+        if body.trim_start().starts_with("```") {
+            flush(&mut out, &mut words, &mut head, hang);
+            verbatim = !verbatim;
+            out.push(body.to_string());
+        } else if verbatim || body.starts_with("    ") || body.starts_with('#') {
+            flush(&mut out, &mut words, &mut head, hang);
+            out.push(body.to_string());
+        } else if body.trim().is_empty() {
+            flush(&mut out, &mut words, &mut head, hang);
+            out.push(String::new());
+        } else if let Some(marker) =
+            is_list_item(body).or_else(|| body.starts_with('@').then_some(0))
+        {
+            flush(&mut out, &mut words, &mut head, hang);
+            head = body[..marker].to_string();
+            hang = if marker == 0 { 2 } else { marker };
+            let mut rest = body[marker..].split_whitespace();
+            if marker == 0 {
+                if let Some(tag) = rest.next() {
+                    head.push_str(tag);
+                }
+            }
+            words.extend(rest);
+        } else {
+            if words.is_empty() && head.is_empty() {
+                hang = 0;
+            }
+            words.extend(body.split_whitespace());
+        }
+    }
+    flush(&mut out, &mut words, &mut head, hang);
+    out
+}
+
+fn join_prefixed(prefix: &str, body: &str) -> String {
+    if body.is_empty() {
+        prefix.trim_end().to_string()
+    } else {
+        format!("{}{}", prefix, body)
+    }
+}
+
+fn rewrap_line_comment(
+    text: &str,
+    lines: &[Range<usize>],
+    cursor_line: usize,
+    width: usize,
+    is_comment_line: impl Fn(usize) -> bool,
+) -> Option<(Range<usize>, String)> {
+    let leader = line_leader(&text[lines[cursor_line].clone()])?;
+    let matches = |i: usize| {
+        is_comment_line(i)
+            && text[lines[i].clone()].starts_with(&leader)
+            && line_leader(&text[lines[i].clone()]).as_deref() == Some(leader.as_str())
+    };
+    if !matches(cursor_line) {
+        return None;
+    }
+    let (mut first, mut last) = (cursor_line, cursor_line);
+    while first > 0 && matches(first - 1) {
+        first -= 1;
+    }
+    while last + 1 < lines.len() && matches(last + 1) {
+        last += 1;
+    }
+
+    let bodies: Vec<&str> = (first..=last)
+        .map(|i| {
+            let body = &text[lines[i].clone()][leader.len()..];
+            body.strip_prefix(' ').unwrap_or(body).trim_end()
+        })
+        .collect();
+    let prefix = format!("{} ", leader);
+    let avail = width.saturating_sub(prefix.chars().count()).max(10);
+    let wrapped: Vec<String> = wrap_bodies(&bodies, avail)
+        .iter()
+        .map(|body| join_prefixed(&prefix, body))
+        .collect();
+    Some((lines[first].start..lines[last].end, wrapped.join("\n")))
+}
+
+fn rewrap_block_comment(
+    text: &str,
+    lines: &[Range<usize>],
+    first: usize,
+    last: usize,
+    width: usize,
+) -> Option<(Range<usize>, String)> {
+    let open_line = &text[lines[first].clone()];
+    let indent = indent_of(open_line);
+    let after_indent = open_line.trim_start();
+    let opener = ["/**", "/*!", "/*"]
+        .into_iter()
+        .find(|o| after_indent.starts_with(o))?;
+
+    let mut cont_prefix = format!("{} * ", indent);
+    if first < last {
+        let second = &text[lines[first + 1].clone()];
+        if !second.trim_start().starts_with('*') {
+            cont_prefix = indent_of(second).to_string();
+        } else if !second.trim_start().starts_with("*/") {
+            cont_prefix = format!("{}* ", indent_of(second));
+        }
+    }
+
+    let mut bodies: Vec<&str> = Vec::new();
+    let mut keep_open = None;
+    let mut keep_close = None;
+    let mut inline_close = false;
+    for i in first..=last {
+        let line = &text[lines[i].clone()];
+        let mut body = line.trim();
+        if i == first {
+            body = body[opener.len()..].trim();
+            if body.is_empty() {
+                keep_open = Some(line);
+                continue;
+            }
+        } else if body.starts_with("*/") {
+            keep_close = Some(line);
+            continue;
+        } else if let Some(rest) = body.strip_prefix('*') {
+            body = rest.strip_prefix(' ').unwrap_or(rest);
+        } else {
+            body = line
+                .strip_prefix(cont_prefix.as_str())
+                .unwrap_or(body)
+                .trim_end();
+        }
+        if i == last {
+            if let Some(rest) = body.strip_suffix("*/") {
+                body = rest.trim_end();
+                inline_close = true;
+            }
+        }
+        bodies.push(body);
+    }
+
+    let avail = width.saturating_sub(cont_prefix.chars().count()).max(10);
+    let mut wrapped = wrap_bodies(&bodies, avail).into_iter();
+    let mut out: Vec<String> = Vec::new();
+    match keep_open {
+        Some(line) => out.push(line.to_string()),
+        None => {
+            let first_body = wrapped.next().unwrap_or_default();
+            out.push(join_prefixed(
+                &format!("{}{} ", indent, opener),
+                &first_body,
+            ));
+        }
+    }
+    out.extend(wrapped.map(|body| join_prefixed(&cont_prefix, &body)));
+    if inline_close {
+        if let Some(last_line) = out.last_mut() {
+            last_line.push_str(" */");
+        }
+    }
+    if let Some(line) = keep_close {
+        out.push(line.to_string());
+    }
+    Some((lines[first].start..lines[last].end, out.join("\n")))
+}
+
+pub fn rewrap_comment(
+    tree: Option<&Tree>,
+    text: &str,
+    cursor: usize,
+    width: usize,
+) -> Option<(Range<usize>, String)> {
+    let lines = line_ranges(text);
+    let cursor_line = lines.iter().position(|l| cursor <= l.end)?;
+    let first_non_ws = |i: usize| {
+        let line = &text[lines[i].clone()];
+        (!line.trim().is_empty()).then(|| lines[i].start + (line.len() - line.trim_start().len()))
+    };
+    let pos = first_non_ws(cursor_line)?;
+
+    let result = match tree {
+        Some(tree) => {
+            let node = comment_node_at(tree, pos)?;
+            if node.start_position().row == cursor_line && node.start_byte() != pos {
+                return None;
+            }
+            if text[node.byte_range()].starts_with("/*") {
+                let first = node.start_position().row;
+                let last = node.end_position().row.min(lines.len() - 1);
+                if first_non_ws(first) != Some(node.start_byte()) {
+                    return None;
+                }
+                rewrap_block_comment(text, &lines, first, last, width)?
+            } else {
+                rewrap_line_comment(text, &lines, cursor_line, width, |i| {
+                    first_non_ws(i).is_some_and(|p| {
+                        comment_node_at(tree, p).is_some_and(|n| n.start_byte() == p)
+                    })
+                })?
+            }
+        }
+        None => rewrap_line_comment(text, &lines, cursor_line, width, |_| true)?,
+    };
+    (text[result.0.clone()] != result.1).then_some(result)
+}
+
+fn block_leader(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    if let Some(rest) = ["/**", "/*!", "/*"]
+        .into_iter()
+        .find_map(|o| trimmed.strip_prefix(o))
+    {
+        return (!rest.contains("*/")).then(|| format!("{} * ", indent_of(line)));
+    }
+    let rest = trimmed.strip_prefix('*')?;
+    if rest.starts_with('/') || rest.contains("*/") {
+        return None;
+    }
+    let gap = &rest[..rest.len() - rest.trim_start().len()];
+    Some(format!(
+        "{}*{}",
+        indent_of(line),
+        if gap.is_empty() { " " } else { gap }
+    ))
+}
+
+fn inside_block_comment(text: &str, lines: &[Range<usize>], line: usize) -> bool {
+    for i in (0..line).rev() {
+        let trimmed = text[lines[i].clone()].trim_start();
+        if trimmed.starts_with("/*") {
+            return !trimmed.contains("*/");
+        }
+        if !trimmed.starts_with('*') || trimmed.contains("*/") {
+            return false;
+        }
+    }
+    false
+}
+
+pub fn continue_comment(
+    tree: Option<&Tree>,
+    text: &str,
+    cursor: usize,
+) -> Option<(Range<usize>, String)> {
+    let lines = line_ranges(text);
+    let cursor_line = lines.iter().position(|l| cursor <= l.end)?;
+    let range = lines[cursor_line].clone();
+    let line = &text[range.clone()];
+    let body_start = range.start + indent_of(line).len();
+
+    if let Some(leader) = line_leader(line) {
+        let rest = &line[leader.len()..];
+        let leader = format!(
+            "{}{}",
+            leader,
+            &rest[..rest.len() - rest.trim_start().len()]
+        );
+        if cursor < range.start + leader.trim_end().len() {
+            return None;
+        }
+        if let Some(tree) = tree {
+            comment_node_at(tree, body_start).filter(|n| n.start_byte() == body_start)?;
+        }
+        if leader.len() == line.len() {
+            return Some((range, format!("\n{}", indent_of(line))));
+        }
+        return Some((cursor..cursor, format!("\n{}", leader)));
+    }
+
+    let leader = block_leader(line)?;
+    if cursor < body_start + 1 {
+        return None;
+    }
+    if !line.trim_start().starts_with("/*") {
+        let in_comment = match tree {
+            Some(tree) => comment_node_at(tree, body_start)
+                .is_some_and(|n| text[n.byte_range()].starts_with("/*")),
+            None => inside_block_comment(text, &lines, cursor_line),
+        };
+        if !in_comment {
+            return None;
+        }
+        let closed = match tree {
+            Some(tree) => comment_node_at(tree, body_start)
+                .is_some_and(|n| text[n.byte_range()].ends_with("*/")),
+            None => block_comment_closed(text, &lines, cursor_line),
+        };
+        if line.trim() == "*" && !closed {
+            let indent = indent_of(line);
+            let outer = indent.strip_suffix(' ').unwrap_or(indent);
+            return Some((range, format!("{}*/\n{}", indent, outer)));
+        }
+    }
+    Some((cursor..cursor, format!("\n{}", leader)))
+}
+
+fn block_comment_closed(text: &str, lines: &[Range<usize>], line: usize) -> bool {
+    for range in &lines[line + 1..] {
+        let trimmed = text[range.clone()].trim_start();
+        if trimmed.contains("*/") {
+            return true;
+        }
+        if trimmed.starts_with("/*") || !trimmed.starts_with('*') {
+            return false;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{continue_comment, rewrap_comment};
+
+    #[test]
+    fn test_rewrap_line_comments() {
+        let text =
+            "fn main() {\n    // one two three four five six\n    // seven\n    let x = 1;\n}";
+        let (range, wrapped) = rewrap_comment(None, text, 20, 30).unwrap();
+        assert_eq!(
+            &text[range],
+            "    // one two three four five six\n    // seven"
+        );
+        assert_eq!(wrapped, "    // one two three four five\n    // six seven");
+        assert!(rewrap_comment(None, text, 60, 30).is_none());
+    }
+
+    #[test]
+    fn test_rewrap_keeps_paragraphs_and_lists() {
+        let text = "# alpha beta\n#\n# - gamma delta epsilon";
+        let (_, wrapped) = rewrap_comment(None, text, 0, 14).unwrap();
+        assert_eq!(
+            wrapped,
+            "# alpha beta\n#\n# - gamma\n#   delta\n#   epsilon"
+        );
+    }
+
+    #[test]
+    fn test_continue_comment_leaders() {
+        let text = "    /// docs here\n    // \n";
+        assert_eq!(
+            continue_comment(None, text, 17),
+            Some((17..17, "\n    /// ".to_string()))
+        );
+        assert_eq!(
+            continue_comment(None, text, 25),
+            Some((18..25, "\n    ".to_string()))
+        );
+        assert_eq!(continue_comment(None, text, 2), None);
+
+        let block = "/**\n *  item\n */\n*p = 1";
+        assert_eq!(
+            continue_comment(None, block, 3),
+            Some((3..3, "\n * ".to_string()))
+        );
+        assert_eq!(
+            continue_comment(None, block, 12),
+            Some((12..12, "\n *  ".to_string()))
+        );
+        assert_eq!(continue_comment(None, block, 16), None);
+        assert_eq!(continue_comment(None, block, 23), None);
+
+        let open = "  /*
+   * 
+";
+        assert_eq!(
+            continue_comment(None, open, 10),
+            Some((5..10, "   */\n  ".to_string()))
+        );
+        let closed = "/*
+ * 
+ */";
+        assert_eq!(
+            continue_comment(None, closed, 6),
+            Some((6..6, "\n * ".to_string()))
+        );
+    }
+}
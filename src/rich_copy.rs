@@ -0,0 +1,157 @@
+use std::ops::Range;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+
+use gpui::{Hsla, Rgba};
+use thiserror::Error;
+
+use crate::diff_highlighter::HighlightRun;
+
+#[derive(Debug, Error)]
+pub enum RichCopyError {
+    #[cfg(target_os = "macos")]
+    #[error("Failed to run osascript: {0}")]
+    Spawn(std::io::Error),
+    #[cfg(target_os = "macos")]
+    #[error("{0}")]
+    Failed(String),
+    #[cfg(not(target_os = "macos"))]
+    #[error("Copying with highlighting is not supported on this platform")]
+    Unsupported,
+}
+
+fn css_color(color: Hsla) -> String {
+    let rgba = Rgba::from(color);
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(rgba.r),
+        channel(rgba.g),
+        channel(rgba.b)
+    )
+}
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+pub fn highlighted_html(
+    content: &str,
+    range: Range<usize>,
+    lines: &[Vec<HighlightRun>],
+    fg: Hsla,
+    bg: Hsla,
+    font: &str,
+) -> String {
+    let mut out = format!(
+        "<pre style=\"background:{};color:{};font-family:'{}',monospace;padding:8px;\">",
+        css_color(bg),
+        css_color(fg),
+        font.replace('\'', "")
+    );
+    let mut line_start = 0;
+    for (idx, line) in content.split('\n').enumerate() {
+        let line_end = line_start + line.len();
+        let from = range.start.max(line_start);
+        let to = range.end.min(line_end);
+        if idx > 0 && line_start > range.start && line_start <= range.end {
+            out.push('\n');
+        }
+        if from < to {
+            let mut colors: Vec<Option<Hsla>> = vec![None; line.len()];
+            for run in lines.get(idx).into_iter().flatten() {
+                let end = (run.start + run.len).min(line.len());
+                for slot in colors.iter_mut().take(end).skip(run.start) {
+                    *slot = Some(run.color);
+                }
+            }
+            let mut pos = from - line_start;
+            let stop = to - line_start;
+            while pos < stop {
+                let color = colors[pos];
+                let mut end = pos + 1;
+                while end < stop && (colors[end] == color || !line.is_char_boundary(end)) {
+                    end += 1;
+                }
+                match color {
+                    Some(color) => {
+                        out.push_str(&format!("<span style=\"color:{};\">", css_color(color)));
+                        escape_html(&line[pos..end], &mut out);
+                        out.push_str("</span>");
+                    }
+                    None => escape_html(&line[pos..end], &mut out),
+                }
+                pos = end;
+            }
+        }
+        line_start = line_end + 1;
+        if line_start > range.end {
+            break;
+        }
+    }
+    out.push_str("</pre>");
+    out
+}
+
+#[cfg(target_os = "macos")]
+pub fn copy_html(text: &str, html: &str) -> Result<(), RichCopyError> {
+    const SCRIPT: &str = "ObjC.import('AppKit');
+function run(argv) {
+    var pb = $.NSPasteboard.generalPasteboard;
+    pb.clearContents;
+    pb.setStringForType($(argv[1]), $.NSPasteboardTypeHTML);
+    pb.setStringForType($(argv[0]), $.NSPasteboardTypeString);
+}";
+    let output = Command::new("osascript")
+        .args(["-l", "JavaScript", "-e", SCRIPT, text, html])
+        .output()
+        .map_err(RichCopyError::Spawn)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(RichCopyError::Failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn copy_html(_text: &str, _html: &str) -> Result<(), RichCopyError> {
+    Err(RichCopyError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::highlighted_html;
+    use crate::diff_highlighter::HighlightRun;
+    use gpui::{hsla, Hsla};
+
+    #[test]
+    fn test_highlighted_html_clips_to_selection() {
+        let red: Hsla = hsla(0.0, 1.0, 0.5, 1.0);
+        let content = "let a = 1;\nfn <b>() {}\n";
+        let lines = vec![
+            vec![HighlightRun {
+                start: 0,
+                len: 3,
+                color: red,
+            }],
+            vec![HighlightRun {
+                start: 0,
+                len: 2,
+                color: red,
+            }],
+        ];
+        let html = highlighted_html(content, 4..16, &lines, red, red, "Mono");
+        assert!(html.starts_with("<pre style=\"background:#ff0000;color:#ff0000;"));
+        assert!(html.ends_with(";\">a = 1;\n<span style=\"color:#ff0000;\">fn</span> &lt;b</pre>"));
+    }
+}
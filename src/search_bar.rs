@@ -1,7 +1,7 @@
 use crate::ide_theme::use_ide_theme;
 use adabraka_ui::components::editor::EditorState;
 use adabraka_ui::components::icon::Icon;
-use adabraka_ui::components::input::{Input, InputEvent, InputState};
+use adabraka_ui::components::input::{Input, InputEvent, InputState, SelectAll};
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 actions!(
@@ -14,6 +14,9 @@ actions!(
         ReplaceOne,
         ReplaceAllMatches,
         DismissSearch,
+        HistoryPrevious,
+        HistoryNext,
+        SelectAllMatches,
     ]
 );
 
@@ -22,17 +25,106 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("enter", FindNext, Some("SearchBar")),
         KeyBinding::new("shift-enter", FindPrevious, Some("SearchBar")),
         KeyBinding::new("escape", DismissSearch, Some("SearchBar")),
+        KeyBinding::new("alt-enter", SelectAllMatches, Some("SearchBar")),
+        KeyBinding::new("up", HistoryPrevious, Some("SearchBar")),
+        KeyBinding::new("down", HistoryNext, Some("SearchBar")),
+        KeyBinding::new("up", HistoryPrevious, Some("ContentSearch")),
+        KeyBinding::new("down", HistoryNext, Some("ContentSearch")),
     ]);
 }
 
+pub struct SearchHistory {
+    entries: Vec<String>,
+    cursor: Option<usize>,
+    draft: String,
+}
+
+impl SearchHistory {
+    pub fn new(entries: Vec<String>) -> Self {
+        Self {
+            entries,
+            cursor: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn record(&mut self, query: &str, limit: usize) -> bool {
+        self.cursor = None;
+        if query.is_empty() || self.entries.first().map(|e| e.as_str()) == Some(query) {
+            return false;
+        }
+        self.entries.retain(|e| e != query);
+        self.entries.insert(0, query.to_string());
+        self.entries.truncate(limit);
+        true
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.entries.truncate(limit);
+    }
+
+    pub fn older(&mut self, current: &str) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let next = match self.cursor {
+            None => {
+                self.draft = current.to_string();
+                0
+            }
+            Some(i) => (i + 1).min(self.entries.len() - 1),
+        };
+        self.cursor = Some(next);
+        self.entries.get(next).cloned()
+    }
+
+    pub fn newer(&mut self) -> Option<String> {
+        match self.cursor {
+            None => None,
+            Some(0) => {
+                self.cursor = None;
+                Some(std::mem::take(&mut self.draft))
+            }
+            Some(i) => {
+                self.cursor = Some(i - 1);
+                self.entries.get(i - 1).cloned()
+            }
+        }
+    }
+
+    pub fn sync(&mut self, current: &str) {
+        if let Some(i) = self.cursor {
+            if self.entries.get(i).map(|e| e.as_str()) != Some(current) {
+                self.cursor = None;
+            }
+        }
+    }
+}
+
+type HistoryCallback = Box<dyn Fn(&[String], &[String], &mut App)>;
+type WindowCallback = Box<dyn Fn(&mut Window, &mut App)>;
+type StickyCallback = Box<dyn Fn(bool, &mut App)>;
+
 pub struct SearchBar {
     find_input: Entity<InputState>,
     replace_input: Entity<InputState>,
     editor: Option<Entity<EditorState>>,
     pub show_replace: bool,
     dismiss_callback: Option<Box<dyn Fn(&mut App)>>,
+    select_all_callback: Option<WindowCallback>,
+    unfocus_callback: Option<WindowCallback>,
+    pub sticky: bool,
+    sticky_callback: Option<StickyCallback>,
     search_task: Option<Task<()>>,
     last_query: SharedString,
+    find_history: SearchHistory,
+    replace_history: SearchHistory,
+    history_limit: usize,
+    history_callback: Option<HistoryCallback>,
 }
 
 impl SearchBar {
@@ -43,6 +135,7 @@ impl SearchBar {
         cx.subscribe(&find_input, |this, _input, event, cx| {
             if matches!(event, InputEvent::Change) {
                 let query = this.find_input.read(cx).content.clone();
+                this.find_history.sync(&query);
                 if query == this.last_query {
                     return;
                 }
@@ -69,14 +162,107 @@ impl SearchBar {
         })
         .detach();
 
+        cx.subscribe(&replace_input, |this, input, event, cx| {
+            if matches!(event, InputEvent::Change) {
+                let text = input.read(cx).content.clone();
+                this.replace_history.sync(&text);
+            }
+        })
+        .detach();
+
         Self {
             find_input,
             replace_input,
             editor: None,
             show_replace: false,
             dismiss_callback: None,
+            select_all_callback: None,
+            unfocus_callback: None,
+            sticky: false,
+            sticky_callback: None,
             search_task: None,
             last_query: SharedString::from(""),
+            find_history: SearchHistory::new(Vec::new()),
+            replace_history: SearchHistory::new(Vec::new()),
+            history_limit: 50,
+            history_callback: None,
+        }
+    }
+
+    pub fn set_history(&mut self, find: Vec<String>, replace: Vec<String>, limit: usize) {
+        self.find_history = SearchHistory::new(find);
+        self.replace_history = SearchHistory::new(replace);
+        self.set_history_limit(limit);
+    }
+
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        self.find_history.set_limit(limit);
+        self.replace_history.set_limit(limit);
+    }
+
+    pub fn on_history_change<F: Fn(&[String], &[String], &mut App) + 'static>(
+        &mut self,
+        callback: F,
+    ) {
+        self.history_callback = Some(Box::new(callback));
+    }
+
+    fn commit_history(&mut self, include_replace: bool, cx: &mut Context<Self>) {
+        let query = self.find_input.read(cx).content().to_string();
+        let mut changed = self.find_history.record(&query, self.history_limit);
+        if include_replace {
+            let replacement = self.replace_input.read(cx).content().to_string();
+            changed |= self
+                .replace_history
+                .record(&replacement, self.history_limit);
+        }
+        if changed {
+            if let Some(cb) = &self.history_callback {
+                cb(
+                    self.find_history.entries(),
+                    self.replace_history.entries(),
+                    cx,
+                );
+            }
+        }
+    }
+
+    fn history_previous(
+        &mut self,
+        _: &HistoryPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.step_history(true, window, cx);
+    }
+
+    fn history_next(&mut self, _: &HistoryNext, window: &mut Window, cx: &mut Context<Self>) {
+        self.step_history(false, window, cx);
+    }
+
+    fn step_history(&mut self, older: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let in_replace = self
+            .replace_input
+            .read(cx)
+            .focus_handle(cx)
+            .is_focused(window);
+        let (input, history) = if in_replace {
+            (self.replace_input.clone(), &mut self.replace_history)
+        } else {
+            (self.find_input.clone(), &mut self.find_history)
+        };
+        let current = input.read(cx).content().to_string();
+        let entry = if older {
+            history.older(&current)
+        } else {
+            history.newer()
+        };
+        if let Some(entry) = entry {
+            input.update(cx, |state, cx| {
+                state.select_all(&SelectAll, window, cx);
+                state.set_value(SharedString::from(entry), window, cx);
+            });
         }
     }
 
@@ -101,6 +287,18 @@ impl SearchBar {
         self.dismiss_callback = Some(Box::new(callback));
     }
 
+    pub fn on_unfocus<F: Fn(&mut Window, &mut App) + 'static>(&mut self, callback: F) {
+        self.unfocus_callback = Some(Box::new(callback));
+    }
+
+    pub fn on_sticky_change<F: Fn(bool, &mut App) + 'static>(&mut self, callback: F) {
+        self.sticky_callback = Some(Box::new(callback));
+    }
+
+    pub fn on_select_all_matches<F: Fn(&mut Window, &mut App) + 'static>(&mut self, callback: F) {
+        self.select_all_callback = Some(Box::new(callback));
+    }
+
     pub fn get_prefill_text(&self, cx: &App) -> Option<String> {
         self.editor
             .as_ref()
@@ -116,6 +314,7 @@ impl SearchBar {
     }
 
     fn find_next(&mut self, _: &FindNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.commit_history(false, cx);
         if let Some(editor) = &self.editor {
             let editor = editor.clone();
             editor.update(cx, |state, ecx| state.find_next(ecx));
@@ -124,6 +323,7 @@ impl SearchBar {
     }
 
     fn find_previous(&mut self, _: &FindPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        self.commit_history(false, cx);
         if let Some(editor) = &self.editor {
             let editor = editor.clone();
             editor.update(cx, |state, ecx| state.find_previous(ecx));
@@ -131,11 +331,30 @@ impl SearchBar {
         cx.notify();
     }
 
-    fn dismiss(&mut self, _: &DismissSearch, _: &mut Window, cx: &mut Context<Self>) {
+    fn dismiss(&mut self, _: &DismissSearch, window: &mut Window, cx: &mut Context<Self>) {
+        self.commit_history(false, cx);
+        if self.sticky {
+            if let Some(cb) = &self.unfocus_callback {
+                cb(window, cx);
+                return;
+            }
+        }
         if let Some(cb) = &self.dismiss_callback {
             cb(cx);
         }
     }
+
+    fn select_all_matches(
+        &mut self,
+        _: &SelectAllMatches,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_history(false, cx);
+        if let Some(cb) = &self.select_all_callback {
+            cb(window, cx);
+        }
+    }
 }
 
 impl Focusable for SearchBar {
@@ -194,6 +413,11 @@ impl Render for SearchBar {
         } else {
             chrome.text_secondary
         };
+        let sticky_fg = if self.sticky {
+            chrome.accent
+        } else {
+            chrome.text_secondary
+        };
         let btn_bg = chrome.dim.opacity(0.3);
         let btn_fg = chrome.text_secondary;
         let hover_bg = chrome.dim.opacity(0.5);
@@ -203,6 +427,9 @@ impl Render for SearchBar {
             .on_action(cx.listener(Self::find_next))
             .on_action(cx.listener(Self::find_previous))
             .on_action(cx.listener(Self::dismiss))
+            .on_action(cx.listener(Self::select_all_matches))
+            .on_action(cx.listener(Self::history_previous))
+            .on_action(cx.listener(Self::history_next))
             .w_full()
             .flex()
             .flex_col()
@@ -303,6 +530,27 @@ impl Render for SearchBar {
                             }))
                             .child(".*"),
                     )
+                    .child(
+                        div()
+                            .id("sticky-btn")
+                            .w(px(24.0))
+                            .h(px(24.0))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .rounded(px(4.0))
+                            .when(self.sticky, |el| el.bg(chrome.accent.opacity(0.2)))
+                            .cursor_pointer()
+                            .hover(|s| s.bg(hover_bg))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.sticky = !this.sticky;
+                                if let Some(cb) = &this.sticky_callback {
+                                    cb(this.sticky, cx);
+                                }
+                                cx.notify();
+                            }))
+                            .child(Icon::new("pin").size(px(14.0)).color(sticky_fg)),
+                    )
                     .child(
                         div()
                             .id("prev-btn")
@@ -354,6 +602,27 @@ impl Render for SearchBar {
                             .min_w(px(70.0))
                             .child(match_info),
                     )
+                    .when(match_count > 0, |el| {
+                        el.child(
+                            div()
+                                .id("select-all-btn")
+                                .h(px(24.0))
+                                .px(px(8.0))
+                                .flex()
+                                .items_center()
+                                .justify_center()
+                                .rounded(px(4.0))
+                                .bg(btn_bg)
+                                .text_color(btn_fg)
+                                .text_size(px(12.0))
+                                .cursor_pointer()
+                                .hover(|s| s.bg(hover_bg))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.select_all_matches(&SelectAllMatches, window, cx);
+                                }))
+                                .child("Select All"),
+                        )
+                    })
                     .child(
                         div()
                             .id("close-search-btn")
@@ -368,6 +637,7 @@ impl Render for SearchBar {
                             .text_color(btn_fg)
                             .hover(|s| s.bg(hover_bg))
                             .on_click(cx.listener(|this, _, _, cx| {
+                                this.commit_history(false, cx);
                                 if let Some(cb) = &this.dismiss_callback {
                                     cb(cx);
                                 }
@@ -405,6 +675,7 @@ impl Render for SearchBar {
                                 .cursor_pointer()
                                 .hover(|s| s.bg(hover_bg))
                                 .on_click(cx.listener(|this, _, _, cx| {
+                                    this.commit_history(true, cx);
                                     let replacement =
                                         this.replace_input.read(cx).content().to_string();
                                     if let Some(editor) = &this.editor {
@@ -432,6 +703,7 @@ impl Render for SearchBar {
                                 .cursor_pointer()
                                 .hover(|s| s.bg(hover_bg))
                                 .on_click(cx.listener(|this, _, _, cx| {
+                                    this.commit_history(true, cx);
                                     let replacement =
                                         this.replace_input.read(cx).content().to_string();
                                     if let Some(editor) = &this.editor {
@@ -448,3 +720,40 @@ impl Render for SearchBar {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SearchHistory;
+
+    #[test]
+    fn test_search_history_record_and_navigate() {
+        let mut history = SearchHistory::new(Vec::new());
+        assert!(history.record("foo", 3));
+        assert!(!history.record("foo", 3));
+        assert!(!history.record("", 3));
+        history.record("bar", 3);
+        history.record("baz", 3);
+        history.record("foo", 3);
+        history.record("qux", 3);
+        assert_eq!(history.entries(), ["qux", "foo", "baz"]);
+
+        assert_eq!(history.newer(), None);
+        assert_eq!(history.older("dra").as_deref(), Some("qux"));
+        assert_eq!(history.older("qux").as_deref(), Some("foo"));
+        assert_eq!(history.older("foo").as_deref(), Some("baz"));
+        assert_eq!(history.older("baz").as_deref(), Some("baz"));
+        assert_eq!(history.newer().as_deref(), Some("foo"));
+        assert_eq!(history.newer().as_deref(), Some("qux"));
+        assert_eq!(history.newer().as_deref(), Some("dra"));
+        assert_eq!(history.newer(), None);
+
+        history.older("edit");
+        history.sync("qux");
+        assert_eq!(history.older("qux").as_deref(), Some("foo"));
+        history.sync("food");
+        assert_eq!(history.older("food").as_deref(), Some("qux"));
+
+        history.set_limit(1);
+        assert_eq!(history.entries(), ["qux"]);
+    }
+}
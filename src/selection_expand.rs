@@ -0,0 +1,189 @@
+use std::ops::Range;
+use tree_sitter::Tree;
+
+pub fn expand_range(
+    tree: Option<&Tree>,
+    text: &str,
+    range: Range<usize>,
+    word_chars: &str,
+) -> Option<Range<usize>> {
+    if range.is_empty() {
+        if let Some(word) = word_range_with(text, range.start, word_chars) {
+            return Some(word);
+        }
+    }
+    tree.and_then(|tree| expand_syntax(tree, &range))
+        .or_else(|| expand_lines(text, &range, word_chars))
+}
+
+fn contains(outer: &Range<usize>, inner: &Range<usize>) -> bool {
+    outer.start <= inner.start && outer.end >= inner.end && outer != inner
+}
+
+fn expand_syntax(tree: &Tree, range: &Range<usize>) -> Option<Range<usize>> {
+    let mut node = tree
+        .root_node()
+        .descendant_for_byte_range(range.start, range.end)?;
+    loop {
+        let node_range = node.byte_range();
+        if contains(&node_range, range) {
+            return Some(node_range);
+        }
+        node = node.parent()?;
+    }
+}
+
+fn is_word_char(c: char, extra: &str) -> bool {
+    c.is_alphanumeric() || c == '_' || extra.contains(c)
+}
+
+pub fn word_range_at(text: &str, pos: usize) -> Option<Range<usize>> {
+    word_range_with(text, pos, "")
+}
+
+pub fn word_range_with(text: &str, pos: usize, extra: &str) -> Option<Range<usize>> {
+    let start = text[..pos]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word_char(*c, extra))
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(pos);
+    let end = pos
+        + text[pos..]
+            .chars()
+            .take_while(|c| is_word_char(*c, extra))
+            .map(char::len_utf8)
+            .sum::<usize>();
+    (start < end).then_some(start..end)
+}
+
+fn expand_lines(text: &str, range: &Range<usize>, word_chars: &str) -> Option<Range<usize>> {
+    if range.is_empty() {
+        if let Some(word) = word_range_with(text, range.start, word_chars) {
+            return Some(word);
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in text.split('\n') {
+        lines.push(offset..offset + line.len());
+        offset += line.len() + 1;
+    }
+    let line_of = |pos: usize| {
+        lines
+            .iter()
+            .position(|l| pos <= l.end)
+            .unwrap_or(lines.len() - 1)
+    };
+    let first = line_of(range.start);
+    let mut last = line_of(range.end);
+    if last > first && range.end == lines[last].start {
+        last -= 1;
+    }
+
+    if first == last {
+        let line = &text[lines[first].clone()];
+        let trimmed = lines[first].start + (line.len() - line.trim_start().len())
+            ..lines[first].start + line.trim_end().len();
+        if !trimmed.is_empty() && contains(&trimmed, range) {
+            return Some(trimmed);
+        }
+    }
+    let full = lines[first].start..lines[last].end;
+    if contains(&full, range) {
+        return Some(full);
+    }
+
+    let indent = |i: usize| {
+        let line = &text[lines[i].clone()];
+        (!line.trim().is_empty()).then(|| line.len() - line.trim_start().len())
+    };
+    if let Some(base) = (first..=last).filter_map(indent).min() {
+        let (mut lo, mut hi) = (first, last);
+        while lo > 0 && indent(lo - 1).is_none_or(|d| d >= base) {
+            lo -= 1;
+        }
+        while hi + 1 < lines.len() && indent(hi + 1).is_none_or(|d| d >= base) {
+            hi += 1;
+        }
+        while lo < first && indent(lo).is_none() {
+            lo += 1;
+        }
+        while hi > last && indent(hi).is_none() {
+            hi -= 1;
+        }
+        if (lo, hi) != (first, last) {
+            return Some(lines[lo].start..lines[hi].end);
+        }
+
+        if let Some(header) = (0..lo).rev().find(|&i| indent(i).is_some_and(|d| d < base)) {
+            let header_indent = indent(header).unwrap_or(0);
+            let mut end = hi;
+            while end + 1 < lines.len() && indent(end + 1).is_none_or(|d| d > header_indent) {
+                end += 1;
+            }
+            while end > hi && indent(end).is_none() {
+                end -= 1;
+            }
+            if end + 1 < lines.len() && indent(end + 1) == Some(header_indent) {
+                let next = text[lines[end + 1].clone()].trim_start();
+                if next.starts_with(['}', ')', ']']) || next.starts_with("end") {
+                    end += 1;
+                }
+            }
+            return Some(lines[header].start..lines[end].end);
+        }
+    }
+
+    let all = 0..text.len();
+    contains(&all, range).then_some(all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_range;
+    use adabraka_ui::components::editor::Language;
+
+    #[test]
+    fn test_expand_range_chain() {
+        let text = "fn f() {\n    call(\"hello world\", x);\n}\n";
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&Language::Rust.tree_sitter_language().unwrap())
+            .unwrap();
+        let tree = parser.parse(text, None).unwrap();
+        let start = text.find("ello").unwrap();
+        let mut stack = Vec::new();
+        let mut range = start..start;
+        while let Some(next) = expand_range(Some(&tree), text, range.clone(), "") {
+            stack.push(range);
+            range = next;
+        }
+        stack.push(range);
+        let chain: Vec<&str> = stack[1..].iter().map(|r| &text[r.clone()]).collect();
+        assert_eq!(
+            chain[..5],
+            [
+                "hello",
+                "hello world",
+                "\"hello world\"",
+                "(\"hello world\", x)",
+                "call(\"hello world\", x)",
+            ]
+        );
+        assert_eq!(chain[6], "{\n    call(\"hello world\", x);\n}");
+
+        stack.truncate(3);
+        assert_eq!(&text[stack.pop().unwrap()], "hello world");
+        assert_eq!(&text[stack.pop().unwrap()], "hello");
+        assert_eq!(stack.pop(), Some(start..start));
+
+        let plain = "if x {\n    a-b\n}\n";
+        let at = plain.find('b').unwrap();
+        assert_eq!(expand_range(None, plain, at..at, "-"), Some(11..14));
+        assert_eq!(expand_range(None, plain, 11..14, ""), Some(7..14));
+        assert_eq!(expand_range(None, plain, 7..14, ""), Some(0..16));
+    }
+}
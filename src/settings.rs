@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+const MAX_RECENT_FOLDERS: usize = 10;
+const MAX_RECENT_FILES: usize = 10;
+const PROJECT_SETTINGS_PATH: &str = ".shiori/settings.json";
+const COMMAND_SETTINGS: [&str; 2] = ["language_servers", "formatters"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShioriSettings {
@@ -16,19 +22,474 @@ pub struct ShioriSettings {
     pub editor_font: String,
     #[serde(default)]
     pub language_servers: HashMap<String, LanguageServerConfig>,
+    #[serde(default = "default_search_history_limit")]
+    pub search_history_limit: usize,
+    #[serde(default = "default_search_max_results")]
+    pub search_max_results: usize,
+    #[serde(default = "default_search_max_file_size_mb")]
+    pub search_max_file_size_mb: u64,
+    #[serde(default = "default_search_context_lines")]
+    pub search_context_lines: usize,
+    #[serde(default)]
+    pub search_history: Vec<String>,
+    #[serde(default)]
+    pub replace_history: Vec<String>,
+    #[serde(default)]
+    pub content_search_history: Vec<String>,
+    #[serde(default)]
+    pub recent_folders: Vec<PathBuf>,
+    #[serde(default)]
+    pub recent_files: Vec<PathBuf>,
+    #[serde(default)]
+    pub startup: StartupBehavior,
+    #[serde(default)]
+    pub startup_folder: Option<PathBuf>,
+    #[serde(default)]
+    pub favorite_folders: Vec<PathBuf>,
+    #[serde(default)]
+    pub expanded_folders: HashMap<PathBuf, Vec<PathBuf>>,
+    #[serde(default = "default_true")]
+    pub commit_lint_enabled: bool,
+    #[serde(default = "default_commit_subject_limit")]
+    pub commit_subject_limit: usize,
+    #[serde(default = "default_true")]
+    pub conventional_commit_prefixes: bool,
+    #[serde(default)]
+    pub diff_ignore_whitespace: bool,
+    #[serde(default)]
+    pub diff_ignore_blank_lines: bool,
+    #[serde(default = "default_true")]
+    pub diff_word_level: bool,
+    #[serde(default = "default_true")]
+    pub terminal_line_wrap: bool,
+    #[serde(default = "default_true")]
+    pub terminal_tab_strip: bool,
+    #[serde(default = "default_terminal_scrollback")]
+    pub terminal_scrollback: usize,
+    #[serde(default = "default_true")]
+    pub terminal_confirm_multiline_paste: bool,
+    #[serde(default)]
+    pub scrollbar_visibility: ScrollbarVisibility,
+    #[serde(default)]
+    pub line_numbers: LineNumbers,
+    #[serde(default = "default_gutter_items")]
+    pub gutter_items: Vec<GutterItem>,
+    #[serde(default)]
+    pub tab_overflow: TabOverflow,
+    #[serde(default)]
+    pub tree_sort: TreeSort,
+    #[serde(default = "default_true")]
+    pub tree_folders_first: bool,
+    #[serde(default)]
+    pub delete_behavior: DeleteBehavior,
+    #[serde(default = "default_undo_history_limit")]
+    pub undo_history_limit: usize,
+    #[serde(default)]
+    pub undo_grouping: UndoGrouping,
+    #[serde(default = "default_undo_max_entries")]
+    pub undo_max_entries: usize,
+    #[serde(default)]
+    pub indent: IndentConfig,
+    #[serde(default = "default_language_indent")]
+    pub language_indent: HashMap<String, IndentConfig>,
+    #[serde(default)]
+    pub strip_bom_on_save: bool,
+    #[serde(default)]
+    pub insert_final_newline: bool,
+    #[serde(default = "default_word_chars")]
+    pub word_chars: HashMap<String, String>,
+    #[serde(default = "default_auto_pairs")]
+    pub auto_pairs: HashMap<String, Vec<(String, String)>>,
+    #[serde(default = "default_true")]
+    pub indent_guides: bool,
+    #[serde(default = "default_true")]
+    pub indent_guide_active_highlight: bool,
+    #[serde(default = "default_true")]
+    pub bracket_pair_guides: bool,
+    #[serde(default = "default_true")]
+    pub gutter_click_selects_lines: bool,
+    #[serde(default = "default_completion_max_items")]
+    pub completion_max_items: usize,
+    #[serde(default = "default_preferred_line_length")]
+    pub preferred_line_length: usize,
+    #[serde(default = "default_true")]
+    pub hover_enabled: bool,
+    #[serde(default = "default_hover_delay_ms")]
+    pub hover_delay_ms: u64,
+    #[serde(default)]
+    pub formatters: HashMap<String, FormatterConfig>,
+    #[serde(default)]
+    pub format_on_save: bool,
+    #[serde(default)]
+    pub paste_normalize_indent: bool,
+    #[serde(default)]
+    pub search_sticky: bool,
+    #[serde(default = "default_editor_line_height")]
+    pub editor_line_height: f32,
+    #[serde(default = "default_zoom_level", alias = "zoom_level")]
+    pub editor_zoom: f32,
+    #[serde(default = "default_zoom_level")]
+    pub terminal_zoom: f32,
+    #[serde(default)]
+    pub editor_padding_left: f32,
+    #[serde(default)]
+    pub editor_padding_top: f32,
+    #[serde(default)]
+    pub keybindings: HashMap<String, String>,
+    #[serde(default)]
+    pub outline_docked: bool,
+    #[serde(skip)]
+    global_values: Map<String, Value>,
+}
+
+fn cycle<T: Copy + PartialEq>(all: &[T], value: T, delta: i32) -> T {
+    let len = all.len() as i32;
+    let idx = all.iter().position(|v| *v == value).unwrap_or(0) as i32;
+    all[(idx + delta).rem_euclid(len) as usize]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScrollbarVisibility {
+    #[default]
+    Always,
+    OnHover,
+    OnScroll,
+    Hidden,
+}
+
+impl ScrollbarVisibility {
+    const ALL: [ScrollbarVisibility; 4] = [
+        ScrollbarVisibility::Always,
+        ScrollbarVisibility::OnHover,
+        ScrollbarVisibility::OnScroll,
+        ScrollbarVisibility::Hidden,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScrollbarVisibility::Always => "Always",
+            ScrollbarVisibility::OnHover => "On Hover",
+            ScrollbarVisibility::OnScroll => "On Scroll",
+            ScrollbarVisibility::Hidden => "Hidden",
+        }
+    }
+
+    pub fn cycle(self, delta: i32) -> Self {
+        cycle(&Self::ALL, self, delta)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LineNumbers {
+    #[default]
+    Absolute,
+    Relative,
+    Hybrid,
+}
+
+impl LineNumbers {
+    const ALL: [LineNumbers; 3] = [
+        LineNumbers::Absolute,
+        LineNumbers::Relative,
+        LineNumbers::Hybrid,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineNumbers::Absolute => "Absolute",
+            LineNumbers::Relative => "Relative",
+            LineNumbers::Hybrid => "Hybrid",
+        }
+    }
+
+    pub fn cycle(self, delta: i32) -> Self {
+        cycle(&Self::ALL, self, delta)
+    }
+
+    pub fn number(self, row: usize, cursor_row: usize, line: usize) -> usize {
+        match self {
+            LineNumbers::Absolute => line + 1,
+            LineNumbers::Hybrid if row == cursor_row => line + 1,
+            LineNumbers::Relative | LineNumbers::Hybrid => row.abs_diff(cursor_row),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TabOverflow {
+    #[default]
+    Arrows,
+    Dropdown,
+}
+
+impl TabOverflow {
+    const ALL: [TabOverflow; 2] = [TabOverflow::Arrows, TabOverflow::Dropdown];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TabOverflow::Arrows => "Scroll Arrows",
+            TabOverflow::Dropdown => "Dropdown List",
+        }
+    }
+
+    pub fn cycle(self, delta: i32) -> Self {
+        cycle(&Self::ALL, self, delta)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TreeSort {
+    #[default]
+    Name,
+    NameCaseSensitive,
+    Extension,
+    Modified,
+}
+
+impl TreeSort {
+    pub const ALL: [TreeSort; 4] = [
+        TreeSort::Name,
+        TreeSort::NameCaseSensitive,
+        TreeSort::Extension,
+        TreeSort::Modified,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TreeSort::Name => "Name",
+            TreeSort::NameCaseSensitive => "Name (Case Sensitive)",
+            TreeSort::Extension => "Type",
+            TreeSort::Modified => "Date Modified",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartupBehavior {
+    #[default]
+    Welcome,
+    LastSession,
+    NewFile,
+    Folder,
+}
+
+impl StartupBehavior {
+    const ALL: [StartupBehavior; 4] = [
+        StartupBehavior::Welcome,
+        StartupBehavior::LastSession,
+        StartupBehavior::NewFile,
+        StartupBehavior::Folder,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StartupBehavior::Welcome => "Welcome Screen",
+            StartupBehavior::LastSession => "Last Session",
+            StartupBehavior::NewFile => "Untitled File",
+            StartupBehavior::Folder => "Folder",
+        }
+    }
+
+    pub fn cycle(self, delta: i32) -> Self {
+        cycle(&Self::ALL, self, delta)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeleteBehavior {
+    #[default]
+    Trash,
+    Confirm,
+    Immediate,
+}
+
+impl DeleteBehavior {
+    const ALL: [DeleteBehavior; 3] = [
+        DeleteBehavior::Trash,
+        DeleteBehavior::Confirm,
+        DeleteBehavior::Immediate,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DeleteBehavior::Trash => "Move to Trash",
+            DeleteBehavior::Confirm => "Confirm, Then Delete",
+            DeleteBehavior::Immediate => "Delete Without Confirming",
+        }
+    }
+
+    pub fn cycle(self, delta: i32) -> Self {
+        cycle(&Self::ALL, self, delta)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UndoGrouping {
+    Keystroke,
+    #[default]
+    Word,
+    Line,
+}
+
+impl UndoGrouping {
+    const ALL: [UndoGrouping; 3] = [
+        UndoGrouping::Keystroke,
+        UndoGrouping::Word,
+        UndoGrouping::Line,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            UndoGrouping::Keystroke => "Per Keystroke",
+            UndoGrouping::Word => "Per Word",
+            UndoGrouping::Line => "Per Line",
+        }
+    }
+
+    pub fn cycle(self, delta: i32) -> Self {
+        cycle(&Self::ALL, self, delta)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GutterItem {
+    Diagnostics,
+    LineNumbers,
+    GitChanges,
+    Folds,
+}
+
+impl GutterItem {
+    pub const ALL: [GutterItem; 4] = [
+        GutterItem::Diagnostics,
+        GutterItem::LineNumbers,
+        GutterItem::GitChanges,
+        GutterItem::Folds,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GutterItem::Diagnostics => "Diagnostics",
+            GutterItem::LineNumbers => "Line Numbers",
+            GutterItem::GitChanges => "Git Changes",
+            GutterItem::Folds => "Fold Markers",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            GutterItem::Diagnostics => "gutter-diagnostics",
+            GutterItem::LineNumbers => "gutter-line-numbers",
+            GutterItem::GitChanges => "gutter-git-changes",
+            GutterItem::Folds => "gutter-folds",
+        }
+    }
+}
+
+fn default_gutter_items() -> Vec<GutterItem> {
+    GutterItem::ALL.to_vec()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndentConfig {
+    #[serde(default = "default_tab_size")]
+    pub tab_size: usize,
+    #[serde(default)]
+    pub hard_tabs: bool,
+}
+
+impl Default for IndentConfig {
+    fn default() -> Self {
+        Self {
+            tab_size: default_tab_size(),
+            hard_tabs: false,
+        }
+    }
+}
+
+fn default_tab_size() -> usize {
+    4
+}
+
+fn default_language_indent() -> HashMap<String, IndentConfig> {
+    let spaces = |tab_size| IndentConfig {
+        tab_size,
+        hard_tabs: false,
+    };
+    let mut map = HashMap::new();
+    map.insert(
+        "go".into(),
+        IndentConfig {
+            tab_size: 4,
+            hard_tabs: true,
+        },
+    );
+    map.insert("python".into(), spaces(4));
+    for key in ["javascript", "typescript", "html", "css", "ruby", "lua"] {
+        map.insert(key.into(), spaces(2));
+    }
+    map
+}
+
+fn default_word_chars() -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    for (key, chars) in [
+        ("css", "-"),
+        ("html", "-"),
+        ("javascript", "$"),
+        ("typescript", "$"),
+        ("ruby", "?!"),
+    ] {
+        map.insert(key.into(), chars.into());
+    }
+    map
+}
+
+fn default_auto_pairs() -> HashMap<String, Vec<(String, String)>> {
+    let mut map = HashMap::new();
+    for (key, chars) in [
+        ("default", "()[]{}\"\"''``"),
+        ("rust", "()[]{}\"\""),
+        ("html", "<>\"\"''"),
+    ] {
+        let chars: Vec<char> = chars.chars().collect();
+        let pairs = chars
+            .chunks(2)
+            .map(|pair| (pair[0].to_string(), pair[1].to_string()))
+            .collect();
+        map.insert(key.into(), pairs);
+    }
+    map
 }
 
 fn default_font() -> String {
     "JetBrains Mono".into()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LanguageServerConfig {
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
     #[serde(default = "default_true")]
     pub enabled: bool,
+    #[serde(default)]
+    pub install_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatterConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
 }
 
 fn default_theme() -> String {
@@ -39,6 +500,58 @@ fn default_true() -> bool {
     true
 }
 
+fn default_search_history_limit() -> usize {
+    50
+}
+
+fn default_search_max_results() -> usize {
+    100
+}
+
+fn default_search_max_file_size_mb() -> u64 {
+    2
+}
+
+fn default_search_context_lines() -> usize {
+    2
+}
+
+fn default_commit_subject_limit() -> usize {
+    72
+}
+
+fn default_terminal_scrollback() -> usize {
+    crate::terminal_state::DEFAULT_SCROLLBACK
+}
+
+fn default_undo_history_limit() -> usize {
+    20
+}
+
+fn default_undo_max_entries() -> usize {
+    500
+}
+
+fn default_completion_max_items() -> usize {
+    50
+}
+
+fn default_preferred_line_length() -> usize {
+    80
+}
+
+fn default_hover_delay_ms() -> u64 {
+    500
+}
+
+fn default_editor_line_height() -> f32 {
+    1.5
+}
+
+fn default_zoom_level() -> f32 {
+    1.0
+}
+
 impl Default for ShioriSettings {
     fn default() -> Self {
         Self {
@@ -48,8 +561,116 @@ impl Default for ShioriSettings {
             terminal_font_fallback: None,
             editor_font: default_font(),
             language_servers: default_language_servers(),
+            search_history_limit: default_search_history_limit(),
+            search_max_results: default_search_max_results(),
+            search_max_file_size_mb: default_search_max_file_size_mb(),
+            search_context_lines: default_search_context_lines(),
+            search_history: Vec::new(),
+            replace_history: Vec::new(),
+            content_search_history: Vec::new(),
+            recent_folders: Vec::new(),
+            recent_files: Vec::new(),
+            startup: StartupBehavior::default(),
+            startup_folder: None,
+            favorite_folders: Vec::new(),
+            expanded_folders: HashMap::new(),
+            commit_lint_enabled: true,
+            commit_subject_limit: default_commit_subject_limit(),
+            conventional_commit_prefixes: true,
+            diff_ignore_whitespace: false,
+            diff_ignore_blank_lines: false,
+            diff_word_level: true,
+            terminal_line_wrap: true,
+            terminal_tab_strip: true,
+            terminal_scrollback: default_terminal_scrollback(),
+            terminal_confirm_multiline_paste: true,
+            scrollbar_visibility: ScrollbarVisibility::default(),
+            line_numbers: LineNumbers::default(),
+            gutter_items: default_gutter_items(),
+            tab_overflow: TabOverflow::default(),
+            tree_sort: TreeSort::default(),
+            tree_folders_first: true,
+            delete_behavior: DeleteBehavior::default(),
+            undo_history_limit: default_undo_history_limit(),
+            undo_grouping: UndoGrouping::default(),
+            undo_max_entries: default_undo_max_entries(),
+            indent: IndentConfig::default(),
+            language_indent: default_language_indent(),
+            strip_bom_on_save: false,
+            insert_final_newline: false,
+            word_chars: default_word_chars(),
+            auto_pairs: default_auto_pairs(),
+            indent_guides: true,
+            indent_guide_active_highlight: true,
+            bracket_pair_guides: true,
+            gutter_click_selects_lines: true,
+            completion_max_items: default_completion_max_items(),
+            preferred_line_length: default_preferred_line_length(),
+            hover_enabled: true,
+            hover_delay_ms: default_hover_delay_ms(),
+            formatters: HashMap::new(),
+            format_on_save: false,
+            paste_normalize_indent: false,
+            search_sticky: false,
+            editor_line_height: default_editor_line_height(),
+            editor_zoom: default_zoom_level(),
+            terminal_zoom: default_zoom_level(),
+            editor_padding_left: 0.0,
+            editor_padding_top: 0.0,
+            keybindings: HashMap::new(),
+            outline_docked: false,
+            global_values: Map::new(),
+        }
+    }
+}
+
+fn merge_value(target: &mut Value, local: Value) {
+    match (target, local) {
+        (Value::Object(target), Value::Object(local)) => {
+            for (key, value) in local {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
         }
+        (target, local) => *target = local,
+    }
+}
+
+pub fn project_settings_path(root: &Path) -> PathBuf {
+    root.join(PROJECT_SETTINGS_PATH)
+}
+
+pub fn load_project_settings(root: &Path) -> Result<Map<String, Value>, serde_json::Error> {
+    match std::fs::read_to_string(project_settings_path(root)) {
+        Ok(contents) => serde_json::from_str(&contents),
+        Err(_) => Ok(Map::new()),
+    }
+}
+
+pub fn strip_command_settings(settings: &mut Map<String, Value>) -> Vec<String> {
+    COMMAND_SETTINGS
+        .iter()
+        .filter(|key| settings.remove(**key).is_some())
+        .map(|key| key.to_string())
+        .collect()
+}
+
+pub fn save_project_setting(root: &Path, key: &str, value: Option<Value>) -> std::io::Result<()> {
+    let path = project_settings_path(root);
+    let mut settings = load_project_settings(root).unwrap_or_default();
+    match value {
+        Some(value) => settings.insert(key.to_string(), value),
+        None => settings.remove(key),
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
     }
+    let json = serde_json::to_string_pretty(&settings).map_err(std::io::Error::other)?;
+    std::fs::write(path, json)
 }
 
 fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
@@ -60,6 +681,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "rust-analyzer".into(),
             args: vec![],
             enabled: true,
+            install_command: Some("rustup component add rust-analyzer".into()),
         },
     );
     map.insert(
@@ -68,6 +690,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "typescript-language-server".into(),
             args: vec!["--stdio".into()],
             enabled: true,
+            install_command: Some("npm install -g typescript-language-server typescript".into()),
         },
     );
     map.insert(
@@ -76,6 +699,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "pyright-langserver".into(),
             args: vec!["--stdio".into()],
             enabled: true,
+            install_command: Some("npm install -g pyright".into()),
         },
     );
     map.insert(
@@ -84,6 +708,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "gopls".into(),
             args: vec![],
             enabled: true,
+            install_command: Some("go install golang.org/x/tools/gopls@latest".into()),
         },
     );
     map.insert(
@@ -92,6 +717,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "clangd".into(),
             args: vec![],
             enabled: true,
+            install_command: Some("brew install llvm".into()),
         },
     );
     map.insert(
@@ -100,6 +726,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "lua-language-server".into(),
             args: vec![],
             enabled: true,
+            install_command: Some("brew install lua-language-server".into()),
         },
     );
     map.insert(
@@ -108,6 +735,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "zls".into(),
             args: vec![],
             enabled: true,
+            install_command: Some("brew install zls".into()),
         },
     );
     map.insert(
@@ -116,6 +744,7 @@ fn default_language_servers() -> HashMap<String, LanguageServerConfig> {
             command: "bash-language-server".into(),
             args: vec!["start".into()],
             enabled: true,
+            install_command: Some("npm install -g bash-language-server".into()),
         },
     );
     map
@@ -136,10 +765,12 @@ impl ShioriSettings {
             None => return Self::default(),
         };
 
-        match std::fs::read_to_string(&path) {
+        let mut settings: Self = match std::fs::read_to_string(&path) {
             Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
             Err(_) => Self::default(),
-        }
+        };
+        settings.prune_missing_recent();
+        settings
     }
 
     pub fn save(&self) {
@@ -156,14 +787,214 @@ impl ShioriSettings {
             return;
         }
 
-        if let Ok(json) = serde_json::to_string_pretty(self) {
+        let Ok(mut value) = serde_json::to_value(self) else {
+            return;
+        };
+        if let Some(obj) = value.as_object_mut() {
+            for (key, global) in &self.global_values {
+                obj.insert(key.clone(), global.clone());
+            }
+        }
+        if let Ok(json) = serde_json::to_string_pretty(&value) {
             let _ = std::fs::write(&path, json);
         }
     }
 
+    pub fn apply_local_overrides(
+        &mut self,
+        overrides: &Map<String, Value>,
+    ) -> Result<Vec<String>, serde_json::Error> {
+        self.clear_local_overrides();
+        let mut value = serde_json::to_value(&*self)?;
+        let Some(obj) = value.as_object_mut() else {
+            return Ok(Vec::new());
+        };
+        let mut unknown = Vec::new();
+        let mut global_values = Map::new();
+        for (key, local) in overrides {
+            match obj.get_mut(key) {
+                Some(current) => {
+                    let mut merged = current.clone();
+                    merge_value(&mut merged, local.clone());
+                    global_values.insert(key.clone(), std::mem::replace(current, merged));
+                }
+                None => unknown.push(key.clone()),
+            }
+        }
+        let mut merged: ShioriSettings = serde_json::from_value(value)?;
+        merged.global_values = global_values;
+        *self = merged;
+        Ok(unknown)
+    }
+
+    pub fn clear_local_overrides(&mut self) {
+        if self.global_values.is_empty() {
+            return;
+        }
+        let Ok(mut value) = serde_json::to_value(&*self) else {
+            return;
+        };
+        if let Some(obj) = value.as_object_mut() {
+            for (key, global) in std::mem::take(&mut self.global_values) {
+                obj.insert(key, global);
+            }
+        }
+        if let Ok(restored) = serde_json::from_value(value) {
+            *self = restored;
+        }
+    }
+
+    pub fn has_local_override(&self, key: &str) -> bool {
+        self.global_values.contains_key(key)
+    }
+
+    pub fn set_local_override(&mut self, key: &str, global: Value) {
+        self.global_values.insert(key.to_string(), global);
+    }
+
+    pub fn clear_local_override(&mut self, key: &str) {
+        let Some(global) = self.global_values.remove(key) else {
+            return;
+        };
+        let Ok(mut value) = serde_json::to_value(&*self) else {
+            return;
+        };
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert(key.to_string(), global);
+        }
+        if let Ok(mut restored) = serde_json::from_value::<ShioriSettings>(value) {
+            restored.global_values = std::mem::take(&mut self.global_values);
+            *self = restored;
+        }
+    }
+
+    pub fn local_overrides(&self) -> Map<String, Value> {
+        let Ok(Value::Object(obj)) = serde_json::to_value(self) else {
+            return Map::new();
+        };
+        self.global_values
+            .keys()
+            .filter_map(|key| obj.get(key).map(|v| (key.clone(), v.clone())))
+            .collect()
+    }
+
+    pub fn push_recent_folder(&mut self, path: PathBuf) {
+        self.recent_folders.retain(|p| p != &path && p.is_dir());
+        self.recent_folders.insert(0, path);
+        self.recent_folders.truncate(MAX_RECENT_FOLDERS);
+    }
+
+    pub fn push_recent_file(&mut self, path: PathBuf) {
+        self.recent_files.retain(|p| p != &path && p.is_file());
+        self.recent_files.insert(0, path);
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    fn prune_missing_recent(&mut self) {
+        self.recent_folders.retain(|p| p.is_dir());
+        self.recent_files.retain(|p| p.is_file());
+    }
+
+    pub fn remember_expanded(&mut self, root: &Path, expanded: Vec<PathBuf>) {
+        let recent = &self.recent_folders;
+        self.expanded_folders.retain(|key, _| recent.contains(key));
+        self.expanded_folders.insert(root.to_path_buf(), expanded);
+    }
+
+    pub fn is_favorite_folder(&self, path: &Path) -> bool {
+        self.favorite_folders.iter().any(|p| p == path)
+    }
+
+    pub fn toggle_favorite_folder(&mut self, path: &Path) {
+        if self.is_favorite_folder(path) {
+            self.favorite_folders.retain(|p| p != path);
+        } else {
+            self.favorite_folders.push(path.to_path_buf());
+        }
+    }
+
+    pub fn install_command_for(&self, language_key: &str) -> Option<String> {
+        self.language_servers
+            .get(language_key)
+            .and_then(|c| c.install_command.clone())
+            .or_else(|| {
+                default_language_servers()
+                    .remove(language_key)
+                    .and_then(|c| c.install_command)
+            })
+    }
+
+    pub fn indent_for(&self, language_key: &str) -> IndentConfig {
+        if self.has_local_override("indent") {
+            return self.indent;
+        }
+        self.language_indent
+            .get(language_key)
+            .copied()
+            .unwrap_or(self.indent)
+    }
+
+    pub fn word_chars_for(&self, language_key: &str) -> &str {
+        self.word_chars
+            .get(language_key)
+            .map(String::as_str)
+            .unwrap_or("")
+    }
+
+    pub fn auto_pairs_for(&self, language_key: &str) -> &[(String, String)] {
+        self.auto_pairs
+            .get(language_key)
+            .or_else(|| self.auto_pairs.get("default"))
+            .map_or(&[], Vec::as_slice)
+    }
+
     pub fn server_config_for(&self, language_key: &str) -> Option<&LanguageServerConfig> {
         self.language_servers
             .get(language_key)
             .filter(|c| c.enabled)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{strip_command_settings, ShioriSettings};
+    use serde_json::json;
+
+    #[test]
+    fn test_local_overrides_merge_language_servers() {
+        let mut settings = ShioriSettings::default();
+        let overrides = json!({
+            "lsp_enabled": true,
+            "language_servers": { "rust": { "command": "ra-multiplex" } },
+        });
+        let unknown = settings
+            .apply_local_overrides(overrides.as_object().unwrap())
+            .unwrap();
+        assert!(unknown.is_empty());
+        assert!(settings.lsp_enabled);
+        let rust = &settings.language_servers["rust"];
+        assert_eq!(rust.command, "ra-multiplex");
+        assert!(rust.enabled);
+        assert!(settings.language_servers.contains_key("python"));
+
+        settings.clear_local_override("lsp_enabled");
+        assert!(!settings.lsp_enabled);
+        assert_eq!(settings.language_servers["rust"].command, "ra-multiplex");
+
+        settings.clear_local_overrides();
+        assert_eq!(settings.language_servers["rust"].command, "rust-analyzer");
+    }
+
+    #[test]
+    fn test_strip_command_settings_from_project_files() {
+        let mut project = json!({
+            "tab_size": 2,
+            "formatters": { "rust": { "command": "rustfmt" } },
+            "language_servers": { "rust": { "command": "sh" } },
+        });
+        let project = project.as_object_mut().unwrap();
+        let stripped = strip_command_settings(project);
+        assert_eq!(stripped, vec!["language_servers", "formatters"]);
+        assert_eq!(project.keys().collect::<Vec<_>>(), vec!["tab_size"]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    pub text: String,
+    pub stops: Vec<Range<usize>>,
+}
+
+pub fn parse_snippet(body: &str, indent: &str, unit: &str) -> Snippet {
+    let mut text = String::new();
+    let mut numbered: Vec<(usize, Range<usize>)> = Vec::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('$' | '}' | '\\')) => {
+                text.extend(chars.next());
+            }
+            '\n' => {
+                text.push('\n');
+                text.push_str(indent);
+            }
+            '\t' => text.push_str(unit),
+            '$' => {
+                let braced = chars.peek() == Some(&'{');
+                if braced {
+                    chars.next();
+                }
+                let mut digits = String::new();
+                while let Some(d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(*d);
+                    chars.next();
+                }
+                let Ok(index) = digits.parse::<usize>() else {
+                    text.push('$');
+                    if braced {
+                        text.push('{');
+                    }
+                    continue;
+                };
+                let start = text.len();
+                let mut has_placeholder = false;
+                if braced {
+                    if chars.peek() == Some(&':') {
+                        chars.next();
+                        has_placeholder = true;
+                        for p in chars.by_ref() {
+                            if p == '}' {
+                                break;
+                            }
+                            text.push(p);
+                        }
+                    } else if chars.peek() == Some(&'}') {
+                        chars.next();
+                    }
+                }
+                match numbered.iter().find(|(i, _)| *i == index) {
+                    Some((_, first)) if !has_placeholder => {
+                        let mirror = text[first.clone()].to_string();
+                        text.push_str(&mirror);
+                    }
+                    Some(_) => {}
+                    None => numbered.push((index, start..text.len())),
+                }
+            }
+            c => text.push(c),
+        }
+    }
+    numbered.sort_by_key(|(i, _)| if *i == 0 { usize::MAX } else { *i });
+    let mut stops: Vec<Range<usize>> = numbered.into_iter().map(|(_, r)| r).collect();
+    if stops.is_empty() || stops.len() == 1 && stops[0].end == text.len() {
+        stops.clear();
+    } else if !body.contains("$0") && !body.contains("${0") {
+        stops.push(text.len()..text.len());
+    }
+    Snippet { text, stops }
+}
+
+pub struct TabStops {
+    stops: Vec<Range<usize>>,
+    current: usize,
+}
+
+impl TabStops {
+    pub fn new(base: usize, snippet: &Snippet) -> Option<Self> {
+        let stops: Vec<Range<usize>> = snippet
+            .stops
+            .iter()
+            .map(|r| base + r.start..base + r.end)
+            .collect();
+        (!stops.is_empty()).then_some(Self { stops, current: 0 })
+    }
+
+    pub fn current(&self) -> Range<usize> {
+        self.stops[self.current].clone()
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.current + 1 == self.stops.len()
+    }
+
+    pub fn advance(&mut self, forward: bool) -> Option<Range<usize>> {
+        if forward {
+            if self.is_last() {
+                return None;
+            }
+            self.current += 1;
+        } else {
+            self.current = self.current.saturating_sub(1);
+        }
+        Some(self.current())
+    }
+
+    pub fn apply_edit(&mut self, old: Range<usize>, new_len: usize) {
+        let delta = new_len as isize - old.len() as isize;
+        let shift = |pos: usize| (pos as isize + delta).max(0) as usize;
+        for (i, stop) in self.stops.iter_mut().enumerate() {
+            let inside = old.start >= stop.start && old.end <= stop.end;
+            if i == self.current && inside {
+                stop.end = shift(stop.end);
+            } else if old.end <= stop.start {
+                *stop = shift(stop.start)..shift(stop.end);
+            } else if old.start >= stop.end {
+                continue;
+            } else {
+                let start = stop.start.min(old.start);
+                *stop = start..shift(stop.end).max(old.start + new_len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_snippet, TabStops};
+
+    #[test]
+    fn test_parse_snippet_orders_stops_and_indents() {
+        let snippet = parse_snippet("fn ${1:name}($2) {\n\t$0\n}", "    ", "  ");
+        assert_eq!(snippet.text, "fn name() {\n      \n    }");
+        assert_eq!(snippet.stops, vec![3..7, 8..8, 18..18]);
+
+        let plain = parse_snippet("cost \\$5 $", "", "\t");
+        assert_eq!(plain.text, "cost $5 $");
+        assert!(plain.stops.is_empty());
+    }
+
+    #[test]
+    fn test_tab_stops_follow_edits() {
+        let snippet = parse_snippet("for ${1:x} in $2 {}", "", "\t");
+        let mut stops = TabStops::new(10, &snippet).unwrap();
+        assert_eq!(stops.current(), 14..15);
+
+        stops.apply_edit(14..15, 4);
+        assert_eq!(stops.current(), 14..18);
+        assert_eq!(stops.advance(true), Some(22..22));
+
+        stops.apply_edit(22..22, 3);
+        assert_eq!(stops.current(), 22..25);
+        assert_eq!(stops.advance(true), Some(28..28));
+        assert_eq!(stops.advance(true), None);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::settings::ShioriSettings;
+
+#[derive(Debug, Error)]
+pub enum SnippetLibraryError {
+    #[error("Failed to read snippets from {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+    #[error("Invalid snippets in {0}: {1}")]
+    Parse(PathBuf, serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserSnippet {
+    pub prefix: String,
+    pub description: Option<String>,
+    pub body: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnippetBody {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl SnippetBody {
+    fn into_text(self) -> String {
+        match self {
+            SnippetBody::Text(text) => text,
+            SnippetBody::Lines(lines) => lines.join("\n"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnippetEntry {
+    Body(SnippetBody),
+    Full {
+        body: SnippetBody,
+        #[serde(default)]
+        description: Option<String>,
+    },
+}
+
+pub fn snippets_dir() -> Option<PathBuf> {
+    ShioriSettings::config_dir().map(|d| d.join("snippets"))
+}
+
+pub fn parse_snippets(json: &str) -> Result<Vec<UserSnippet>, serde_json::Error> {
+    let entries: BTreeMap<String, SnippetEntry> = serde_json::from_str(json)?;
+    Ok(entries
+        .into_iter()
+        .map(|(prefix, entry)| {
+            let (body, description) = match entry {
+                SnippetEntry::Body(body) => (body, None),
+                SnippetEntry::Full { body, description } => (body, description),
+            };
+            UserSnippet {
+                prefix,
+                description,
+                body: body.into_text(),
+            }
+        })
+        .collect())
+}
+
+pub fn load_snippet_library(
+    dir: &Path,
+) -> (HashMap<String, Vec<UserSnippet>>, Vec<SnippetLibraryError>) {
+    let mut library = HashMap::new();
+    let mut errors = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (library, errors);
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(language) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                errors.push(SnippetLibraryError::Read(path.clone(), err));
+                continue;
+            }
+        };
+        match parse_snippets(&contents) {
+            Ok(snippets) => {
+                library.insert(language.to_string(), snippets);
+            }
+            Err(err) => errors.push(SnippetLibraryError::Parse(path.clone(), err)),
+        }
+    }
+    (library, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_snippets, UserSnippet};
+
+    #[test]
+    fn test_parse_snippets_accepts_text_lines_and_descriptions() {
+        let json = r##"{
+            "log": "println!(\"{:?}\", $1);",
+            "tst": ["#[test]", "fn ${1:name}() {", "\t$0", "}"],
+            "todo": { "body": "// TODO: $0", "description": "todo comment" }
+        }"##;
+        let snippets = parse_snippets(json).unwrap();
+        let expected = vec![
+            UserSnippet {
+                prefix: "log".into(),
+                description: None,
+                body: "println!(\"{:?}\", $1);".into(),
+            },
+            UserSnippet {
+                prefix: "todo".into(),
+                description: Some("todo comment".into()),
+                body: "// TODO: $0".into(),
+            },
+            UserSnippet {
+                prefix: "tst".into(),
+                description: None,
+                body: "#[test]\nfn ${1:name}() {\n\t$0\n}".into(),
+            },
+        ];
+        assert_eq!(snippets, expected);
+        assert!(parse_snippets("{\"bad\": 3}").is_err());
+    }
+}
@@ -8,7 +8,8 @@ pub type HyperlinkRef = Arc<String>;
 
 pub const DEFAULT_COLS: usize = 80;
 pub const DEFAULT_ROWS: usize = 24;
-pub const DEFAULT_SCROLLBACK: usize = 5000;
+pub const DEFAULT_SCROLLBACK: usize = 10_000;
+pub const MAX_SCROLLBACK: usize = 200_000;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct CellStyle {
@@ -345,6 +346,8 @@ pub struct TerminalState {
     rows: usize,
     scroll_offset: usize,
     max_scrollback: usize,
+    lines_evicted: usize,
+    reflow: bool,
     working_directory: PathBuf,
     is_running: bool,
     current_style: CellStyle,
@@ -427,6 +430,8 @@ impl TerminalState {
             rows,
             scroll_offset: 0,
             max_scrollback: DEFAULT_SCROLLBACK,
+            lines_evicted: 0,
+            reflow: true,
             working_directory: std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
             is_running: false,
             current_style: CellStyle::default(),
@@ -806,6 +811,46 @@ impl TerminalState {
         self.lines.get(index)
     }
 
+    pub fn set_reflow(&mut self, reflow: bool) {
+        self.reflow = reflow;
+    }
+
+    pub fn lines_evicted(&self) -> usize {
+        self.lines_evicted
+    }
+
+    pub fn find_matches(&self, query: &str, case_sensitive: bool) -> Vec<(usize, usize, usize)> {
+        let fold = |c: char| {
+            if case_sensitive {
+                c
+            } else {
+                c.to_lowercase().next().unwrap_or(c)
+            }
+        };
+        let needle: Vec<char> = query.chars().map(fold).collect();
+        if needle.is_empty() || self.use_alt_screen {
+            return Vec::new();
+        }
+
+        let mut matches = Vec::new();
+        for (idx, line) in self.lines.iter().enumerate() {
+            if line.cells.len() < needle.len() {
+                continue;
+            }
+            let haystack: Vec<char> = line.cells.iter().map(|c| fold(c.char)).collect();
+            let mut col = 0;
+            while col + needle.len() <= haystack.len() {
+                if haystack[col..col + needle.len()] == needle[..] {
+                    matches.push((idx, col, col + needle.len()));
+                    col += needle.len();
+                } else {
+                    col += 1;
+                }
+            }
+        }
+        matches
+    }
+
     fn viewport_to_absolute(&self, row: usize) -> usize {
         let total = self.lines.len();
         total.saturating_sub(self.rows) + row
@@ -952,23 +997,41 @@ impl TerminalState {
                     TerminalLine::new(self.cols),
                 );
             }
-            while self.lines.len() > self.rows + self.max_scrollback {
-                self.lines.pop_front();
-            }
+            self.trim_scrollback();
         } else {
             self.lines.push_back(TerminalLine::new(self.cols));
-            while self.lines.len() > self.rows + self.max_scrollback {
-                self.lines.pop_front();
-                for placement in &mut self.image_placements {
-                    placement.anchor_line = placement.anchor_line.saturating_sub(1);
-                }
-                self.image_placements
-                    .retain(|p| p.anchor_line + p.image.display_rows > 0);
+            if self.user_scrolled {
+                self.scroll_offset += 1;
             }
+            self.trim_scrollback();
         }
         self.mark_all_dirty();
     }
 
+    pub fn set_max_scrollback(&mut self, lines: usize) {
+        self.max_scrollback = lines.min(MAX_SCROLLBACK);
+        self.trim_scrollback();
+        self.mark_all_dirty();
+    }
+
+    fn trim_scrollback(&mut self) {
+        let excess = self
+            .lines
+            .len()
+            .saturating_sub(self.rows + self.max_scrollback);
+        if excess == 0 {
+            return;
+        }
+        self.lines.drain(..excess);
+        self.lines_evicted += excess;
+        self.image_placements
+            .retain(|p| p.anchor_line + p.image.display_rows > excess);
+        for placement in &mut self.image_placements {
+            placement.anchor_line = placement.anchor_line.saturating_sub(excess);
+        }
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll_offset());
+    }
+
     fn scroll_down_region(&mut self) {
         let top = self.scroll_region_top;
         let bottom = self.scroll_region_bottom;
@@ -1199,6 +1262,7 @@ impl TerminalState {
         let scrollback = self.lines.len().saturating_sub(self.rows);
         if scrollback > 0 {
             self.lines.drain(..scrollback);
+            self.lines_evicted += scrollback;
             self.image_placements
                 .retain(|p| p.anchor_line >= scrollback);
             for placement in &mut self.image_placements {
@@ -1464,8 +1528,16 @@ impl TerminalState {
         self.rows = rows;
 
         if !self.use_alt_screen {
-            let (new_cursor_abs, new_cursor_col) =
-                Self::reflow_lines(&mut self.lines, old_cols, cols, cursor_abs, self.cursor.col);
+            let (new_cursor_abs, new_cursor_col) = if self.reflow {
+                Self::reflow_lines(&mut self.lines, old_cols, cols, cursor_abs, self.cursor.col)
+            } else {
+                for line in &mut self.lines {
+                    if line.cells.len() < cols {
+                        line.resize(cols);
+                    }
+                }
+                (cursor_abs, self.cursor.col.min(cols.saturating_sub(1)))
+            };
 
             let max_lines = new_cursor_abs.saturating_add(rows);
             while self.lines.len() > max_lines {
@@ -1,10 +1,11 @@
 use gpui::{
-    div, img, point, px, App, ClipboardItem, Context, FocusHandle, Focusable, Font, FontStyle,
-    FontWeight, Image, ImageFormat, InteractiveElement, IntoElement, KeyDownEvent, MouseButton,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, ObjectFit, ParentElement, Pixels, Point, Render,
-    ScrollWheelEvent, SharedString, StatefulInteractiveElement, Styled, StyledImage, Subscription,
-    Timer, Window,
+    actions, div, img, point, px, App, ClipboardItem, Context, FocusHandle, Focusable, Font,
+    FontStyle, FontWeight, Image, ImageFormat, InteractiveElement, IntoElement, KeyBinding,
+    KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ObjectFit,
+    ParentElement, Pixels, Point, Render, ScrollWheelEvent, SharedString,
+    StatefulInteractiveElement, Styled, StyledImage, Subscription, Timer, Window,
 };
+use std::ops::Range;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -12,16 +13,57 @@ use std::time::{Duration, Instant};
 use crate::ide_theme::use_ide_theme;
 
 use crate::ansi_parser::{AnsiParser, ClearMode, ImageDimension, ParsedSegment};
+use crate::file_location::find_locations;
 use crate::pty_service::{key_codes, PtyService};
 use crate::terminal_state::{
     Charset, CursorStyle, ImageCellKind, TerminalLine, TerminalState, UnderlineStyle,
 };
 
+#[derive(Debug, Clone)]
+pub enum TerminalMenuTarget {
+    Url(String),
+    Path(PathBuf),
+    Selection(String),
+}
+
 const LINE_HEIGHT: f32 = 18.0;
 const DEFAULT_CHAR_WIDTH: f32 = 7.8;
 const TERMINAL_PADDING: f32 = 8.0;
 const CURSOR_BLINK_INTERVAL_MS: u64 = 530;
 
+type LineLocations = Vec<(Range<usize>, Option<(PathBuf, usize, usize)>)>;
+
+actions!(terminal, [ToggleTerminalSearch]);
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new(
+        "cmd-f",
+        ToggleTerminalSearch,
+        Some("Terminal"),
+    )]);
+}
+
+#[derive(Default)]
+struct TerminalSearch {
+    query: String,
+    case_sensitive: bool,
+    matches: Vec<(usize, usize, usize)>,
+    active: Option<usize>,
+    lines_evicted: usize,
+}
+
+impl TerminalSearch {
+    fn matches_on_line(&self, line: usize) -> Vec<(usize, usize, bool)> {
+        let start = self.matches.partition_point(|m| m.0 < line);
+        self.matches[start..]
+            .iter()
+            .take_while(|m| m.0 == line)
+            .enumerate()
+            .map(|(i, m)| (m.1, m.2, self.active == Some(start + i)))
+            .collect()
+    }
+}
+
 pub struct TerminalView {
     state: TerminalState,
     parser: AnsiParser,
@@ -32,6 +74,7 @@ pub struct TerminalView {
     last_blink_time: Instant,
     selection_start: Option<(usize, usize)>,
     selection_end: Option<(usize, usize)>,
+    selection_evicted: usize,
     is_selecting: bool,
     viewport_height: f32,
     viewport_width: f32,
@@ -55,6 +98,17 @@ pub struct TerminalView {
     pub line_height: f32,
     pub font_family: String,
     pub font_fallback: Option<String>,
+    search: Option<TerminalSearch>,
+    confirm_multiline_paste: bool,
+    pending_paste: Option<String>,
+    paste_confirm_disabled: bool,
+    pending_context_menu: Option<(Point<Pixels>, TerminalMenuTarget)>,
+    pending_link: Option<String>,
+    hovered_link: Option<(Point<Pixels>, String)>,
+    pending_location: Option<(PathBuf, usize, usize)>,
+    hovered_location: Option<(usize, Range<usize>)>,
+    location_cache: Option<(usize, String, LineLocations)>,
+    git_command_sent: bool,
 }
 
 impl TerminalView {
@@ -76,6 +130,40 @@ impl TerminalView {
         self.last_resize = None;
     }
 
+    pub fn set_line_wrap(&mut self, wrap: bool) {
+        self.state.set_reflow(wrap);
+    }
+
+    pub fn set_confirm_multiline_paste(&mut self, confirm: bool) {
+        self.confirm_multiline_paste = confirm;
+    }
+
+    pub fn take_paste_confirm_disabled(&mut self) -> bool {
+        std::mem::take(&mut self.paste_confirm_disabled)
+    }
+
+    pub fn take_context_menu(&mut self) -> Option<(Point<Pixels>, TerminalMenuTarget)> {
+        self.pending_context_menu.take()
+    }
+
+    pub fn take_link(&mut self) -> Option<String> {
+        self.pending_link.take()
+    }
+
+    pub fn take_location(&mut self) -> Option<(PathBuf, usize, usize)> {
+        self.pending_location.take()
+    }
+
+    pub fn take_git_command(&mut self) -> bool {
+        std::mem::take(&mut self.git_command_sent)
+    }
+
+    pub fn set_scrollback_limit(&mut self, lines: usize) {
+        self.state.set_max_scrollback(lines);
+        self.rebase_selection();
+        self.refresh_search();
+    }
+
     fn font_fallbacks(&self) -> Option<gpui::FontFallbacks> {
         self.font_fallback.as_ref().map(|fb| {
             gpui::FontFallbacks::from_fonts(vec![fb.clone()])
@@ -95,6 +183,10 @@ impl TerminalView {
             })
     }
 
+    pub fn working_directory(&self) -> &PathBuf {
+        self.state.working_directory()
+    }
+
     pub fn new(cx: &mut Context<Self>) -> Self {
         let focus_handle = cx.focus_handle();
         let ide = crate::ide_theme::use_ide_theme();
@@ -110,6 +202,7 @@ impl TerminalView {
             last_blink_time: Instant::now(),
             selection_start: None,
             selection_end: None,
+            selection_evicted: 0,
             is_selecting: false,
             viewport_height: 400.0,
             viewport_width: 800.0,
@@ -133,6 +226,17 @@ impl TerminalView {
             line_height: LINE_HEIGHT,
             font_family: "JetBrains Mono".to_string(),
             font_fallback: None,
+            search: None,
+            confirm_multiline_paste: true,
+            pending_paste: None,
+            paste_confirm_disabled: false,
+            pending_context_menu: None,
+            pending_link: None,
+            hovered_link: None,
+            pending_location: None,
+            hovered_location: None,
+            location_cache: None,
+            git_command_sent: false,
         }
     }
 
@@ -151,6 +255,10 @@ impl TerminalView {
         self.pty.as_ref().map(|p| p.is_running()).unwrap_or(false)
     }
 
+    pub fn take_notification(&mut self) -> Option<(String, Option<String>)> {
+        self.pending_notification.take()
+    }
+
     pub fn start_with_polling(
         &mut self,
         window: &mut Window,
@@ -220,6 +328,17 @@ impl TerminalView {
         Ok(())
     }
 
+    pub fn restart(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Result<(), String> {
+        self.stop();
+        self.state.reset();
+        self.parser = AnsiParser::new();
+        self.apply_ide_theme();
+        self.clear_selection();
+        self.clear_search();
+        self.invalidate_line_cache();
+        self.start_with_polling(window, cx)
+    }
+
     pub fn stop(&mut self) {
         if let Some(mut pty) = self.pty.take() {
             pty.stop();
@@ -233,6 +352,8 @@ impl TerminalView {
             let output = pty.drain_output();
             if !output.is_empty() {
                 self.process_bytes(&output);
+                self.rebase_selection();
+                self.refresh_search();
                 return true;
             }
         }
@@ -609,6 +730,7 @@ impl TerminalView {
                 self.pending_pty_resize = None;
                 if cols != self.state.cols() || rows != self.state.rows() {
                     self.state.resize(cols, rows);
+                    self.refresh_search();
                     if let Some(pty) = &mut self.pty {
                         let pixel_width = (cols as f32 * self.char_width) as u16;
                         let pixel_height = (rows as f32 * self.line_height) as u16;
@@ -619,6 +741,132 @@ impl TerminalView {
         }
     }
 
+    pub fn open_search(&mut self) {
+        if self.search.is_none() {
+            self.search = Some(TerminalSearch {
+                lines_evicted: self.state.lines_evicted(),
+                ..Default::default()
+            });
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    fn refresh_search(&mut self) {
+        let evicted = self.state.lines_evicted();
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let anchor = search
+            .active
+            .and_then(|i| search.matches.get(i))
+            .map(|&(line, col, _)| (line + search.lines_evicted, col));
+        search.matches = self
+            .state
+            .find_matches(&search.query, search.case_sensitive);
+        search.lines_evicted = evicted;
+        search.active = anchor.and_then(|anchor| {
+            search
+                .matches
+                .iter()
+                .position(|&(line, col, _)| (line + evicted, col) >= anchor)
+                .or(search.matches.len().checked_sub(1))
+        });
+    }
+
+    fn update_search_query(&mut self, query: String) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        search.query = query;
+        search.active = None;
+        self.refresh_search();
+        self.search_step(false);
+    }
+
+    fn search_step(&mut self, forward: bool) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let len = search.matches.len();
+        if len == 0 {
+            search.active = None;
+            return;
+        }
+        let next = match search.active {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None => len - 1,
+        };
+        search.active = Some(next);
+        let line = search.matches[next].0;
+        self.reveal_line(line);
+    }
+
+    fn reveal_line(&mut self, line: usize) {
+        let total = self.state.total_lines();
+        let rows = self.state.rows();
+        let offset = self.state.scroll_offset();
+        let start = total.saturating_sub(rows + offset);
+        if line >= start && line < start + rows {
+            return;
+        }
+        let target_start = line.saturating_sub(rows / 2);
+        let target = total
+            .saturating_sub(rows + target_start)
+            .min(self.state.max_scroll_offset());
+        if target > offset {
+            self.state.scroll_viewport_up(target - offset);
+        } else {
+            self.state.scroll_viewport_down(offset - target);
+        }
+    }
+
+    fn handle_paste_key(&mut self, event: &KeyDownEvent) -> bool {
+        if self.pending_paste.is_none() {
+            return false;
+        }
+        match event.keystroke.key.as_str() {
+            "enter" => self.confirm_paste(),
+            "escape" => self.pending_paste = None,
+            _ => {}
+        }
+        true
+    }
+
+    fn handle_search_key(&mut self, event: &KeyDownEvent) -> bool {
+        let Some(search) = self.search.as_ref() else {
+            return false;
+        };
+        let mods = &event.keystroke.modifiers;
+        let key = event.keystroke.key.as_str();
+        match key {
+            "escape" => self.clear_search(),
+            "enter" => self.search_step(mods.shift),
+            "backspace" => {
+                let mut query = search.query.clone();
+                query.pop();
+                self.update_search_query(query);
+            }
+            _ => {
+                if mods.platform || mods.control {
+                    return false;
+                }
+                let text = match &event.keystroke.key_char {
+                    Some(text) => text.clone(),
+                    None if key == "space" => " ".to_string(),
+                    None if key.chars().count() == 1 => key.to_string(),
+                    None => return true,
+                };
+                let query = format!("{}{}", search.query, text);
+                self.update_search_query(query);
+            }
+        }
+        true
+    }
+
     pub fn scroll_up(&mut self, lines: usize) {
         self.state.scroll_viewport_up(lines);
     }
@@ -718,6 +966,11 @@ impl TerminalView {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.handle_paste_key(event) || self.handle_search_key(event) {
+            cx.notify();
+            return;
+        }
+
         if !self.is_running() {
             return;
         }
@@ -737,6 +990,7 @@ impl TerminalView {
         let app_cursor = self.state.application_cursor_keys();
         let handled = match key {
             "enter" => {
+                self.git_command_sent |= self.cursor_line_runs_git();
                 self.send_input(key_codes::ENTER);
                 true
             }
@@ -1143,13 +1397,19 @@ impl TerminalView {
 
             if event.modifiers.platform {
                 if let Some(url) = self.hyperlink_at(line, col) {
-                    let _ = open::that(&url);
+                    self.pending_link = Some(url);
+                    cx.notify();
                     return;
                 }
                 if let Some(url) = self.detect_url_at(line, col) {
                     let _ = open::that(&url);
                     return;
                 }
+                if let Some((_, path, target_line, target_col)) = self.location_at(line, col) {
+                    self.pending_location = Some((path, target_line, target_col));
+                    cx.notify();
+                    return;
+                }
             }
 
             let now = Instant::now();
@@ -1189,6 +1449,106 @@ impl TerminalView {
         }
     }
 
+    fn handle_right_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.state.mouse_tracking() && !event.modifiers.shift {
+            return;
+        }
+        window.focus(&self.focus_handle);
+        let (line, col) = self.position_from_mouse(event.position);
+        let target = self
+            .get_selected_text()
+            .filter(|text| !text.trim().is_empty())
+            .map(TerminalMenuTarget::Selection)
+            .or_else(|| {
+                self.hyperlink_at(line, col)
+                    .or_else(|| self.detect_url_at(line, col))
+                    .map(TerminalMenuTarget::Url)
+            })
+            .or_else(|| self.detect_path_at(line, col).map(TerminalMenuTarget::Path));
+        if let Some(target) = target {
+            self.pending_context_menu = Some((event.position, target));
+            cx.notify();
+        }
+    }
+
+    fn detect_path_at(&mut self, line_idx: usize, col: usize) -> Option<PathBuf> {
+        if let Some((_, path, _, _)) = self.location_at(line_idx, col) {
+            return Some(path);
+        }
+        let line = self.state.line(line_idx)?;
+        let chars: Vec<char> = line.cells.iter().map(|c| c.char).collect();
+        let is_path_char = |c: char| {
+            !c.is_whitespace()
+                && !matches!(
+                    c,
+                    '\0' | '\'' | '"' | '`' | '<' | '>' | '(' | ')' | '[' | ']'
+                )
+        };
+        if !chars.get(col).is_some_and(|&c| is_path_char(c)) {
+            return None;
+        }
+        let start = (0..col)
+            .rev()
+            .take_while(|&i| is_path_char(chars[i]))
+            .last()
+            .unwrap_or(col);
+        let end = (col..chars.len())
+            .take_while(|&i| is_path_char(chars[i]))
+            .last()
+            .map_or(col, |i| i + 1);
+        let token: String = chars[start..end].iter().collect();
+        if token.contains("://") {
+            return None;
+        }
+        let path = self.resolve_path(token.trim_end_matches(['.', ',', ';', ':']))?;
+        path.exists().then_some(path)
+    }
+
+    fn resolve_path(&self, token: &str) -> Option<PathBuf> {
+        Some(match token.strip_prefix("~/") {
+            Some(rest) => PathBuf::from(std::env::var_os("HOME")?).join(rest),
+            None => self.state.working_directory().join(token),
+        })
+    }
+
+    fn location_at(
+        &mut self,
+        line_idx: usize,
+        col: usize,
+    ) -> Option<(Range<usize>, PathBuf, usize, usize)> {
+        let line = self.state.line(line_idx)?;
+        let text: String = line.cells.iter().map(|c| c.char).collect();
+        let cached = self
+            .location_cache
+            .as_ref()
+            .is_some_and(|(idx, cached, _)| *idx == line_idx && *cached == text);
+        if !cached {
+            let locations = find_locations(&text)
+                .into_iter()
+                .map(|location| {
+                    let target = self
+                        .resolve_path(&location.path)
+                        .filter(|path| path.is_file())
+                        .map(|path| {
+                            let target_col = location.col.unwrap_or(1).saturating_sub(1);
+                            (path, location.line - 1, target_col)
+                        });
+                    (location.range, target)
+                })
+                .collect();
+            self.location_cache = Some((line_idx, text, locations));
+        }
+        let (_, _, locations) = self.location_cache.as_ref()?;
+        let (range, target) = locations.iter().find(|(range, _)| range.contains(&col))?;
+        let (path, line, col) = target.clone()?;
+        Some((range.clone(), path, line, col))
+    }
+
     fn hyperlink_at(&self, line_idx: usize, col: usize) -> Option<String> {
         let line = self.state.line(line_idx)?;
         let cell = line.get(col)?;
@@ -1313,6 +1673,25 @@ impl TerminalView {
             let snapped_col = self.snap_to_primary_cell(line, col);
             self.selection_end = Some((line, snapped_col));
             cx.notify();
+            return;
+        }
+
+        let (line, col) = self.position_from_mouse(event.position);
+        let hovered = self
+            .hyperlink_at(line, col)
+            .map(|url| (event.position - self.content_origin, url));
+        let location = match hovered {
+            Some(_) => None,
+            None => self
+                .location_at(line, col)
+                .map(|(range, _, _, _)| (line, range)),
+        };
+        if hovered.as_ref().map(|(_, url)| url) != self.hovered_link.as_ref().map(|(_, url)| url)
+            || location != self.hovered_location
+        {
+            self.hovered_link = hovered;
+            self.hovered_location = location;
+            cx.notify();
         }
     }
 
@@ -1475,19 +1854,56 @@ impl TerminalView {
     }
 
     pub fn paste_from_clipboard(&mut self, cx: &mut Context<Self>) {
-        if let Some(item) = cx.read_from_clipboard() {
-            if let Some(text) = item.text() {
-                if self.state.bracketed_paste() {
-                    self.send_input(b"\x1b[200~");
-                    self.send_str(&text);
-                    self.send_input(b"\x1b[201~");
-                } else {
-                    self.send_str(&text);
-                }
-            }
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            self.paste_text(text);
+            cx.notify();
+        }
+    }
+
+    pub fn paste_text(&mut self, text: String) {
+        if self.state.bracketed_paste() {
+            self.send_input(b"\x1b[200~");
+            self.send_str(&text.replace("\x1b[201~", ""));
+            self.send_input(b"\x1b[201~");
+        } else if self.confirm_multiline_paste && text.trim_end_matches(['\r', '\n']).contains('\n')
+        {
+            self.pending_paste = Some(text);
+        } else {
+            self.send_str(&text);
         }
     }
 
+    fn confirm_paste(&mut self) {
+        if let Some(text) = self.pending_paste.take() {
+            self.send_str(&text);
+        }
+    }
+
+    fn rebase_selection(&mut self) {
+        let evicted = self.state.lines_evicted();
+        let shift = evicted - self.selection_evicted;
+        self.selection_evicted = evicted;
+        if shift == 0 {
+            return;
+        }
+        let (Some(start), Some(end)) = (self.selection_start, self.selection_end) else {
+            return;
+        };
+        if start.0.max(end.0) < shift {
+            self.clear_selection();
+            return;
+        }
+        let rebase = |(line, col): (usize, usize)| {
+            if line < shift {
+                (0, 0)
+            } else {
+                (line - shift, col)
+            }
+        };
+        self.selection_start = Some(rebase(start));
+        self.selection_end = Some(rebase(end));
+    }
+
     pub fn clear_selection(&mut self) {
         self.selection_start = None;
         self.selection_end = None;
@@ -1558,6 +1974,13 @@ impl TerminalView {
         let cursor_bg = chrome.accent;
         let cursor_fg = chrome.bg;
         let selection_bg = chrome.accent.opacity(0.35);
+        let match_bg = ide.editor.search_match;
+        let active_match_bg = ide.editor.search_match_active;
+        let line_matches = self
+            .search
+            .as_ref()
+            .map(|search| search.matches_on_line(idx))
+            .unwrap_or_default();
 
         let has_selection = self.has_selection() && {
             let (start, end) = (self.selection_start.unwrap(), self.selection_end.unwrap());
@@ -1569,11 +1992,17 @@ impl TerminalView {
             idx >= s_line && idx <= e_line
         };
 
+        let hovered_range = self
+            .hovered_location
+            .as_ref()
+            .filter(|(line, _)| *line == idx)
+            .map(|(_, range)| range);
+
         let cols = self.state.cols();
         let mut spans: Vec<gpui::AnyElement> = Vec::new();
         let mut current_text = String::new();
         let mut current_style: Option<&crate::terminal_state::CellStyle> = None;
-        let mut current_selected = false;
+        let mut current_highlight: Option<gpui::Hsla> = None;
         let mut current_has_link = false;
 
         for col in 0..cols {
@@ -1587,15 +2016,14 @@ impl TerminalView {
                             spans.push(self.make_span(
                                 &current_text,
                                 &style,
-                                current_selected,
+                                current_highlight,
                                 current_has_link,
-                                &selection_bg,
                             ));
                             current_text.clear();
                         }
                         current_text.push(' ');
                         current_style = Some(&c.style);
-                        current_selected = false;
+                        current_highlight = None;
                         current_has_link = false;
                         continue;
                     }
@@ -1605,7 +2033,16 @@ impl TerminalView {
 
             let is_cursor_pos = show_cursor && col == cursor_col;
             let is_selected = has_selection && self.is_position_selected(idx, col);
-            let has_link = cell.map(|c| c.hyperlink.is_some()).unwrap_or(false);
+            let highlight = if is_selected {
+                Some(selection_bg)
+            } else {
+                line_matches
+                    .iter()
+                    .find(|(start, end, _)| col >= *start && col < *end)
+                    .map(|(_, _, active)| if *active { active_match_bg } else { match_bg })
+            };
+            let hovered = hovered_range.is_some_and(|r| r.contains(&col));
+            let has_link = hovered || cell.is_some_and(|c| c.hyperlink.is_some());
 
             if is_cursor_pos {
                 if !current_text.is_empty() {
@@ -1613,9 +2050,8 @@ impl TerminalView {
                     spans.push(self.make_span(
                         &current_text,
                         &style,
-                        current_selected,
+                        current_highlight,
                         current_has_link,
-                        &selection_bg,
                     ));
                     current_text.clear();
                 }
@@ -1645,14 +2081,14 @@ impl TerminalView {
                 };
                 spans.push(cursor_span.into_any_element());
                 current_style = cell.map(|c| &c.style);
-                current_selected = is_selected;
+                current_highlight = highlight;
                 current_has_link = has_link;
             } else if let Some(cell) = cell {
-                let cell_has_link = cell.hyperlink.is_some();
+                let cell_has_link = has_link;
                 let cell_blink = cell.style.blink && !self.blink_visible;
                 let display_char = if cell_blink { ' ' } else { cell.char };
                 let needs_flush = current_style.map(|s| s != &cell.style).unwrap_or(true)
-                    || is_selected != current_selected
+                    || highlight != current_highlight
                     || cell_has_link != current_has_link;
 
                 if needs_flush && !current_text.is_empty() {
@@ -1660,15 +2096,14 @@ impl TerminalView {
                     spans.push(self.make_span(
                         &current_text,
                         &style,
-                        current_selected,
+                        current_highlight,
                         current_has_link,
-                        &selection_bg,
                     ));
                     current_text.clear();
                 }
 
                 current_style = Some(&cell.style);
-                current_selected = is_selected;
+                current_highlight = highlight;
                 current_has_link = cell_has_link;
                 current_text.push(display_char);
             }
@@ -1676,13 +2111,7 @@ impl TerminalView {
 
         if !current_text.is_empty() {
             let style = current_style.cloned().unwrap_or_default();
-            spans.push(self.make_span(
-                &current_text,
-                &style,
-                current_selected,
-                current_has_link,
-                &selection_bg,
-            ));
+            spans.push(self.make_span(&current_text, &style, current_highlight, current_has_link));
         }
 
         if show_cursor && cursor_col >= cols {
@@ -1709,17 +2138,16 @@ impl TerminalView {
         &self,
         text: &str,
         style: &crate::terminal_state::CellStyle,
-        selected: bool,
+        highlight: Option<gpui::Hsla>,
         has_hyperlink: bool,
-        selection_bg: &gpui::Hsla,
     ) -> gpui::AnyElement {
         let fg = style.effective_fg();
         let bg = style.effective_bg();
 
         let mut el = div().text_color(fg);
 
-        if selected {
-            el = el.bg(*selection_bg);
+        if let Some(highlight) = highlight {
+            el = el.bg(highlight);
         } else if bg.a > 0.01 {
             el = el.bg(bg);
         }
@@ -1755,6 +2183,202 @@ impl TerminalView {
         el.child(text.to_string()).into_any_element()
     }
 
+    fn render_paste_prompt(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let text = self.pending_paste.as_ref()?;
+        let chrome = use_ide_theme().chrome;
+        let lines = text.trim_end_matches(['\r', '\n']).lines().count();
+        let button = |id: &'static str, label: &'static str, primary: bool| {
+            div()
+                .id(id)
+                .px(px(6.0))
+                .rounded(px(3.0))
+                .cursor_pointer()
+                .font_weight(FontWeight::SEMIBOLD)
+                .text_color(if primary {
+                    chrome.accent
+                } else {
+                    chrome.text_secondary
+                })
+                .hover(|s| s.bg(chrome.dim.opacity(0.4)).text_color(chrome.bright))
+                .child(label)
+        };
+
+        Some(
+            div()
+                .w_full()
+                .h(px(28.0))
+                .flex()
+                .flex_shrink_0()
+                .items_center()
+                .gap(px(10.0))
+                .px(px(12.0))
+                .bg(chrome.dim.opacity(0.2))
+                .border_b_1()
+                .border_color(chrome.header_border)
+                .text_size(px(11.0))
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .child(
+                    div()
+                        .flex_1()
+                        .overflow_hidden()
+                        .text_color(chrome.bright)
+                        .child(format!(
+                            "Paste {} lines? Each line may run as a command.",
+                            lines
+                        )),
+                )
+                .child(
+                    button("terminal-paste-confirm", "Paste", true).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, cx| {
+                            this.confirm_paste();
+                            cx.stop_propagation();
+                            cx.notify();
+                        }),
+                    ),
+                )
+                .child(
+                    button("terminal-paste-always", "Always Allow", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, cx| {
+                            this.confirm_multiline_paste = false;
+                            this.paste_confirm_disabled = true;
+                            this.confirm_paste();
+                            cx.stop_propagation();
+                            cx.notify();
+                        }),
+                    ),
+                )
+                .child(
+                    button("terminal-paste-cancel", "Cancel", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, cx| {
+                            this.pending_paste = None;
+                            cx.stop_propagation();
+                            cx.notify();
+                        }),
+                    ),
+                ),
+        )
+    }
+
+    fn render_link_tooltip(&self) -> Option<impl IntoElement> {
+        let (offset, url) = self.hovered_link.as_ref()?;
+        let chrome = use_ide_theme().chrome;
+        Some(
+            div()
+                .absolute()
+                .left(offset.x - px(TERMINAL_PADDING) + px(12.0))
+                .top(offset.y - px(TERMINAL_PADDING) + px(16.0))
+                .max_w(px(480.0))
+                .px(px(6.0))
+                .py(px(2.0))
+                .rounded(px(3.0))
+                .bg(chrome.panel_bg)
+                .border_1()
+                .border_color(chrome.header_border)
+                .text_size(px(11.0))
+                .text_color(chrome.bright)
+                .overflow_hidden()
+                .whitespace_nowrap()
+                .text_ellipsis()
+                .child(url.clone()),
+        )
+    }
+
+    fn render_search_bar(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let search = self.search.as_ref()?;
+        let chrome = use_ide_theme().chrome;
+        let count = if search.query.is_empty() {
+            String::new()
+        } else if search.matches.is_empty() {
+            "No results".to_string()
+        } else if let Some(active) = search.active {
+            format!("{} of {}", active + 1, search.matches.len())
+        } else {
+            format!("{} matches", search.matches.len())
+        };
+        let query = if search.query.is_empty() {
+            div()
+                .text_color(chrome.text_secondary.opacity(0.6))
+                .child("Search scrollback...")
+        } else {
+            div()
+                .text_color(chrome.bright)
+                .child(format!("{}▏", search.query))
+        };
+        let case_color = if search.case_sensitive {
+            chrome.accent
+        } else {
+            chrome.text_secondary
+        };
+
+        Some(
+            div()
+                .w_full()
+                .h(px(28.0))
+                .flex()
+                .flex_shrink_0()
+                .items_center()
+                .gap(px(10.0))
+                .px(px(12.0))
+                .bg(chrome.dim.opacity(0.2))
+                .border_b_1()
+                .border_color(chrome.header_border)
+                .text_size(px(11.0))
+                .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .child(
+                    div()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .text_color(chrome.accent)
+                        .child("Find"),
+                )
+                .child(div().flex_1().overflow_hidden().child(query))
+                .child(div().text_color(chrome.text_secondary).child(count))
+                .child(
+                    div()
+                        .id("terminal-search-case")
+                        .px(px(4.0))
+                        .rounded(px(3.0))
+                        .cursor_pointer()
+                        .font_weight(FontWeight::SEMIBOLD)
+                        .text_color(case_color)
+                        .hover(|s| s.bg(chrome.dim.opacity(0.4)))
+                        .child("Aa")
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _, _, cx| {
+                                if let Some(search) = this.search.as_mut() {
+                                    search.case_sensitive = !search.case_sensitive;
+                                    let query = search.query.clone();
+                                    this.update_search_query(query);
+                                }
+                                cx.stop_propagation();
+                                cx.notify();
+                            }),
+                        ),
+                )
+                .child(
+                    div()
+                        .id("terminal-search-clear")
+                        .px(px(4.0))
+                        .rounded(px(3.0))
+                        .cursor_pointer()
+                        .text_color(chrome.text_secondary)
+                        .hover(|s| s.bg(chrome.dim.opacity(0.4)).text_color(chrome.bright))
+                        .child("✕")
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _, _, cx| {
+                                this.clear_search();
+                                cx.stop_propagation();
+                                cx.notify();
+                            }),
+                        ),
+                ),
+        )
+    }
+
     fn place_inline_image(&mut self, image_data: crate::ansi_parser::InlineImageData) {
         let format = detect_image_format(&image_data.data);
         let gpui_image = Arc::new(Image::from_bytes(format, image_data.data));
@@ -1832,6 +2456,15 @@ impl TerminalView {
         (row, col)
     }
 
+    fn cursor_line_runs_git(&self) -> bool {
+        let Some(line) = self.state.line(self.cursor_absolute_line()) else {
+            return false;
+        };
+        let text: String = line.cells.iter().map(|c| c.char).collect();
+        text.split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|' | '('))
+            .any(|word| matches!(word, "git" | "gh" | "lazygit" | "tig"))
+    }
+
     fn cursor_absolute_line(&self) -> usize {
         let total = self.state.total_lines();
         let rows = self.state.rows();
@@ -1931,8 +2564,6 @@ impl Render for TerminalView {
             cx.write_to_clipboard(ClipboardItem::new_string(text));
         }
 
-        self.pending_notification.take();
-
         if self.is_running() && !self.polling_started {
             self.polling_started = true;
             cx.spawn_in(window, async move |this, cx| {
@@ -2051,12 +2682,20 @@ impl Render for TerminalView {
             .id("terminal-view")
             .key_context("Terminal")
             .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &ToggleTerminalSearch, _, cx| {
+                this.open_search();
+                cx.notify();
+            }))
             .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
                 this.handle_key_down(event, window, cx);
                 cx.stop_propagation();
             }))
             .on_scroll_wheel(cx.listener(Self::handle_scroll))
             .on_mouse_down(MouseButton::Left, cx.listener(Self::handle_mouse_down))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(Self::handle_right_mouse_down),
+            )
             .on_mouse_move(cx.listener(Self::handle_mouse_move))
             .on_mouse_up(MouseButton::Left, cx.listener(Self::handle_mouse_up))
             .size_full()
@@ -2126,12 +2765,21 @@ impl Render for TerminalView {
                             ),
                     ),
             )
+            .children(self.render_paste_prompt(cx))
+            .children(self.render_search_bar(cx))
             .child(
                 div()
                     .id("terminal-content")
                     .flex_1()
                     .overflow_hidden()
                     .p(px(TERMINAL_PADDING))
+                    .on_hover(cx.listener(|this, hovered: &bool, _, cx| {
+                        if !*hovered {
+                            this.hovered_link = None;
+                            this.hovered_location = None;
+                            cx.notify();
+                        }
+                    }))
                     .on_resize({
                         let this = cx.entity().clone();
                         move |ev, _window, cx| {
@@ -2164,6 +2812,7 @@ impl Render for TerminalView {
                                     .collect::<Vec<_>>(),
                             )
                             .children(image_overlays)
+                            .children(self.render_link_tooltip())
                             .children(if bell_active {
                                 vec![div()
                                     .absolute()
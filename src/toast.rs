@@ -0,0 +1,100 @@
+use crate::ide_theme::{use_ide_theme, ChromeColors};
+use adabraka_ui::components::icon::Icon;
+use gpui::*;
+use std::rc::Rc;
+use std::time::Duration;
+
+pub const MAX_TOASTS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastSeverity {
+    Info,
+    Success,
+    Error,
+}
+
+impl ToastSeverity {
+    pub fn duration(self) -> Duration {
+        match self {
+            ToastSeverity::Error => Duration::from_secs(8),
+            _ => Duration::from_secs(4),
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            ToastSeverity::Info => "bell",
+            ToastSeverity::Success => "circle-check",
+            ToastSeverity::Error => "circle-alert",
+        }
+    }
+
+    fn color(self, chrome: &ChromeColors) -> Hsla {
+        match self {
+            ToastSeverity::Info => chrome.accent,
+            ToastSeverity::Success => chrome.diff_add_text,
+            ToastSeverity::Error => chrome.diff_del_text,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub id: usize,
+    pub message: String,
+    pub severity: ToastSeverity,
+}
+
+pub fn render_toasts(
+    toasts: &[Toast],
+    on_dismiss: impl Fn(usize, &mut App) + 'static,
+) -> impl IntoElement {
+    let chrome = use_ide_theme().chrome;
+    let on_dismiss = Rc::new(on_dismiss);
+
+    div()
+        .absolute()
+        .right(px(16.0))
+        .bottom(px(40.0))
+        .w(px(340.0))
+        .flex()
+        .flex_col()
+        .gap(px(8.0))
+        .children(toasts.iter().map(|toast| {
+            let color = toast.severity.color(&chrome);
+            let id = toast.id;
+            let on_dismiss = on_dismiss.clone();
+            div()
+                .id(ElementId::Name(format!("toast-{}", id).into()))
+                .w_full()
+                .flex()
+                .items_start()
+                .gap(px(8.0))
+                .px(px(12.0))
+                .py(px(10.0))
+                .rounded(px(8.0))
+                .bg(chrome.panel_bg)
+                .border_1()
+                .border_color(color.opacity(0.4))
+                .shadow_lg()
+                .child(
+                    div()
+                        .pt(px(1.0))
+                        .child(Icon::new(toast.severity.icon()).size(px(14.0)).color(color)),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .text_size(px(12.0))
+                        .text_color(chrome.bright)
+                        .child(toast.message.clone()),
+                )
+                .child(
+                    div()
+                        .id(ElementId::Name(format!("toast-{}-close", id).into()))
+                        .cursor_pointer()
+                        .child(Icon::new("x").size(px(12.0)).color(chrome.text_secondary))
+                        .on_click(move |_, _, cx| on_dismiss(id, cx)),
+                )
+        }))
+}
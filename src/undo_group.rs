@@ -0,0 +1,304 @@
+use crate::settings::UndoGrouping;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Group {
+    start: usize,
+    removed: String,
+    inserted: String,
+    typing: bool,
+}
+
+impl Group {
+    fn end(&self) -> usize {
+        self.start + self.inserted.len()
+    }
+}
+
+pub struct UndoStack {
+    current: String,
+    version: u64,
+    cursor: (usize, usize),
+    undo: Vec<Group>,
+    redo: Vec<Group>,
+    sealed: bool,
+}
+
+const DIFF_CHUNK: usize = 4096;
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    let len = a.len().min(b.len());
+    let mut start = 0;
+    while start + DIFF_CHUNK <= len {
+        let chunk = start..start + DIFF_CHUNK;
+        if a[chunk.clone()] != b[chunk] {
+            break;
+        }
+        start += DIFF_CHUNK;
+    }
+    start
+        + a[start..len]
+            .iter()
+            .zip(&b[start..len])
+            .take_while(|(x, y)| x == y)
+            .count()
+}
+
+fn common_suffix(a: &[u8], b: &[u8], max: usize) -> usize {
+    let mut suffix = 0;
+    while suffix + DIFF_CHUNK <= max
+        && a[a.len() - suffix - DIFF_CHUNK..a.len() - suffix]
+            == b[b.len() - suffix - DIFF_CHUNK..b.len() - suffix]
+    {
+        suffix += DIFF_CHUNK;
+    }
+    suffix
+        + a[..a.len() - suffix]
+            .iter()
+            .rev()
+            .zip(b[..b.len() - suffix].iter().rev())
+            .take(max - suffix)
+            .take_while(|(x, y)| x == y)
+            .count()
+}
+
+fn diff(before: &str, after: &str) -> Group {
+    let mut start = common_prefix(before.as_bytes(), after.as_bytes());
+    while !before.is_char_boundary(start) || !after.is_char_boundary(start) {
+        start -= 1;
+    }
+    let max_suffix = before.len().min(after.len()) - start;
+    let mut suffix = common_suffix(before.as_bytes(), after.as_bytes(), max_suffix);
+    while !before.is_char_boundary(before.len() - suffix) {
+        suffix -= 1;
+    }
+    let removed = before[start..before.len() - suffix].to_string();
+    let inserted = after[start..after.len() - suffix].to_string();
+    let small = removed.chars().count() + inserted.chars().count() <= 2;
+    let newline = removed.is_empty() && inserted.starts_with('\n') && inserted.trim().is_empty();
+    Group {
+        start,
+        removed,
+        inserted,
+        typing: small || newline,
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl UndoStack {
+    pub fn new(content: String, version: u64, cursor: (usize, usize)) -> Self {
+        Self {
+            current: content,
+            version,
+            cursor,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            sealed: false,
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn move_cursor(&mut self, cursor: (usize, usize)) {
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            self.sealed = true;
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        content: String,
+        version: u64,
+        cursor: (usize, usize),
+        grouping: UndoGrouping,
+        limit: usize,
+    ) {
+        self.version = version;
+        self.cursor = cursor;
+        if content == self.current {
+            return;
+        }
+        let edit = diff(&self.current, &content);
+        self.current = content;
+        self.redo.clear();
+        let merged = !self.sealed
+            && edit.typing
+            && grouping != UndoGrouping::Keystroke
+            && self
+                .undo
+                .last_mut()
+                .is_some_and(|group| Self::merge(group, &edit, grouping));
+        self.sealed = !edit.typing;
+        if !merged {
+            self.undo.push(edit);
+            let excess = self.undo.len().saturating_sub(limit.max(1));
+            self.undo.drain(..excess);
+        }
+    }
+
+    fn merge(group: &mut Group, edit: &Group, grouping: UndoGrouping) -> bool {
+        if !group.typing {
+            return false;
+        }
+        let breaks = match grouping {
+            UndoGrouping::Keystroke => true,
+            UndoGrouping::Word => {
+                let prev = group.inserted.chars().next_back();
+                let next = edit.inserted.chars().next();
+                next.is_some_and(is_word_char) && prev.is_some_and(|c| !is_word_char(c))
+            }
+            UndoGrouping::Line => group.inserted.contains('\n'),
+        };
+        if breaks {
+            return false;
+        }
+        if edit.start >= group.start && edit.start + edit.removed.len() <= group.end() {
+            let at = edit.start - group.start;
+            group
+                .inserted
+                .replace_range(at..at + edit.removed.len(), &edit.inserted);
+            return true;
+        }
+        if edit.inserted.is_empty() && edit.start + edit.removed.len() == group.start {
+            group.start = edit.start;
+            group.removed.insert_str(0, &edit.removed);
+            return true;
+        }
+        false
+    }
+
+    pub fn undo(&mut self) -> Option<(Range<usize>, String, usize)> {
+        let group = self.undo.pop()?;
+        let range = group.start..group.end();
+        if self.current.get(range.clone()) != Some(group.inserted.as_str()) {
+            self.undo.clear();
+            self.redo.clear();
+            return None;
+        }
+        self.current.replace_range(range.clone(), &group.removed);
+        let caret = group.start + group.removed.len();
+        let edit = (range, group.removed.clone(), caret);
+        self.redo.push(group);
+        self.sealed = true;
+        Some(edit)
+    }
+
+    pub fn redo(&mut self) -> Option<(Range<usize>, String, usize)> {
+        let group = self.redo.pop()?;
+        let range = group.start..group.start + group.removed.len();
+        if self.current.get(range.clone()) != Some(group.removed.as_str()) {
+            self.redo.clear();
+            return None;
+        }
+        self.current.replace_range(range.clone(), &group.inserted);
+        let edit = (range, group.inserted.clone(), group.end());
+        self.undo.push(group);
+        self.sealed = true;
+        Some(edit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UndoStack;
+    use crate::settings::UndoGrouping;
+
+    fn end_of(doc: &str) -> (usize, usize) {
+        let line_start = doc.rfind('\n').map_or(0, |i| i + 1);
+        (doc.matches('\n').count(), doc.len() - line_start)
+    }
+
+    fn type_text(stack: &mut UndoStack, doc: &mut String, text: &str, grouping: UndoGrouping) {
+        for c in text.chars() {
+            doc.push(c);
+            stack.record(doc.clone(), 0, end_of(doc), grouping, 100);
+        }
+    }
+
+    fn undo(stack: &mut UndoStack, doc: &mut String) {
+        let (range, text, _) = stack.undo().unwrap();
+        doc.replace_range(range, &text);
+    }
+
+    #[test]
+    fn test_typing_groups_by_word() {
+        let mut doc = String::new();
+        let mut stack = UndoStack::new(doc.clone(), 0, (0, 0));
+        type_text(&mut stack, &mut doc, "let x = 1;", UndoGrouping::Word);
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "let x = ");
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "let ");
+        let (range, text, _) = stack.redo().unwrap();
+        doc.replace_range(range, &text);
+        assert_eq!(doc, "let x = ");
+
+        doc.pop();
+        stack.record(doc.clone(), 0, end_of(&doc), UndoGrouping::Word, 100);
+        stack.seal();
+        type_text(&mut stack, &mut doc, "!", UndoGrouping::Word);
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "let x =");
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "let x = ");
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "let ");
+    }
+
+    #[test]
+    fn test_grouping_modes_and_limit() {
+        let mut doc = String::new();
+        let mut stack = UndoStack::new(doc.clone(), 0, (0, 0));
+        type_text(&mut stack, &mut doc, "a b\nc d", UndoGrouping::Line);
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "a b\n");
+        undo(&mut stack, &mut doc);
+        assert!(doc.is_empty());
+
+        let mut stack = UndoStack::new(doc.clone(), 0, (0, 0));
+        for c in "abc".chars() {
+            doc.push(c);
+            stack.record(doc.clone(), 0, end_of(&doc), UndoGrouping::Keystroke, 2);
+        }
+        undo(&mut stack, &mut doc);
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "a");
+        assert!(!stack.can_undo());
+    }
+
+    #[test]
+    fn test_cursor_jump_starts_new_step() {
+        let mut doc = String::from("fn a() {}");
+        let mut stack = UndoStack::new(doc.clone(), 0, (0, 9));
+        type_text(&mut stack, &mut doc, "xy", UndoGrouping::Word);
+        stack.move_cursor((0, 0));
+        stack.move_cursor((0, 11));
+        type_text(&mut stack, &mut doc, "zw", UndoGrouping::Word);
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "fn a() {}xy");
+        undo(&mut stack, &mut doc);
+        assert_eq!(doc, "fn a() {}");
+
+        let big = "a".repeat(3 * 4096 + 7);
+        let mut stack = UndoStack::new(big.clone(), 0, (0, 0));
+        let mut doc = big.clone();
+        doc.insert(5000, 'é');
+        stack.record(doc.clone(), 1, (0, 5002), UndoGrouping::Word, 100);
+        let (range, text, caret) = stack.undo().unwrap();
+        assert_eq!((range, text.as_str(), caret), (5000..5002, "", 5000));
+    }
+}
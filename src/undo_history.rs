@@ -0,0 +1,155 @@
+use crate::settings::ShioriSettings;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const MAX_FILES: usize = 50;
+const MAX_SNAPSHOT_BYTES: usize = 512 * 1024;
+const MAX_TOTAL_BYTES: usize = 8 * 1024 * 1024;
+const SECRET_EXTENSIONS: &[&str] = &["pem", "key", "p12", "pfx", "keystore", "jks"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileHistory {
+    path: PathBuf,
+    content_hash: u64,
+    snapshots: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UndoHistory {
+    files: Vec<FileHistory>,
+}
+
+pub fn content_hash(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn is_persistable(path: &Path) -> bool {
+    let hidden = path.components().any(|c| match c {
+        std::path::Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+        _ => false,
+    });
+    let secret = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SECRET_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+    !hidden && !secret
+}
+
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents)
+}
+
+impl UndoHistory {
+    fn path() -> Option<PathBuf> {
+        ShioriSettings::config_dir().map(|d| d.join("undo_history.json"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let (Some(dir), Some(path)) = (ShioriSettings::config_dir(), Self::path()) else {
+            return;
+        };
+        if std::fs::create_dir_all(&dir).is_err() {
+            return;
+        }
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = write_private(&path, json.as_bytes());
+        }
+    }
+
+    pub fn forget(&mut self, path: &Path) {
+        self.files.retain(|f| !f.path.starts_with(path));
+    }
+
+    pub fn take(&mut self, path: &Path, current: &str) -> Vec<String> {
+        let Some(idx) = self.files.iter().position(|f| f.path == path) else {
+            return Vec::new();
+        };
+        let entry = self.files.remove(idx);
+        if entry.content_hash == content_hash(current) {
+            entry.snapshots
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        path: PathBuf,
+        mut snapshots: Vec<String>,
+        current: &str,
+        limit: usize,
+    ) {
+        self.files.retain(|f| f.path != path);
+        if limit == 0 {
+            self.files.clear();
+            return;
+        }
+        if !is_persistable(&path) || !path.exists() {
+            return;
+        }
+        snapshots.retain(|s| s.len() <= MAX_SNAPSHOT_BYTES);
+        snapshots.dedup();
+        if snapshots.last().map(|s| s.as_str()) == Some(current) {
+            snapshots.pop();
+        }
+        if snapshots.is_empty() {
+            return;
+        }
+        let excess = snapshots.len().saturating_sub(limit);
+        snapshots.drain(..excess);
+        self.files.push(FileHistory {
+            path,
+            content_hash: content_hash(current),
+            snapshots,
+        });
+
+        let excess = self.files.len().saturating_sub(MAX_FILES);
+        self.files.drain(..excess);
+        let mut total: usize = self
+            .files
+            .iter()
+            .flat_map(|f| f.snapshots.iter())
+            .map(String::len)
+            .sum();
+        while total > MAX_TOTAL_BYTES && !self.files.is_empty() {
+            let removed = self.files.remove(0);
+            total -= removed.snapshots.iter().map(String::len).sum::<usize>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secrets_and_dotfiles_are_not_persisted() {
+        assert!(is_persistable(Path::new("/work/src/main.rs")));
+        assert!(!is_persistable(Path::new("/work/.env")));
+        assert!(!is_persistable(Path::new("/home/me/.ssh/id_ed25519")));
+        assert!(!is_persistable(Path::new("/work/certs/server.KEY")));
+    }
+}
@@ -0,0 +1,227 @@
+use crate::app::ViewMode;
+use crate::settings::ShioriSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub const WORKSPACE_EXTENSION: &str = "shiori-workspace";
+const WORKSPACE_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum WorkspaceFileError {
+    #[error("Failed to read workspace file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid workspace file: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Unsupported workspace file version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Workspace file lists no folders")]
+    NoFolders,
+    #[error("None of the workspace folders exist")]
+    NoValidFolders,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceLayout {
+    #[serde(default = "default_view")]
+    pub view: ViewMode,
+    #[serde(default = "default_true")]
+    pub panel_visible: bool,
+    #[serde(default)]
+    pub symbol_outline_visible: bool,
+    #[serde(default)]
+    pub terminal_fullscreen: bool,
+    #[serde(default)]
+    pub terminals: TerminalLayout,
+    #[serde(default)]
+    pub expanded: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TerminalLayout {
+    #[serde(default)]
+    pub working_dirs: Vec<PathBuf>,
+    #[serde(default)]
+    pub active: usize,
+}
+
+impl Default for WorkspaceLayout {
+    fn default() -> Self {
+        Self {
+            view: default_view(),
+            panel_visible: true,
+            symbol_outline_visible: false,
+            terminal_fullscreen: false,
+            terminals: TerminalLayout::default(),
+            expanded: Vec::new(),
+        }
+    }
+}
+
+fn default_view() -> ViewMode {
+    ViewMode::Explorer
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_version() -> u32 {
+    WORKSPACE_VERSION
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewState {
+    pub line: usize,
+    pub col: usize,
+    pub top_line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceFile {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub folders: Vec<PathBuf>,
+    #[serde(default)]
+    pub tabs: Vec<PathBuf>,
+    #[serde(default)]
+    pub active_tab: Option<usize>,
+    #[serde(default)]
+    pub views: Vec<Option<ViewState>>,
+    #[serde(default)]
+    pub layout: WorkspaceLayout,
+    #[serde(default)]
+    pub settings: Map<String, Value>,
+}
+
+pub struct LoadedWorkspace {
+    pub file: WorkspaceFile,
+    pub warnings: Vec<String>,
+}
+
+fn relative_to(base: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(base) {
+        Ok(rel) if rel.as_os_str().is_empty() => PathBuf::from("."),
+        Ok(rel) => rel.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+fn resolve(base: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else if path == Path::new(".") {
+        base.to_path_buf()
+    } else {
+        base.join(path)
+    }
+}
+
+pub fn is_workspace_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some(WORKSPACE_EXTENSION)
+}
+
+pub fn last_session_path() -> Option<PathBuf> {
+    ShioriSettings::config_dir().map(|d| d.join(format!("last-session.{}", WORKSPACE_EXTENSION)))
+}
+
+impl WorkspaceFile {
+    pub fn load(path: &Path) -> Result<LoadedWorkspace, WorkspaceFileError> {
+        Self::read(path, true)
+    }
+
+    pub fn load_session(path: &Path) -> Result<LoadedWorkspace, WorkspaceFileError> {
+        Self::read(path, false)
+    }
+
+    fn read(path: &Path, require_folders: bool) -> Result<LoadedWorkspace, WorkspaceFileError> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut file: WorkspaceFile = serde_json::from_str(&contents)?;
+        if file.version > WORKSPACE_VERSION {
+            return Err(WorkspaceFileError::UnsupportedVersion(file.version));
+        }
+        if require_folders && file.folders.is_empty() {
+            return Err(WorkspaceFileError::NoFolders);
+        }
+
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut warnings = Vec::new();
+        let mut folders = Vec::new();
+        for folder in &file.folders {
+            let resolved = resolve(base, folder);
+            if resolved.is_dir() {
+                if !folders.contains(&resolved) {
+                    folders.push(resolved);
+                }
+            } else {
+                warnings.push(format!("Folder not found: {}", folder.display()));
+            }
+        }
+        if folders.is_empty() && !file.folders.is_empty() {
+            return Err(WorkspaceFileError::NoValidFolders);
+        }
+
+        let active = file.active_tab.and_then(|i| file.tabs.get(i)).cloned();
+        let mut tabs = Vec::new();
+        let mut views = Vec::new();
+        for (i, tab) in file.tabs.iter().enumerate() {
+            let resolved = resolve(base, tab);
+            if resolved.is_file() {
+                tabs.push(resolved);
+                views.push(file.views.get(i).copied().flatten());
+            } else {
+                warnings.push(format!("File not found: {}", tab.display()));
+            }
+        }
+        file.active_tab = active
+            .map(|a| resolve(base, &a))
+            .and_then(|a| tabs.iter().position(|t| *t == a));
+        let mut terminal_dirs = Vec::new();
+        for dir in &file.layout.terminals.working_dirs {
+            let resolved = resolve(base, dir);
+            if resolved.is_dir() {
+                terminal_dirs.push(resolved);
+            } else {
+                warnings.push(format!("Terminal folder not found: {}", dir.display()));
+            }
+        }
+        file.layout.terminals.working_dirs = terminal_dirs;
+        file.layout.expanded = file
+            .layout
+            .expanded
+            .iter()
+            .map(|dir| resolve(base, dir))
+            .filter(|dir| dir.is_dir())
+            .collect();
+        file.folders = folders;
+        file.tabs = tabs;
+        file.views = views;
+        Ok(LoadedWorkspace { file, warnings })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), WorkspaceFileError> {
+        let base = path.parent().unwrap_or(Path::new("."));
+        let mut layout = self.layout.clone();
+        for dir in layout
+            .terminals
+            .working_dirs
+            .iter_mut()
+            .chain(layout.expanded.iter_mut())
+        {
+            *dir = relative_to(base, dir);
+        }
+        let relative = WorkspaceFile {
+            version: WORKSPACE_VERSION,
+            folders: self.folders.iter().map(|f| relative_to(base, f)).collect(),
+            tabs: self.tabs.iter().map(|t| relative_to(base, t)).collect(),
+            active_tab: self.active_tab,
+            views: self.views.clone(),
+            layout,
+            settings: self.settings.clone(),
+        };
+        let json = serde_json::to_string_pretty(&relative)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}